@@ -29,7 +29,7 @@ use crate::id::Id;
 use crate::magic::{Magic, MAGIC};
 use crate::SID;
 
-pub struct Migration<B>(PhantomData<B>);
+pub struct Migration<B>(PhantomData<fn() -> B>);
 
 impl<B: Backend> nuts_container::Migration for Migration<B> {
     fn migrate_rev0(&self, userdata: &[u8]) -> Result<(u32, Vec<u8>), String> {
@@ -0,0 +1,366 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::{Backend, BackendTypes, Create, Open, ReceiveHeader, HEADER_MAX_SIZE};
+
+/// Asynchronous variant of the [`ReceiveHeader`] trait.
+///
+/// The container uses the trait to receive the header (and the extended
+/// header blocks) from an [`AsyncBackend`]. See [`ReceiveHeader`] for
+/// details.
+#[async_trait]
+pub trait AsyncReceiveHeader<B: AsyncBackend>: Send {
+    /// Receives the binary header data from the backend.
+    ///
+    /// See [`ReceiveHeader::get_header_bytes()`] for details.
+    async fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), B::Err>;
+
+    /// Receives the binary data of an extended header block from the backend.
+    ///
+    /// See [`ReceiveHeader::get_extended_header_bytes()`] for details.
+    async fn get_extended_header_bytes(
+        &mut self,
+        _idx: u32,
+        _bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, B::Err> {
+        Ok(false)
+    }
+}
+
+/// Asynchronous variant of the [`Create`] trait.
+///
+/// Used to create a new [`AsyncBackend`] instance. See [`Create`] for
+/// details.
+#[async_trait]
+pub trait AsyncCreate<B: AsyncBackend>: Send {
+    /// Returns the settings of this backend instance.
+    ///
+    /// See [`Create::settings()`] for details.
+    fn settings(&self) -> B::Settings;
+
+    /// Create an instance of the [`AsyncBackend`].
+    ///
+    /// See [`Create::build()`] for details.
+    async fn build(self, header: [u8; HEADER_MAX_SIZE], overwrite: bool) -> Result<B, B::Err>;
+}
+
+/// Asynchronous variant of the [`Open`] trait.
+///
+/// Used to open an existing [`AsyncBackend`] instance. See [`Open`] for
+/// details.
+#[async_trait]
+pub trait AsyncOpen<B: AsyncBackend>: AsyncReceiveHeader<B> {
+    /// Create an instance of the [`AsyncBackend`].
+    ///
+    /// See [`Open::build()`] for details.
+    async fn build(self, settings: B::Settings) -> Result<B, B::Err>;
+}
+
+/// Trait that describes an asynchronous backend of a container.
+///
+/// The trait is the asynchronous variant of the [`Backend`] trait. The same
+/// rules apply to each method, see [`Backend`] for details. The types of the
+/// backend are defined by the [`BackendTypes`] trait.
+#[async_trait]
+pub trait AsyncBackend: BackendTypes + AsyncReceiveHeader<Self> + Send + Sync + Sized {
+    /// Returns information from the backend.
+    ///
+    /// See [`Backend::info()`] for details.
+    async fn info(&self) -> Result<Self::Info, Self::Err>;
+
+    /// Returns the block size of the backend.
+    fn block_size(&self) -> u32;
+
+    /// Tests whether the backend stores blocks of variable length.
+    ///
+    /// See [`Backend::variable_block_size()`] for details.
+    fn variable_block_size(&self) -> bool {
+        false
+    }
+
+    /// Aquires a new block in the backend.
+    ///
+    /// See [`Backend::aquire()`] for details.
+    async fn aquire(&mut self, buf: &[u8]) -> Result<Self::Id, Self::Err>;
+
+    /// Releases a block again.
+    ///
+    /// See [`Backend::release()`] for details.
+    async fn release(&mut self, id: Self::Id) -> Result<(), Self::Err>;
+
+    /// Returns the ids of all aquired blocks.
+    ///
+    /// See [`Backend::ids()`] for details. The default implementation
+    /// returns `None`.
+    async fn ids(&mut self) -> Result<Option<Vec<Self::Id>>, Self::Err> {
+        Ok(None)
+    }
+
+    /// Reads a block from the backend.
+    ///
+    /// See [`Backend::read()`] for details.
+    async fn read(&mut self, id: &Self::Id, buf: &mut [u8]) -> Result<usize, Self::Err>;
+
+    /// Writes a block into the backend.
+    ///
+    /// See [`Backend::write()`] for details.
+    async fn write(&mut self, id: &Self::Id, buf: &[u8]) -> Result<usize, Self::Err>;
+
+    /// Puts the given `buf` into the header of the backend.
+    ///
+    /// See [`Backend::write_header()`] for details.
+    async fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), Self::Err>;
+
+    /// Puts the given `buf` into the extended header block `idx`.
+    ///
+    /// See [`Backend::write_extended_header()`] for details.
+    async fn write_extended_header(
+        &mut self,
+        _idx: u32,
+        _buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, Self::Err> {
+        Ok(false)
+    }
+
+    /// Deletes the entire instance and all traces.
+    ///
+    /// The method must not fail!
+    async fn delete(self);
+}
+
+/// Adapter, which turns blocking backends into asynchronous backends.
+///
+/// * `Blocking<B>` is an [`AsyncBackend`] for a blocking [`Backend`] `B`.
+/// * `Blocking<C>` is an [`AsyncCreate`] for a [`Create`] builder `C`.
+/// * `Blocking<O>` is an [`AsyncOpen`] for an [`Open`] builder `O`.
+///
+/// The blocking calls are executed on a thread pool (see the [blocking]
+/// crate), thus they do not block the executor. The adapter does not depend
+/// on a specific async runtime.
+///
+/// [blocking]: https://crates.io/crates/blocking
+#[derive(Debug)]
+pub struct Blocking<T>(Arc<Mutex<Option<T>>>);
+
+impl<T: Send + 'static> Blocking<T> {
+    /// Wraps the given blocking `inner` instance.
+    pub fn new(inner: T) -> Blocking<T> {
+        Blocking(Arc::new(Mutex::new(Some(inner))))
+    }
+
+    /// Returns the wrapped instance.
+    ///
+    /// If a call was cancelled (its future dropped) while it still runs on
+    /// the thread pool, the method waits for the call to finish.
+    pub fn into_inner(self) -> T {
+        Self::lock(&self.0).take().expect(CONSUMED)
+    }
+
+    fn lock(inner: &Mutex<Option<T>>) -> MutexGuard<'_, Option<T>> {
+        // A panic of a blocking call does not leave the instance in an
+        // inconsistent state, continue with the poisoned instance.
+        inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn with_inner<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        f(Self::lock(&self.0).as_mut().expect(CONSUMED))
+    }
+
+    async fn run<R, F>(&self, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut T) -> R + Send + 'static,
+    {
+        let inner = Arc::clone(&self.0);
+
+        blocking::unblock(move || f(Self::lock(&inner).as_mut().expect(CONSUMED))).await
+    }
+
+    async fn run_once<R, F>(self, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(T) -> R + Send + 'static,
+    {
+        blocking::unblock(move || f(Self::lock(&self.0).take().expect(CONSUMED))).await
+    }
+}
+
+const CONSUMED: &str = "the wrapped instance is already consumed";
+
+impl<B: Backend> BackendTypes for Blocking<B> {
+    type Settings = B::Settings;
+    type Err = B::Err;
+    type Id = B::Id;
+    type Info = B::Info;
+}
+
+#[async_trait]
+impl<B: Backend + Send + 'static> AsyncBackend for Blocking<B>
+where
+    B::Info: Send,
+{
+    async fn info(&self) -> Result<B::Info, B::Err> {
+        self.run(|backend| backend.info()).await
+    }
+
+    fn block_size(&self) -> u32 {
+        self.with_inner(|backend| backend.block_size())
+    }
+
+    fn variable_block_size(&self) -> bool {
+        self.with_inner(|backend| backend.variable_block_size())
+    }
+
+    async fn aquire(&mut self, buf: &[u8]) -> Result<B::Id, B::Err> {
+        let buf = buf.to_vec();
+
+        self.run(move |backend| backend.aquire(&buf)).await
+    }
+
+    async fn release(&mut self, id: B::Id) -> Result<(), B::Err> {
+        self.run(move |backend| backend.release(id)).await
+    }
+
+    async fn ids(&mut self) -> Result<Option<Vec<B::Id>>, B::Err> {
+        self.run(|backend| backend.ids()).await
+    }
+
+    async fn read(&mut self, id: &B::Id, buf: &mut [u8]) -> Result<usize, B::Err> {
+        let id = id.clone();
+        let mut vec = vec![0; buf.len()];
+
+        let (result, vec) = self
+            .run(move |backend| (backend.read(&id, &mut vec), vec))
+            .await;
+
+        if let Ok(n) = result {
+            buf[..n].copy_from_slice(&vec[..n]);
+        }
+
+        result
+    }
+
+    async fn write(&mut self, id: &B::Id, buf: &[u8]) -> Result<usize, B::Err> {
+        let id = id.clone();
+        let buf = buf.to_vec();
+
+        self.run(move |backend| backend.write(&id, &buf)).await
+    }
+
+    async fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), B::Err> {
+        let buf = *buf;
+
+        self.run(move |backend| backend.write_header(&buf)).await
+    }
+
+    async fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, B::Err> {
+        let buf = *buf;
+
+        self.run(move |backend| backend.write_extended_header(idx, &buf))
+            .await
+    }
+
+    async fn delete(self) {
+        self.run_once(|backend| backend.delete()).await
+    }
+}
+
+#[async_trait]
+impl<B, C> AsyncCreate<Blocking<B>> for Blocking<C>
+where
+    B: Backend + Send + 'static,
+    B::Info: Send,
+    C: Create<B> + Send + 'static,
+{
+    fn settings(&self) -> B::Settings {
+        self.with_inner(|create| create.settings())
+    }
+
+    async fn build(
+        self,
+        header: [u8; HEADER_MAX_SIZE],
+        overwrite: bool,
+    ) -> Result<Blocking<B>, B::Err> {
+        self.run_once(move |create| create.build(header, overwrite))
+            .await
+            .map(Blocking::new)
+    }
+}
+
+#[async_trait]
+impl<B, R> AsyncReceiveHeader<Blocking<B>> for Blocking<R>
+where
+    B: Backend + Send + 'static,
+    B::Info: Send,
+    R: ReceiveHeader<B> + Send + 'static,
+{
+    async fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), B::Err> {
+        let (result, buf) = self
+            .run(|inner| {
+                let mut buf = [0; HEADER_MAX_SIZE];
+                (inner.get_header_bytes(&mut buf), buf)
+            })
+            .await;
+
+        *bytes = buf;
+
+        result
+    }
+
+    async fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, B::Err> {
+        let (result, buf) = self
+            .run(move |inner| {
+                let mut buf = [0; HEADER_MAX_SIZE];
+                (inner.get_extended_header_bytes(idx, &mut buf), buf)
+            })
+            .await;
+
+        *bytes = buf;
+
+        result
+    }
+}
+
+#[async_trait]
+impl<B, O> AsyncOpen<Blocking<B>> for Blocking<O>
+where
+    B: Backend + Send + 'static,
+    B::Info: Send,
+    O: Open<B> + Send + 'static,
+{
+    async fn build(self, settings: B::Settings) -> Result<Blocking<B>, B::Err> {
+        self.run_once(move |open| open.build(settings))
+            .await
+            .map(Blocking::new)
+    }
+}
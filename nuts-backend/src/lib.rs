@@ -47,12 +47,49 @@
 //!
 //! The final [`Open::build()`] call creates the backend instance, which is
 //! used by the container.
+//!
+//! # Extended header
+//!
+//! The header of the container is stored in a root header block of
+//! [`HEADER_MAX_SIZE`] bytes. If the header does not fit into the root
+//! block, the container puts the remaining data into _extended header
+//! blocks_, which are numbered starting at `1`. The root block records the
+//! number of extended blocks. A header update writes its extended blocks
+//! into other slots than the blocks of the current header, thus a backend
+//! must be able to store (up to) twice as many extended blocks.
+//!
+//! Extended header blocks are needed before the backend is built (they can
+//! contain the settings of the backend), thus they are received with the
+//! [`ReceiveHeader`] trait as well. They are written with
+//! [`Backend::write_extended_header()`].
+//!
+//! Support for extended header blocks is optional. The default
+//! implementations of both methods return `false`, the container then
+//! rejects a header, which does not fit into the root block.
+//!
+//! # Asynchronous backends
+//!
+//! With the `async` feature enabled, the crate provides the `AsyncBackend`
+//! trait family (`AsyncBackend`, `AsyncCreate`, `AsyncOpen` and
+//! `AsyncReceiveHeader`). The traits mirror their blocking counterparts, but
+//! the I/O methods are `async`. A blocking backend is turned into an
+//! asynchronous backend with the `Blocking` adapter, which runs the blocking
+//! calls on a thread pool.
+//!
+//! Both, [`Backend`] and `AsyncBackend` share their types by means of the
+//! [`BackendTypes`] trait.
+
+#[cfg(feature = "async")]
+mod asynchronous;
 
 use std::error;
 use std::fmt::Display;
 use std::str::FromStr;
 
-// The maximun size of the header.
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncBackend, AsyncCreate, AsyncOpen, AsyncReceiveHeader, Blocking};
+
+// The maximun size of the header resp. an extended header block.
 pub const HEADER_MAX_SIZE: usize = 512;
 
 /// Trait for binary conversion.
@@ -97,6 +134,24 @@ pub trait ReceiveHeader<B: Backend> {
     ///
     /// The method should put the data into the `bytes` slice.
     fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), B::Err>;
+
+    /// Receives the binary data of an extended header block from the backend.
+    ///
+    /// If the header of the container does not fit into [`HEADER_MAX_SIZE`]
+    /// bytes, the remaining data are stored in extended header blocks. `idx`
+    /// is the number of the block, starting at `1`. The blocks were written
+    /// with [`Backend::write_extended_header()`].
+    ///
+    /// The method should put the data into the `bytes` slice and return
+    /// `true`. A backend, which does not support extended header blocks,
+    /// returns `false`. This is the default implementation.
+    fn get_extended_header_bytes(
+        &mut self,
+        _idx: u32,
+        _bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, B::Err> {
+        Ok(false)
+    }
 }
 
 /// Trait to configure the creation of a [`Backend`].
@@ -180,14 +235,14 @@ where
     /// trait for more information on how the backend is opened.
     ///
     /// The [`Create`] trait is used to create the settings of a backend.
-    type Settings: Binary + Clone;
+    type Settings: Binary + Clone + Send + Sync;
 
     /// The error type used by methods of this trait.
     type Err: error::Error + Send + Sync;
 
     /// The id identifies a block in the storage. It is used everywhere you
     /// need a pointer to a block.
-    type Id: Binary + Clone + Display + FromStr + IdSize + PartialEq;
+    type Id: Binary + Clone + Display + FromStr + IdSize + PartialEq + Send + Sync;
 
     /// Information of the backend.
     ///
@@ -210,6 +265,22 @@ where
     /// Returns the block size of the backend.
     fn block_size(&self) -> u32;
 
+    /// Tests whether the backend stores blocks of variable length.
+    ///
+    /// Such a backend stores only the data actually passed to
+    /// [`Backend::aquire`] resp. [`Backend::write`], a shorter `buf` is not
+    /// padded to the [block-size](Backend::block_size). [`Backend::read`]
+    /// returns the number of bytes, which were stored. The
+    /// [block-size](Backend::block_size) is still the upper limit of a
+    /// block.
+    ///
+    /// The container makes use of it for compressed blocks, which are
+    /// stored with their compressed size. The default implementation
+    /// returns `false`.
+    fn variable_block_size(&self) -> bool {
+        false
+    }
+
     /// Aquires a new block in the backend.
     ///
     /// Once aquired you should be able to [read](Backend::read) and
@@ -218,7 +289,8 @@ where
     /// `buf` contains the initial data, which should be copied into the block.
     ///
     /// * A `buf` which is not large enough to fill the whole block must be
-    ///   rejected and an error must be returned.
+    ///   rejected and an error must be returned, unless the backend has a
+    ///   [variable block size](Backend::variable_block_size).
     /// * If `buf` holds more data than the [block-size](Backend::block_size),
     ///   then only the first [block-size](Backend::block_size) bytes are
     ///   copied into the block.
@@ -244,6 +316,22 @@ where
     /// On any error a self-defined [`Backend::Err`] is returned.
     fn release(&mut self, id: Self::Id) -> Result<(), Self::Err>;
 
+    /// Returns the ids of all aquired blocks.
+    ///
+    /// The header is not part of the list. The order of the ids is not
+    /// specified.
+    ///
+    /// A backend, which cannot list its blocks, returns `None`. This is the
+    /// default implementation. The container needs the list to rekey and
+    /// verify the container, these operations fail without it.
+    ///
+    /// # Errors
+    ///
+    /// On any error a self-defined [`Backend::Err`] is returned.
+    fn ids(&mut self) -> Result<Option<Vec<Self::Id>>, Self::Err> {
+        Ok(None)
+    }
+
     /// Reads a block from the backend.
     ///
     /// Reads the block with the given `id` and places the data in `buf`.
@@ -268,7 +356,8 @@ where
     /// the block with the given `id`.
     ///
     /// * A `buf` which is not large enough to fill the whole block must be
-    ///   rejected and an error must be returned.
+    ///   rejected and an error must be returned, unless the backend has a
+    ///   [variable block size](Backend::variable_block_size).
     /// * If `buf` holds more data than the [block-size](Backend::block_size),
     ///   then only the first [block-size](Backend::block_size) bytes are
     ///   copied into the block.
@@ -280,6 +369,65 @@ where
     /// On any error a self-defined [`Backend::Err`] is returned.
     fn write(&mut self, id: &Self::Id, buf: &[u8]) -> Result<usize, Self::Err>;
 
+    /// Aquires a new block for each buffer in `bufs`.
+    ///
+    /// This is the batch variant of [`Backend::aquire`]. The same rules
+    /// apply to each buffer. Returns the [ids](Backend::Id) of the new
+    /// blocks in the order of `bufs`.
+    ///
+    /// The default implementation calls [`Backend::aquire`] for each buffer.
+    /// A backend, where a single request is expensive, should override it
+    /// and aquire all blocks at once.
+    ///
+    /// # Errors
+    ///
+    /// On any error a self-defined [`Backend::Err`] is returned. The
+    /// operation is not atomic, blocks aquired before the error remain
+    /// aquired.
+    fn aquire_many(&mut self, bufs: &[&[u8]]) -> Result<Vec<Self::Id>, Self::Err> {
+        bufs.iter().map(|buf| self.aquire(buf)).collect()
+    }
+
+    /// Reads several blocks from the backend.
+    ///
+    /// This is the batch variant of [`Backend::read`]. For each `(id, buf)`
+    /// pair of `blocks` the block with the given `id` is read into `buf`. The
+    /// same rules as for [`Backend::read`] apply. Returns the number of bytes
+    /// actually read for each block in the order of `blocks`.
+    ///
+    /// The default implementation calls [`Backend::read`] for each block.
+    ///
+    /// # Errors
+    ///
+    /// On any error a self-defined [`Backend::Err`] is returned.
+    fn read_many(
+        &mut self,
+        blocks: &mut [(&Self::Id, &mut [u8])],
+    ) -> Result<Vec<usize>, Self::Err> {
+        blocks
+            .iter_mut()
+            .map(|(id, buf)| self.read(id, buf))
+            .collect()
+    }
+
+    /// Writes several blocks into the backend.
+    ///
+    /// This is the batch variant of [`Backend::write`]. For each `(id, buf)`
+    /// pair of `blocks` `buf` is written into the block with the given `id`.
+    /// The same rules as for [`Backend::write`] apply. Returns the number of
+    /// bytes actually written for each block in the order of `blocks`.
+    ///
+    /// The default implementation calls [`Backend::write`] for each block.
+    ///
+    /// # Errors
+    ///
+    /// On any error a self-defined [`Backend::Err`] is returned. The
+    /// operation is not atomic, blocks written before the error remain
+    /// written.
+    fn write_many(&mut self, blocks: &[(&Self::Id, &[u8])]) -> Result<Vec<usize>, Self::Err> {
+        blocks.iter().map(|(id, buf)| self.write(id, buf)).collect()
+    }
+
     /// Puts the given `buf` into the header of the backend.
     ///
     /// The container uses this method to ask the backend to put data into the
@@ -288,8 +436,57 @@ where
     /// [`HEADER_MAX_SIZE`] bytes can be stored in the header.
     fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), Self::Err>;
 
+    /// Puts the given `buf` into the extended header block `idx`.
+    ///
+    /// Extended header blocks are used, if the header of the container does
+    /// not fit into [`HEADER_MAX_SIZE`] bytes. `idx` is the number of the
+    /// block, starting at `1`. The container reads the block again with
+    /// [`ReceiveHeader::get_extended_header_bytes()`]. Like the header the
+    /// extended header blocks are not part of the [ids](Backend::ids).
+    ///
+    /// Returns `true`, if the block was written. A backend, which does not
+    /// support extended header blocks, returns `false`. This is the default
+    /// implementation.
+    fn write_extended_header(
+        &mut self,
+        _idx: u32,
+        _buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, Self::Err> {
+        Ok(false)
+    }
+
     /// Deletes the entire instance and all traces.
     ///
     /// The method must not fail!
     fn delete(self);
 }
+
+/// The types of a backend.
+///
+/// The types are shared by the blocking [`Backend`] and the asynchronous
+/// `AsyncBackend`, so code which does not perform any I/O (like the header of
+/// a container) can be used by both. See the [`Backend`] trait for a
+/// description of the types.
+///
+/// A [`Backend`] implements the trait automatically, an `AsyncBackend`, which
+/// is not a [`Backend`] at the same time, needs to implement it.
+pub trait BackendTypes {
+    /// Runtime configuration used by the backend, see [`Backend::Settings`].
+    type Settings: Binary + Clone + Send + Sync;
+
+    /// The error type of the backend, see [`Backend::Err`].
+    type Err: error::Error + Send + Sync;
+
+    /// The id of a block, see [`Backend::Id`].
+    type Id: Binary + Clone + Display + FromStr + IdSize + PartialEq + Send + Sync;
+
+    /// Information of the backend, see [`Backend::Info`].
+    type Info;
+}
+
+impl<B: Backend> BackendTypes for B {
+    type Settings = B::Settings;
+    type Err = B::Err;
+    type Id = B::Id;
+    type Info = B::Info;
+}
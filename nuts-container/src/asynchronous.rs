@@ -0,0 +1,663 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use async_trait::async_trait;
+use log::debug;
+use nuts_backend::{
+    AsyncBackend, AsyncCreate, AsyncOpen, AsyncReceiveHeader, Binary, HEADER_MAX_SIZE,
+};
+use std::{any, mem};
+
+use crate::cipher::CipherContext;
+use crate::error::{ContainerResult, Error};
+use crate::header::{ExtendedBlocks, Header, HeaderError};
+use crate::info::Info;
+use crate::journal::{self, Journal, JournalError};
+use crate::migrate::Migrator;
+use crate::options::{CreateOptions, OpenOptions};
+use crate::password::PasswordStore;
+use crate::service::Service;
+use crate::snapshot::{self, SnapshotError, Snapshots};
+use crate::{block_size, decrypt_block, encrypt_block, net_block_size, open_store, split_header};
+
+/// Factory used to instantiate a [service](Service) on top of an
+/// [`AsyncContainer`].
+///
+/// This is the asynchronous variant of the
+/// [`ServiceFactory`](crate::ServiceFactory).
+#[async_trait]
+pub trait AsyncServiceFactory<B: AsyncBackend> {
+    /// The service
+    type Service: Service<B>;
+
+    /// Error returned by [`Self::open`] and [`Self::create`].
+    ///
+    /// It must be possible to put an [`Error`] instance into this error.
+    type Err: From<Error<B>>;
+
+    /// Create a [service](Service) instance.
+    ///
+    /// Called by [`AsyncContainer::create_service`] the method should create
+    /// a new [`Self::Service`] instance. The created
+    /// [container](AsyncContainer) is passed to this method.
+    async fn create(container: AsyncContainer<B>) -> Result<Self::Service, Self::Err>;
+
+    /// Open a [service](Service).
+    ///
+    /// Called by [`AsyncContainer::open_service`] the method should open an
+    /// existing [`Self::Service`] instance. The opened
+    /// [container](AsyncContainer) is passed to this method.
+    async fn open(container: AsyncContainer<B>) -> Result<Self::Service, Self::Err>;
+}
+
+/// The asynchronous container type.
+///
+/// An `AsyncContainer` is the asynchronous variant of the
+/// [`Container`](crate::Container) running on top of an [`AsyncBackend`].
+/// The header, the encryption of the blocks and the handling of services are
+/// the same, a container created by a [`Container`](crate::Container) can be
+/// opened by an `AsyncContainer` and vice versa.
+///
+/// The `AsyncContainer` covers the basic operations of a container. There is
+/// no block cache, administrative tasks (like
+/// [modifying](crate::Container::modify), [re-keying](crate::Container::rekey)
+/// or [verifying](crate::Container::verify) a container) are only available
+/// for a [`Container`](crate::Container). Use the
+/// [`Blocking`](nuts_backend::Blocking) adapter to run a blocking
+/// [`Backend`](nuts_backend::Backend) in an `AsyncContainer`.
+///
+/// Blocks are read through the
+/// [snapshot table](crate::Container::create_snapshot), but there is no
+/// copy on write. Thus a container with snapshots is opened read-only.
+#[derive(Debug)]
+pub struct AsyncContainer<B: AsyncBackend> {
+    backend: B,
+    store: PasswordStore,
+    header: Header<'static, B>,
+    ctx: CipherContext,
+    extended: ExtendedBlocks,
+    snapshots: Snapshots<B>,
+    view: Option<usize>,
+}
+
+impl<B: AsyncBackend> AsyncContainer<B> {
+    /// Creates a new container.
+    ///
+    /// This is the asynchronous variant of
+    /// [`Container::create`](crate::Container::create). `backend_options`
+    /// is a type that implements the [`AsyncCreate`] trait.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub async fn create<C: AsyncCreate<B>>(
+        backend_options: C,
+        options: CreateOptions,
+    ) -> ContainerResult<AsyncContainer<B>, B> {
+        let settings = backend_options.settings();
+        let mut header = Header::create(&options, settings)?;
+
+        let callback = options.callback.clone();
+        let mut store = PasswordStore::new(callback).with_keyfile(options.keyfile.clone());
+
+        let blocks = split_header(&header.write(&mut store)?);
+        let backend = backend_options
+            .build(blocks[0], options.overwrite)
+            .await
+            .map_err(Error::Backend)?;
+
+        debug!(
+            "Container created, backend: {}, header: {:?}",
+            any::type_name::<B>(),
+            header
+        );
+
+        let ctx = CipherContext::new(header.cipher());
+        let mut container = AsyncContainer {
+            backend,
+            store,
+            header,
+            ctx,
+            extended: ExtendedBlocks::default(),
+            snapshots: Snapshots::new(),
+            view: None,
+        };
+
+        if blocks.len() > 1 {
+            container.write_header_blocks(&blocks).await?;
+        }
+
+        Ok(container)
+    }
+
+    /// Creates a [service](Service) running on top of the given `container`.
+    ///
+    /// This is the asynchronous variant of
+    /// [`Container::create_service`](crate::Container::create_service).
+    pub async fn create_service<F: AsyncServiceFactory<B>>(
+        mut container: AsyncContainer<B>,
+    ) -> Result<F::Service, F::Err> {
+        // ensure that you are on the current revision
+        container
+            .header
+            .latest_revision_or_err()
+            .map_err(Error::<B>::Header)?;
+
+        // ensure that the container does not already have a service
+        container
+            .header
+            .accept_sid_for_create()
+            .map_err(Error::<B>::Header)?;
+
+        container.writable_or_err()?;
+
+        // aquire top-id (if requested)
+        let top_id = if F::Service::need_top_id() {
+            Some(container.aquire().await?)
+        } else {
+            None
+        };
+
+        container
+            .update_header(|header| {
+                header.set_sid(F::Service::sid())?;
+
+                if let Some(id) = top_id {
+                    header.set_top_id(id);
+                }
+
+                Ok(true)
+            })
+            .await?;
+
+        F::create(container).await
+    }
+
+    /// Opens an existing container.
+    ///
+    /// This is the asynchronous variant of
+    /// [`Container::open`](crate::Container::open). `backend_options` is a
+    /// type that implements the [`AsyncOpen`] trait. Like
+    /// [`Container::open`](crate::Container::open) an interrupted
+    /// transaction is recovered and a
+    /// [snapshot](crate::OpenOptionsBuilder::with_snapshot) can be opened.
+    ///
+    /// A container with snapshots is opened read-only: Modifying methods
+    /// return a [`SnapshotError::ReadOnly`] error, use a
+    /// [`Container`](crate::Container) instead.
+    ///
+    /// # Errors
+    ///
+    /// An interrupted transaction of a container with snapshots cannot be
+    /// recovered, a [`SnapshotError::ReadOnly`] error is returned. Open the
+    /// container with a [`Container`](crate::Container) first. Further errors
+    /// are listed in the [`Error`] type.
+    pub async fn open<O: AsyncOpen<B>>(
+        mut backend_options: O,
+        options: OpenOptions,
+    ) -> ContainerResult<AsyncContainer<B>, B> {
+        let mut store = open_store(&options);
+        let migrator = Migrator::default();
+
+        let header_bytes = read_header_bytes(&mut backend_options).await?;
+        let mut header = Header::<B>::read(&header_bytes, migrator, &mut store)?;
+        let settings = header.settings().clone();
+        let backend = backend_options
+            .build(settings)
+            .await
+            .map_err(Error::Backend)?;
+
+        header.migrate()?;
+
+        debug!(
+            "Container opened, backend: {}, header: {:?}",
+            any::type_name::<B>(),
+            header
+        );
+
+        let ctx = CipherContext::new(header.cipher());
+        let mut container = AsyncContainer {
+            backend,
+            store,
+            header,
+            ctx,
+            extended: ExtendedBlocks::peek(&header_bytes)?,
+            snapshots: Snapshots::new(),
+            view: None,
+        };
+
+        container.load_snapshots().await?;
+
+        match options.snapshot.as_ref() {
+            Some(name) => container.view = Some(container.snapshots.position(name)?),
+            None => container.recover().await?,
+        }
+
+        Ok(container)
+    }
+
+    /// Opens a [service](Service) running on top of an existing container.
+    ///
+    /// This is the asynchronous variant of
+    /// [`Container::open_service`](crate::Container::open_service).
+    pub async fn open_service<F: AsyncServiceFactory<B>>(
+        mut container: AsyncContainer<B>,
+        migrate: bool,
+    ) -> Result<F::Service, F::Err> {
+        let migration = F::Service::migration();
+        let migrator = Migrator::default().with_migration(migration);
+
+        container.header.set_migrator(migrator);
+        container.header.migrate().map_err(Error::Header)?;
+        container
+            .header
+            .accept_sid_for_open(F::Service::sid())
+            .map_err(Error::Header)?;
+
+        if migrate {
+            container
+                .update_header(|header| {
+                    let changed = header.convert_to_rev2(F::Service::sid());
+                    Ok(changed)
+                })
+                .await?;
+        }
+
+        F::open(container).await
+    }
+
+    /// Returns the backend of this container.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Consumes this container, returning the inner backend.
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+
+    /// Returns information from the container.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub async fn info(&self) -> ContainerResult<Info<B>, B> {
+        let backend = self.backend.info().await.map_err(Error::Backend)?;
+
+        Ok(Info {
+            backend,
+            revision: self.header.revision(),
+            cipher: self.header.cipher(),
+            compression: self.header.compression(),
+            kdf: self.header.kdf().clone(),
+            slots: self.header.slots(),
+            active_slot: self.header.active_slot(),
+            bsize_gross: self.backend.block_size(),
+            bsize_net: self.block_size(),
+        })
+    }
+
+    /// Returns the _top-id_ of the container.
+    ///
+    /// See [`Container::top_id`](crate::Container::top_id) for details.
+    pub fn top_id(&self) -> Option<&B::Id> {
+        self.header.top_id()
+    }
+
+    /// The (net) block size specifies the number of userdata bytes you can
+    /// store in a block.
+    ///
+    /// See [`Container::block_size`](crate::Container::block_size) for
+    /// details.
+    pub fn block_size(&self) -> u32 {
+        block_size(&self.header, self.backend.block_size())
+    }
+
+    /// Aquires a new block in the container.
+    ///
+    /// See [`Container::aquire`](crate::Container::aquire) for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub async fn aquire(&mut self) -> ContainerResult<B::Id, B> {
+        self.writable_or_err()?;
+
+        let cipher = self.header.cipher();
+        let net_size = net_block_size(
+            self.backend.block_size(),
+            cipher,
+            self.header.iv().is_some(),
+        );
+
+        self.ctx.set_cipher(cipher);
+        self.ctx.copy_from_slice(net_size as usize, &[]);
+
+        let key = self.header.key();
+        let ctext = match self.header.iv() {
+            Some(iv) => self.ctx.encrypt(key, iv)?,
+            None => self.ctx.encrypt_with_nonce(&[], key)?,
+        };
+
+        let id = self.backend.aquire(ctext).await.map_err(Error::Backend)?;
+
+        if self.header.binds_blocks() {
+            // The block is bound to its id, which is not known before the
+            // block was aquired. Write it again, now with the final id.
+            self.store_block(&id, &[]).await?;
+        }
+
+        Ok(id)
+    }
+
+    /// Releases a block again.
+    ///
+    /// See [`Container::release`](crate::Container::release) for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub async fn release(&mut self, id: B::Id) -> ContainerResult<(), B> {
+        self.writable_or_err()?;
+        self.release_block(id).await
+    }
+
+    async fn release_block(&mut self, id: B::Id) -> ContainerResult<(), B> {
+        self.backend.release(id).await.map_err(Error::Backend)
+    }
+
+    /// Reads a block from the container.
+    ///
+    /// See [`Container::read`](crate::Container::read) for details. The
+    /// block is resolved through the snapshot table.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub async fn read(&mut self, id: &B::Id, buf: &mut [u8]) -> ContainerResult<usize, B> {
+        let target = self.snapshots.resolve(self.view, id)?;
+
+        self.read_block(&target, buf).await
+    }
+
+    /// Reads and decrypts the block `id` of the backend, bypassing the
+    /// snapshot table.
+    async fn read_block(&mut self, id: &B::Id, buf: &mut [u8]) -> ContainerResult<usize, B> {
+        let ctext = self.ctx.inp_mut(self.backend.block_size() as usize);
+        let n = self.backend.read(id, ctext).await.map_err(Error::Backend)?;
+        self.ctx.truncate_inp(n);
+
+        let bsize = self.block_size() as usize;
+
+        decrypt_block(&self.header, &mut self.ctx, bsize, id, buf)
+    }
+
+    /// Writes a block into the container.
+    ///
+    /// See [`Container::write`](crate::Container::write) for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub async fn write(&mut self, id: &B::Id, buf: &[u8]) -> ContainerResult<usize, B> {
+        self.writable_or_err()?;
+        self.store_block(id, buf).await
+    }
+
+    /// Encrypts `buf` and writes it into the block `id` of the backend.
+    async fn store_block(&mut self, id: &B::Id, buf: &[u8]) -> ContainerResult<usize, B> {
+        let gross = self.backend.block_size();
+        let variable = self.backend.variable_block_size();
+        let (len, ctext) = encrypt_block(&self.header, &mut self.ctx, gross, variable, id, buf)?;
+
+        self.backend
+            .write(id, ctext)
+            .await
+            .map_err(Error::Backend)
+            .map(|_| len)
+    }
+
+    /// Tests whether the container can be modified.
+    ///
+    /// Neither a snapshot nor a container with snapshots can be modified,
+    /// there is no copy on write.
+    fn writable_or_err(&self) -> ContainerResult<(), B> {
+        if self.view.is_some() || !self.snapshots.is_empty() {
+            Err(SnapshotError::ReadOnly.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Loads the snapshot table referenced by the header.
+    ///
+    /// See `Container::load_snapshots` for details.
+    async fn load_snapshots(&mut self) -> ContainerResult<(), B> {
+        let first = match self.header.snapshots() {
+            Some(id) => <B::Id as Binary>::from_bytes(id).ok_or(SnapshotError::InvalidId)?,
+            None => return Ok(()),
+        };
+
+        let mut buf = vec![0; self.block_size() as usize];
+        let mut ids = vec![];
+        let mut table = vec![];
+        let mut next = Some(first);
+
+        while let Some(id) = next {
+            self.read_block(&id, &mut buf).await?;
+
+            let (chunk, id_next) = snapshot::decode_index::<B>(&buf)?;
+
+            table.extend_from_slice(&chunk);
+            ids.push(id);
+            next = id_next;
+        }
+
+        self.snapshots = Snapshots::decode(&table)?;
+        self.snapshots.set_index(ids);
+
+        debug!("snapshots loaded: {:?}", self.snapshots);
+
+        Ok(())
+    }
+
+    /// Tests whether the block `id` is still aquired.
+    ///
+    /// See `Container::block_exists` for details.
+    async fn block_exists(&mut self, ids: Option<&[B::Id]>, id: &B::Id) -> bool {
+        match ids {
+            Some(ids) => ids.contains(id),
+            None => {
+                let mut buf = vec![0; self.backend.block_size() as usize];
+                self.backend.read(id, &mut buf).await.is_ok()
+            }
+        }
+    }
+
+    /// Replays or discards the journal of an interrupted transaction.
+    ///
+    /// See `Container::recover` for details.
+    async fn recover(&mut self) -> ContainerResult<(), B> {
+        let (first, committed) = match self.header.journal() {
+            Some((id, committed)) => (id.to_vec(), committed),
+            None => return Ok(()),
+        };
+
+        // The journal cannot be replayed without copy on write.
+        self.writable_or_err()?;
+
+        let first = <B::Id as Binary>::from_bytes(&first).ok_or(JournalError::InvalidId)?;
+        let journal = self.read_journal(first).await?;
+
+        if committed {
+            debug!("replay journal, {} blocks", journal.entries.len());
+
+            let ids = self.backend.ids().await.map_err(Error::Backend)?;
+            let mut buf = vec![0; self.block_size() as usize];
+
+            for entry in journal.entries.iter() {
+                match entry.source.as_ref() {
+                    Some(source) => {
+                        self.read_block(source, &mut buf).await?;
+                        self.store_block(&entry.target, &buf).await?;
+                    }
+                    // the block was already released before the interruption
+                    None if !self.block_exists(ids.as_deref(), &entry.target).await => {}
+                    None => self.release_block(entry.target.clone()).await?,
+                }
+            }
+        } else {
+            debug!("discard journal, {} blocks", journal.entries.len());
+        }
+
+        self.update_header(|header| Ok(header.clear_journal()))
+            .await?;
+
+        for id in journal.into_ids() {
+            self.release_block(id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the journal, which starts at the index block `id`.
+    ///
+    /// See `Container::read_journal` for details.
+    async fn read_journal(&mut self, id: B::Id) -> ContainerResult<Journal<B>, B> {
+        let mut buf = vec![0; self.block_size() as usize];
+        let mut ids = vec![];
+        let mut index = vec![];
+        let mut next = Some(id);
+
+        while let Some(id) = next {
+            self.read_block(&id, &mut buf).await?;
+
+            let (chunk, id_next) = journal::decode_index::<B>(&buf)?;
+
+            index.extend_from_slice(&chunk);
+            ids.push(id);
+            next = id_next;
+        }
+
+        Ok(Journal::new(ids, journal::decode_entries(&index)?))
+    }
+
+    /// Writes the header `blocks` into the backend.
+    ///
+    /// See `Container::write_header_blocks` for details.
+    async fn write_header_blocks(
+        &mut self,
+        blocks: &[[u8; HEADER_MAX_SIZE]],
+    ) -> ContainerResult<(), B> {
+        let extended = ExtendedBlocks::peek(&blocks[0])?;
+
+        for (idx, block) in extended.indices().zip(&blocks[1..]) {
+            let supported = self
+                .backend
+                .write_extended_header(idx, block)
+                .await
+                .map_err(Error::Backend)?;
+
+            if !supported {
+                return Err(HeaderError::ExtendedHeaderUnsupported.into());
+            }
+        }
+
+        self.backend
+            .write_header(&blocks[0])
+            .await
+            .map_err(Error::Backend)?;
+
+        let replaced = mem::replace(&mut self.extended, extended);
+
+        for idx in replaced.indices() {
+            self.backend
+                .write_extended_header(idx, &[0; HEADER_MAX_SIZE])
+                .await
+                .map_err(Error::Backend)?;
+        }
+
+        Ok(())
+    }
+
+    async fn update_header<F: FnOnce(&mut Header<B>) -> Result<bool, HeaderError>>(
+        &mut self,
+        f: F,
+    ) -> ContainerResult<(), B> {
+        debug!("header before update: {:?}", self.header);
+
+        let changed = f(&mut self.header)?;
+
+        debug!(
+            "header after update: {:?}, changed: {}",
+            self.header, changed
+        );
+
+        if changed {
+            self.header.set_generation(self.extended.next_generation());
+
+            let blocks = split_header(&self.header.write(&mut self.store)?);
+            self.write_header_blocks(&blocks).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the entire container and all traces.
+    ///
+    /// The method must not fail!
+    pub async fn delete(self) {
+        self.backend.delete().await
+    }
+}
+
+/// Reads the root header followed by its extended header blocks.
+async fn read_header_bytes<B: AsyncBackend, H: AsyncReceiveHeader<B>>(
+    reader: &mut H,
+) -> ContainerResult<Vec<u8>, B> {
+    let mut buf = [0; HEADER_MAX_SIZE];
+
+    reader
+        .get_header_bytes(&mut buf)
+        .await
+        .map_err(Error::Backend)?;
+
+    let extended = ExtendedBlocks::peek(&buf)?;
+    let mut header_bytes = buf.to_vec();
+
+    for idx in extended.indices() {
+        let supported = reader
+            .get_extended_header_bytes(idx, &mut buf)
+            .await
+            .map_err(Error::Backend)?;
+
+        if !supported {
+            return Err(HeaderError::ExtendedHeaderUnsupported.into());
+        }
+
+        header_bytes.extend_from_slice(&buf);
+    }
+
+    debug!("got {} header bytes", header_bytes.len());
+
+    Ok(header_bytes)
+}
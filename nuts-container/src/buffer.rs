@@ -27,6 +27,8 @@ use std::convert::TryInto;
 use std::{cmp, mem};
 use thiserror::Error;
 
+use crate::svec::SecureVec;
+
 /// Errors while (de-) serializing binary data.
 #[derive(Debug, Error)]
 pub enum BufferError {
@@ -78,7 +80,8 @@ pub trait Buffer: Sized {
     get_func!(get_u32, u32);
     get_func!(get_u64, u64);
 
-    fn get_vec<const L: usize>(&mut self) -> Result<Vec<u8>, BufferError> {
+    /// Reads the length of a vec, which is encoded in `L` bytes.
+    fn get_vec_len<const L: usize>(&mut self) -> Result<usize, BufferError> {
         let mut len = 0usize;
 
         for n in self.get_array::<L>()? {
@@ -89,6 +92,12 @@ pub trait Buffer: Sized {
             }
         }
 
+        Ok(len)
+    }
+
+    fn get_vec<const L: usize>(&mut self) -> Result<Vec<u8>, BufferError> {
+        let len = self.get_vec_len::<L>()?;
+
         self.get_chunk(len).map(|buf| buf.to_vec())
     }
 }
@@ -154,6 +163,13 @@ impl BufferMut for Vec<u8> {
     }
 }
 
+impl BufferMut for SecureVec {
+    fn put_chunk(&mut self, buf: &[u8]) -> Result<(), BufferError> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
 impl BufferMut for &mut [u8] {
     fn put_chunk(&mut self, buf: &[u8]) -> Result<(), BufferError> {
         if self.len() >= buf.len() {
@@ -0,0 +1,220 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use log::error;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::svec::SecureVec;
+
+struct Entry<Id> {
+    id: Id,
+    data: SecureVec,
+    dirty: bool,
+    tick: u64,
+}
+
+/// A bounded LRU cache of decrypted blocks.
+///
+/// Blocks are identified by the binary representation of their ids. The
+/// plaintext of a block is stored in a [`SecureVec`], thus it is wiped, when
+/// the block is evicted.
+///
+/// A _dirty_ block was written into the cache only (write-back), it still
+/// needs to be written into the backend. Dirty blocks, which are discarded
+/// (by [`BlockCache::clear`] or when the cache is dropped), are logged.
+pub struct BlockCache<Id> {
+    capacity: usize,
+    write_back: bool,
+    tick: u64,
+    entries: HashMap<Vec<u8>, Entry<Id>>,
+    lru: BTreeMap<u64, Vec<u8>>,
+}
+
+impl<Id: Clone> BlockCache<Id> {
+    /// Creates a cache, which holds up to `capacity` blocks.
+    ///
+    /// A `capacity` of `0` disables the cache.
+    pub fn new(capacity: usize, write_back: bool) -> BlockCache<Id> {
+        BlockCache {
+            capacity,
+            write_back,
+            tick: 0,
+            entries: HashMap::new(),
+            lru: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub fn is_write_back(&self) -> bool {
+        self.is_enabled() && self.write_back
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the plaintext of the block `key`.
+    ///
+    /// The block becomes the most recently used block.
+    pub fn get(&mut self, key: &[u8]) -> Option<&[u8]> {
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(key)?;
+
+        self.lru.remove(&entry.tick);
+        self.lru.insert(tick, key.to_vec());
+        entry.tick = tick;
+
+        Some(&entry.data)
+    }
+
+    /// Inserts the plaintext `data` of the block `id` into the cache.
+    ///
+    /// If the cache is full, the least recently used block is evicted. An
+    /// evicted dirty block is returned, it must be written into the backend
+    /// by the caller.
+    pub fn insert(
+        &mut self,
+        key: Vec<u8>,
+        id: Id,
+        data: SecureVec,
+        dirty: bool,
+    ) -> Option<(Id, SecureVec)> {
+        debug_assert!(self.is_enabled());
+
+        let tick = self.next_tick();
+
+        if let Some(entry) = self.entries.remove(&key) {
+            self.lru.remove(&entry.tick);
+        }
+
+        self.lru.insert(tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                id,
+                data,
+                dirty,
+                tick,
+            },
+        );
+
+        if self.entries.len() > self.capacity {
+            self.evict()
+        } else {
+            None
+        }
+    }
+
+    /// Removes the block `key` from the cache.
+    ///
+    /// A dirty block is discarded.
+    pub fn remove(&mut self, key: &[u8]) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.lru.remove(&entry.tick);
+        }
+    }
+
+    /// Returns all dirty blocks, the least recently used block first.
+    pub fn dirty(&self) -> Vec<(Vec<u8>, Id, SecureVec)> {
+        self.lru
+            .values()
+            .filter_map(|key| {
+                self.entries
+                    .get(key)
+                    .filter(|entry| entry.dirty)
+                    .map(|entry| (key.clone(), entry.id.clone(), entry.data.clone()))
+            })
+            .collect()
+    }
+
+    /// Marks the block `key` as clean, it was written into the backend.
+    pub fn set_clean(&mut self, key: &[u8]) {
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.dirty = false;
+        }
+    }
+
+    /// Removes all blocks from the cache.
+    ///
+    /// Dirty blocks are discarded.
+    pub fn clear(&mut self) {
+        self.error_dirty();
+        self.entries.clear();
+        self.lru.clear();
+    }
+
+    fn evict(&mut self) -> Option<(Id, SecureVec)> {
+        let tick = *self.lru.keys().next()?;
+        let key = self.lru.remove(&tick)?;
+        let entry = self.entries.remove(&key)?;
+
+        if entry.dirty {
+            Some((entry.id, entry.data))
+        } else {
+            None
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+impl<Id> BlockCache<Id> {
+    /// Returns the number of dirty blocks.
+    pub fn num_dirty(&self) -> usize {
+        self.entries.values().filter(|entry| entry.dirty).count()
+    }
+
+    fn error_dirty(&self) {
+        let n = self.num_dirty();
+
+        if n > 0 {
+            error!("{} dirty blocks of the write-back cache are discarded", n);
+        }
+    }
+}
+
+impl<Id> Drop for BlockCache<Id> {
+    fn drop(&mut self) {
+        self.error_dirty();
+    }
+}
+
+impl<Id> fmt::Debug for BlockCache<Id> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BlockCache")
+            .field("capacity", &self.capacity)
+            .field("write_back", &self.write_back)
+            .field("len", &self.entries.len())
+            .finish()
+    }
+}
@@ -0,0 +1,161 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::cache::BlockCache;
+use crate::svec::SecureVec;
+
+fn data(n: u8) -> SecureVec {
+    vec![n; 3].into()
+}
+
+fn insert(cache: &mut BlockCache<u8>, n: u8, dirty: bool) -> Option<(u8, SecureVec)> {
+    cache.insert(vec![n], n, data(n), dirty)
+}
+
+#[test]
+fn disabled() {
+    let cache = BlockCache::<u8>::new(0, true);
+
+    assert!(!cache.is_enabled());
+    assert!(!cache.is_write_back());
+}
+
+#[test]
+fn enabled() {
+    let cache = BlockCache::<u8>::new(1, false);
+
+    assert!(cache.is_enabled());
+    assert!(!cache.is_write_back());
+}
+
+#[test]
+fn write_back() {
+    let cache = BlockCache::<u8>::new(1, true);
+
+    assert!(cache.is_enabled());
+    assert!(cache.is_write_back());
+}
+
+#[test]
+fn get() {
+    let mut cache = BlockCache::new(2, false);
+
+    assert_eq!(insert(&mut cache, 1, false), None);
+    assert_eq!(cache.get(&[1]).unwrap(), [1; 3]);
+    assert_eq!(cache.get(&[2]), None);
+}
+
+#[test]
+fn replace() {
+    let mut cache = BlockCache::new(2, false);
+
+    assert_eq!(insert(&mut cache, 1, false), None);
+    assert_eq!(cache.insert(vec![1], 1, data(2), false), None);
+
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&[1]).unwrap(), [2; 3]);
+}
+
+#[test]
+fn evict_lru() {
+    let mut cache = BlockCache::new(2, false);
+
+    assert_eq!(insert(&mut cache, 1, false), None);
+    assert_eq!(insert(&mut cache, 2, false), None);
+
+    // 1 becomes the most recently used block
+    cache.get(&[1]).unwrap();
+
+    assert_eq!(insert(&mut cache, 3, false), None);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&[1]).unwrap(), [1; 3]);
+    assert_eq!(cache.get(&[2]), None);
+    assert_eq!(cache.get(&[3]).unwrap(), [3; 3]);
+}
+
+#[test]
+fn evict_dirty() {
+    let mut cache = BlockCache::new(1, true);
+
+    assert_eq!(insert(&mut cache, 1, true), None);
+    assert_eq!(insert(&mut cache, 2, false), Some((1, data(1))));
+    assert_eq!(insert(&mut cache, 3, false), None);
+}
+
+#[test]
+fn remove() {
+    let mut cache = BlockCache::new(2, true);
+
+    assert_eq!(insert(&mut cache, 1, true), None);
+    cache.remove(&[1]);
+    cache.remove(&[2]);
+
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.dirty(), []);
+}
+
+#[test]
+fn dirty() {
+    let mut cache = BlockCache::new(3, true);
+
+    assert_eq!(insert(&mut cache, 1, true), None);
+    assert_eq!(insert(&mut cache, 2, false), None);
+    assert_eq!(insert(&mut cache, 3, true), None);
+
+    cache.get(&[1]).unwrap();
+
+    assert_eq!(
+        cache.dirty(),
+        [(vec![3], 3, data(3)), (vec![1], 1, data(1))]
+    );
+
+    cache.set_clean(&[3]);
+
+    assert_eq!(cache.dirty(), [(vec![1], 1, data(1))]);
+}
+
+#[test]
+fn num_dirty() {
+    let mut cache = BlockCache::new(3, true);
+
+    assert_eq!(cache.num_dirty(), 0);
+
+    assert_eq!(insert(&mut cache, 1, true), None);
+    assert_eq!(insert(&mut cache, 2, false), None);
+    assert_eq!(cache.num_dirty(), 1);
+
+    cache.set_clean(&[1]);
+    assert_eq!(cache.num_dirty(), 0);
+}
+
+#[test]
+fn clear() {
+    let mut cache = BlockCache::new(2, true);
+
+    assert_eq!(insert(&mut cache, 1, true), None);
+    assert_eq!(insert(&mut cache, 2, false), None);
+    cache.clear();
+
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.get(&[1]), None);
+    assert_eq!(cache.dirty(), []);
+}
@@ -22,15 +22,18 @@
 
 #[cfg(test)]
 mod tests;
+mod xchacha;
 
 use openssl::cipher as ossl_cipher;
 use openssl::cipher_ctx::CipherCtx;
 use openssl::error::ErrorStack;
+use std::ops::Deref;
 use std::str::FromStr;
 use std::{cmp, fmt};
 use thiserror::Error;
 
 use crate::buffer::{Buffer, BufferError, BufferMut};
+use crate::ossl;
 use crate::svec::SecureVec;
 
 /// [`Cipher`] related error codes.
@@ -84,30 +87,54 @@ pub enum Cipher {
 
     /// AES with a 256-bit key in GCM mode
     Aes256Gcm,
+
+    /// ChaCha20-Poly1305 with a 96-bit nonce
+    ChaCha20Poly1305,
+
+    /// XChaCha20-Poly1305 with an extended 192-bit nonce
+    XChaCha20Poly1305,
+
+    /// AES with a 128-bit key in GCM-SIV mode (nonce-misuse-resistant)
+    Aes128GcmSiv,
+
+    /// AES with a 256-bit key in GCM-SIV mode (nonce-misuse-resistant)
+    Aes256GcmSiv,
 }
 
 impl Cipher {
     /// Returns the block size of the cipher.
     pub fn block_size(&self) -> usize {
-        match self.to_openssl() {
-            None => 1,
-            Some(c) => c.block_size(),
+        match self {
+            Cipher::Aes128GcmSiv | Cipher::Aes256GcmSiv => 1,
+            _ => match self.to_openssl() {
+                None => 1,
+                Some(c) => c.block_size(),
+            },
         }
     }
 
     /// Returns the key size of the cipher.
     pub fn key_len(&self) -> usize {
-        match self.to_openssl() {
-            None => 0,
-            Some(c) => c.key_length(),
+        match self {
+            Cipher::Aes128GcmSiv => 16,
+            Cipher::Aes256GcmSiv => 32,
+            _ => match self.to_openssl() {
+                None => 0,
+                Some(c) => c.key_length(),
+            },
         }
     }
 
     /// Returns the IV size of the cipher.
     pub fn iv_len(&self) -> usize {
-        match self.to_openssl() {
-            None => 0,
-            Some(c) => c.iv_length(),
+        match self {
+            // the extended nonce is not known by OpenSSL
+            Cipher::XChaCha20Poly1305 => 24,
+            Cipher::Aes128GcmSiv | Cipher::Aes256GcmSiv => 12,
+            _ => match self.to_openssl() {
+                None => 0,
+                Some(c) => c.iv_length(),
+            },
         }
     }
 
@@ -126,6 +153,8 @@ impl Cipher {
             Cipher::None => 0,
             Cipher::Aes128Ctr | Cipher::Aes192Ctr | Cipher::Aes256Ctr => 0,
             Cipher::Aes128Gcm | Cipher::Aes192Gcm | Cipher::Aes256Gcm => 16,
+            Cipher::ChaCha20Poly1305 | Cipher::XChaCha20Poly1305 => 16,
+            Cipher::Aes128GcmSiv | Cipher::Aes256GcmSiv => 16,
         }
     }
 
@@ -140,6 +169,10 @@ impl Cipher {
             4 => Ok(Cipher::Aes256Ctr),
             5 => Ok(Cipher::Aes192Gcm),
             6 => Ok(Cipher::Aes256Gcm),
+            7 => Ok(Cipher::ChaCha20Poly1305),
+            8 => Ok(Cipher::XChaCha20Poly1305),
+            9 => Ok(Cipher::Aes128GcmSiv),
+            10 => Ok(Cipher::Aes256GcmSiv),
             _ => Err(BufferError::InvalidIndex("Cipher".to_string(), b)),
         }
     }
@@ -153,6 +186,10 @@ impl Cipher {
             Cipher::Aes256Ctr => 4,
             Cipher::Aes192Gcm => 5,
             Cipher::Aes256Gcm => 6,
+            Cipher::ChaCha20Poly1305 => 7,
+            Cipher::XChaCha20Poly1305 => 8,
+            Cipher::Aes128GcmSiv => 9,
+            Cipher::Aes256GcmSiv => 10,
         };
 
         buf.put_u32(b)
@@ -160,6 +197,8 @@ impl Cipher {
 
     fn to_openssl(self) -> Option<&'static ossl_cipher::CipherRef> {
         match self {
+            // no static cipher available, see Cipher::fetch_openssl()
+            Cipher::Aes128GcmSiv | Cipher::Aes256GcmSiv => None,
             Cipher::None => None,
             Cipher::Aes128Ctr => Some(ossl_cipher::Cipher::aes_128_ctr()),
             Cipher::Aes192Ctr => Some(ossl_cipher::Cipher::aes_192_ctr()),
@@ -167,6 +206,36 @@ impl Cipher {
             Cipher::Aes128Gcm => Some(ossl_cipher::Cipher::aes_128_gcm()),
             Cipher::Aes192Gcm => Some(ossl_cipher::Cipher::aes_192_gcm()),
             Cipher::Aes256Gcm => Some(ossl_cipher::Cipher::aes_256_gcm()),
+            Cipher::ChaCha20Poly1305 | Cipher::XChaCha20Poly1305 => {
+                Some(ossl_cipher::Cipher::chacha20_poly1305())
+            }
+        }
+    }
+
+    fn fetch_openssl(self) -> Result<Option<OpensslCipher>, ErrorStack> {
+        let name = match self {
+            Cipher::Aes128GcmSiv => "AES-128-GCM-SIV",
+            Cipher::Aes256GcmSiv => "AES-256-GCM-SIV",
+            _ => return Ok(self.to_openssl().map(OpensslCipher::Static)),
+        };
+
+        ossl_cipher::Cipher::fetch(None, name, None).map(|c| Some(OpensslCipher::Fetched(c)))
+    }
+}
+
+/// An OpenSSL cipher, either a static one or one fetched from a provider.
+enum OpensslCipher {
+    Static(&'static ossl_cipher::CipherRef),
+    Fetched(ossl_cipher::Cipher),
+}
+
+impl Deref for OpensslCipher {
+    type Target = ossl_cipher::CipherRef;
+
+    fn deref(&self) -> &ossl_cipher::CipherRef {
+        match self {
+            OpensslCipher::Static(c) => c,
+            OpensslCipher::Fetched(c) => c,
         }
     }
 }
@@ -181,6 +250,10 @@ impl fmt::Display for Cipher {
             Cipher::Aes128Gcm => "aes128-gcm",
             Cipher::Aes192Gcm => "aes192-gcm",
             Cipher::Aes256Gcm => "aes256-gcm",
+            Cipher::ChaCha20Poly1305 => "chacha20-poly1305",
+            Cipher::XChaCha20Poly1305 => "xchacha20-poly1305",
+            Cipher::Aes128GcmSiv => "aes128-gcm-siv",
+            Cipher::Aes256GcmSiv => "aes256-gcm-siv",
         };
 
         fmt.write_str(s)
@@ -199,6 +272,10 @@ impl FromStr for Cipher {
             "aes128-gcm" => Ok(Cipher::Aes128Gcm),
             "aes192-gcm" => Ok(Cipher::Aes192Gcm),
             "aes256-gcm" => Ok(Cipher::Aes256Gcm),
+            "chacha20-poly1305" => Ok(Cipher::ChaCha20Poly1305),
+            "xchacha20-poly1305" => Ok(Cipher::XChaCha20Poly1305),
+            "aes128-gcm-siv" => Ok(Cipher::Aes128GcmSiv),
+            "aes256-gcm-siv" => Ok(Cipher::Aes256GcmSiv),
             _ => Err(()),
         }
     }
@@ -212,14 +289,37 @@ pub(super) struct CipherContext {
 }
 
 impl CipherContext {
+    /// Creates a context for `cipher`.
+    ///
+    /// The buffers hold plaintext blocks. They are bulk buffers, thus they
+    /// stay on the heap and are wiped when they are dropped. Use
+    /// [`CipherContext::guarded`] to process key material.
     pub(super) fn new(cipher: Cipher) -> CipherContext {
         CipherContext {
             cipher,
-            inp: vec![].into(),
-            outp: vec![].into(),
+            inp: SecureVec::new(),
+            outp: SecureVec::new(),
+        }
+    }
+
+    /// Creates a context for `cipher`, which processes key material.
+    ///
+    /// The buffers are [guarded](SecureVec::into_guarded), e.g. the header
+    /// key resp. the secret with the master-key are decrypted into locked
+    /// memory.
+    pub(super) fn guarded(cipher: Cipher) -> CipherContext {
+        CipherContext {
+            cipher,
+            inp: SecureVec::new().into_guarded(),
+            outp: SecureVec::new().into_guarded(),
         }
     }
 
+    /// Switches to another cipher, the buffers are kept.
+    pub fn set_cipher(&mut self, cipher: Cipher) {
+        self.cipher = cipher;
+    }
+
     pub fn copy_from_slice(&mut self, buf_size: usize, buf: &[u8]) -> usize {
         let len = cmp::min(buf_size, buf.len());
 
@@ -230,16 +330,62 @@ impl CipherContext {
         len
     }
 
+    pub fn inp(&self) -> &[u8] {
+        &self.inp
+    }
+
     pub fn inp_mut(&mut self, buf_size: usize) -> &mut [u8] {
         self.copy_from_slice(buf_size, &[]); // whiteout
 
         &mut self.inp
     }
 
+    /// Shortens the input buffer to `len` bytes, e.g. to the number of bytes
+    /// read from a backend with a variable block size.
+    pub fn truncate_inp(&mut self, len: usize) {
+        self.inp.truncate(len);
+    }
+
     pub fn encrypt(&mut self, key: &[u8], iv: &[u8]) -> Result<&[u8], CipherError> {
         match self.cipher {
             Cipher::None => self.make_none(),
-            _ => self.encrypt_aad(None, key, iv).map(|_| ())?,
+            _ => self.encrypt_aad(None, key, iv, false).map(|_| ())?,
+        };
+
+        Ok(self.outp.as_slice())
+    }
+
+    /// Encrypts the input buffer.
+    ///
+    /// For AE-ciphers `aad` is authenticated additionally.
+    pub fn encrypt_with_aad(
+        &mut self,
+        aad: &[u8],
+        key: &[u8],
+        iv: &[u8],
+    ) -> Result<&[u8], CipherError> {
+        match self.cipher {
+            Cipher::None => self.make_none(),
+            _ => self.encrypt_aad(Some(aad), key, iv, false).map(|_| ())?,
+        };
+
+        Ok(self.outp.as_slice())
+    }
+
+    /// Encrypts the input buffer with a fresh random nonce.
+    ///
+    /// The nonce is placed in front of the ciphertext, thus the output
+    /// buffer has the layout `nonce || ciphertext || tag`. For AE-ciphers
+    /// `aad` is authenticated additionally.
+    pub fn encrypt_with_nonce(&mut self, aad: &[u8], key: &[u8]) -> Result<&[u8], CipherError> {
+        match self.cipher {
+            Cipher::None => self.make_none(),
+            _ => {
+                let mut nonce = vec![0; self.cipher.iv_len()];
+                ossl::rand_bytes(&mut nonce)?;
+
+                self.encrypt_aad(Some(aad), key, &nonce, true).map(|_| ())?
+            }
         };
 
         Ok(self.outp.as_slice())
@@ -250,6 +396,7 @@ impl CipherContext {
         aad: Option<&[u8]>,
         key: &[u8],
         iv: &[u8],
+        iv_prefix: bool,
     ) -> Result<usize, CipherError> {
         let key = key
             .get(..self.cipher.key_len())
@@ -275,22 +422,33 @@ impl CipherContext {
             return Err(CipherError::InvalidBlockSize);
         }
 
+        // number of leading bytes reserved for the iv
+        let offs = if iv_prefix { iv.len() } else { 0 };
+
         let mut ctx = CipherCtx::new()?;
 
-        ctx.encrypt_init(self.cipher.to_openssl(), Some(key), Some(iv))?;
+        let ossl_cipher = self.cipher.fetch_openssl()?;
+        let (ossl_key, ossl_iv) = self.openssl_key_iv(key, iv);
+
+        ctx.encrypt_init(ossl_cipher.as_deref(), Some(&ossl_key), Some(&ossl_iv))?;
         ctx.set_padding(false);
 
-        if let Some(buf) = aad {
+        // only AE-ciphers are able to authenticate additional data
+        if let Some(buf) = aad.filter(|_| self.cipher.tag_size() > 0) {
             ctx.cipher_update(buf, None)?;
         }
 
         self.outp
-            .resize(ctext_len + self.cipher.tag_size() as usize, 0);
-        ctx.cipher_update(&self.inp[..ptext_len], Some(&mut self.outp[..ctext_len]))?;
+            .resize(offs + ctext_len + self.cipher.tag_size() as usize, 0);
+        self.outp[..offs].copy_from_slice(&iv[..offs]);
+        ctx.cipher_update(
+            &self.inp[..ptext_len],
+            Some(&mut self.outp[offs..offs + ctext_len]),
+        )?;
 
         if self.cipher.tag_size() > 0 {
             ctx.cipher_final(&mut [])?;
-            ctx.tag(&mut self.outp[ctext_len..])?;
+            ctx.tag(&mut self.outp[offs + ctext_len..])?;
         }
 
         Ok(ctext_len)
@@ -299,7 +457,47 @@ impl CipherContext {
     pub fn decrypt(&mut self, key: &[u8], iv: &[u8]) -> Result<&[u8], CipherError> {
         match self.cipher {
             Cipher::None => self.make_none(),
-            _ => self.decrypt_aad(None, key, iv).map(|_| ())?,
+            _ => self.decrypt_aad(None, key, iv, false).map(|_| ())?,
+        }
+
+        Ok(self.outp.as_slice())
+    }
+
+    /// Decrypts the input buffer, which was encrypted with
+    /// [`CipherContext::encrypt_with_aad`].
+    ///
+    /// For AE-ciphers the same `aad` used for encryption must be passed here.
+    pub fn decrypt_with_aad(
+        &mut self,
+        aad: &[u8],
+        key: &[u8],
+        iv: &[u8],
+    ) -> Result<&[u8], CipherError> {
+        match self.cipher {
+            Cipher::None => self.make_none(),
+            _ => self.decrypt_aad(Some(aad), key, iv, false).map(|_| ())?,
+        }
+
+        Ok(self.outp.as_slice())
+    }
+
+    /// Decrypts the input buffer, which was encrypted with
+    /// [`CipherContext::encrypt_with_nonce`].
+    ///
+    /// The nonce is taken from the beginning of the input buffer. For
+    /// AE-ciphers the same `aad` used for encryption must be passed here.
+    pub fn decrypt_with_nonce(&mut self, aad: &[u8], key: &[u8]) -> Result<&[u8], CipherError> {
+        match self.cipher {
+            Cipher::None => self.make_none(),
+            _ => {
+                let nonce = self
+                    .inp
+                    .get(..self.cipher.iv_len())
+                    .ok_or(CipherError::InvalidIv)?
+                    .to_vec();
+
+                self.decrypt_aad(Some(aad), key, &nonce, true).map(|_| ())?
+            }
         }
 
         Ok(self.outp.as_slice())
@@ -310,6 +508,7 @@ impl CipherContext {
         aad: Option<&[u8]>,
         key: &[u8],
         iv: &[u8],
+        iv_prefix: bool,
     ) -> Result<usize, CipherError> {
         let key = key
             .get(..self.cipher.key_len())
@@ -318,11 +517,14 @@ impl CipherContext {
             .get(..self.cipher.iv_len())
             .ok_or(CipherError::InvalidIv)?;
 
-        // number of ciphertext bytes: remove tag from the input.
+        // number of leading bytes, where the iv is stored
+        let offs = if iv_prefix { iv.len() } else { 0 };
+
+        // number of ciphertext bytes: remove iv and tag from the input.
         let ctext_bytes = self
             .inp
             .len()
-            .saturating_sub(self.cipher.tag_size() as usize);
+            .saturating_sub(offs + self.cipher.tag_size() as usize);
 
         // number of plaintext bytes: equals to ciphertext bytes (for now) because
         // blocksize is 1 for all ciphers.
@@ -338,21 +540,29 @@ impl CipherContext {
 
         let mut ctx = CipherCtx::new()?;
 
-        ctx.decrypt_init(self.cipher.to_openssl(), Some(key), Some(iv))?;
+        let ossl_cipher = self.cipher.fetch_openssl()?;
+        let (ossl_key, ossl_iv) = self.openssl_key_iv(key, iv);
+
+        ctx.decrypt_init(ossl_cipher.as_deref(), Some(&ossl_key), Some(&ossl_iv))?;
         ctx.set_padding(false);
 
-        if let Some(buf) = aad {
+        // GCM-SIV needs the tag before the ciphertext is processed
+        if self.cipher.tag_size() > 0 {
+            ctx.set_tag(&self.inp[offs + ctext_bytes..])?;
+        }
+
+        // only AE-ciphers are able to authenticate additional data
+        if let Some(buf) = aad.filter(|_| self.cipher.tag_size() > 0) {
             ctx.cipher_update(buf, None)?;
         }
 
         self.outp.resize(ptext_bytes, 0);
         ctx.cipher_update(
-            &self.inp[..ctext_bytes],
+            &self.inp[offs..offs + ctext_bytes],
             Some(&mut self.outp[..ptext_bytes]),
         )?;
 
         if self.cipher.tag_size() > 0 {
-            ctx.set_tag(&self.inp[ctext_bytes..])?;
             ctx.cipher_final(&mut [])
                 .map_err(|_| CipherError::NotTrustworthy)?;
         }
@@ -360,6 +570,16 @@ impl CipherContext {
         Ok(ctext_bytes)
     }
 
+    fn openssl_key_iv(&self, key: &[u8], iv: &[u8]) -> (SecureVec, Vec<u8>) {
+        match self.cipher {
+            Cipher::XChaCha20Poly1305 => {
+                let (key, iv) = xchacha::derive(key, iv);
+                (key, iv.to_vec())
+            }
+            _ => (key.into(), iv.to_vec()),
+        }
+    }
+
     fn make_none(&mut self) {
         self.outp.clear();
         self.outp.extend_from_slice(&self.inp);
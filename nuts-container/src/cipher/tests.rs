@@ -22,16 +22,22 @@
 
 mod aes128_ctr;
 mod aes128_gcm;
+mod aes128_gcm_siv;
 mod aes192_ctr;
 mod aes192_gcm;
 mod aes256_ctr;
 mod aes256_gcm;
+mod aes256_gcm_siv;
 mod bytes;
+mod chacha20_poly1305;
 mod none;
 mod string;
+mod xchacha20_poly1305;
+
+use crate::cipher::{Cipher, CipherContext};
 
 const KEY: [u8; 32] = [b'x'; 32];
-const IV: [u8; 16] = [b'y'; 16];
+const IV: [u8; 24] = [b'y'; 24];
 
 macro_rules! ctx_test {
     ($name:ident, $cipher:ident . $method:ident, $num:literal, [ $($input:literal),* ] -> [ $($expected:literal),* ]) => {
@@ -57,3 +63,37 @@ macro_rules! ctx_test {
 }
 
 pub(crate) use ctx_test;
+
+/// Runs a known-answer test: `ptext` encrypted with `key`, `nonce` and `aad`
+/// results into `expected`, which decrypts back into `ptext`.
+fn kat(cipher: Cipher, key: &[u8], nonce: &[u8], aad: &[u8], ptext: &[u8], expected: &[u8]) {
+    let mut ctx = CipherContext::new(cipher);
+
+    ctx.copy_from_slice(ptext.len(), ptext);
+    assert_eq!(ctx.encrypt_with_aad(aad, key, nonce).unwrap(), expected);
+
+    ctx.copy_from_slice(expected.len(), expected);
+    assert_eq!(ctx.decrypt_with_aad(aad, key, nonce).unwrap(), ptext);
+}
+
+#[test]
+fn ctx_not_guarded() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Ctr);
+
+    ctx.copy_from_slice(16, &[1; 16]);
+    ctx.encrypt(&KEY[..16], &IV[..16]).unwrap();
+
+    assert!(!ctx.inp.is_guarded());
+    assert!(!ctx.outp.is_guarded());
+}
+
+#[test]
+fn ctx_guarded() {
+    let mut ctx = CipherContext::guarded(Cipher::Aes128Ctr);
+
+    ctx.copy_from_slice(16, &[1; 16]);
+    ctx.encrypt(&KEY[..16], &IV[..16]).unwrap();
+
+    assert!(ctx.inp.is_guarded());
+    assert!(ctx.outp.is_guarded());
+}
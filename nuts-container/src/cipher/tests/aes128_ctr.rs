@@ -109,3 +109,51 @@ ctx_test!(ctx_encrypt_1_2, Aes128Ctr.encrypt, 0, [1] -> []);
 ctx_test!(ctx_encrypt_1_3, Aes128Ctr.encrypt, 2, [1] -> [146, 142]);
 ctx_test!(ctx_encrypt_0_1, Aes128Ctr.encrypt, 0, [] -> []);
 ctx_test!(ctx_encrypt_0_2, Aes128Ctr.encrypt, 1, [] -> [147]);
+
+#[test]
+fn ctx_encrypt_with_nonce() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Ctr);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx.encrypt_with_nonce(&[], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(
+        output,
+        [
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, 250, 213, 47, 48, // nonce
+            82, 246, 253, // ciphertext
+        ]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_nonce() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Ctr);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, 250, 213, 47, 48, // nonce
+            82, 246, 253, // ciphertext
+        ],
+    );
+
+    let output = ctx.decrypt_with_nonce(&[], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(output, [1, 2, 3]);
+}
+
+#[test]
+fn ctx_decrypt_with_nonce_aad_ignored() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Ctr);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, 250, 213, 47, 48, // nonce
+            82, 246, 253, // ciphertext
+        ],
+    );
+
+    let output = ctx.decrypt_with_nonce(&[4, 5, 6], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(output, [1, 2, 3]);
+}
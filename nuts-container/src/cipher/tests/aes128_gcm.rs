@@ -171,3 +171,166 @@ ctx_test!(
 );
 ctx_test!(ctx_encrypt_0_1, Aes128Gcm.encrypt, 0, [] -> []);
 ctx_test!(ctx_encrypt_0_2, Aes128Gcm.encrypt, 1, [] -> [142, 77, 158, 210, 213, 221, 151, 217, 234, 130, 117, 125, 12, 137, 10, 45, 127]);
+
+#[test]
+fn ctx_encrypt_with_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx
+        .encrypt_with_aad(&[4, 5, 6], &KEY[..KEY_LEN], &IV)
+        .unwrap();
+    assert_eq!(
+        output,
+        [143, 103, 80, 88, 212, 55, 3, 16, 150, 253, 180, 172, 181, 124, 203, 136, 130, 93, 206]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            143, 103, 80, 88, 212, 55, 3, 16, 150, 253, 180, 172, 181, 124, 203, 136, 130, 93, 206,
+        ],
+    );
+
+    let output = ctx
+        .decrypt_with_aad(&[4, 5, 6], &KEY[..KEY_LEN], &IV)
+        .unwrap();
+    assert_eq!(output, [1, 2, 3]);
+}
+
+#[test]
+fn ctx_decrypt_with_aad_wrong_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            143, 103, 80, 88, 212, 55, 3, 16, 150, 253, 180, 172, 181, 124, 203, 136, 130, 93, 206,
+        ],
+    );
+
+    let err = ctx
+        .decrypt_with_aad(&[4, 5, 7], &KEY[..KEY_LEN], &IV)
+        .unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+#[test]
+fn ctx_encrypt_with_nonce() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx.encrypt_with_nonce(&[], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(
+        output,
+        [
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, // nonce
+            175, 42, 184, // ciphertext
+            124, 192, 162, 89, 92, 26, 216, 99, 149, 28, 152, 35, 202, 196, 123, 178, // tag
+        ]
+    );
+}
+
+#[test]
+fn ctx_encrypt_with_nonce_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx.encrypt_with_nonce(&[4, 5, 6], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(
+        output,
+        [
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, // nonce
+            175, 42, 184, // ciphertext
+            6, 178, 150, 125, 86, 131, 23, 175, 9, 160, 55, 110, 140, 240, 161, 39, // tag
+        ]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_nonce() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(
+        31,
+        &[
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, // nonce
+            175, 42, 184, // ciphertext
+            124, 192, 162, 89, 92, 26, 216, 99, 149, 28, 152, 35, 202, 196, 123, 178, // tag
+        ],
+    );
+
+    let output = ctx.decrypt_with_nonce(&[], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(output, [1, 2, 3]);
+}
+
+#[test]
+fn ctx_decrypt_with_nonce_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(
+        31,
+        &[
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, // nonce
+            175, 42, 184, // ciphertext
+            6, 178, 150, 125, 86, 131, 23, 175, 9, 160, 55, 110, 140, 240, 161, 39, // tag
+        ],
+    );
+
+    let output = ctx.decrypt_with_nonce(&[4, 5, 6], &KEY[..KEY_LEN]).unwrap();
+    assert_eq!(output, [1, 2, 3]);
+}
+
+#[test]
+fn ctx_decrypt_with_nonce_wrong_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(
+        31,
+        &[
+            145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, // nonce
+            175, 42, 184, // ciphertext
+            6, 178, 150, 125, 86, 131, 23, 175, 9, 160, 55, 110, 140, 240, 161, 39, // tag
+        ],
+    );
+
+    let err = ctx
+        .decrypt_with_nonce(&[4, 5, 7], &KEY[..KEY_LEN])
+        .unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+#[test]
+fn ctx_decrypt_with_nonce_not_trustworthy() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(
+        31,
+        &[
+            146, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196, 72, // nonce
+            175, 42, 184, // ciphertext
+            124, 192, 162, 89, 92, 26, 216, 99, 149, 28, 152, 35, 202, 196, 123, 178, // tag
+        ],
+    );
+
+    let err = ctx.decrypt_with_nonce(&[], &KEY[..KEY_LEN]).unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+#[test]
+fn ctx_decrypt_with_nonce_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::Aes128Gcm);
+
+    ctx.copy_from_slice(11, &[145, 192, 178, 207, 231, 209, 30, 227, 25, 23, 196]);
+
+    let err = ctx.decrypt_with_nonce(&[], &KEY[..KEY_LEN]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
@@ -0,0 +1,261 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::cipher::{Cipher, CipherContext, CipherError};
+
+use super::{ctx_test, kat, IV, KEY};
+
+const KEY_LEN: usize = 16;
+
+#[test]
+fn block_size() {
+    assert_eq!(Cipher::Aes128GcmSiv.block_size(), 1);
+}
+
+#[test]
+fn key_len() {
+    assert_eq!(Cipher::Aes128GcmSiv.key_len(), KEY_LEN);
+}
+
+#[test]
+fn iv_len() {
+    assert_eq!(Cipher::Aes128GcmSiv.iv_len(), 12);
+}
+
+#[test]
+fn tag_size() {
+    assert_eq!(Cipher::Aes128GcmSiv.tag_size(), 16);
+}
+
+#[test]
+fn ctx_decrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            218, 161, 116, 106, 136, 193, 134, 48, 6, 211, 61, 121, 200, 16, 165, 165, 73, 202, 132,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_decrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            218, 161, 116, 106, 136, 193, 134, 48, 6, 211, 61, 121, 200, 16, 165, 165, 73, 202, 132,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV[..11]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+#[test]
+fn ctx_decrypt_not_trustworthy() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            218, 161, 116, 106, 136, 193, 134, 48, 6, 211, 61, 121, 200, 16, 165, 165, 73, 202,
+            b'x',
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+ctx_test!(
+    ctx_decrypt_3, Aes128GcmSiv.decrypt,
+    19, [218, 161, 116, 106, 136, 193, 134, 48, 6, 211, 61, 121, 200, 16, 165, 165, 73, 202, 132] -> [1, 2, 3]
+);
+ctx_test!(
+    ctx_decrypt_2, Aes128GcmSiv.decrypt,
+    18, [106, 11, 127, 118, 169, 118, 100, 175, 64, 165, 249, 49, 228, 204, 152, 254, 215, 91] -> [1, 2]
+);
+ctx_test!(
+    ctx_decrypt_1, Aes128GcmSiv.decrypt,
+    17, [85, 77, 149, 153, 3, 207, 34, 0, 163, 132, 91, 13, 173, 68, 245, 184, 3] -> [1]
+);
+ctx_test!(
+    ctx_decrypt_0_1, Aes128GcmSiv.decrypt,
+    16, [100, 245, 62, 112, 108, 123, 123, 245, 170, 234, 30, 166, 135, 69, 49, 255] -> []
+);
+ctx_test!(
+    ctx_decrypt_0_2, Aes128GcmSiv.decrypt,
+    15, [245, 62, 112, 108, 123, 123, 245, 170, 234, 30, 166, 135, 69, 49, 255] -> []
+);
+ctx_test!(ctx_decrypt_0_3, Aes128GcmSiv.decrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_encrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN], &IV[..11]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+ctx_test!(
+    ctx_encrypt_3_1, Aes128GcmSiv.encrypt,
+    3, [1, 2, 3] -> [218, 161, 116, 106, 136, 193, 134, 48, 6, 211, 61, 121, 200, 16, 165, 165, 73, 202, 132]
+);
+ctx_test!(
+    ctx_encrypt_3_2, Aes128GcmSiv.encrypt,
+    2, [1, 2, 3] -> [106, 11, 127, 118, 169, 118, 100, 175, 64, 165, 249, 49, 228, 204, 152, 254, 215, 91]
+);
+ctx_test!(
+    ctx_encrypt_3_3, Aes128GcmSiv.encrypt,
+    4, [1, 2, 3] -> [190, 95, 170, 238, 184, 247, 74, 98, 153, 134, 20, 229, 46, 19, 185, 60, 73, 62, 201, 179]
+);
+ctx_test!(
+    ctx_encrypt_2_1, Aes128GcmSiv.encrypt,
+    2, [1, 2] -> [106, 11, 127, 118, 169, 118, 100, 175, 64, 165, 249, 49, 228, 204, 152, 254, 215, 91]
+);
+ctx_test!(
+    ctx_encrypt_2_2, Aes128GcmSiv.encrypt,
+    1, [1, 2] -> [85, 77, 149, 153, 3, 207, 34, 0, 163, 132, 91, 13, 173, 68, 245, 184, 3]
+);
+ctx_test!(
+    ctx_encrypt_1_1, Aes128GcmSiv.encrypt,
+    1, [1] -> [85, 77, 149, 153, 3, 207, 34, 0, 163, 132, 91, 13, 173, 68, 245, 184, 3]
+);
+ctx_test!(
+    ctx_encrypt_1_2, Aes128GcmSiv.encrypt,
+    0, [1] -> []
+);
+ctx_test!(ctx_encrypt_0_1, Aes128GcmSiv.encrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_with_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx
+        .encrypt_with_aad(&[4, 5, 6], &KEY[..KEY_LEN], &IV)
+        .unwrap();
+    assert_eq!(
+        output,
+        [183, 145, 176, 93, 67, 83, 76, 129, 209, 181, 244, 2, 140, 189, 240, 155, 131, 140, 165]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_aad_wrong_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes128GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            183, 145, 176, 93, 67, 83, 76, 129, 209, 181, 244, 2, 140, 189, 240, 155, 131, 140, 165,
+        ],
+    );
+
+    let err = ctx
+        .decrypt_with_aad(&[4, 5, 7], &KEY[..KEY_LEN], &IV)
+        .unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+// RFC 8452, Appendix C.1
+const RFC8452_KEY: [u8; 16] = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+const RFC8452_NONCE: [u8; 12] = [3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+#[test]
+fn rfc8452_8() {
+    kat(
+        Cipher::Aes128GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[],
+        &[1, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            181, 216, 57, 51, 10, 199, 183, 134, 87, 135, 130, 255, 246, 1, 59, 129, 91, 40, 124,
+            34, 73, 58, 54, 76,
+        ],
+    );
+}
+
+#[test]
+fn rfc8452_12() {
+    kat(
+        Cipher::Aes128GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[],
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            115, 35, 234, 97, 208, 89, 50, 38, 0, 71, 217, 66, 164, 151, 141, 179, 87, 57, 26, 11,
+            196, 253, 236, 139, 13, 16, 102, 57,
+        ],
+    );
+}
+
+#[test]
+fn rfc8452_16() {
+    kat(
+        Cipher::Aes128GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[],
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            116, 63, 124, 128, 119, 171, 37, 248, 98, 78, 46, 148, 133, 121, 207, 119, 48, 58, 175,
+            144, 246, 254, 33, 25, 156, 96, 104, 87, 116, 55, 160, 196,
+        ],
+    );
+}
+
+#[test]
+fn rfc8452_aad() {
+    kat(
+        Cipher::Aes128GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[1],
+        &[2, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            30, 109, 171, 163, 86, 105, 244, 39, 59, 10, 26, 37, 96, 150, 156, 223, 121, 13, 153,
+            117, 154, 189, 21, 8,
+        ],
+    );
+}
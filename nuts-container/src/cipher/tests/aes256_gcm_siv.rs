@@ -0,0 +1,262 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::cipher::{Cipher, CipherContext, CipherError};
+
+use super::{ctx_test, kat, IV, KEY};
+
+const KEY_LEN: usize = 32;
+
+#[test]
+fn block_size() {
+    assert_eq!(Cipher::Aes256GcmSiv.block_size(), 1);
+}
+
+#[test]
+fn key_len() {
+    assert_eq!(Cipher::Aes256GcmSiv.key_len(), KEY_LEN);
+}
+
+#[test]
+fn iv_len() {
+    assert_eq!(Cipher::Aes256GcmSiv.iv_len(), 12);
+}
+
+#[test]
+fn tag_size() {
+    assert_eq!(Cipher::Aes256GcmSiv.tag_size(), 16);
+}
+
+#[test]
+fn ctx_decrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            210, 180, 188, 67, 202, 18, 97, 39, 145, 188, 221, 11, 48, 24, 41, 204, 234, 253, 36,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_decrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            210, 180, 188, 67, 202, 18, 97, 39, 145, 188, 221, 11, 48, 24, 41, 204, 234, 253, 36,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV[..11]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+#[test]
+fn ctx_decrypt_not_trustworthy() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            210, 180, 188, 67, 202, 18, 97, 39, 145, 188, 221, 11, 48, 24, 41, 204, 234, 253, b'x',
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+ctx_test!(
+    ctx_decrypt_3, Aes256GcmSiv.decrypt,
+    19, [210, 180, 188, 67, 202, 18, 97, 39, 145, 188, 221, 11, 48, 24, 41, 204, 234, 253, 36] -> [1, 2, 3]
+);
+ctx_test!(
+    ctx_decrypt_2, Aes256GcmSiv.decrypt,
+    18, [39, 68, 133, 148, 216, 244, 175, 75, 101, 98, 4, 170, 215, 98, 89, 43, 98, 252] -> [1, 2]
+);
+ctx_test!(
+    ctx_decrypt_1, Aes256GcmSiv.decrypt,
+    17, [86, 168, 65, 238, 213, 133, 209, 69, 124, 208, 35, 52, 62, 48, 47, 13, 157] -> [1]
+);
+ctx_test!(
+    ctx_decrypt_0_1, Aes256GcmSiv.decrypt,
+    16, [214, 212, 64, 46, 11, 55, 15, 56, 93, 144, 192, 36, 191, 20, 191, 133] -> []
+);
+ctx_test!(
+    ctx_decrypt_0_2, Aes256GcmSiv.decrypt,
+    15, [212, 64, 46, 11, 55, 15, 56, 93, 144, 192, 36, 191, 20, 191, 133] -> []
+);
+ctx_test!(ctx_decrypt_0_3, Aes256GcmSiv.decrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_encrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN], &IV[..11]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+ctx_test!(
+    ctx_encrypt_3_1, Aes256GcmSiv.encrypt,
+    3, [1, 2, 3] -> [210, 180, 188, 67, 202, 18, 97, 39, 145, 188, 221, 11, 48, 24, 41, 204, 234, 253, 36]
+);
+ctx_test!(
+    ctx_encrypt_3_2, Aes256GcmSiv.encrypt,
+    2, [1, 2, 3] -> [39, 68, 133, 148, 216, 244, 175, 75, 101, 98, 4, 170, 215, 98, 89, 43, 98, 252]
+);
+ctx_test!(
+    ctx_encrypt_3_3, Aes256GcmSiv.encrypt,
+    4, [1, 2, 3] -> [9, 9, 4, 20, 6, 208, 46, 73, 138, 91, 200, 62, 169, 74, 216, 129, 253, 156, 118, 0]
+);
+ctx_test!(
+    ctx_encrypt_2_1, Aes256GcmSiv.encrypt,
+    2, [1, 2] -> [39, 68, 133, 148, 216, 244, 175, 75, 101, 98, 4, 170, 215, 98, 89, 43, 98, 252]
+);
+ctx_test!(
+    ctx_encrypt_2_2, Aes256GcmSiv.encrypt,
+    1, [1, 2] -> [86, 168, 65, 238, 213, 133, 209, 69, 124, 208, 35, 52, 62, 48, 47, 13, 157]
+);
+ctx_test!(
+    ctx_encrypt_1_1, Aes256GcmSiv.encrypt,
+    1, [1] -> [86, 168, 65, 238, 213, 133, 209, 69, 124, 208, 35, 52, 62, 48, 47, 13, 157]
+);
+ctx_test!(
+    ctx_encrypt_1_2, Aes256GcmSiv.encrypt,
+    0, [1] -> []
+);
+ctx_test!(ctx_encrypt_0_1, Aes256GcmSiv.encrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_with_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx
+        .encrypt_with_aad(&[4, 5, 6], &KEY[..KEY_LEN], &IV)
+        .unwrap();
+    assert_eq!(
+        output,
+        [40, 201, 57, 249, 190, 142, 73, 154, 113, 25, 111, 143, 53, 233, 180, 78, 160, 158, 193]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_aad_wrong_aad() {
+    let mut ctx = CipherContext::new(Cipher::Aes256GcmSiv);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            40, 201, 57, 249, 190, 142, 73, 154, 113, 25, 111, 143, 53, 233, 180, 78, 160, 158, 193,
+        ],
+    );
+
+    let err = ctx
+        .decrypt_with_aad(&[4, 5, 7], &KEY[..KEY_LEN], &IV)
+        .unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+// RFC 8452, Appendix C.2
+const RFC8452_KEY: [u8; 32] = [
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+const RFC8452_NONCE: [u8; 12] = [3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+#[test]
+fn rfc8452_8() {
+    kat(
+        Cipher::Aes256GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[],
+        &[1, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            194, 239, 50, 142, 92, 113, 200, 59, 132, 49, 34, 19, 15, 115, 100, 183, 97, 224, 185,
+            116, 39, 227, 223, 40,
+        ],
+    );
+}
+
+#[test]
+fn rfc8452_12() {
+    kat(
+        Cipher::Aes256GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[],
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            154, 171, 42, 235, 63, 170, 10, 52, 174, 168, 226, 177, 140, 165, 13, 169, 174, 101,
+            89, 228, 143, 209, 15, 110, 92, 156, 161, 126,
+        ],
+    );
+}
+
+#[test]
+fn rfc8452_16() {
+    kat(
+        Cipher::Aes256GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[],
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            133, 160, 27, 99, 2, 91, 161, 155, 127, 211, 221, 252, 3, 59, 62, 118, 201, 234, 198,
+            250, 112, 9, 66, 112, 46, 144, 134, 35, 131, 198, 195, 102,
+        ],
+    );
+}
+
+#[test]
+fn rfc8452_aad() {
+    kat(
+        Cipher::Aes256GcmSiv,
+        &RFC8452_KEY,
+        &RFC8452_NONCE,
+        &[1],
+        &[2, 0, 0, 0, 0, 0, 0, 0],
+        &[
+            29, 226, 41, 103, 35, 122, 129, 50, 145, 33, 63, 38, 126, 59, 69, 47, 2, 208, 26, 227,
+            62, 78, 200, 84,
+        ],
+    );
+}
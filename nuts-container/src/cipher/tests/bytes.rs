@@ -86,6 +86,42 @@ fn de_aes256_gcm() {
     );
 }
 
+#[test]
+fn de_chacha20_poly1305() {
+    let buf = [0x00, 0x00, 0x00, 0x07];
+    assert_eq!(
+        Cipher::get_from_buffer(&mut &buf[..]).unwrap(),
+        Cipher::ChaCha20Poly1305
+    );
+}
+
+#[test]
+fn de_xchacha20_poly1305() {
+    let buf = [0x00, 0x00, 0x00, 0x08];
+    assert_eq!(
+        Cipher::get_from_buffer(&mut &buf[..]).unwrap(),
+        Cipher::XChaCha20Poly1305
+    );
+}
+
+#[test]
+fn de_aes128_gcm_siv() {
+    let buf = [0x00, 0x00, 0x00, 0x09];
+    assert_eq!(
+        Cipher::get_from_buffer(&mut &buf[..]).unwrap(),
+        Cipher::Aes128GcmSiv
+    );
+}
+
+#[test]
+fn de_aes256_gcm_siv() {
+    let buf = [0x00, 0x00, 0x00, 0x0a];
+    assert_eq!(
+        Cipher::get_from_buffer(&mut &buf[..]).unwrap(),
+        Cipher::Aes256GcmSiv
+    );
+}
+
 #[test]
 fn de_eof() {
     let buf = [0x00, 0x00, 0x00];
@@ -96,10 +132,10 @@ fn de_eof() {
 
 #[test]
 fn de_invalid() {
-    let buf = [0x00, 0x00, 0x00, 0x07];
+    let buf = [0x00, 0x00, 0x00, 0x0b];
     let err = Cipher::get_from_buffer(&mut &buf[..]).unwrap_err();
 
-    assert_eq!(err.to_string(), "no Cipher at 7");
+    assert_eq!(err.to_string(), "no Cipher at 11");
 }
 
 #[test]
@@ -158,6 +194,38 @@ fn ser_aes256_gcm() {
     assert_eq!(buf, [0x00, 0x00, 0x00, 0x06]);
 }
 
+#[test]
+fn ser_chacha20_poly1305() {
+    let mut buf = vec![];
+
+    Cipher::ChaCha20Poly1305.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, [0x00, 0x00, 0x00, 0x07]);
+}
+
+#[test]
+fn ser_xchacha20_poly1305() {
+    let mut buf = vec![];
+
+    Cipher::XChaCha20Poly1305.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, [0x00, 0x00, 0x00, 0x08]);
+}
+
+#[test]
+fn ser_aes128_gcm_siv() {
+    let mut buf = vec![];
+
+    Cipher::Aes128GcmSiv.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, [0x00, 0x00, 0x00, 0x09]);
+}
+
+#[test]
+fn ser_aes256_gcm_siv() {
+    let mut buf = vec![];
+
+    Cipher::Aes256GcmSiv.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, [0x00, 0x00, 0x00, 0x0a]);
+}
+
 #[test]
 fn ser_write_zero() {
     let mut buf = [0; 3];
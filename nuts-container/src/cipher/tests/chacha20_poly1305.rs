@@ -0,0 +1,196 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::cipher::{Cipher, CipherContext, CipherError};
+
+use super::{ctx_test, IV, KEY};
+
+const KEY_LEN: usize = 32;
+
+#[test]
+fn block_size() {
+    assert_eq!(Cipher::ChaCha20Poly1305.block_size(), 1);
+}
+
+#[test]
+fn key_len() {
+    assert_eq!(Cipher::ChaCha20Poly1305.key_len(), KEY_LEN);
+}
+
+#[test]
+fn iv_len() {
+    assert_eq!(Cipher::ChaCha20Poly1305.iv_len(), 12);
+}
+
+#[test]
+fn tag_size() {
+    assert_eq!(Cipher::ChaCha20Poly1305.tag_size(), 16);
+}
+
+#[test]
+fn ctx_decrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            81, 178, 170, 118, 224, 230, 194, 243, 6, 177, 250, 83, 9, 212, 229, 17, 191, 66, 138,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_decrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            81, 178, 170, 118, 224, 230, 194, 243, 6, 177, 250, 83, 9, 212, 229, 17, 191, 66, 138,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV[..11]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+#[test]
+fn ctx_decrypt_not_trustworthy() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            81, 178, 170, 118, 224, 230, 194, 243, 6, 177, 250, 83, 9, 212, 229, 17, 191, 66, b'x',
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+ctx_test!(
+    ctx_decrypt_3, ChaCha20Poly1305.decrypt,
+    19, [81, 178, 170, 118, 224, 230, 194, 243, 6, 177, 250, 83, 9, 212, 229, 17, 191, 66, 138] -> [1, 2, 3]
+);
+ctx_test!(
+    ctx_decrypt_2, ChaCha20Poly1305.decrypt,
+    18, [81, 178, 168, 12, 136, 186, 7, 118, 173, 206, 169, 190, 249, 158, 195, 199, 128, 154] -> [1, 2]
+);
+ctx_test!(
+    ctx_decrypt_1, ChaCha20Poly1305.decrypt,
+    17, [81, 127, 92, 154, 7, 16, 220, 172, 130, 32, 1, 164, 72, 91, 5, 21, 207] -> [1]
+);
+ctx_test!(
+    ctx_decrypt_0_1, ChaCha20Poly1305.decrypt,
+    16, [112, 232, 249, 26, 110, 158, 113, 145, 41, 228, 237, 5, 159, 127, 103, 17] -> []
+);
+ctx_test!(
+    ctx_decrypt_0_2, ChaCha20Poly1305.decrypt,
+    15, [232, 249, 26, 110, 158, 113, 145, 41, 228, 237, 5, 159, 127, 103, 17] -> []
+);
+ctx_test!(ctx_decrypt_0_3, ChaCha20Poly1305.decrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_encrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN], &IV[..11]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+ctx_test!(
+    ctx_encrypt_3_1, ChaCha20Poly1305.encrypt,
+    3, [1, 2, 3] -> [81, 178, 170, 118, 224, 230, 194, 243, 6, 177, 250, 83, 9, 212, 229, 17, 191, 66, 138]
+);
+ctx_test!(
+    ctx_encrypt_3_2, ChaCha20Poly1305.encrypt,
+    2, [1, 2, 3] -> [81, 178, 168, 12, 136, 186, 7, 118, 173, 206, 169, 190, 249, 158, 195, 199, 128, 154]
+);
+ctx_test!(
+    ctx_encrypt_3_3, ChaCha20Poly1305.encrypt,
+    4, [1, 2, 3] -> [81, 178, 170, 191, 74, 146, 14, 78, 241, 154, 217, 231, 194, 229, 218, 214, 167, 216, 59, 177]
+);
+ctx_test!(
+    ctx_encrypt_2_1, ChaCha20Poly1305.encrypt,
+    2, [1, 2] -> [81, 178, 168, 12, 136, 186, 7, 118, 173, 206, 169, 190, 249, 158, 195, 199, 128, 154]
+);
+ctx_test!(
+    ctx_encrypt_2_2, ChaCha20Poly1305.encrypt,
+    1, [1, 2] -> [81, 127, 92, 154, 7, 16, 220, 172, 130, 32, 1, 164, 72, 91, 5, 21, 207]
+);
+ctx_test!(
+    ctx_encrypt_1_1, ChaCha20Poly1305.encrypt,
+    1, [1] -> [81, 127, 92, 154, 7, 16, 220, 172, 130, 32, 1, 164, 72, 91, 5, 21, 207]
+);
+ctx_test!(
+    ctx_encrypt_1_2, ChaCha20Poly1305.encrypt,
+    0, [1] -> []
+);
+ctx_test!(ctx_encrypt_0_1, ChaCha20Poly1305.encrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_with_aad() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx
+        .encrypt_with_aad(&[4, 5, 6], &KEY[..KEY_LEN], &IV)
+        .unwrap();
+    assert_eq!(
+        output,
+        [81, 178, 170, 98, 201, 156, 74, 191, 217, 190, 134, 93, 14, 104, 252, 34, 112, 173, 188]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_aad_wrong_aad() {
+    let mut ctx = CipherContext::new(Cipher::ChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            81, 178, 170, 98, 201, 156, 74, 191, 217, 190, 134, 93, 14, 104, 252, 34, 112, 173, 188,
+        ],
+    );
+
+    let err = ctx
+        .decrypt_with_aad(&[4, 5, 7], &KEY[..KEY_LEN], &IV)
+        .unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
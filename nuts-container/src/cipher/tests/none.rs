@@ -20,7 +20,7 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use crate::cipher::Cipher;
+use crate::cipher::{Cipher, CipherContext};
 
 use super::ctx_test;
 
@@ -67,3 +67,21 @@ ctx_test!(ctx_encrypt_1_2, None.encrypt, 0, [1] -> []);
 ctx_test!(ctx_encrypt_1_3, None.encrypt, 2, [1] -> [1, 0]);
 ctx_test!(ctx_encrypt_0_1, None.encrypt, 0, [] -> []);
 ctx_test!(ctx_encrypt_0_2, None.encrypt, 1, [] -> [0]);
+
+#[test]
+fn ctx_encrypt_with_nonce() {
+    let mut ctx = CipherContext::new(Cipher::None);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    assert_eq!(ctx.encrypt_with_nonce(&[], &[]).unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn ctx_decrypt_with_nonce() {
+    let mut ctx = CipherContext::new(Cipher::None);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    assert_eq!(ctx.decrypt_with_nonce(&[], &[]).unwrap(), [1, 2, 3]);
+}
@@ -57,6 +57,38 @@ fn from_str_aes256_gcm() {
     assert_eq!("aes256-gcm".parse::<Cipher>().unwrap(), Cipher::Aes256Gcm);
 }
 
+#[test]
+fn from_str_chacha20_poly1305() {
+    assert_eq!(
+        "chacha20-poly1305".parse::<Cipher>().unwrap(),
+        Cipher::ChaCha20Poly1305
+    );
+}
+
+#[test]
+fn from_str_xchacha20_poly1305() {
+    assert_eq!(
+        "xchacha20-poly1305".parse::<Cipher>().unwrap(),
+        Cipher::XChaCha20Poly1305
+    );
+}
+
+#[test]
+fn from_str_aes128_gcm_siv() {
+    assert_eq!(
+        "aes128-gcm-siv".parse::<Cipher>().unwrap(),
+        Cipher::Aes128GcmSiv
+    );
+}
+
+#[test]
+fn from_str_aes256_gcm_siv() {
+    assert_eq!(
+        "aes256-gcm-siv".parse::<Cipher>().unwrap(),
+        Cipher::Aes256GcmSiv
+    );
+}
+
 #[test]
 fn from_str_invalid() {
     "xxx".parse::<Cipher>().unwrap_err();
@@ -96,3 +128,23 @@ fn to_string_aes192_gcm() {
 fn to_string_aes256_gcm() {
     assert_eq!(Cipher::Aes256Gcm.to_string(), "aes256-gcm");
 }
+
+#[test]
+fn to_string_chacha20_poly1305() {
+    assert_eq!(Cipher::ChaCha20Poly1305.to_string(), "chacha20-poly1305");
+}
+
+#[test]
+fn to_string_xchacha20_poly1305() {
+    assert_eq!(Cipher::XChaCha20Poly1305.to_string(), "xchacha20-poly1305");
+}
+
+#[test]
+fn to_string_aes128_gcm_siv() {
+    assert_eq!(Cipher::Aes128GcmSiv.to_string(), "aes128-gcm-siv");
+}
+
+#[test]
+fn to_string_aes256_gcm_siv() {
+    assert_eq!(Cipher::Aes256GcmSiv.to_string(), "aes256-gcm-siv");
+}
@@ -0,0 +1,227 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::cipher::{Cipher, CipherContext, CipherError};
+
+use super::{ctx_test, kat, IV, KEY};
+
+const KEY_LEN: usize = 32;
+
+#[test]
+fn block_size() {
+    assert_eq!(Cipher::XChaCha20Poly1305.block_size(), 1);
+}
+
+#[test]
+fn key_len() {
+    assert_eq!(Cipher::XChaCha20Poly1305.key_len(), KEY_LEN);
+}
+
+#[test]
+fn iv_len() {
+    assert_eq!(Cipher::XChaCha20Poly1305.iv_len(), 24);
+}
+
+#[test]
+fn tag_size() {
+    assert_eq!(Cipher::XChaCha20Poly1305.tag_size(), 16);
+}
+
+#[test]
+fn ctx_decrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            90, 246, 236, 200, 245, 235, 132, 5, 181, 166, 117, 149, 164, 168, 28, 94, 221, 184,
+            120,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_decrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            90, 246, 236, 200, 245, 235, 132, 5, 181, 166, 117, 149, 164, 168, 28, 94, 221, 184,
+            120,
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV[..23]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+#[test]
+fn ctx_decrypt_not_trustworthy() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            90, 246, 236, 200, 245, 235, 132, 5, 181, 166, 117, 149, 164, 168, 28, 94, 221, 184,
+            b'y',
+        ],
+    );
+
+    let err = ctx.decrypt(&KEY[..KEY_LEN], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+ctx_test!(
+    ctx_decrypt_3, XChaCha20Poly1305.decrypt,
+    19, [90, 246, 236, 200, 245, 235, 132, 5, 181, 166, 117, 149, 164, 168, 28, 94, 221, 184, 120] -> [1, 2, 3]
+);
+ctx_test!(
+    ctx_decrypt_2, XChaCha20Poly1305.decrypt,
+    18, [90, 246, 35, 171, 152, 132, 52, 143, 68, 252, 133, 74, 218, 119, 52, 171, 4, 252] -> [1, 2]
+);
+ctx_test!(
+    ctx_decrypt_1, XChaCha20Poly1305.decrypt,
+    17, [90, 137, 72, 179, 237, 214, 143, 201, 193, 77, 44, 243, 231, 87, 203, 132, 44] -> [1]
+);
+ctx_test!(
+    ctx_decrypt_0_1, XChaCha20Poly1305.decrypt,
+    16, [202, 72, 117, 138, 224, 75, 153, 221, 9, 111, 50, 8, 201, 1, 13, 16] -> []
+);
+ctx_test!(
+    ctx_decrypt_0_2, XChaCha20Poly1305.decrypt,
+    15, [72, 117, 138, 224, 75, 153, 221, 9, 111, 50, 8, 201, 1, 13, 16] -> []
+);
+ctx_test!(ctx_decrypt_0_3, XChaCha20Poly1305.decrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_inval_key() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN - 1], &IV).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidKey));
+}
+
+#[test]
+fn ctx_encrypt_inval_iv() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let err = ctx.encrypt(&KEY[..KEY_LEN], &IV[..23]).unwrap_err();
+    assert!(matches!(err, CipherError::InvalidIv));
+}
+
+ctx_test!(
+    ctx_encrypt_3_1, XChaCha20Poly1305.encrypt,
+    3, [1, 2, 3] -> [90, 246, 236, 200, 245, 235, 132, 5, 181, 166, 117, 149, 164, 168, 28, 94, 221, 184, 120]
+);
+ctx_test!(
+    ctx_encrypt_3_2, XChaCha20Poly1305.encrypt,
+    2, [1, 2, 3] -> [90, 246, 35, 171, 152, 132, 52, 143, 68, 252, 133, 74, 218, 119, 52, 171, 4, 252]
+);
+ctx_test!(
+    ctx_encrypt_3_3, XChaCha20Poly1305.encrypt,
+    4, [1, 2, 3] -> [90, 246, 236, 108, 186, 243, 215, 245, 4, 128, 24, 105, 228, 96, 207, 140, 151, 124, 71, 219]
+);
+ctx_test!(
+    ctx_encrypt_2_1, XChaCha20Poly1305.encrypt,
+    2, [1, 2] -> [90, 246, 35, 171, 152, 132, 52, 143, 68, 252, 133, 74, 218, 119, 52, 171, 4, 252]
+);
+ctx_test!(
+    ctx_encrypt_2_2, XChaCha20Poly1305.encrypt,
+    1, [1, 2] -> [90, 137, 72, 179, 237, 214, 143, 201, 193, 77, 44, 243, 231, 87, 203, 132, 44]
+);
+ctx_test!(
+    ctx_encrypt_1_1, XChaCha20Poly1305.encrypt,
+    1, [1] -> [90, 137, 72, 179, 237, 214, 143, 201, 193, 77, 44, 243, 231, 87, 203, 132, 44]
+);
+ctx_test!(
+    ctx_encrypt_1_2, XChaCha20Poly1305.encrypt,
+    0, [1] -> []
+);
+ctx_test!(ctx_encrypt_0_1, XChaCha20Poly1305.encrypt, 0, [] -> []);
+
+#[test]
+fn ctx_encrypt_with_aad() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(3, &[1, 2, 3]);
+
+    let output = ctx
+        .encrypt_with_aad(&[4, 5, 6], &KEY[..KEY_LEN], &IV)
+        .unwrap();
+    assert_eq!(
+        output,
+        [90, 246, 236, 11, 216, 3, 222, 62, 89, 54, 153, 243, 14, 218, 91, 12, 161, 205, 214]
+    );
+}
+
+#[test]
+fn ctx_decrypt_with_aad_wrong_aad() {
+    let mut ctx = CipherContext::new(Cipher::XChaCha20Poly1305);
+
+    ctx.copy_from_slice(
+        19,
+        &[
+            90, 246, 236, 11, 216, 3, 222, 62, 89, 54, 153, 243, 14, 218, 91, 12, 161, 205, 214,
+        ],
+    );
+
+    let err = ctx
+        .decrypt_with_aad(&[4, 5, 7], &KEY[..KEY_LEN], &IV)
+        .unwrap_err();
+    assert!(matches!(err, CipherError::NotTrustworthy));
+}
+
+#[test]
+fn xchacha_a_3_1() {
+    // draft-irtf-cfrg-xchacha-03, appendix A.3.1
+    kat(
+        Cipher::XChaCha20Poly1305,
+        &[
+            128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143, 144,
+            145, 146, 147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159,
+        ],
+        &[
+            64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85,
+            86, 87,
+        ],
+        &[80, 81, 82, 83, 192, 193, 194, 195, 196, 197, 198, 199],
+        b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for \
+          the future, sunscreen would be it.",
+        &[
+            189, 109, 23, 157, 62, 131, 212, 59, 149, 118, 87, 148, 147, 192, 233, 57, 87, 42, 23,
+            0, 37, 43, 250, 204, 190, 210, 144, 44, 33, 57, 108, 187, 115, 28, 127, 27, 11, 74,
+            166, 68, 11, 243, 168, 47, 78, 218, 126, 57, 174, 100, 198, 112, 140, 84, 194, 22, 203,
+            150, 183, 46, 18, 19, 180, 82, 47, 140, 155, 164, 13, 181, 217, 69, 177, 27, 105, 185,
+            130, 193, 187, 158, 63, 63, 172, 43, 195, 105, 72, 143, 118, 178, 56, 53, 101, 211,
+            255, 249, 33, 249, 102, 76, 151, 99, 125, 169, 118, 136, 18, 246, 21, 198, 139, 19,
+            181, 46, 192, 135, 89, 36, 193, 199, 152, 121, 71, 222, 175, 216, 120, 10, 207, 73,
+        ],
+    );
+}
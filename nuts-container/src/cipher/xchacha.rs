@@ -0,0 +1,92 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+//! XChaCha20-Poly1305 on top of OpenSSL's ChaCha20-Poly1305.
+//!
+//! OpenSSL does not support XChaCha20-Poly1305 directly. The extended nonce
+//! is reduced with the HChaCha20 function: The first 16 bytes of the 24 byte
+//! nonce are used to derive a subkey, the remaining 8 bytes (prefixed by 4
+//! zero bytes) are used as the nonce for ChaCha20-Poly1305.
+
+#[cfg(test)]
+mod tests;
+
+use crate::svec::SecureVec;
+
+const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn hchacha20(key: &[u8], nonce: &[u8]) -> SecureVec {
+    let mut state = [0u32; 16];
+
+    state[..4].copy_from_slice(&SIGMA);
+
+    for (i, chunk) in key.chunks_exact(4).take(8).enumerate() {
+        state[4 + i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    for (i, chunk) in nonce.chunks_exact(4).take(4).enumerate() {
+        state[12 + i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut subkey = SecureVec::zeroed(32);
+
+    for (i, n) in state[..4].iter().chain(state[12..].iter()).enumerate() {
+        subkey[i * 4..(i + 1) * 4].copy_from_slice(&n.to_le_bytes());
+    }
+
+    state.iter_mut().for_each(|n| *n = 0);
+
+    subkey
+}
+
+/// Derives the ChaCha20-Poly1305 key and nonce from the XChaCha20-Poly1305
+/// `key` (32 bytes) and `nonce` (24 bytes).
+pub fn derive(key: &[u8], nonce: &[u8]) -> (SecureVec, [u8; 12]) {
+    let subkey = hchacha20(key, &nonce[..16]);
+    let mut subnonce = [0; 12];
+
+    subnonce[4..].copy_from_slice(&nonce[16..24]);
+
+    (subkey, subnonce)
+}
@@ -0,0 +1,55 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::cipher::xchacha::{derive, hchacha20};
+
+#[test]
+fn hchacha20_vector() {
+    // draft-irtf-cfrg-xchacha-03, section 2.2.1
+    let key = (0..32).collect::<Vec<u8>>();
+    let nonce = [
+        0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00, 0x31, 0x41, 0x59,
+        0x27,
+    ];
+
+    let subkey = hchacha20(&key, &nonce);
+
+    assert_eq!(
+        subkey.as_ref(),
+        [
+            0x82, 0x41, 0x3b, 0x42, 0x27, 0xb2, 0x7b, 0xfe, 0xd3, 0x0e, 0x42, 0x50, 0x8a, 0x87,
+            0x7d, 0x73, 0xa0, 0xf9, 0xe4, 0xd5, 0x8a, 0x74, 0xa8, 0x53, 0xc1, 0x2e, 0xc4, 0x13,
+            0x26, 0xd3, 0xec, 0xdc
+        ]
+    );
+}
+
+#[test]
+fn derive_nonce() {
+    let key = [b'x'; 32];
+    let nonce = (0..24).collect::<Vec<u8>>();
+
+    let (subkey, subnonce) = derive(&key, &nonce);
+
+    assert_eq!(subkey.as_ref(), hchacha20(&key, &nonce[..16]).as_ref());
+    assert_eq!(subnonce, [0, 0, 0, 0, 16, 17, 18, 19, 20, 21, 22, 23]);
+}
@@ -0,0 +1,222 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::buffer::{Buffer, BufferError, BufferMut};
+use crate::svec::SecureVec;
+
+// The frame in front of the data of a block: the method (u8) followed by the
+// length of the data (u32).
+const FRAME_SIZE: usize = 5;
+
+const METHOD_STORED: u8 = 0;
+const METHOD_LZ4: u8 = 1;
+const METHOD_ZSTD: u8 = 2;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// [`Compression`] related error codes.
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    /// The frame of a compressed block is invalid.
+    #[error("invalid compression frame")]
+    InvalidFrame,
+
+    /// The data of a block could not be decompressed.
+    #[error("failed to decompress the block: {0}")]
+    Decompress(String),
+}
+
+/// Supported compression algorithms.
+///
+/// The data of a block are compressed before they are encrypted. Each block
+/// is prefixed with a small frame, which records whether the block was
+/// compressed and the length of the compressed data. Blocks, which do not
+/// compress, are stored as-is. Thus the net [block
+/// size](crate::Container::block_size) of a container with compression is
+/// reduced by the size of the frame.
+///
+/// A backend with a [variable block
+/// size](nuts_backend::Backend::variable_block_size) stores only the
+/// encrypted frame, thus a compressed block occupies less space in the
+/// backend. Any other backend stores blocks of its fixed block size, the
+/// remaining space of a compressed block is padded.
+///
+/// # Security
+///
+/// On a backend with a variable block size the size of a stored block
+/// reveals how well its plaintext compressed. An attacker, who can observe
+/// the size of the blocks and influence parts of their content, can learn
+/// something about the remaining plaintext (the same class of attack as
+/// CRIME and BREACH). Do not enable compression on such a backend, if
+/// attacker-controlled and secret data are mixed in the same block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    /// No compression
+    None,
+
+    /// LZ4 block compression
+    Lz4,
+
+    /// Zstandard compression
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the number of bytes of a block, which are occupied by the
+    /// compression frame.
+    pub fn overhead(&self) -> u32 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 | Compression::Zstd => FRAME_SIZE as u32,
+        }
+    }
+
+    pub(crate) fn get_from_buffer<T: Buffer>(buf: &mut T) -> Result<Compression, BufferError> {
+        let b = buf.get_u32()?;
+
+        match b {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Lz4),
+            2 => Ok(Compression::Zstd),
+            _ => Err(BufferError::InvalidIndex("Compression".to_string(), b)),
+        }
+    }
+
+    pub(crate) fn put_into_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), BufferError> {
+        let b = match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+            Compression::Zstd => 2,
+        };
+
+        buf.put_u32(b)
+    }
+
+    /// Compresses `data` and returns the framed result.
+    ///
+    /// Trailing zeros are not part of the frame, they are restored by
+    /// [`Compression::decompress`]. If the data do not compress, they are
+    /// stored as-is.
+    pub(crate) fn compress(&self, data: &[u8]) -> SecureVec {
+        let len = data.iter().rposition(|n| *n != 0).map_or(0, |n| n + 1);
+        let data = &data[..len];
+
+        let compressed: Option<(u8, SecureVec)> = match self {
+            _ if data.is_empty() => None,
+            Compression::None => None,
+            Compression::Lz4 => Some((METHOD_LZ4, lz4_flex::block::compress(data).into())),
+            Compression::Zstd => zstd::bulk::compress(data, ZSTD_LEVEL)
+                .ok()
+                .map(|vec| (METHOD_ZSTD, vec.into())),
+        };
+
+        match compressed {
+            Some((method, payload)) if payload.len() < data.len() => Self::frame(method, &payload),
+            _ => Self::frame(METHOD_STORED, data),
+        }
+    }
+
+    /// Decompresses the framed data of a block into a buffer of `size`
+    /// bytes.
+    ///
+    /// The method of the frame is evaluated, so a block can be decompressed
+    /// independent of the selected compression algorithm.
+    pub(crate) fn decompress(frame: &[u8], size: usize) -> Result<SecureVec, CompressionError> {
+        if frame.len() < FRAME_SIZE {
+            return Err(CompressionError::InvalidFrame);
+        }
+
+        let mut len = [0; 4];
+        len.copy_from_slice(&frame[1..FRAME_SIZE]);
+
+        let len =
+            usize::try_from(u32::from_be_bytes(len)).map_err(|_| CompressionError::InvalidFrame)?;
+        let payload = FRAME_SIZE
+            .checked_add(len)
+            .and_then(|end| frame.get(FRAME_SIZE..end))
+            .ok_or(CompressionError::InvalidFrame)?;
+        let mut data = SecureVec::zeroed(size);
+
+        let n = match frame[0] {
+            METHOD_STORED => {
+                data.get_mut(..len)
+                    .ok_or(CompressionError::InvalidFrame)?
+                    .copy_from_slice(payload);
+                len
+            }
+            METHOD_LZ4 => lz4_flex::block::decompress_into(payload, &mut data)
+                .map_err(|err| CompressionError::Decompress(err.to_string()))?,
+            METHOD_ZSTD => zstd::bulk::decompress_to_buffer(payload, &mut data[..])
+                .map_err(|err| CompressionError::Decompress(err.to_string()))?,
+            _ => return Err(CompressionError::InvalidFrame),
+        };
+
+        // The decompressor is free to use the whole buffer, restore the
+        // trailing zeros.
+        data[n..].iter_mut().for_each(|b| *b = 0);
+
+        Ok(data)
+    }
+
+    fn frame(method: u8, payload: &[u8]) -> SecureVec {
+        let mut frame = SecureVec::zeroed(FRAME_SIZE + payload.len());
+
+        frame[0] = method;
+        frame[1..FRAME_SIZE].copy_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame[FRAME_SIZE..].copy_from_slice(payload);
+
+        frame
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Compression::None => "none",
+            Compression::Lz4 => "lz4",
+            Compression::Zstd => "zstd",
+        };
+
+        fmt.write_str(s)
+    }
+}
+
+impl FromStr for Compression {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        match str {
+            "none" => Ok(Compression::None),
+            "lz4" => Ok(Compression::Lz4),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(()),
+        }
+    }
+}
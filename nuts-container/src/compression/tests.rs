@@ -0,0 +1,234 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use std::str::FromStr;
+
+use crate::buffer::BufferError;
+use crate::compression::{Compression, CompressionError};
+
+fn text() -> Vec<u8> {
+    b"The quick brown fox jumps over the lazy dog. ".repeat(10)
+}
+
+fn noise() -> Vec<u8> {
+    // xorshift, good enough to get data, which do not compress
+    let mut x = 0x12345678u32;
+
+    (0..450)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as u8
+        })
+        .collect()
+}
+
+#[test]
+fn overhead() {
+    assert_eq!(Compression::None.overhead(), 0);
+    assert_eq!(Compression::Lz4.overhead(), 5);
+    assert_eq!(Compression::Zstd.overhead(), 5);
+}
+
+#[test]
+fn de_none() {
+    let buf = [0x00, 0x00, 0x00, 0x00];
+
+    assert_eq!(
+        Compression::get_from_buffer(&mut &buf[..]).unwrap(),
+        Compression::None
+    );
+}
+
+#[test]
+fn de_lz4() {
+    let buf = [0x00, 0x00, 0x00, 0x01];
+
+    assert_eq!(
+        Compression::get_from_buffer(&mut &buf[..]).unwrap(),
+        Compression::Lz4
+    );
+}
+
+#[test]
+fn de_zstd() {
+    let buf = [0x00, 0x00, 0x00, 0x02];
+
+    assert_eq!(
+        Compression::get_from_buffer(&mut &buf[..]).unwrap(),
+        Compression::Zstd
+    );
+}
+
+#[test]
+fn de_invalid() {
+    let buf = [0x00, 0x00, 0x00, 0x03];
+    let err = Compression::get_from_buffer(&mut &buf[..]).unwrap_err();
+
+    assert!(
+        matches!(err, BufferError::InvalidIndex(name, idx) if name == "Compression" && idx == 3)
+    );
+}
+
+#[test]
+fn ser() {
+    for (compression, n) in [
+        (Compression::None, 0),
+        (Compression::Lz4, 1),
+        (Compression::Zstd, 2),
+    ] {
+        let mut buf = vec![];
+
+        compression.put_into_buffer(&mut buf).unwrap();
+        assert_eq!(buf, [0, 0, 0, n]);
+    }
+}
+
+#[test]
+fn string() {
+    for (compression, s) in [
+        (Compression::None, "none"),
+        (Compression::Lz4, "lz4"),
+        (Compression::Zstd, "zstd"),
+    ] {
+        assert_eq!(compression.to_string(), s);
+        assert_eq!(Compression::from_str(s).unwrap(), compression);
+    }
+
+    assert!(Compression::from_str("xxx").is_err());
+}
+
+#[test]
+fn compress() {
+    for (compression, method) in [(Compression::Lz4, 1), (Compression::Zstd, 2)] {
+        let data = text();
+        let frame = compression.compress(&data);
+
+        assert_eq!(frame[0], method);
+        assert!(frame.len() < data.len());
+        assert_eq!(
+            frame[1..5],
+            ((frame.len() - 5) as u32).to_be_bytes(),
+            "{}",
+            compression
+        );
+
+        let plain = Compression::decompress(&frame, 512).unwrap();
+
+        assert_eq!(plain[..data.len()], data);
+        assert!(plain[data.len()..].iter().all(|n| *n == 0));
+    }
+}
+
+#[test]
+fn compress_none() {
+    let data = text();
+    let frame = Compression::None.compress(&data);
+
+    assert_eq!(frame[..5], [0, 0, 0, 0x01, 0xC2]);
+    assert_eq!(frame[5..], data);
+}
+
+#[test]
+fn compress_stored() {
+    for compression in [Compression::Lz4, Compression::Zstd] {
+        let data = noise();
+        let frame = compression.compress(&data);
+
+        assert_eq!(frame[..5], [0, 0, 0, 0x01, 0xC2]);
+        assert_eq!(frame[5..], data);
+
+        let plain = Compression::decompress(&frame, 512).unwrap();
+
+        assert_eq!(plain[..data.len()], data);
+        assert!(plain[data.len()..].iter().all(|n| *n == 0));
+    }
+}
+
+#[test]
+fn compress_zeros() {
+    for compression in [Compression::Lz4, Compression::Zstd] {
+        let frame = compression.compress(&[0; 512]);
+
+        assert_eq!(*frame, [0; 5]);
+        assert_eq!(*Compression::decompress(&frame, 512).unwrap(), [0; 512]);
+    }
+}
+
+#[test]
+fn compress_trailing_zeros() {
+    for compression in [Compression::Lz4, Compression::Zstd] {
+        let frame = compression.compress(&[1, 2, 3, 0, 0, 0]);
+
+        assert_eq!(*frame, [0, 0, 0, 0, 3, 1, 2, 3]);
+        assert_eq!(
+            *Compression::decompress(&frame, 6).unwrap(),
+            [1, 2, 3, 0, 0, 0]
+        );
+    }
+}
+
+#[test]
+fn decompress_padded() {
+    let frame = [0, 0, 0, 0, 2, 1, 2, 0xFF, 0xFF];
+
+    assert_eq!(*Compression::decompress(&frame, 4).unwrap(), [1, 2, 0, 0]);
+}
+
+#[test]
+fn decompress_short_frame() {
+    let err = Compression::decompress(&[0, 0, 0, 0], 4).unwrap_err();
+
+    assert!(matches!(err, CompressionError::InvalidFrame));
+}
+
+#[test]
+fn decompress_short_payload() {
+    let err = Compression::decompress(&[0, 0, 0, 0, 3, 1, 2], 4).unwrap_err();
+
+    assert!(matches!(err, CompressionError::InvalidFrame));
+}
+
+#[test]
+fn decompress_too_large() {
+    let err = Compression::decompress(&[0, 0, 0, 0, 3, 1, 2, 3], 2).unwrap_err();
+
+    assert!(matches!(err, CompressionError::InvalidFrame));
+}
+
+#[test]
+fn decompress_invalid_method() {
+    let err = Compression::decompress(&[3, 0, 0, 0, 0], 4).unwrap_err();
+
+    assert!(matches!(err, CompressionError::InvalidFrame));
+}
+
+#[test]
+fn decompress_invalid_data() {
+    for method in [1, 2] {
+        let frame = [method, 0, 0, 0, 3, 0xFF, 0xFF, 0xFF];
+        let err = Compression::decompress(&frame, 512).unwrap_err();
+
+        assert!(matches!(err, CompressionError::Decompress(_)));
+    }
+}
@@ -20,15 +20,19 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use nuts_backend::Backend;
+use nuts_backend::BackendTypes;
 use thiserror::Error as ThisError;
 
 use crate::cipher::CipherError;
+use crate::compression::CompressionError;
 use crate::header::HeaderError;
+use crate::journal::JournalError;
+use crate::snapshot::SnapshotError;
+use crate::Container;
 
 /// Error type used by this module.
 #[derive(Debug, ThisError)]
-pub enum Error<B: Backend> {
+pub enum Error<B: BackendTypes> {
     /// An error occured in the attached backend.
     #[error(transparent)]
     Backend(B::Err),
@@ -37,9 +41,46 @@ pub enum Error<B: Backend> {
     #[error(transparent)]
     Cipher(#[from] CipherError),
 
+    /// A compression related error
+    #[error(transparent)]
+    Compression(#[from] CompressionError),
+
     /// Errors coming from header evaluation.
     #[error(transparent)]
     Header(#[from] HeaderError),
+
+    /// A transaction related error.
+    #[error(transparent)]
+    Journal(#[from] JournalError),
+
+    /// A snapshot related error.
+    #[error(transparent)]
+    Snapshot(#[from] SnapshotError),
+
+    /// The conversion into another cipher changes the net block size of a
+    /// service, which cannot adjust its data.
+    #[error("the net block size of the service changes from {0} to {1} bytes")]
+    ServiceBlockSize(u32, u32),
+
+    /// The data of a block do not fit into the net block size of the cipher
+    /// the container is converted into.
+    #[error("the data of block {0} exceed the new block size of {1} bytes")]
+    BlockTooLarge(String, u32),
+
+    /// The restored header does not belong to the container.
+    #[error("the header does not belong to the container")]
+    HeaderMismatch,
+
+    /// The backend cannot list its blocks, see
+    /// [`Backend::ids`](nuts_backend::Backend::ids).
+    #[error("the backend cannot list its blocks")]
+    IdsUnsupported,
 }
 
 pub type ContainerResult<T, B> = Result<T, Error<B>>;
+
+/// The result of a method, which consumes a [`Container`].
+///
+/// On error the unchanged container is passed back together with the error.
+/// Both are boxed, the container is large.
+pub type IntoContainerResult<T, B> = Result<T, Box<(Error<B>, Container<B>)>>;
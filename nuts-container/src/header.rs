@@ -26,24 +26,28 @@ mod revision;
 mod tests;
 
 use log::{debug, error};
-use nuts_backend::Backend;
+use nuts_backend::{BackendTypes, HEADER_MAX_SIZE};
 use openssl::error::ErrorStack;
-use plain_secret::PlainSecret;
+use plain_secret::{Journal, PlainSecret, Rekey};
+use std::convert::TryFrom;
 use std::fmt;
-use std::ops::DerefMut;
 use thiserror::Error;
 
 use crate::buffer::{BufferError, ToBuffer};
 use crate::cipher::{Cipher, CipherContext, CipherError};
-use crate::header::revision::{Data, Revision};
+use crate::compression::Compression;
+use crate::header::revision::{Data, DataRev3, KeySlot, Revision};
+use crate::info::SlotInfo;
 use crate::kdf::{Kdf, KdfError};
 use crate::migrate::{MigrationError, Migrator};
 use crate::options::CreateOptions;
 use crate::ossl;
 use crate::password::{PasswordError, PasswordStore};
+use crate::recipient::{Recipient, RecipientError};
+use crate::recovery::RecoveryKey;
 use crate::svec::SecureVec;
 
-pub const LATEST_REVISION: u32 = 2;
+pub const LATEST_REVISION: u32 = 3;
 
 /// Header related errors.
 #[derive(Debug, Error)]
@@ -60,10 +64,22 @@ pub enum HeaderError {
     #[error(transparent)]
     Password(#[from] PasswordError),
 
+    /// A recipient related error.
+    #[error(transparent)]
+    Recipient(#[from] RecipientError),
+
     /// The password is wrong.
     #[error("the password is wrong")]
     WrongPassword,
 
+    /// The recovery key is wrong.
+    #[error("the recovery key is wrong")]
+    WrongRecoveryKey,
+
+    /// The container is not encrypted, there is no recovery key.
+    #[error("the container is not encrypted")]
+    NotEncrypted,
+
     /// Invalid header revision
     #[error("invalid header revision, expected {0} but got {1}")]
     InvalidRevision(u32, u32),
@@ -72,6 +88,46 @@ pub enum HeaderError {
     #[error("unknown header revision {0}")]
     UnknownRevision(u32),
 
+    /// The header is not trustworthy. Either the password is wrong or the
+    /// header was modified.
+    #[error("the header is not trustworthy, wrong password or modified header")]
+    NotTrustworthy,
+
+    /// There is no key slot with the given index.
+    #[error("no such key slot: {0}")]
+    NoSuchKeySlot(usize),
+
+    /// The key slot, which unlocked the container, cannot be removed.
+    #[error("cannot remove key slot {0}, the container was unlocked with it")]
+    ActiveKeySlot(usize),
+
+    /// None of the key slots is wrapped to the recipient of the identity.
+    #[error("the identity is not a recipient of the container")]
+    UnknownIdentity,
+
+    /// A key slot cannot be converted into another cipher, because its
+    /// password is not known.
+    #[error("cannot convert key slot {0}, it was not used to unlock the container")]
+    InactiveKeySlot(usize),
+
+    /// A conversion into another cipher is pending.
+    #[error("a conversion into {0} is pending")]
+    PendingConversion(Cipher),
+
+    /// The header does not fit into the header blocks of the backend.
+    #[error("the header is too large ({0} bytes)")]
+    TooLarge(usize),
+
+    /// The header needs extended header blocks, but the backend does not
+    /// support them.
+    #[error("the backend does not support extended header blocks")]
+    ExtendedHeaderUnsupported,
+
+    /// The password cannot be changed, because the container was not
+    /// unlocked with a password.
+    #[error("the container was not unlocked with a password")]
+    NoPasswordSlot,
+
     /// Invalid header, could not validate magic
     #[error("invalid header")]
     InvalidHeader,
@@ -110,34 +166,106 @@ pub enum HeaderError {
     Migration(#[from] MigrationError),
 }
 
-pub struct Header<'a, B: Backend> {
+/// The extended header blocks, which follow a root header.
+///
+/// The extended header blocks of two consecutive generations are stored in
+/// alternating slots: the `n`th block of an even generation is stored at
+/// index `2n - 1`, of an odd generation at index `2n`. Thus, the blocks of a
+/// new generation never overwrite the blocks referenced by the current root
+/// header. Writing the root header is the only commit point.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExtendedBlocks {
+    pub count: usize,
+    pub generation: u8,
+}
+
+impl ExtendedBlocks {
+    /// Returns the extended header blocks, which follow the root header
+    /// `buf`.
+    pub fn peek(buf: &[u8]) -> Result<ExtendedBlocks, HeaderError> {
+        Revision::peek_blocks(buf).map(|(count, generation)| ExtendedBlocks {
+            count: count as usize,
+            generation,
+        })
+    }
+
+    /// Returns the generation, which follows this generation.
+    pub fn next_generation(&self) -> u8 {
+        self.generation.wrapping_add(1)
+    }
+
+    /// Returns the backend indices of the extended header blocks.
+    pub fn indices(&self) -> impl Iterator<Item = u32> {
+        let offset = (self.generation & 1) as u32;
+
+        (1..=self.count as u32).map(move |n| 2 * n - 1 + offset)
+    }
+}
+
+pub struct Header<'a, B: BackendTypes> {
     revision: u32,
+    generation: u8,
     migrator: Migrator<'a>,
     cipher: Cipher,
+    compression: Compression,
     kdf: Kdf,
+    header_key: SecureVec,
+    slots: Vec<KeySlot>,
+    slot: Option<usize>,
     data: PlainSecret<B>,
 }
 
-impl<'a, B: Backend> Header<'a, B> {
+impl<'a, B: BackendTypes> Header<'a, B> {
     pub fn create(
         options: &CreateOptions,
         settings: B::Settings,
     ) -> Result<Header<'a, B>, HeaderError> {
         let cipher = options.cipher;
-        let mut key = vec![0; cipher.key_len()];
-        let mut iv = vec![0; cipher.iv_len()];
+        let mut key = SecureVec::zeroed_guarded(cipher.key_len());
 
         ossl::rand_bytes(&mut key)?;
-        ossl::rand_bytes(&mut iv)?;
 
-        let kdf = options.kdf.build()?;
-        let (revision, plain_secret) = PlainSecret::create_latest(key.into(), iv.into(), settings)?;
+        let mut header_key = SecureVec::zeroed_guarded(cipher.key_len());
+
+        ossl::rand_bytes(&mut header_key)?;
+
+        let mut kdf = options.kdf.build()?;
+        let (revision, plain_secret) = PlainSecret::create_latest(key, settings)?;
+
+        // Without any credentials for a password the container is only
+        // protected by its recipients.
+        let with_password = options.recipients.is_empty()
+            || options.callback.is_some()
+            || options.keyfile.is_some();
+
+        let mut slots = vec![];
+        let mut slot = None;
+
+        if cipher.key_len() > 0 {
+            if with_password {
+                // The first slot is wrapped with the password on the first
+                // write.
+                slots.push(KeySlot::password(kdf.clone(), vec![], vec![]));
+                slot = Some(0);
+            } else {
+                kdf = Kdf::None;
+            }
+
+            for recipient in options.recipients.iter() {
+                slots.push(Self::create_recipient_slot(cipher, &header_key, recipient)?);
+            }
+        }
 
         Ok(Header {
             revision,
+            generation: 0,
             migrator: Migrator::default(),
             cipher,
+            compression: options.compression,
             kdf,
+            header_key,
+            slots,
+            slot,
             data: plain_secret,
         })
     }
@@ -147,10 +275,28 @@ impl<'a, B: Backend> Header<'a, B> {
         migrator: Migrator<'a>,
         store: &mut PasswordStore,
     ) -> Result<Header<'a, B>, HeaderError> {
-        match Revision::get_from_buffer(&mut &buf[..])? {
+        let rev = Revision::get_from_buffer(&mut &buf[..])?;
+        let aad = rev.aad()?;
+
+        if store.recovery_key().is_some() {
+            // only the header key of revision 3 can be recovered
+            let revision = match rev {
+                Revision::Rev0(_) => 0,
+                Revision::Rev1(_) => 1,
+                Revision::Rev2(_) => 2,
+                Revision::Rev3(_) => 3,
+            };
+
+            if revision != LATEST_REVISION {
+                return Err(HeaderError::InvalidRevision(LATEST_REVISION, revision));
+            }
+        }
+
+        match rev {
             Revision::Rev0(data) => Self::read_rev0(data, migrator, store),
             Revision::Rev1(data) => Self::read_rev1(data, migrator, store),
             Revision::Rev2(data) => Self::read_rev2(data, migrator, store),
+            Revision::Rev3(data) => Self::read_rev3(data, &aad, migrator, store),
         }
     }
 
@@ -167,9 +313,14 @@ impl<'a, B: Backend> Header<'a, B> {
 
         Ok(Header {
             revision: 0,
+            generation: 0,
             migrator,
             cipher: data.cipher,
+            compression: Compression::None,
             kdf: data.kdf,
+            header_key: SecureVec::new(),
+            slots: vec![],
+            slot: None,
             data: plain_secret,
         })
     }
@@ -187,9 +338,14 @@ impl<'a, B: Backend> Header<'a, B> {
 
         Ok(Header {
             revision: 1,
+            generation: 0,
             migrator,
             cipher: data.cipher,
+            compression: Compression::None,
             kdf: data.kdf,
+            header_key: SecureVec::new(),
+            slots: vec![],
+            slot: None,
             data: plain_secret,
         })
     }
@@ -207,33 +363,333 @@ impl<'a, B: Backend> Header<'a, B> {
 
         Ok(Header {
             revision: 2,
+            generation: 0,
             migrator,
             cipher: data.cipher,
+            compression: Compression::None,
             kdf: data.kdf,
+            header_key: SecureVec::new(),
+            slots: vec![],
+            slot: None,
             data: plain_secret,
         })
     }
 
-    pub fn write(&self, buf: &mut [u8], store: &mut PasswordStore) -> Result<(), HeaderError> {
+    fn read_rev3(
+        data: DataRev3,
+        aad: &[u8],
+        migrator: Migrator<'a>,
+        store: &mut PasswordStore,
+    ) -> Result<Header<'a, B>, HeaderError> {
+        if data.cipher.key_len() == 0 {
+            let plain_secret = Self::decrypt_rev3(&data, aad, &[])?;
+
+            return Ok(Header {
+                revision: 3,
+                generation: data.generation,
+                migrator,
+                cipher: data.cipher,
+                compression: data.compression,
+                kdf: Kdf::None,
+                header_key: SecureVec::new(),
+                slots: data.slots,
+                slot: None,
+                data: plain_secret,
+            });
+        }
+
+        if let Some(recovery_key) = store.recovery_key() {
+            let header_key = recovery_key.as_bytes();
+
+            if header_key.len() != data.cipher.key_len() {
+                return Err(HeaderError::WrongRecoveryKey);
+            }
+
+            let plain_secret =
+                Self::decrypt_rev3(&data, aad, header_key).map_err(|err| match err {
+                    HeaderError::NotTrustworthy | HeaderError::WrongPassword => {
+                        HeaderError::WrongRecoveryKey
+                    }
+                    _ => err,
+                })?;
+
+            debug!("unlocked with recovery key");
+
+            return Ok(Header {
+                revision: 3,
+                generation: data.generation,
+                migrator,
+                cipher: data.cipher,
+                compression: data.compression,
+                kdf: Kdf::None,
+                header_key: SecureVec::from_guarded(header_key),
+                slots: data.slots,
+                slot: None,
+                data: plain_secret,
+            });
+        }
+
+        // With an identity but without a password only the slots of the
+        // identity are tried.
+        let identity = store.identity().cloned();
+        let skip_password = identity.is_some() && !store.has_password();
+        let recipient = identity.as_ref().map(|id| id.recipient()).transpose()?;
+        let mut last_err = match identity {
+            Some(_) => HeaderError::UnknownIdentity,
+            None => HeaderError::NotTrustworthy,
+        };
+
+        for (idx, slot) in data.slots.iter().enumerate() {
+            let result = match slot {
+                KeySlot::Password { kdf, iv, key } => {
+                    if skip_password {
+                        continue;
+                    }
+
+                    let wrapping_key = kdf.create_key(store.value()?, data.cipher.key_len())?;
+                    Self::unlock_slot(&data, aad, &wrapping_key, iv, key)
+                }
+                KeySlot::Recipient {
+                    recipient: slot_recipient,
+                    ephemeral,
+                    iv,
+                    key,
+                } => match (identity.as_ref(), recipient.as_ref()) {
+                    (Some(identity), Some(recipient)) if recipient == slot_recipient => {
+                        let wrapping_key =
+                            identity.wrapping_key(ephemeral, data.cipher.key_len())?;
+                        Self::unlock_slot(&data, aad, &wrapping_key, iv, key)
+                    }
+                    _ => continue,
+                },
+            };
+
+            match result {
+                Ok((header_key, plain_secret)) => {
+                    debug!("unlocked with key slot {}", idx);
+
+                    let kdf = match slot {
+                        KeySlot::Password { kdf, .. } => kdf.clone(),
+                        KeySlot::Recipient { .. } => Kdf::None,
+                    };
+
+                    return Ok(Header {
+                        revision: 3,
+                        generation: data.generation,
+                        migrator,
+                        cipher: data.cipher,
+                        compression: data.compression,
+                        kdf,
+                        header_key,
+                        slots: data.slots.clone(),
+                        slot: Some(idx),
+                        data: plain_secret,
+                    });
+                }
+                Err(err @ (HeaderError::NotTrustworthy | HeaderError::WrongPassword)) => {
+                    debug!("key slot {} rejected: {}", idx, err);
+                    last_err = err;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(last_err)
+    }
+
+    fn unlock_slot(
+        data: &DataRev3,
+        aad: &[u8],
+        wrapping_key: &[u8],
+        iv: &[u8],
+        key: &[u8],
+    ) -> Result<(SecureVec, PlainSecret<B>), HeaderError> {
+        let mut ctx = Self::prepare_cipher_ctx(data.cipher, key);
+
+        let header_key = ctx
+            .decrypt(wrapping_key, iv)
+            .map(SecureVec::from_guarded)
+            .map_err(Self::map_not_trustworthy)?;
+        let plain_secret = Self::decrypt_rev3(data, aad, &header_key)?;
+
+        Ok((header_key, plain_secret))
+    }
+
+    fn decrypt_rev3(
+        data: &DataRev3,
+        aad: &[u8],
+        header_key: &[u8],
+    ) -> Result<PlainSecret<B>, HeaderError> {
+        let mut ctx = Self::prepare_cipher_ctx(data.cipher, &data.secret);
+
+        let pbuf = ctx
+            .decrypt_with_aad(aad, header_key, &data.iv)
+            .map_err(Self::map_not_trustworthy)?;
+
+        PlainSecret::from_buffer_rev3(&mut &pbuf[..])
+    }
+
+    fn map_not_trustworthy(err: CipherError) -> HeaderError {
+        match err {
+            CipherError::NotTrustworthy => HeaderError::NotTrustworthy,
+            _ => err.into(),
+        }
+    }
+
+    /// Sets the generation of the header.
+    ///
+    /// The generation is stored in the next [written](Self::write) header,
+    /// it selects the slots of the extended header blocks (see
+    /// [`ExtendedBlocks`]).
+    pub fn set_generation(&mut self, generation: u8) {
+        self.generation = generation;
+    }
+
+    /// Serializes the header.
+    ///
+    /// A revision 3 header, which does not fit into [`HEADER_MAX_SIZE`]
+    /// bytes, is continued in extended header blocks. The returned buffer
+    /// contains the root header followed by the extended header blocks, so
+    /// it can be larger than [`HEADER_MAX_SIZE`] bytes.
+    pub fn write(&mut self, store: &mut PasswordStore) -> Result<Vec<u8>, HeaderError> {
         let mut iv = vec![0; self.cipher.iv_len()];
         ossl::rand_bytes(&mut iv)?;
 
-        let mut pbuf: SecureVec = vec![].into();
-        self.data.to_buffer(pbuf.deref_mut())?;
+        let mut pbuf = SecureVec::new().into_guarded();
+        self.data.to_buffer(&mut pbuf)?;
 
-        let key = Self::create_key(self.cipher, &self.kdf, store)?;
+        let mut rev = match self.data {
+            PlainSecret::Rev0(_) => {
+                Revision::new_rev0(self.cipher, iv.clone(), self.kdf.clone(), vec![])
+            }
+            PlainSecret::Rev1(_) => {
+                Revision::new_rev1(self.cipher, iv.clone(), self.kdf.clone(), vec![])
+            }
+            PlainSecret::Rev2(_) => {
+                Revision::new_rev2(self.cipher, iv.clone(), self.kdf.clone(), vec![])
+            }
+            PlainSecret::Rev3(_) => {
+                self.wrap_slots(store)?;
+                Revision::new_rev3(
+                    self.cipher,
+                    self.compression,
+                    iv.clone(),
+                    self.slots.clone(),
+                    vec![],
+                )
+            }
+        };
+
+        // The number of extended header blocks is part of the additional
+        // data, thus it is evaluated before the secret is encrypted.
+        let secret_len = pbuf.len() + self.cipher.tag_size() as usize;
+        let blocks = self.count_extended_blocks(&rev, secret_len)?;
+
+        rev.set_blocks(blocks);
+        rev.set_generation(self.generation);
+
+        let aad = rev.aad()?;
+
+        let key = match self.data {
+            PlainSecret::Rev3(_) => self.header_key.clone(),
+            _ => Self::create_key(self.cipher, &self.kdf, store)?,
+        };
         let mut ctx = Self::prepare_cipher_ctx(self.cipher, &pbuf);
 
-        let cbuf = ctx.encrypt(&key, &iv)?;
-        let secret = cbuf.to_vec();
+        let cbuf = ctx.encrypt_with_aad(&aad, &key, &iv)?;
+        debug_assert_eq!(cbuf.len(), secret_len);
+        rev.set_secret(cbuf.to_vec());
+
+        let mut buf = vec![];
+        rev.put_into_buffer(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Evaluates the number of extended header blocks needed for `rev`
+    /// (which does not contain the secret yet) and a secret of `secret_len`
+    /// bytes.
+    fn count_extended_blocks(&self, rev: &Revision, secret_len: usize) -> Result<u8, HeaderError> {
+        let mut buf = vec![];
+        rev.put_into_buffer(&mut buf)?;
+
+        let len = buf.len() + secret_len;
+        let blocks = (len - 1) / HEADER_MAX_SIZE;
 
-        let rev = match self.data {
-            PlainSecret::Rev0(_) => Revision::new_rev0(self.cipher, iv, self.kdf.clone(), secret),
-            PlainSecret::Rev1(_) => Revision::new_rev1(self.cipher, iv, self.kdf.clone(), secret),
-            PlainSecret::Rev2(_) => Revision::new_rev2(self.cipher, iv, self.kdf.clone(), secret),
+        match self.data {
+            PlainSecret::Rev3(_) => u8::try_from(blocks).map_err(|_| HeaderError::TooLarge(len)),
+            _ if blocks == 0 => Ok(0),
+            _ => Err(HeaderError::TooLarge(len)),
+        }
+    }
+
+    /// Wraps the password slot, which unlocked the header, with the current
+    /// password and kdf.
+    ///
+    /// This is only necessary, if the slot was [reset](Self::reset_password_slot)
+    /// before, the kdf is not evaluated on every write.
+    fn wrap_slots(&mut self, store: &mut PasswordStore) -> Result<(), HeaderError> {
+        let idx = match self.slot {
+            Some(idx) => idx,
+            None => return Ok(()),
         };
 
-        rev.put_into_buffer(&mut &mut buf[..])
+        if let Some(KeySlot::Password { key, .. }) = self.slots.get(idx) {
+            if key.is_empty() {
+                self.slots[idx] = self.create_password_slot(&self.kdf, store.value()?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resets the password slot, which unlocked the header.
+    ///
+    /// The slot is wrapped again with the current password and kdf on the
+    /// next write.
+    pub fn reset_password_slot(&mut self) {
+        if let Some(idx) = self.slot {
+            if let Some(KeySlot::Password { .. }) = self.slots.get(idx) {
+                self.slots[idx] = KeySlot::password(self.kdf.clone(), vec![], vec![]);
+            }
+        }
+    }
+
+    fn create_password_slot(&self, kdf: &Kdf, password: &[u8]) -> Result<KeySlot, HeaderError> {
+        let wrapping_key = kdf.create_key(password, self.cipher.key_len())?;
+        let (iv, wrapped) = Self::wrap_header_key(self.cipher, &self.header_key, &wrapping_key)?;
+
+        Ok(KeySlot::password(kdf.clone(), iv, wrapped))
+    }
+
+    fn create_recipient_slot(
+        cipher: Cipher,
+        header_key: &[u8],
+        recipient: &Recipient,
+    ) -> Result<KeySlot, HeaderError> {
+        let (ephemeral, wrapping_key) = recipient.create_wrapping_key(cipher.key_len())?;
+        let (iv, wrapped) = Self::wrap_header_key(cipher, header_key, &wrapping_key)?;
+
+        Ok(KeySlot::recipient(
+            recipient.clone(),
+            ephemeral,
+            iv,
+            wrapped,
+        ))
+    }
+
+    fn wrap_header_key(
+        cipher: Cipher,
+        header_key: &[u8],
+        wrapping_key: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), HeaderError> {
+        let mut iv = vec![0; cipher.iv_len()];
+        ossl::rand_bytes(&mut iv)?;
+
+        let mut ctx = Self::prepare_cipher_ctx(cipher, header_key);
+        let wrapped = ctx.encrypt(wrapping_key, &iv)?.to_vec();
+
+        Ok((iv, wrapped))
     }
 
     pub fn migrate(&mut self) -> Result<(), HeaderError> {
@@ -260,6 +716,10 @@ impl<'a, B: Backend> Header<'a, B> {
         self.cipher
     }
 
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
     pub fn kdf(&self) -> &Kdf {
         &self.kdf
     }
@@ -267,17 +727,135 @@ impl<'a, B: Backend> Header<'a, B> {
     pub fn set_kdf(&mut self, kdf: Kdf) -> bool {
         if self.cipher != Cipher::None && kdf != Kdf::None {
             self.kdf = kdf;
+            self.reset_password_slot();
             true
         } else {
             false
         }
     }
 
+    /// Returns information about all key slots.
+    pub fn slots(&self) -> Vec<SlotInfo> {
+        self.slots
+            .iter()
+            .enumerate()
+            .map(|(idx, slot)| match slot {
+                KeySlot::Password { .. } if Some(idx) == self.slot => {
+                    SlotInfo::Password(self.kdf.clone())
+                }
+                KeySlot::Password { kdf, .. } => SlotInfo::Password(kdf.clone()),
+                KeySlot::Recipient { recipient, .. } => SlotInfo::Recipient(recipient.clone()),
+            })
+            .collect()
+    }
+
+    /// Returns the index of the key slot, which unlocked the header.
+    ///
+    /// `None` is returned, if the header has no key slots or the header was
+    /// not unlocked with a key slot.
+    pub fn active_slot(&self) -> Option<usize> {
+        self.slot
+    }
+
+    /// Returns the recovery key of the header.
+    ///
+    /// The recovery key is the header key, which unlocks the secret. It is
+    /// available starting with revision 3 for encrypted headers.
+    pub fn recovery_key(&self) -> Result<RecoveryKey, HeaderError> {
+        self.latest_revision_or_err()?;
+
+        if self.cipher == Cipher::None {
+            return Err(HeaderError::NotEncrypted);
+        }
+
+        Ok(RecoveryKey::new(&self.header_key))
+    }
+
+    /// Tests whether the password of the header can be changed.
+    ///
+    /// Starting with revision 3 this is only the case, if the header was
+    /// unlocked with a password slot.
+    pub fn password_slot_or_err(&self) -> Result<(), HeaderError> {
+        let ok = match self.data {
+            PlainSecret::Rev3(_) if self.cipher != Cipher::None => matches!(
+                self.slot.and_then(|idx| self.slots.get(idx)),
+                Some(KeySlot::Password { .. })
+            ),
+            _ => true,
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(HeaderError::NoPasswordSlot)
+        }
+    }
+
+    /// Adds a new key slot, which can be unlocked with the given `password`.
+    ///
+    /// Returns `false`, if the container is not encrypted or `kdf` is
+    /// [`Kdf::None`]. Key slots are available starting with revision 3.
+    pub fn add_slot(&mut self, kdf: Kdf, password: &[u8]) -> Result<bool, HeaderError> {
+        self.latest_revision_or_err()?;
+
+        if self.cipher == Cipher::None || kdf == Kdf::None {
+            return Ok(false);
+        }
+
+        let slot = self.create_password_slot(&kdf, password)?;
+        self.slots.push(slot);
+
+        Ok(true)
+    }
+
+    /// Adds a new key slot, which is wrapped to `recipient`.
+    ///
+    /// Returns `false`, if the container is not encrypted. Key slots are
+    /// available starting with revision 3.
+    pub fn add_recipient(&mut self, recipient: &Recipient) -> Result<bool, HeaderError> {
+        self.latest_revision_or_err()?;
+
+        if self.cipher == Cipher::None {
+            return Ok(false);
+        }
+
+        let slot = Self::create_recipient_slot(self.cipher, &self.header_key, recipient)?;
+        self.slots.push(slot);
+
+        Ok(true)
+    }
+
+    /// Removes the key slot at the given index.
+    ///
+    /// The slot, which unlocked the header, cannot be removed.
+    pub fn remove_slot(&mut self, idx: usize) -> Result<bool, HeaderError> {
+        self.latest_revision_or_err()?;
+
+        if idx >= self.slots.len() {
+            return Err(HeaderError::NoSuchKeySlot(idx));
+        }
+
+        if Some(idx) == self.slot {
+            return Err(HeaderError::ActiveKeySlot(idx));
+        }
+
+        self.slots.remove(idx);
+
+        if let Some(slot) = self.slot.as_mut() {
+            if idx < *slot {
+                *slot -= 1;
+            }
+        }
+
+        Ok(true)
+    }
+
     pub fn settings(&self) -> &B::Settings {
         match &self.data {
             PlainSecret::Rev0(rev0) => &rev0.settings,
             PlainSecret::Rev1(rev1) => &rev1.settings,
             PlainSecret::Rev2(rev2) => &rev2.settings,
+            PlainSecret::Rev3(rev3) => &rev3.settings,
         }
     }
 
@@ -286,23 +864,353 @@ impl<'a, B: Backend> Header<'a, B> {
             PlainSecret::Rev0(rev0) => &rev0.key,
             PlainSecret::Rev1(rev1) => &rev1.key,
             PlainSecret::Rev2(rev2) => &rev2.key,
+            PlainSecret::Rev3(rev3) => &rev3.key,
+        }
+    }
+
+    /// Returns the cipher, key and [iv](Self::iv) of the block with the given
+    /// `id`.
+    ///
+    /// This is the master-key unless a [rotation](Self::begin_rekey) is
+    /// pending. Then blocks, which are already re-encrypted, use the new
+    /// cipher and key. When reading a block pass its raw data in `ctext`, it
+    /// is needed to decide about the block, which was re-encrypted when the
+    /// rotation was interrupted.
+    pub fn block_key(&self, id: &[u8], ctext: Option<&[u8]>) -> (Cipher, &[u8], Option<&[u8]>) {
+        match self.rekey() {
+            Some(rekey) if rekey.is_done(id, ctext) => (rekey.cipher, &rekey.key, None),
+            _ => (self.cipher, self.key(), self.iv()),
         }
     }
 
-    pub fn iv(&self) -> &[u8] {
+    fn rekey(&self) -> Option<&Rekey> {
         match &self.data {
-            PlainSecret::Rev0(rev0) => &rev0.iv,
-            PlainSecret::Rev1(rev1) => &rev1.iv,
-            PlainSecret::Rev2(rev2) => &rev2.iv,
+            PlainSecret::Rev3(rev3) => rev3.rekey.as_ref(),
+            _ => None,
         }
     }
 
-    pub fn accept_sid_for_create(&self) -> Result<(), HeaderError> {
-        let sid_opt = match &self.data {
+    fn rekey_mut(&mut self) -> Option<&mut Rekey> {
+        match &mut self.data {
+            PlainSecret::Rev3(rev3) => rev3.rekey.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Starts a rotation of the master-key.
+    ///
+    /// A new master-key for `cipher` is generated, which is stored next to
+    /// the current master-key until the rotation is
+    /// [finished](Self::finish_rekey). If `cipher` differs from the cipher of
+    /// the header, the container is converted into `cipher`.
+    ///
+    /// The header key is replaced as well, thus all key slots must be
+    /// [convertible](Self::convertible_slots_or_err).
+    ///
+    /// A header of revision 0 to 2 is upgraded into revision 3. The
+    /// rotation re-encrypts the blocks into the layout of revision 3, the
+    /// container-wide iv is kept until the rotation is finished. The
+    /// password slot is wrapped with the current password on the next write.
+    ///
+    /// Returns `false`, if neither the container nor the target is encrypted
+    /// (and the header is not upgraded) or a rotation into `cipher` is
+    /// already pending.
+    pub fn begin_rekey(&mut self, cipher: Cipher) -> Result<bool, HeaderError> {
+        if self.revision != LATEST_REVISION {
+            return self.begin_upgrade(cipher);
+        }
+
+        if let Some(rekey) = self.rekey() {
+            return if rekey.cipher == cipher {
+                Ok(false)
+            } else {
+                Err(HeaderError::PendingConversion(rekey.cipher))
+            };
+        }
+
+        if self.cipher == Cipher::None && cipher == Cipher::None {
+            return Ok(false);
+        }
+
+        self.convertible_slots_or_err(cipher)?;
+
+        let mut key = SecureVec::zeroed_guarded(cipher.key_len());
+        ossl::rand_bytes(&mut key)?;
+
+        if let PlainSecret::Rev3(rev3) = &mut self.data {
+            rev3.rekey = Some(Rekey::new(cipher, key));
+        }
+
+        Ok(true)
+    }
+
+    /// Upgrades a header of revision 0 to 2 into revision 3 and starts a
+    /// rotation into `cipher`, see [`Header::begin_rekey`].
+    fn begin_upgrade(&mut self, cipher: Cipher) -> Result<bool, HeaderError> {
+        // A block of an unencrypted container has the same layout in all
+        // revisions, there is nothing to re-encrypt.
+        let rekey = if self.cipher == Cipher::None && cipher == Cipher::None {
+            None
+        } else {
+            let mut key = SecureVec::zeroed_guarded(cipher.key_len());
+            ossl::rand_bytes(&mut key)?;
+
+            Some(Rekey::new(cipher, key))
+        };
+
+        let mut header_key = SecureVec::zeroed_guarded(self.cipher.key_len());
+        ossl::rand_bytes(&mut header_key)?;
+
+        if self.cipher == Cipher::None {
+            self.slots = vec![];
+            self.slot = None;
+        } else {
+            // The slot is wrapped with the current password on the next
+            // write.
+            self.slots = vec![KeySlot::password(self.kdf.clone(), vec![], vec![])];
+            self.slot = Some(0);
+        }
+
+        self.data.convert_to_rev3(rekey);
+        self.revision = LATEST_REVISION;
+        self.header_key = header_key;
+
+        Ok(true)
+    }
+
+    /// Returns the target cipher of a pending rotation.
+    pub fn rekey_cipher(&self) -> Option<Cipher> {
+        self.rekey().map(|rekey| rekey.cipher)
+    }
+
+    /// Returns the id of the last re-encrypted block of a pending rotation.
+    ///
+    /// An empty id is returned, if no block was re-encrypted yet, `None` if
+    /// no rotation is pending.
+    pub fn rekey_cursor(&self) -> Option<&[u8]> {
+        self.rekey().map(|rekey| rekey.cursor.as_slice())
+    }
+
+    /// Tests whether the block with the given `id` and raw data `ctext` is
+    /// already re-encrypted with the new key.
+    pub fn rekey_done(&self, id: &[u8], ctext: &[u8]) -> bool {
+        match self.rekey() {
+            Some(rekey) => rekey.is_done(id, Some(ctext)),
+            None => false,
+        }
+    }
+
+    /// Records the progress of a pending rotation.
+    ///
+    /// All blocks up to `cursor` are re-encrypted, the block `inflight` is
+    /// re-encrypted next. `digest` is the SHA-256 digest of the raw data of
+    /// the inflight block before it is re-encrypted.
+    pub fn set_rekey_progress(&mut self, cursor: &[u8], inflight: &[u8], digest: &[u8]) -> bool {
+        match self.rekey_mut() {
+            Some(rekey) => {
+                rekey.cursor = cursor.to_vec();
+                rekey.inflight = inflight.to_vec();
+                rekey.digest = digest.to_vec();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Finishes a pending rotation, the new key replaces the master-key.
+    ///
+    /// The key slots are converted into the target cipher with a new header
+    /// key, even if the cipher does not change. Thus the old
+    /// [recovery key](Self::recovery_key) cannot unlock the header anymore.
+    /// `kdf` is only used, if the container was not encrypted before; it
+    /// protects the new password slot.
+    pub fn finish_rekey(&mut self, kdf: Kdf) -> Result<bool, HeaderError> {
+        let cipher = match self.rekey() {
+            Some(rekey) => rekey.cipher,
+            None => return Ok(false),
+        };
+
+        self.convertible_slots_or_err(cipher)?;
+        self.convert_slots(cipher, kdf)?;
+
+        if let PlainSecret::Rev3(rev3) = &mut self.data {
+            if let Some(rekey) = rev3.rekey.take() {
+                rev3.key = rekey.key;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Tests whether all key slots can be converted into `cipher`.
+    ///
+    /// Only the password slot, which unlocked the header, can be wrapped
+    /// again. The passwords of the other password slots are not known.
+    fn convertible_slots_or_err(&self, cipher: Cipher) -> Result<(), HeaderError> {
+        if cipher == Cipher::None {
+            return Ok(());
+        }
+
+        let inactive = self.slots.iter().enumerate().find(|(idx, slot)| {
+            Some(*idx) != self.slot && matches!(slot, KeySlot::Password { .. })
+        });
+
+        match inactive {
+            Some((idx, _)) => Err(HeaderError::InactiveKeySlot(idx)),
+            None => Ok(()),
+        }
+    }
+
+    /// Converts the key slots into `cipher`.
+    ///
+    /// A new header key is generated. The password slot is wrapped again on
+    /// the next write, the recipient slots are created again.
+    fn convert_slots(&mut self, cipher: Cipher, kdf: Kdf) -> Result<(), HeaderError> {
+        let mut header_key = SecureVec::zeroed_guarded(cipher.key_len());
+        ossl::rand_bytes(&mut header_key)?;
+
+        let mut slots = vec![];
+        let mut slot = None;
+
+        if cipher == Cipher::None {
+            self.kdf = Kdf::None;
+        } else if self.cipher == Cipher::None {
+            self.kdf = kdf;
+            slots.push(KeySlot::password(self.kdf.clone(), vec![], vec![]));
+            slot = Some(0);
+        } else {
+            for (idx, cur) in self.slots.iter().enumerate() {
+                if Some(idx) == self.slot {
+                    slot = Some(slots.len());
+                }
+
+                match cur {
+                    KeySlot::Password { .. } => {
+                        slots.push(KeySlot::password(self.kdf.clone(), vec![], vec![]))
+                    }
+                    KeySlot::Recipient { recipient, .. } => {
+                        slots.push(Self::create_recipient_slot(cipher, &header_key, recipient)?)
+                    }
+                }
+            }
+        }
+
+        self.cipher = cipher;
+        self.header_key = header_key;
+        self.slots = slots;
+        self.slot = slot;
+
+        Ok(())
+    }
+
+    /// Returns the first index block of the journal of a pending
+    /// transaction and whether the transaction is committed.
+    ///
+    /// `None` is returned, if no transaction is pending.
+    pub fn journal(&self) -> Option<(&[u8], bool)> {
+        match &self.data {
+            PlainSecret::Rev3(rev3) => rev3
+                .journal
+                .as_ref()
+                .map(|journal| (journal.id.as_slice(), journal.committed)),
+            _ => None,
+        }
+    }
+
+    /// Records the journal of a pending transaction.
+    ///
+    /// `id` is the first index block of the journal. Once a `committed`
+    /// journal is written, the transaction is replayed, if it is interrupted.
+    /// A journal is available starting with revision 3.
+    pub fn set_journal(&mut self, id: &[u8], committed: bool) -> Result<bool, HeaderError> {
+        self.latest_revision_or_err()?;
+
+        if let PlainSecret::Rev3(rev3) = &mut self.data {
+            rev3.journal = Some(Journal::new(id.to_vec(), committed));
+        }
+
+        Ok(true)
+    }
+
+    /// Removes the journal of a finished transaction.
+    pub fn clear_journal(&mut self) -> bool {
+        match &mut self.data {
+            PlainSecret::Rev3(rev3) => rev3.journal.take().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Returns the first block of the snapshot table.
+    ///
+    /// `None` is returned, if the container has no snapshot table.
+    pub fn snapshots(&self) -> Option<&[u8]> {
+        match &self.data {
+            PlainSecret::Rev3(rev3) => rev3.snapshots.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Updates the first block of the snapshot table.
+    ///
+    /// `None` removes the reference to the snapshot table. Snapshots are
+    /// available starting with revision 3.
+    pub fn set_snapshots(&mut self, id: Option<&[u8]>) -> Result<bool, HeaderError> {
+        self.latest_revision_or_err()?;
+
+        match &mut self.data {
+            PlainSecret::Rev3(rev3) => {
+                let id = id.map(|id| id.to_vec());
+                let changed = rev3.snapshots != id;
+
+                rev3.snapshots = id;
+
+                Ok(changed)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns the service identifier stored in the header.
+    pub fn sid(&self) -> Option<u32> {
+        match &self.data {
             PlainSecret::Rev0(rev0) => rev0.sid,
             PlainSecret::Rev1(_) => None,
             PlainSecret::Rev2(rev2) => rev2.sid,
-        };
+            PlainSecret::Rev3(rev3) => rev3.sid,
+        }
+    }
+
+    /// Returns the container-wide iv.
+    ///
+    /// Starting with revision 3 there is no container-wide iv anymore. Each
+    /// block stores its own random nonce, `None` is returned in this case.
+    /// Returns the container-wide iv of the blocks, which are encrypted with
+    /// the master-key.
+    ///
+    /// Up to revision 2 all blocks share the iv of the header. Starting with
+    /// revision 3 each block stores its own nonce, `None` is returned. Only
+    /// while an [upgrade](Self::begin_rekey) from revision 0 to 2 is pending,
+    /// the blocks, which are not re-encrypted yet, still use the iv of the
+    /// old header.
+    pub fn iv(&self) -> Option<&[u8]> {
+        match &self.data {
+            PlainSecret::Rev0(rev0) => Some(&rev0.iv),
+            PlainSecret::Rev1(rev1) => Some(&rev1.iv),
+            PlainSecret::Rev2(rev2) => Some(&rev2.iv),
+            PlainSecret::Rev3(rev3) => rev3.rekey.as_ref().and_then(|rekey| rekey.iv.as_deref()),
+        }
+    }
+
+    /// Tests whether a block is bound to its id.
+    ///
+    /// Starting with revision 3 the id of a block is part of its encryption,
+    /// thus an aquired block is written again with its final id.
+    pub fn binds_blocks(&self) -> bool {
+        self.revision == LATEST_REVISION
+    }
+
+    pub fn accept_sid_for_create(&self) -> Result<(), HeaderError> {
+        let sid_opt = self.sid();
 
         if sid_opt.is_none() {
             Ok(())
@@ -344,6 +1252,7 @@ impl<'a, B: Backend> Header<'a, B> {
                 Ok(())
             }
             PlainSecret::Rev2(rev2) => accecpt(rev2.sid),
+            PlainSecret::Rev3(rev3) => accecpt(rev3.sid),
         }
     }
 
@@ -359,6 +1268,14 @@ impl<'a, B: Backend> Header<'a, B> {
                     Err(HeaderError::InvalidSid)
                 }
             }
+            PlainSecret::Rev3(rev3) => {
+                if sid > 0 {
+                    rev3.sid = Some(sid);
+                    Ok(())
+                } else {
+                    Err(HeaderError::InvalidSid)
+                }
+            }
         }
     }
 
@@ -367,6 +1284,7 @@ impl<'a, B: Backend> Header<'a, B> {
             PlainSecret::Rev0(rev0) => rev0.top_id.as_ref(),
             PlainSecret::Rev1(rev1) => rev1.top_id.as_ref(),
             PlainSecret::Rev2(rev2) => rev2.top_id.as_ref(),
+            PlainSecret::Rev3(rev3) => rev3.top_id.as_ref(),
         }
     }
 
@@ -375,6 +1293,7 @@ impl<'a, B: Backend> Header<'a, B> {
             PlainSecret::Rev0(_) => panic!("storing a top-id into a rev0 header is not supported"),
             PlainSecret::Rev1(_) => panic!("storing a top-id into a rev1 header is not supported"),
             PlainSecret::Rev2(rev2) => rev2.top_id = Some(id),
+            PlainSecret::Rev3(rev3) => rev3.top_id = Some(id),
         }
     }
 
@@ -382,12 +1301,18 @@ impl<'a, B: Backend> Header<'a, B> {
         self.migrator = migrator;
     }
 
-    pub fn convert_to_latest(&mut self, sid: u32) -> bool {
-        self.data.convert_to_latest(sid)
+    pub fn convert_to_rev2(&mut self, sid: u32) -> bool {
+        let changed = self.data.convert_to_rev2(sid);
+
+        if changed {
+            self.revision = 2;
+        }
+
+        changed
     }
 
     fn prepare_cipher_ctx(cipher: Cipher, input: &[u8]) -> CipherContext {
-        let mut ctx = CipherContext::new(cipher);
+        let mut ctx = CipherContext::guarded(cipher);
 
         ctx.copy_from_slice(input.len(), input);
 
@@ -403,18 +1328,22 @@ impl<'a, B: Backend> Header<'a, B> {
             let password = store.value()?;
             Ok(kdf.create_key(password, cipher.key_len())?)
         } else {
-            Ok(vec![].into())
+            Ok(SecureVec::new())
         }
     }
 }
 
-impl<'a, B: Backend> fmt::Debug for Header<'a, B> {
+impl<'a, B: BackendTypes> fmt::Debug for Header<'a, B> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Header")
             .field("revision", &self.revision)
+            .field("generation", &self.generation)
             .field("migrator", &self.migrator)
             .field("cipher", &self.cipher)
+            .field("compression", &self.compression)
             .field("kdf", &self.kdf)
+            .field("slots", &self.slots)
+            .field("slot", &self.slot)
             .field("data", &self.data)
             .finish()
     }
@@ -23,38 +23,46 @@
 #[cfg(test)]
 mod tests;
 
-use nuts_backend::{Backend, Binary};
+use nuts_backend::{BackendTypes, Binary};
 use openssl::error::ErrorStack;
+use openssl::sha::sha256;
 use std::fmt::{self, Write};
 
 use crate::buffer::{Buffer, BufferError, BufferMut, ToBuffer};
+use crate::cipher::Cipher;
 use crate::header::HeaderError;
 use crate::migrate::Migrator;
 use crate::ossl;
 use crate::svec::SecureVec;
 
-fn fmt_key_iv(key: &[u8], iv: &[u8]) -> Result<(String, String), fmt::Error> {
+fn fmt_key(key: &[u8]) -> Result<String, fmt::Error> {
     if cfg!(feature = "debug-plain-keys") {
-        let mut key_out = String::with_capacity(2 * key.len());
-        let mut iv_out = String::with_capacity(2 * iv.len());
+        let mut out = String::with_capacity(2 * key.len());
 
         for n in key.iter() {
-            write!(key_out, "{:02x}", n)?;
-        }
-
-        for n in iv.iter() {
-            write!(iv_out, "{:02x}", n)?;
+            write!(out, "{:02x}", n)?;
         }
 
-        Ok((key_out, iv_out))
+        Ok(out)
     } else {
-        Ok((
-            format!("<{} bytes>", key.len()),
-            format!("<{} bytes>", iv.len()),
-        ))
+        Ok(format!("<{} bytes>", key.len()))
     }
 }
 
+fn fmt_key_iv(key: &[u8], iv: &[u8]) -> Result<(String, String), fmt::Error> {
+    Ok((fmt_key(key)?, fmt_key(iv)?))
+}
+
+/// Reads key material from `buf` into a guarded [`SecureVec`].
+///
+/// The key is copied directly into the guarded region, there is no
+/// intermediate copy on the heap.
+fn get_key<T: Buffer, const L: usize>(buf: &mut T) -> Result<SecureVec, BufferError> {
+    let len = buf.get_vec_len::<L>()?;
+
+    buf.get_chunk(len).map(SecureVec::from_guarded)
+}
+
 // ** plain-secret history **
 //
 // * rev 0
@@ -70,6 +78,13 @@ fn fmt_key_iv(key: &[u8], iv: &[u8]) -> Result<(String, String), fmt::Error> {
 // * rev 2
 //
 // - sid inserted
+//
+// * rev 3
+//
+// - iv field removed, each block stores its own random nonce
+// - rekey field inserted
+// - journal field inserted
+// - snapshots field inserted
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Magics([u32; 2]);
@@ -104,7 +119,7 @@ impl From<u32> for Magics {
     }
 }
 
-pub struct PlainRev0<B: Backend> {
+pub struct PlainRev0<B: BackendTypes> {
     pub magics: Magics,
     pub key: SecureVec,
     pub iv: SecureVec,
@@ -114,7 +129,7 @@ pub struct PlainRev0<B: Backend> {
     pub top_id: Option<B::Id>, // transient, migrated from userdata attribute
 }
 
-impl<B: Backend> PlainRev0<B> {
+impl<B: BackendTypes> PlainRev0<B> {
     pub fn migrate(&mut self, migrator: &Migrator) -> Result<(), HeaderError> {
         if let Some((sid, top_id_bytes)) = migrator.migrate_rev0(&self.userdata)? {
             self.sid = Some(sid);
@@ -129,7 +144,7 @@ impl<B: Backend> PlainRev0<B> {
     }
 }
 
-impl<B: Backend> PartialEq for PlainRev0<B> {
+impl<B: BackendTypes> PartialEq for PlainRev0<B> {
     fn eq(&self, other: &PlainRev0<B>) -> bool {
         let lhs_settings_bytes = self.settings.as_bytes();
         let rhs_settings_bytes = other.settings.as_bytes();
@@ -144,7 +159,7 @@ impl<B: Backend> PartialEq for PlainRev0<B> {
     }
 }
 
-impl<B: Backend> fmt::Debug for PlainRev0<B> {
+impl<B: BackendTypes> fmt::Debug for PlainRev0<B> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let (key, iv) = fmt_key_iv(&self.key, &self.iv)?;
 
@@ -160,7 +175,7 @@ impl<B: Backend> fmt::Debug for PlainRev0<B> {
     }
 }
 
-pub struct PlainRev1<B: Backend> {
+pub struct PlainRev1<B: BackendTypes> {
     pub magics: Magics,
     pub key: SecureVec,
     pub iv: SecureVec,
@@ -168,7 +183,7 @@ pub struct PlainRev1<B: Backend> {
     pub settings: B::Settings,
 }
 
-impl<B: Backend> PartialEq for PlainRev1<B> {
+impl<B: BackendTypes> PartialEq for PlainRev1<B> {
     fn eq(&self, other: &PlainRev1<B>) -> bool {
         let lhs_settings_bytes = self.settings.as_bytes();
         let rhs_settings_bytes = other.settings.as_bytes();
@@ -181,7 +196,7 @@ impl<B: Backend> PartialEq for PlainRev1<B> {
     }
 }
 
-impl<B: Backend> fmt::Debug for PlainRev1<B> {
+impl<B: BackendTypes> fmt::Debug for PlainRev1<B> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let (key, iv) = fmt_key_iv(&self.key, &self.iv)?;
 
@@ -195,7 +210,7 @@ impl<B: Backend> fmt::Debug for PlainRev1<B> {
     }
 }
 
-pub struct PlainRev2<B: Backend> {
+pub struct PlainRev2<B: BackendTypes> {
     pub magics: Magics,
     pub key: SecureVec,
     pub iv: SecureVec,
@@ -204,7 +219,7 @@ pub struct PlainRev2<B: Backend> {
     pub settings: B::Settings,
 }
 
-impl<B: Backend> PartialEq for PlainRev2<B> {
+impl<B: BackendTypes> PartialEq for PlainRev2<B> {
     fn eq(&self, other: &PlainRev2<B>) -> bool {
         let lhs_settings_bytes = self.settings.as_bytes();
         let rhs_settings_bytes = other.settings.as_bytes();
@@ -218,7 +233,7 @@ impl<B: Backend> PartialEq for PlainRev2<B> {
     }
 }
 
-impl<B: Backend> fmt::Debug for PlainRev2<B> {
+impl<B: BackendTypes> fmt::Debug for PlainRev2<B> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let (key, iv) = fmt_key_iv(&self.key, &self.iv)?;
 
@@ -233,19 +248,212 @@ impl<B: Backend> fmt::Debug for PlainRev2<B> {
     }
 }
 
+/// State of a pending rotation of the master-key.
+///
+/// The blocks are re-encrypted with `key` using `cipher`. If `cipher` differs
+/// from the cipher of the header, the rotation converts the container into
+/// another cipher.
+///
+/// Blocks are re-encrypted in the order of their binary ids. `cursor` is the
+/// id of the last re-encrypted block, `inflight` the block, which is
+/// currently re-encrypted. `digest` is the SHA-256 digest of the inflight
+/// block before it was re-encrypted.
+///
+/// If the rotation upgrades a container of revision 0 to 2, `iv` is the
+/// container-wide iv of the blocks, which are not re-encrypted yet.
+#[derive(Clone, PartialEq)]
+pub struct Rekey {
+    pub cipher: Cipher,
+    pub key: SecureVec,
+    pub cursor: Vec<u8>,
+    pub inflight: Vec<u8>,
+    pub digest: Vec<u8>,
+    pub iv: Option<SecureVec>,
+}
+
+impl Rekey {
+    pub fn new(cipher: Cipher, key: SecureVec) -> Rekey {
+        Rekey {
+            cipher,
+            key: key.into_guarded(),
+            cursor: vec![],
+            inflight: vec![],
+            digest: vec![],
+            iv: None,
+        }
+    }
+
+    /// Tests whether the block with the given `id` is already encrypted with
+    /// the new key.
+    ///
+    /// If the raw block data `ctext` is not available, the inflight block is
+    /// assumed to be re-encrypted.
+    pub fn is_done(&self, id: &[u8], ctext: Option<&[u8]>) -> bool {
+        if !self.inflight.is_empty() && self.inflight == id {
+            match ctext {
+                Some(buf) => sha256(buf)[..] != self.digest[..],
+                None => true,
+            }
+        } else {
+            id <= &self.cursor[..]
+        }
+    }
+
+    fn get<T: Buffer>(buf: &mut T) -> Result<Option<Rekey>, HeaderError> {
+        let b = buf.get_u8()?;
+
+        match b {
+            0 => Ok(None),
+            1 | 2 => Ok(Some(Rekey {
+                cipher: Cipher::get_from_buffer(buf)?,
+                key: get_key::<_, 1>(buf)?,
+                cursor: buf.get_vec::<1>()?,
+                inflight: buf.get_vec::<1>()?,
+                digest: buf.get_vec::<1>()?,
+                iv: if b == 2 {
+                    Some(get_key::<_, 1>(buf)?)
+                } else {
+                    None
+                },
+            })),
+            _ => Err(BufferError::InvalidIndex("Rekey".to_string(), b as u32).into()),
+        }
+    }
+
+    fn put<T: BufferMut>(rekey: Option<&Rekey>, buf: &mut T) -> Result<(), BufferError> {
+        match rekey {
+            Some(rekey) => {
+                buf.put_u8(if rekey.iv.is_some() { 2 } else { 1 })?;
+                rekey.cipher.put_into_buffer(buf)?;
+                buf.put_vec::<1>(&rekey.key)?;
+                buf.put_vec::<1>(&rekey.cursor)?;
+                buf.put_vec::<1>(&rekey.inflight)?;
+                buf.put_vec::<1>(&rekey.digest)?;
+
+                if let Some(iv) = rekey.iv.as_ref() {
+                    buf.put_vec::<1>(iv)?;
+                }
+            }
+            None => buf.put_u8(0)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Rekey {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let key = fmt_key(&self.key)?;
+        let iv = self.iv.as_deref().map(fmt_key).transpose()?;
+
+        fmt.debug_struct("Rekey")
+            .field("cipher", &self.cipher)
+            .field("key", &key)
+            .field("cursor", &self.cursor)
+            .field("inflight", &self.inflight)
+            .field("digest", &self.digest)
+            .field("iv", &iv)
+            .finish()
+    }
+}
+
+/// Reference to the journal of a pending transaction.
+///
+/// `id` is the first index block of the journal. A `committed` journal is
+/// replayed, when the container is opened, otherwise the journal is
+/// discarded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Journal {
+    pub id: Vec<u8>,
+    pub committed: bool,
+}
+
+impl Journal {
+    pub fn new(id: Vec<u8>, committed: bool) -> Journal {
+        Journal { id, committed }
+    }
+
+    fn get<T: Buffer>(buf: &mut T) -> Result<Option<Journal>, HeaderError> {
+        let b = buf.get_u8()?;
+
+        match b {
+            0 => Ok(None),
+            1 | 2 => Ok(Some(Journal::new(buf.get_vec::<1>()?, b == 2))),
+            _ => Err(BufferError::InvalidIndex("Journal".to_string(), b as u32).into()),
+        }
+    }
+
+    fn put<T: BufferMut>(journal: Option<&Journal>, buf: &mut T) -> Result<(), BufferError> {
+        match journal {
+            Some(journal) => {
+                buf.put_u8(if journal.committed { 2 } else { 1 })?;
+                buf.put_vec::<1>(&journal.id)?;
+            }
+            None => buf.put_u8(0)?,
+        }
+
+        Ok(())
+    }
+}
+
+pub struct PlainRev3<B: BackendTypes> {
+    pub magics: Magics,
+    pub key: SecureVec,
+    pub sid: Option<u32>,
+    pub top_id: Option<B::Id>,
+    pub settings: B::Settings,
+    pub rekey: Option<Rekey>,
+    pub journal: Option<Journal>,
+    pub snapshots: Option<Vec<u8>>,
+}
+
+impl<B: BackendTypes> PartialEq for PlainRev3<B> {
+    fn eq(&self, other: &PlainRev3<B>) -> bool {
+        let lhs_settings_bytes = self.settings.as_bytes();
+        let rhs_settings_bytes = other.settings.as_bytes();
+
+        self.magics == other.magics
+            && self.key == other.key
+            && self.sid == other.sid
+            && self.top_id == other.top_id
+            && lhs_settings_bytes == rhs_settings_bytes
+            && self.rekey == other.rekey
+            && self.journal == other.journal
+            && self.snapshots == other.snapshots
+    }
+}
+
+impl<B: BackendTypes> fmt::Debug for PlainRev3<B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let key = fmt_key(&self.key)?;
+
+        fmt.debug_struct("PlainRev3")
+            .field("magics", &self.magics)
+            .field("key", &key)
+            .field("sid", &self.sid)
+            .field("top_id", &self.top_id.as_ref().map(ToString::to_string))
+            .field("settings", &self.settings.as_bytes())
+            .field("rekey", &self.rekey)
+            .field("journal", &self.journal)
+            .field("snapshots", &self.snapshots)
+            .finish()
+    }
+}
+
 #[derive(PartialEq)]
-pub enum PlainSecret<B: Backend> {
+pub enum PlainSecret<B: BackendTypes> {
     Rev0(PlainRev0<B>),
     Rev1(PlainRev1<B>),
     Rev2(PlainRev2<B>),
+    Rev3(PlainRev3<B>),
 }
 
-impl<B: Backend> PlainSecret<B> {
+impl<B: BackendTypes> PlainSecret<B> {
     pub fn from_buffer_rev0<T: Buffer>(buf: &mut T) -> Result<PlainSecret<B>, HeaderError> {
         let magics = Magics::get_and_validate(buf)?;
-        let key = buf.get_vec::<8>()?.into();
-        let iv = buf.get_vec::<8>()?.into();
-        let userdata = buf.get_vec::<8>()?.into();
+        let key = get_key::<_, 8>(buf)?;
+        let iv = get_key::<_, 8>(buf)?;
+        let userdata = get_key::<_, 8>(buf)?;
         let settings_bytes: SecureVec = buf.get_vec::<8>()?.into();
 
         let settings = Binary::from_bytes(&settings_bytes).ok_or(HeaderError::InvalidSettings)?;
@@ -263,8 +471,8 @@ impl<B: Backend> PlainSecret<B> {
 
     pub fn from_buffer_rev1<T: Buffer>(buf: &mut T) -> Result<PlainSecret<B>, HeaderError> {
         let magics = Magics::get_and_validate(buf)?;
-        let key = buf.get_vec::<1>()?.into();
-        let iv = buf.get_vec::<1>()?.into();
+        let key = get_key::<_, 1>(buf)?;
+        let iv = get_key::<_, 1>(buf)?;
         let top_id_bytes: SecureVec = buf.get_vec::<1>()?.into();
         let settings_bytes: SecureVec = buf.get_vec::<2>()?.into();
 
@@ -287,8 +495,8 @@ impl<B: Backend> PlainSecret<B> {
 
     pub fn from_buffer_rev2<T: Buffer>(buf: &mut T) -> Result<PlainSecret<B>, HeaderError> {
         let magics = Magics::get_and_validate(buf)?;
-        let key = buf.get_vec::<1>()?.into();
-        let iv = buf.get_vec::<1>()?.into();
+        let key = get_key::<_, 1>(buf)?;
+        let iv = get_key::<_, 1>(buf)?;
         let sid_raw = buf.get_u32()?;
         let top_id_bytes: SecureVec = buf.get_vec::<1>()?.into();
         let settings_bytes: SecureVec = buf.get_vec::<2>()?.into();
@@ -313,24 +521,60 @@ impl<B: Backend> PlainSecret<B> {
         }))
     }
 
+    pub fn from_buffer_rev3<T: Buffer>(buf: &mut T) -> Result<PlainSecret<B>, HeaderError> {
+        let magics = Magics::get_and_validate(buf)?;
+        let key = get_key::<_, 1>(buf)?;
+        let sid_raw = buf.get_u32()?;
+        let top_id_bytes: SecureVec = buf.get_vec::<1>()?.into();
+        let settings_bytes: SecureVec = buf.get_vec::<2>()?.into();
+
+        let sid = if sid_raw > 0 { Some(sid_raw) } else { None };
+
+        let top_id = if !top_id_bytes.is_empty() {
+            Some(Binary::from_bytes(&top_id_bytes).ok_or(HeaderError::InvalidTopId)?)
+        } else {
+            None
+        };
+
+        let settings = Binary::from_bytes(&settings_bytes).ok_or(HeaderError::InvalidSettings)?;
+        let rekey = Rekey::get(buf)?;
+        let journal = Journal::get(buf)?;
+        let snapshots = match buf.get_vec::<1>()? {
+            bytes if bytes.is_empty() => None,
+            bytes => Some(bytes),
+        };
+
+        Ok(PlainSecret::Rev3(PlainRev3 {
+            magics,
+            key,
+            sid,
+            top_id,
+            settings,
+            rekey,
+            journal,
+            snapshots,
+        }))
+    }
+
     pub fn create_latest(
         key: SecureVec,
-        iv: SecureVec,
         settings: B::Settings,
     ) -> Result<(u32, PlainSecret<B>), ErrorStack> {
-        let rev = Self::Rev2(PlainRev2 {
+        let rev = Self::Rev3(PlainRev3 {
             magics: Magics::generate()?,
-            key,
-            iv,
+            key: key.into_guarded(),
             sid: None,
             top_id: None,
             settings,
+            rekey: None,
+            journal: None,
+            snapshots: None,
         });
 
-        Ok((2, rev))
+        Ok((3, rev))
     }
 
-    pub fn convert_to_latest(&mut self, sid: u32) -> bool {
+    pub fn convert_to_rev2(&mut self, sid: u32) -> bool {
         match self {
             PlainSecret::Rev0(rev0) => {
                 assert_eq!(rev0.sid, Some(sid));
@@ -358,12 +602,66 @@ impl<B: Backend> PlainSecret<B> {
 
                 true
             }
-            PlainSecret::Rev2(_) => false,
+            PlainSecret::Rev2(_) | PlainSecret::Rev3(_) => false,
         }
     }
+
+    /// Converts a secret of revision 0 to 2 into revision 3.
+    ///
+    /// The master-key is kept, `rekey` is the pending rotation, which
+    /// re-encrypts the blocks into the layout of revision 3. It receives the
+    /// container-wide iv of the blocks. Returns `false`, if the secret
+    /// already has revision 3.
+    pub fn convert_to_rev3(&mut self, rekey: Option<Rekey>) -> bool {
+        let (magics, key, iv, sid, top_id, settings) = match self {
+            PlainSecret::Rev0(rev0) => (
+                &rev0.magics,
+                &rev0.key,
+                &rev0.iv,
+                rev0.sid,
+                &rev0.top_id,
+                &rev0.settings,
+            ),
+            PlainSecret::Rev1(rev1) => (
+                &rev1.magics,
+                &rev1.key,
+                &rev1.iv,
+                None,
+                &rev1.top_id,
+                &rev1.settings,
+            ),
+            PlainSecret::Rev2(rev2) => (
+                &rev2.magics,
+                &rev2.key,
+                &rev2.iv,
+                rev2.sid,
+                &rev2.top_id,
+                &rev2.settings,
+            ),
+            PlainSecret::Rev3(_) => return false,
+        };
+
+        let rekey = rekey.map(|rekey| Rekey {
+            iv: Some(iv.clone().into_guarded()),
+            ..rekey
+        });
+
+        *self = Self::Rev3(PlainRev3 {
+            magics: magics.clone(),
+            key: key.clone().into_guarded(),
+            sid,
+            top_id: top_id.clone(),
+            settings: settings.clone(),
+            rekey,
+            journal: None,
+            snapshots: None,
+        });
+
+        true
+    }
 }
 
-impl<B: Backend> ToBuffer for PlainSecret<B> {
+impl<B: BackendTypes> ToBuffer for PlainSecret<B> {
     fn to_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), BufferError> {
         match self {
             PlainSecret::Rev0(rev0) => {
@@ -402,18 +700,42 @@ impl<B: Backend> ToBuffer for PlainSecret<B> {
 
                 buf.put_vec::<2>(&rev2.settings.as_bytes())?;
             }
+            PlainSecret::Rev3(rev3) => {
+                rev3.magics.put(buf)?;
+                buf.put_vec::<1>(&rev3.key)?;
+
+                match rev3.sid {
+                    Some(n) => buf.put_u32(n)?,
+                    None => buf.put_u32(0)?,
+                }
+
+                match rev3.top_id.as_ref() {
+                    Some(id) => buf.put_vec::<1>(&id.as_bytes())?,
+                    None => buf.put_vec::<1>(&[])?,
+                }
+
+                buf.put_vec::<2>(&rev3.settings.as_bytes())?;
+                Rekey::put(rev3.rekey.as_ref(), buf)?;
+                Journal::put(rev3.journal.as_ref(), buf)?;
+
+                match rev3.snapshots.as_ref() {
+                    Some(id) => buf.put_vec::<1>(id)?,
+                    None => buf.put_vec::<1>(&[])?,
+                }
+            }
         }
 
         Ok(())
     }
 }
 
-impl<B: Backend> fmt::Debug for PlainSecret<B> {
+impl<B: BackendTypes> fmt::Debug for PlainSecret<B> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Rev0(rev0) => fmt.debug_tuple("Rev0").field(rev0).finish(),
             Self::Rev1(rev1) => fmt.debug_tuple("Rev1").field(rev1).finish(),
             Self::Rev2(rev2) => fmt.debug_tuple("Rev2").field(rev2).finish(),
+            Self::Rev3(rev3) => fmt.debug_tuple("Rev3").field(rev3).finish(),
         }
     }
 }
@@ -23,11 +23,15 @@
 mod convert;
 mod from_buffer;
 mod migrate;
+mod rekey;
 mod to_buffer;
 
 use nuts_memory::{MemoryBackend, Settings};
 
-use crate::header::plain_secret::{Magics, PlainRev0, PlainRev1, PlainRev2, PlainSecret};
+use crate::cipher::Cipher;
+use crate::header::plain_secret::{
+    Journal, Magics, PlainRev0, PlainRev1, PlainRev2, PlainRev3, PlainSecret, Rekey,
+};
 use crate::migrate::Migration;
 
 const REV0: [u8; 49] = [
@@ -87,6 +91,102 @@ const REV2_NONE: [u8; 22] = [
     0, 0, // settings
 ];
 
+const REV3_SID: [u8; 21] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0x12, 0x67, // sid
+    0,    // top-id
+    0, 0, // settings
+    0, // rekey
+    0, // journal
+    0, // snapshots
+];
+
+const REV3_TOP_ID: [u8; 25] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0, 0, // sid
+    4, 0, 0, 2, 154, // top-id
+    0, 0, // settings
+    0, // rekey
+    0, // journal
+    0, // snapshots
+];
+
+const REV3_NONE: [u8; 21] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0, 0, // sid
+    0, // top-id
+    0, 0, // settings
+    0, // rekey
+    0, // journal
+    0, // snapshots
+];
+
+const REV3_REKEY: [u8; 35] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0, 0, // sid
+    0, // top-id
+    0, 0, // settings
+    1, // rekey
+    0, 0, 0, 1, // rekey: cipher
+    2, 3, 4, // rekey: key
+    1, 5, // rekey: cursor
+    1, 6, // rekey: inflight
+    2, 7, 8, // rekey: digest
+    0, // journal
+    0, // snapshots
+];
+
+const REV3_REKEY_IV: [u8; 38] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0, 0, // sid
+    0, // top-id
+    0, 0, // settings
+    2, // rekey
+    0, 0, 0, 1, // rekey: cipher
+    2, 3, 4, // rekey: key
+    1, 5, // rekey: cursor
+    1, 6, // rekey: inflight
+    2, 7, 8, // rekey: digest
+    2, 9, 10, // rekey: iv
+    0,  // journal
+    0,  // snapshots
+];
+
+const REV3_JOURNAL: [u8; 26] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0, 0, // sid
+    0, // top-id
+    0, 0, // settings
+    0, // rekey
+    2, // journal
+    4, 0, 0, 2, 154, // journal: id
+    0,   // snapshots
+];
+
+const REV3_SNAPSHOTS: [u8; 25] = [
+    0x00, 0x00, 0x12, 0x67, // magic1
+    0x00, 0x00, 0x12, 0x67, // magic2
+    2, 1, 2, // key
+    0, 0, 0, 0, // sid
+    0, // top-id
+    0, 0, // settings
+    0, // rekey
+    0, // journal
+    4, 0, 0, 2, 154, // snapshots
+];
+
 fn rev0() -> PlainRev0<MemoryBackend> {
     PlainRev0 {
         magics: Magics([4711, 4711]),
@@ -127,6 +227,61 @@ fn rev2(sid: Option<u32>, top_id: Option<&str>) -> PlainRev2<MemoryBackend> {
     }
 }
 
+fn rev3(sid: Option<u32>, top_id: Option<&str>) -> PlainRev3<MemoryBackend> {
+    PlainRev3 {
+        magics: Magics([4711, 4711]),
+        key: vec![1, 2].into(),
+        sid,
+        top_id: top_id.map(|id| id.parse().unwrap()),
+        settings: Settings,
+        rekey: None,
+        journal: None,
+        snapshots: None,
+    }
+}
+
+fn rev3_rekey() -> PlainRev3<MemoryBackend> {
+    PlainRev3 {
+        rekey: Some(Rekey {
+            cipher: Cipher::Aes128Ctr,
+            key: vec![3, 4].into(),
+            cursor: vec![5],
+            inflight: vec![6],
+            digest: vec![7, 8],
+            iv: None,
+        }),
+        ..rev3(None, None)
+    }
+}
+
+fn rev3_rekey_iv() -> PlainRev3<MemoryBackend> {
+    PlainRev3 {
+        rekey: Some(Rekey {
+            cipher: Cipher::Aes128Ctr,
+            key: vec![3, 4].into(),
+            cursor: vec![5],
+            inflight: vec![6],
+            digest: vec![7, 8],
+            iv: Some(vec![9, 10].into()),
+        }),
+        ..rev3(None, None)
+    }
+}
+
+fn rev3_journal() -> PlainRev3<MemoryBackend> {
+    PlainRev3 {
+        journal: Some(Journal::new(vec![0, 0, 2, 154], true)),
+        ..rev3(None, None)
+    }
+}
+
+fn rev3_snapshots() -> PlainRev3<MemoryBackend> {
+    PlainRev3 {
+        snapshots: Some(vec![0, 0, 2, 154]),
+        ..rev3(None, None)
+    }
+}
+
 struct SampleMigration;
 
 impl Migration for SampleMigration {
@@ -147,18 +302,19 @@ impl Migration for ErrMigration {
 #[test]
 fn create_latest() {
     let (revision, plain_secret) =
-        PlainSecret::<MemoryBackend>::create_latest(vec![1].into(), vec![2, 3].into(), Settings)
-            .unwrap();
+        PlainSecret::<MemoryBackend>::create_latest(vec![1].into(), Settings).unwrap();
 
-    let expected = PlainRev2::<MemoryBackend> {
+    let expected = PlainRev3::<MemoryBackend> {
         magics: Magics([0x91C0B2CF; 2]),
         key: vec![1].into(),
-        iv: vec![2, 3].into(),
         sid: None,
         top_id: None,
         settings: Settings,
+        rekey: None,
+        journal: None,
+        snapshots: None,
     };
 
-    assert_eq!(revision, 2);
-    assert!(matches!(plain_secret, PlainSecret::Rev2(data) if data == expected));
+    assert_eq!(revision, 3);
+    assert!(matches!(plain_secret, PlainSecret::Rev3(data) if data == expected));
 }
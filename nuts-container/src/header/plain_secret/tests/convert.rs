@@ -22,8 +22,9 @@
 
 use nuts_memory::Settings;
 
-use crate::header::plain_secret::tests::{rev0, rev1, rev2};
-use crate::header::plain_secret::{PlainRev0, PlainRev1, PlainSecret};
+use crate::cipher::Cipher;
+use crate::header::plain_secret::tests::{rev0, rev1, rev2, rev3};
+use crate::header::plain_secret::{PlainRev0, PlainRev1, PlainSecret, Rekey};
 
 #[test]
 fn rev0_no_top_id() {
@@ -32,7 +33,7 @@ fn rev0_no_top_id() {
         ..rev0()
     });
 
-    assert!(plain_secret.convert_to_latest(666));
+    assert!(plain_secret.convert_to_rev2(666));
 
     assert!(matches!(plain_secret, PlainSecret::Rev2(rev2)
         if rev2.magics == 4711.into() &&
@@ -51,7 +52,7 @@ fn rev0_top_id() {
         ..rev0()
     });
 
-    assert!(plain_secret.convert_to_latest(666));
+    assert!(plain_secret.convert_to_rev2(666));
 
     assert!(matches!(plain_secret, PlainSecret::Rev2(rev2)
         if rev2.magics == 4711.into() &&
@@ -69,7 +70,7 @@ fn rev1_no_top_id() {
         ..rev1()
     });
 
-    assert!(plain_secret.convert_to_latest(666));
+    assert!(plain_secret.convert_to_rev2(666));
 
     assert!(matches!(plain_secret, PlainSecret::Rev2(rev2)
         if rev2.magics == 4711.into() &&
@@ -84,7 +85,7 @@ fn rev1_no_top_id() {
 fn rev1_top_id() {
     let mut plain_secret = PlainSecret::Rev1(rev1());
 
-    assert!(plain_secret.convert_to_latest(666));
+    assert!(plain_secret.convert_to_rev2(666));
 
     assert!(matches!(plain_secret, PlainSecret::Rev2(rev2)
         if rev2.magics == 4711.into() &&
@@ -99,6 +100,91 @@ fn rev1_top_id() {
 fn rev2_not_modified() {
     let mut plain_secret = PlainSecret::Rev2(rev2(None, None));
 
-    assert!(!plain_secret.convert_to_latest(666));
+    assert!(!plain_secret.convert_to_rev2(666));
     assert!(matches!(plain_secret, PlainSecret::Rev2(data) if data == rev2(None, None)));
 }
+
+#[test]
+fn rev3_not_modified() {
+    let mut plain_secret = PlainSecret::Rev3(rev3(None, None));
+
+    assert!(!plain_secret.convert_to_rev2(666));
+    assert!(matches!(plain_secret, PlainSecret::Rev3(data) if data == rev3(None, None)));
+}
+
+#[test]
+fn rev0_to_rev3() {
+    let mut plain_secret = PlainSecret::Rev0(PlainRev0 {
+        sid: Some(666),
+        top_id: Some("4711".parse().unwrap()),
+        ..rev0()
+    });
+
+    assert!(plain_secret.convert_to_rev3(None));
+
+    assert!(matches!(plain_secret, PlainSecret::Rev3(rev3)
+        if rev3.magics == 4711.into() &&
+           *rev3.key == [1, 2] &&
+           rev3.sid == Some(666) &&
+           rev3.top_id.unwrap().to_string() == "4711" &&
+           rev3.settings == Settings &&
+           rev3.rekey.is_none() &&
+           rev3.journal.is_none() &&
+           rev3.snapshots.is_none()));
+}
+
+#[test]
+fn rev1_to_rev3() {
+    let mut plain_secret = PlainSecret::Rev1(rev1());
+
+    assert!(plain_secret.convert_to_rev3(None));
+
+    assert!(matches!(plain_secret, PlainSecret::Rev3(rev3)
+        if rev3.magics == 4711.into() &&
+           *rev3.key == [1, 2] &&
+           rev3.sid.is_none() &&
+           rev3.top_id.unwrap().to_string() == "666" &&
+           rev3.settings == Settings &&
+           rev3.rekey.is_none()));
+}
+
+#[test]
+fn rev2_to_rev3() {
+    let mut plain_secret = PlainSecret::Rev2(rev2(Some(666), None));
+
+    assert!(plain_secret.convert_to_rev3(None));
+
+    assert!(matches!(plain_secret, PlainSecret::Rev3(rev3)
+        if rev3.magics == 4711.into() &&
+           *rev3.key == [1, 2] &&
+           rev3.sid == Some(666) &&
+           rev3.top_id.is_none() &&
+           rev3.settings == Settings &&
+           rev3.rekey.is_none()));
+}
+
+#[test]
+fn rev2_to_rev3_rekey() {
+    let mut plain_secret = PlainSecret::Rev2(rev2(None, None));
+    let rekey = Rekey::new(Cipher::Aes128Gcm, vec![6, 7].into());
+
+    assert!(plain_secret.convert_to_rev3(Some(rekey)));
+
+    let rekey = match plain_secret {
+        PlainSecret::Rev3(rev3) => rev3.rekey.unwrap(),
+        _ => panic!("invalid revision"),
+    };
+
+    assert_eq!(rekey.cipher, Cipher::Aes128Gcm);
+    assert_eq!(*rekey.key, [6, 7]);
+    assert!(rekey.cursor.is_empty());
+    assert_eq!(*rekey.iv.unwrap(), [3, 4, 5]);
+}
+
+#[test]
+fn rev3_not_modified_rev3() {
+    let mut plain_secret = PlainSecret::Rev3(rev3(None, None));
+
+    assert!(!plain_secret.convert_to_rev3(None));
+    assert!(matches!(plain_secret, PlainSecret::Rev3(data) if data == rev3(None, None)));
+}
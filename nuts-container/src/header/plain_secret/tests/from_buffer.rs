@@ -22,9 +22,12 @@
 
 use nuts_memory::MemoryBackend;
 
-use crate::header::plain_secret::tests::{rev0, rev1, rev1_no_top_id, rev2};
 use crate::header::plain_secret::tests::{
-    REV0, REV1, REV1_NO_TOP_ID, REV2_NONE, REV2_SID, REV2_TOP_ID,
+    rev0, rev1, rev1_no_top_id, rev2, rev3, rev3_journal, rev3_rekey, rev3_rekey_iv, rev3_snapshots,
+};
+use crate::header::plain_secret::tests::{
+    REV0, REV1, REV1_NO_TOP_ID, REV2_NONE, REV2_SID, REV2_TOP_ID, REV3_JOURNAL, REV3_NONE,
+    REV3_REKEY, REV3_REKEY_IV, REV3_SID, REV3_SNAPSHOTS, REV3_TOP_ID,
 };
 use crate::header::plain_secret::PlainSecret;
 use crate::header::HeaderError;
@@ -102,3 +105,100 @@ fn rev2_inval() {
         Err(err) => assert!(matches!(err, HeaderError::WrongPassword)),
     }
 }
+
+#[test]
+fn rev3_sid() {
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut &REV3_SID[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3(Some(4711), None)));
+}
+
+#[test]
+fn rev3_top_id() {
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut &REV3_TOP_ID[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3(None, Some("666"))));
+}
+
+#[test]
+fn rev3_none() {
+    let out = PlainSecret::from_buffer_rev3(&mut &REV3_NONE[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3(None, None)));
+}
+
+#[test]
+fn rev3_with_rekey() {
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut &REV3_REKEY[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3_rekey()));
+}
+
+#[test]
+fn rev3_with_rekey_iv() {
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut &REV3_REKEY_IV[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3_rekey_iv()));
+}
+
+#[test]
+fn rev3_with_rekey_inval() {
+    let mut vec = REV3_REKEY.to_vec();
+    vec[18] = 3;
+
+    match PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut vec.as_slice()) {
+        Ok(_) => panic!("unexpected result"),
+        Err(err) => assert!(matches!(err, HeaderError::Buffer(_))),
+    }
+}
+
+#[test]
+fn rev3_with_journal() {
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut &REV3_JOURNAL[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3_journal()));
+}
+
+#[test]
+fn rev3_with_journal_pending() {
+    let mut vec = REV3_JOURNAL.to_vec();
+    vec[19] = 1;
+
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut vec.as_slice()).unwrap();
+    let journal = match out {
+        PlainSecret::Rev3(data) => data.journal.unwrap(),
+        _ => panic!("invalid revision"),
+    };
+
+    assert_eq!(journal.id, [0, 0, 2, 154]);
+    assert!(!journal.committed);
+}
+
+#[test]
+fn rev3_with_journal_inval() {
+    let mut vec = REV3_JOURNAL.to_vec();
+    vec[19] = 3;
+
+    match PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut vec.as_slice()) {
+        Ok(_) => panic!("unexpected result"),
+        Err(err) => assert!(matches!(err, HeaderError::Buffer(_))),
+    }
+}
+
+#[test]
+fn rev3_with_snapshots() {
+    let out = PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut &REV3_SNAPSHOTS[..]).unwrap();
+
+    assert!(matches!(out, PlainSecret::Rev3(data) if data == rev3_snapshots()));
+}
+
+#[test]
+fn rev3_inval() {
+    let mut vec = REV3_NONE.to_vec();
+    vec[0] += 1;
+
+    match PlainSecret::<MemoryBackend>::from_buffer_rev3(&mut vec.as_slice()) {
+        Ok(_) => panic!("unexpected result"),
+        Err(err) => assert!(matches!(err, HeaderError::WrongPassword)),
+    }
+}
@@ -0,0 +1,85 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use openssl::sha::sha256;
+
+use crate::cipher::Cipher;
+use crate::header::plain_secret::Rekey;
+
+fn rekey() -> Rekey {
+    Rekey {
+        cipher: Cipher::Aes128Ctr,
+        key: vec![1, 2].into(),
+        cursor: vec![5],
+        inflight: vec![6],
+        digest: sha256(b"abc").to_vec(),
+        iv: None,
+    }
+}
+
+#[test]
+fn new() {
+    let rekey = Rekey::new(Cipher::Aes128Ctr, vec![1, 2].into());
+
+    assert_eq!(rekey.cipher, Cipher::Aes128Ctr);
+    assert_eq!(rekey.key.as_slice(), [1, 2]);
+    assert!(rekey.cursor.is_empty());
+    assert!(rekey.inflight.is_empty());
+    assert!(rekey.digest.is_empty());
+    assert!(rekey.iv.is_none());
+}
+
+#[test]
+fn is_done_no_cursor() {
+    let rekey = Rekey::new(Cipher::Aes128Ctr, vec![1, 2].into());
+
+    assert!(!rekey.is_done(&[0], None));
+    assert!(!rekey.is_done(&[0], Some(b"abc")));
+}
+
+#[test]
+fn is_done_before_cursor() {
+    assert!(rekey().is_done(&[4], None));
+    assert!(rekey().is_done(&[4, 9], Some(b"abc")));
+    assert!(rekey().is_done(&[5], None));
+}
+
+#[test]
+fn is_done_after_cursor() {
+    assert!(!rekey().is_done(&[5, 0], None));
+    assert!(!rekey().is_done(&[7], Some(b"abc")));
+}
+
+#[test]
+fn is_done_inflight_unchanged() {
+    assert!(!rekey().is_done(&[6], Some(b"abc")));
+}
+
+#[test]
+fn is_done_inflight_changed() {
+    assert!(rekey().is_done(&[6], Some(b"xyz")));
+}
+
+#[test]
+fn is_done_inflight_write() {
+    assert!(rekey().is_done(&[6], None));
+}
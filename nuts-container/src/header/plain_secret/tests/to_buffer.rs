@@ -21,9 +21,12 @@
 // IN THE SOFTWARE.
 
 use crate::buffer::ToBuffer;
-use crate::header::plain_secret::tests::{rev0, rev1, rev1_no_top_id, rev2};
 use crate::header::plain_secret::tests::{
-    REV0, REV1, REV1_NO_TOP_ID, REV2_NONE, REV2_SID, REV2_TOP_ID,
+    rev0, rev1, rev1_no_top_id, rev2, rev3, rev3_journal, rev3_rekey, rev3_rekey_iv, rev3_snapshots,
+};
+use crate::header::plain_secret::tests::{
+    REV0, REV1, REV1_NO_TOP_ID, REV2_NONE, REV2_SID, REV2_TOP_ID, REV3_JOURNAL, REV3_NONE,
+    REV3_REKEY, REV3_REKEY_IV, REV3_SID, REV3_SNAPSHOTS, REV3_TOP_ID,
 };
 use crate::header::plain_secret::PlainSecret;
 
@@ -82,3 +85,71 @@ fn rev2_none() {
         .unwrap();
     assert_eq!(buf, REV2_NONE);
 }
+
+#[test]
+fn rev3_sid() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3(Some(4711), None))
+        .to_buffer(&mut buf)
+        .unwrap();
+    assert_eq!(buf, REV3_SID);
+}
+
+#[test]
+fn rev3_top_id() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3(None, Some("666")))
+        .to_buffer(&mut buf)
+        .unwrap();
+    assert_eq!(buf, REV3_TOP_ID);
+}
+
+#[test]
+fn rev3_none() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3(None, None))
+        .to_buffer(&mut buf)
+        .unwrap();
+    assert_eq!(buf, REV3_NONE);
+}
+
+#[test]
+fn rev3_with_rekey() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3_rekey()).to_buffer(&mut buf).unwrap();
+    assert_eq!(buf, REV3_REKEY);
+}
+
+#[test]
+fn rev3_with_rekey_iv() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3_rekey_iv())
+        .to_buffer(&mut buf)
+        .unwrap();
+    assert_eq!(buf, REV3_REKEY_IV);
+}
+
+#[test]
+fn rev3_with_journal() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3_journal())
+        .to_buffer(&mut buf)
+        .unwrap();
+    assert_eq!(buf, REV3_JOURNAL);
+}
+
+#[test]
+fn rev3_with_snapshots() {
+    let mut buf = vec![];
+
+    PlainSecret::Rev3(rev3_snapshots())
+        .to_buffer(&mut buf)
+        .unwrap();
+    assert_eq!(buf, REV3_SNAPSHOTS);
+}
@@ -23,10 +23,14 @@
 #[cfg(test)]
 mod tests;
 
-use crate::buffer::{Buffer, BufferMut};
+use std::convert::TryFrom;
+
+use crate::buffer::{Buffer, BufferError, BufferMut};
 use crate::cipher::Cipher;
+use crate::compression::Compression;
 use crate::header::HeaderError;
 use crate::kdf::Kdf;
+use crate::recipient::Recipient;
 
 const MAGIC: [u8; 7] = *b"nuts-io";
 
@@ -63,13 +67,175 @@ impl Data {
     }
 
     fn put_into_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), HeaderError> {
+        self.put_plain_into_buffer(buf)?;
+        buf.put_vec::<8>(&self.secret)?;
+
+        Ok(())
+    }
+
+    fn put_plain_into_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), HeaderError> {
         Cipher::put_into_buffer(&self.cipher, buf)?;
         buf.put_vec::<8>(&self.iv)?;
         Kdf::put_into_buffer(&self.kdf, buf)?;
+
+        Ok(())
+    }
+}
+
+/// A key slot of a revision 3 header.
+///
+/// The slot stores the header key, which is either wrapped with a key
+/// derived from a password or wrapped to the public key of a recipient.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeySlot {
+    /// The header key is wrapped with a key derived from a password and the
+    /// `kdf` of the slot.
+    Password { kdf: Kdf, iv: Vec<u8>, key: Vec<u8> },
+
+    /// The header key is wrapped to `recipient`. `ephemeral` is the public
+    /// key of the ephemeral key used for the key agreement.
+    Recipient {
+        recipient: Recipient,
+        ephemeral: Vec<u8>,
+        iv: Vec<u8>,
+        key: Vec<u8>,
+    },
+}
+
+impl KeySlot {
+    pub fn password(kdf: Kdf, iv: Vec<u8>, key: Vec<u8>) -> KeySlot {
+        KeySlot::Password { kdf, iv, key }
+    }
+
+    pub fn recipient(
+        recipient: Recipient,
+        ephemeral: Vec<u8>,
+        iv: Vec<u8>,
+        key: Vec<u8>,
+    ) -> KeySlot {
+        KeySlot::Recipient {
+            recipient,
+            ephemeral,
+            iv,
+            key,
+        }
+    }
+
+    fn get_from_buffer<T: Buffer>(buf: &mut T) -> Result<KeySlot, HeaderError> {
+        let b = buf.get_u8()?;
+
+        match b {
+            0 => {
+                let kdf = Kdf::get_from_buffer(buf)?;
+                let iv = buf.get_vec::<1>()?;
+                let key = buf.get_vec::<1>()?;
+
+                Ok(KeySlot::password(kdf, iv, key))
+            }
+            1 => {
+                let recipient = Recipient::from_bytes(&buf.get_vec::<1>()?)?;
+                let ephemeral = buf.get_vec::<1>()?;
+                let iv = buf.get_vec::<1>()?;
+                let key = buf.get_vec::<1>()?;
+
+                Ok(KeySlot::recipient(recipient, ephemeral, iv, key))
+            }
+            _ => Err(BufferError::InvalidIndex("KeySlot".to_string(), b as u32).into()),
+        }
+    }
+
+    fn put_into_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), HeaderError> {
+        match self {
+            KeySlot::Password { kdf, iv, key } => {
+                buf.put_u8(0)?;
+                Kdf::put_into_buffer(kdf, buf)?;
+                buf.put_vec::<1>(iv)?;
+                buf.put_vec::<1>(key)?;
+            }
+            KeySlot::Recipient {
+                recipient,
+                ephemeral,
+                iv,
+                key,
+            } => {
+                buf.put_u8(1)?;
+                buf.put_vec::<1>(recipient.as_bytes())?;
+                buf.put_vec::<1>(ephemeral)?;
+                buf.put_vec::<1>(iv)?;
+                buf.put_vec::<1>(key)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Header data of revision 3.
+///
+/// Instead of a single kdf the header stores a list of key slots. The
+/// compression of the blocks is stored next to the cipher.
+///
+/// A header, which does not fit into the root header block, is continued in
+/// `blocks` extended header blocks. The `generation` is incremented on every
+/// write, it selects the slots of the extended header blocks.
+#[derive(Debug, PartialEq)]
+pub struct DataRev3 {
+    pub blocks: u8,
+    pub generation: u8,
+    pub cipher: Cipher,
+    pub compression: Compression,
+    pub iv: Vec<u8>,
+    pub slots: Vec<KeySlot>,
+    pub secret: Vec<u8>,
+}
+
+impl DataRev3 {
+    fn get_from_buffer<T: Buffer>(buf: &mut T) -> Result<DataRev3, HeaderError> {
+        let blocks = buf.get_u8()?;
+        let generation = buf.get_u8()?;
+        let cipher = Cipher::get_from_buffer(buf)?;
+        let compression = Compression::get_from_buffer(buf)?;
+        let iv = buf.get_vec::<8>()?;
+        let nslots = buf.get_u8()?;
+        let slots = (0..nslots)
+            .map(|_| KeySlot::get_from_buffer(buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        let secret = buf.get_vec::<8>()?;
+
+        Ok(DataRev3 {
+            blocks,
+            generation,
+            cipher,
+            compression,
+            iv,
+            slots,
+            secret,
+        })
+    }
+
+    fn put_into_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), HeaderError> {
+        self.put_plain_into_buffer(buf)?;
         buf.put_vec::<8>(&self.secret)?;
 
         Ok(())
     }
+
+    fn put_plain_into_buffer<T: BufferMut>(&self, buf: &mut T) -> Result<(), HeaderError> {
+        let nslots = u8::try_from(self.slots.len()).map_err(|_| BufferError::VecTooLarge)?;
+
+        buf.put_u8(self.blocks)?;
+        buf.put_u8(self.generation)?;
+        Cipher::put_into_buffer(&self.cipher, buf)?;
+        self.compression.put_into_buffer(buf)?;
+        buf.put_vec::<8>(&self.iv)?;
+        buf.put_u8(nslots)?;
+
+        for slot in self.slots.iter() {
+            slot.put_into_buffer(buf)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -77,6 +243,7 @@ pub enum Revision {
     Rev0(Data),
     Rev1(Data),
     Rev2(Data),
+    Rev3(DataRev3),
 }
 
 impl Revision {
@@ -92,6 +259,91 @@ impl Revision {
         Revision::Rev2(Data::new(cipher, iv, kdf, secret))
     }
 
+    pub fn new_rev3(
+        cipher: Cipher,
+        compression: Compression,
+        iv: Vec<u8>,
+        slots: Vec<KeySlot>,
+        secret: Vec<u8>,
+    ) -> Revision {
+        Revision::Rev3(DataRev3 {
+            blocks: 0,
+            generation: 0,
+            cipher,
+            compression,
+            iv,
+            slots,
+            secret,
+        })
+    }
+
+    /// Returns the additional data, which are authenticated together with
+    /// the secret.
+    ///
+    /// Starting with revision 3 these are all plaintext fields of the header
+    /// (everything except the secret). Older revisions do not authenticate
+    /// additional data, an empty vector is returned.
+    pub fn aad(&self) -> Result<Vec<u8>, HeaderError> {
+        let mut buf = vec![];
+
+        if let Revision::Rev3(data) = self {
+            buf.put_chunk(&MAGIC)?;
+            buf.put_u32(3)?;
+            data.put_plain_into_buffer(&mut buf)?;
+        }
+
+        Ok(buf)
+    }
+
+    /// Sets the number of extended header blocks.
+    ///
+    /// Only a revision 3 header can be continued in extended header blocks,
+    /// for older revisions the call is ignored.
+    pub fn set_blocks(&mut self, blocks: u8) {
+        if let Revision::Rev3(data) = self {
+            data.blocks = blocks;
+        }
+    }
+
+    /// Sets the generation of the header.
+    ///
+    /// Only a revision 3 header has a generation, for older revisions the
+    /// call is ignored.
+    pub fn set_generation(&mut self, generation: u8) {
+        if let Revision::Rev3(data) = self {
+            data.generation = generation;
+        }
+    }
+
+    /// Returns the number of extended header blocks, which follow the root
+    /// header `buf`, and the generation of the header.
+    ///
+    /// Only the plaintext part of the header up to the generation is
+    /// evaluated, the header is not validated yet.
+    pub fn peek_blocks(buf: &[u8]) -> Result<(u8, u8), HeaderError> {
+        let buf = &mut &buf[..];
+        let magic = buf.get_array()?;
+
+        if magic != MAGIC {
+            return Err(HeaderError::InvalidHeader);
+        }
+
+        match buf.get_u32()? {
+            0..=2 => Ok((0, 0)),
+            3 => Ok((buf.get_u8()?, buf.get_u8()?)),
+            b => Err(HeaderError::UnknownRevision(b)),
+        }
+    }
+
+    pub fn set_secret(&mut self, secret: Vec<u8>) {
+        match self {
+            Revision::Rev0(data) | Revision::Rev1(data) | Revision::Rev2(data) => {
+                data.secret = secret
+            }
+            Revision::Rev3(data) => data.secret = secret,
+        }
+    }
+
     pub fn get_from_buffer<T: Buffer>(buf: &mut T) -> Result<Revision, HeaderError> {
         let magic = buf.get_array()?;
 
@@ -105,6 +357,7 @@ impl Revision {
             0 => Data::get_from_buffer(buf).map(Revision::Rev0),
             1 => Data::get_from_buffer(buf).map(Revision::Rev1),
             2 => Data::get_from_buffer(buf).map(Revision::Rev2),
+            3 => DataRev3::get_from_buffer(buf).map(Revision::Rev3),
             _ => Err(HeaderError::UnknownRevision(b)),
         }
     }
@@ -125,6 +378,10 @@ impl Revision {
                 buf.put_u32(2)?;
                 data.put_into_buffer(buf)
             }
+            Revision::Rev3(data) => {
+                buf.put_u32(3)?;
+                data.put_into_buffer(buf)
+            }
         }
     }
 }
@@ -20,10 +20,13 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
+use crate::buffer::BufferError;
 use crate::cipher::Cipher;
-use crate::header::revision::{Data, Revision};
+use crate::compression::Compression;
+use crate::header::revision::{Data, DataRev3, KeySlot, Revision};
 use crate::header::HeaderError;
 use crate::kdf::Kdf;
+use crate::recipient::{Recipient, RecipientError};
 
 const REV0: [u8; 38] = [
     b'n', b'u', b't', b's', b'-', b'i', b'o', // magic
@@ -52,6 +55,42 @@ const REV2: [u8; 38] = [
     0x00, 0x00, 0x00, 0x0, 0x00, 0x00, 0x00, 0x03, 1, 2, 3, // secret
 ];
 
+const REV3: [u8; 51] = [
+    b'n', b'u', b't', b's', b'-', b'i', b'o', // magic
+    0x00, 0x00, 0x00, 0x03, // revision
+    0x00, // extended header blocks
+    0x01, // generation
+    0x00, 0x00, 0x00, 0x00, // cipher
+    0x00, 0x00, 0x00, 0x01, // compression
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // iv,
+    0x01, // number of slots
+    0x00, // slot 1: password
+    0x00, 0x00, 0x00, 0x00, // slot 1: kdf
+    0x01, 4, // slot 1: iv
+    0x02, 5, 6, // slot 1: key
+    0x00, 0x00, 0x00, 0x0, 0x00, 0x00, 0x00, 0x03, 1, 2, 3, // secret
+];
+
+const SLOT_RECIPIENT: [u8; 41] = [
+    0x01, // recipient
+    0x20, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+    25, 26, 27, 28, 29, 30, 31, 32, // recipient
+    0x02, 1, 2, // ephemeral
+    0x01, 3, // iv
+    0x01, 4, // key
+];
+
+fn slot() -> KeySlot {
+    KeySlot::password(Kdf::None, vec![4], vec![5, 6])
+}
+
+fn recipient_slot() -> KeySlot {
+    let bytes = (1..=32).collect::<Vec<u8>>();
+    let recipient = Recipient::from_bytes(&bytes).unwrap();
+
+    KeySlot::recipient(recipient, vec![1, 2], vec![3], vec![4])
+}
+
 #[test]
 fn new_rev0() {
     let revision = Revision::new_rev0(Cipher::None, vec![1], Kdf::None, vec![2, 3]);
@@ -94,15 +133,38 @@ fn new_rev2() {
     assert!(matches!(revision, Revision::Rev2(data) if data == expected));
 }
 
+#[test]
+fn new_rev3() {
+    let revision = Revision::new_rev3(
+        Cipher::None,
+        Compression::Lz4,
+        vec![1],
+        vec![slot()],
+        vec![2, 3],
+    );
+
+    let expected = DataRev3 {
+        blocks: 0,
+        generation: 0,
+        cipher: Cipher::None,
+        compression: Compression::Lz4,
+        iv: vec![1],
+        slots: vec![slot()],
+        secret: vec![2, 3],
+    };
+
+    assert!(matches!(revision, Revision::Rev3(data) if data == expected));
+}
+
 #[test]
 fn de_inval_revision() {
     let mut buf = REV0;
 
-    buf[10] = 3;
+    buf[10] = 4;
 
     let err = Revision::get_from_buffer(&mut &buf[..]).unwrap_err();
 
-    assert!(matches!(err, HeaderError::UnknownRevision(rev) if rev == 3));
+    assert!(matches!(err, HeaderError::UnknownRevision(rev) if rev == 4));
 }
 
 #[test]
@@ -116,6 +178,7 @@ fn de_rev0() {
         }
         Revision::Rev1(_) => panic!("invalid revision"),
         Revision::Rev2(_) => panic!("invalid revision"),
+        Revision::Rev3(_) => panic!("invalid revision"),
     }
 }
 
@@ -155,6 +218,7 @@ fn de_rev1() {
             assert_eq!(rev1.secret, [1, 2, 3]);
         }
         Revision::Rev2(_) => panic!("invalid revision"),
+        Revision::Rev3(_) => panic!("invalid revision"),
     }
 }
 
@@ -194,6 +258,7 @@ fn de_rev2() {
             assert_eq!(rev2.kdf, Kdf::None);
             assert_eq!(rev2.secret, [1, 2, 3]);
         }
+        Revision::Rev3(_) => panic!("invalid revision"),
     }
 }
 
@@ -221,3 +286,214 @@ fn ser_rev2() {
     inner.put_into_buffer(&mut buf).unwrap();
     assert_eq!(buf, REV2);
 }
+
+#[test]
+fn de_rev3() {
+    match Revision::get_from_buffer(&mut &REV3[..]).unwrap() {
+        Revision::Rev0(_) => panic!("invalid revision"),
+        Revision::Rev1(_) => panic!("invalid revision"),
+        Revision::Rev2(_) => panic!("invalid revision"),
+        Revision::Rev3(rev3) => {
+            assert_eq!(rev3.blocks, 0);
+            assert_eq!(rev3.generation, 1);
+            assert_eq!(rev3.cipher, Cipher::None);
+            assert_eq!(rev3.compression, Compression::Lz4);
+            assert_eq!(rev3.iv, []);
+            assert_eq!(rev3.slots, [slot()]);
+            assert_eq!(rev3.secret, [1, 2, 3]);
+        }
+    }
+}
+
+#[test]
+fn de_rev3_inval_magic() {
+    let mut buf = REV3;
+
+    buf[0] = b'x';
+
+    let err = Revision::get_from_buffer(&mut &buf[..]).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidHeader));
+}
+
+#[test]
+fn de_rev3_inval_compression() {
+    let mut buf = REV3;
+
+    buf[20] = 3;
+
+    let err = Revision::get_from_buffer(&mut &buf[..]).unwrap_err();
+
+    assert!(
+        matches!(err, HeaderError::Buffer(BufferError::InvalidIndex(name, 3)) if name == "Compression")
+    );
+}
+
+#[test]
+fn ser_rev3() {
+    let mut buf = vec![];
+    let inner = Revision::Rev3(DataRev3 {
+        blocks: 0,
+        generation: 1,
+        cipher: Cipher::None,
+        compression: Compression::Lz4,
+        iv: vec![],
+        slots: vec![slot()],
+        secret: vec![1, 2, 3],
+    });
+
+    inner.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, REV3);
+}
+
+#[test]
+fn de_slot_recipient() {
+    let slot = KeySlot::get_from_buffer(&mut &SLOT_RECIPIENT[..]).unwrap();
+    assert_eq!(slot, recipient_slot());
+}
+
+#[test]
+fn de_slot_inval() {
+    let mut buf = SLOT_RECIPIENT;
+
+    buf[0] = 2;
+
+    let err = KeySlot::get_from_buffer(&mut &buf[..]).unwrap_err();
+    assert!(
+        matches!(err, HeaderError::Buffer(BufferError::InvalidIndex(name, 2)) if name == "KeySlot")
+    );
+}
+
+#[test]
+fn de_slot_recipient_inval() {
+    let mut buf = SLOT_RECIPIENT.to_vec();
+
+    buf[1] = 0x1f;
+    buf.remove(2);
+
+    let err = KeySlot::get_from_buffer(&mut &buf[..]).unwrap_err();
+    assert!(matches!(
+        err,
+        HeaderError::Recipient(RecipientError::InvalidKey)
+    ));
+}
+
+#[test]
+fn ser_slot_recipient() {
+    let mut buf = vec![];
+
+    recipient_slot().put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, SLOT_RECIPIENT);
+}
+
+#[test]
+fn aad_rev0() {
+    let rev = Revision::get_from_buffer(&mut &REV0[..]).unwrap();
+    assert_eq!(rev.aad().unwrap(), []);
+}
+
+#[test]
+fn aad_rev1() {
+    let rev = Revision::get_from_buffer(&mut &REV1[..]).unwrap();
+    assert_eq!(rev.aad().unwrap(), []);
+}
+
+#[test]
+fn aad_rev2() {
+    let rev = Revision::get_from_buffer(&mut &REV2[..]).unwrap();
+    assert_eq!(rev.aad().unwrap(), []);
+}
+
+#[test]
+fn aad_rev3() {
+    let rev = Revision::get_from_buffer(&mut &REV3[..]).unwrap();
+    assert_eq!(rev.aad().unwrap(), REV3[..40]);
+}
+
+#[test]
+fn set_blocks_rev0() {
+    let mut rev = Revision::get_from_buffer(&mut &REV0[..]).unwrap();
+    let mut buf = vec![];
+
+    rev.set_blocks(1);
+    rev.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, REV0);
+}
+
+#[test]
+fn set_blocks_rev3() {
+    let mut rev = Revision::get_from_buffer(&mut &REV3[..]).unwrap();
+
+    rev.set_blocks(1);
+    assert!(matches!(rev, Revision::Rev3(data) if data.blocks == 1));
+}
+
+#[test]
+fn set_generation_rev0() {
+    let mut rev = Revision::get_from_buffer(&mut &REV0[..]).unwrap();
+    let mut buf = vec![];
+
+    rev.set_generation(2);
+    rev.put_into_buffer(&mut buf).unwrap();
+    assert_eq!(buf, REV0);
+}
+
+#[test]
+fn set_generation_rev3() {
+    let mut rev = Revision::get_from_buffer(&mut &REV3[..]).unwrap();
+
+    rev.set_generation(2);
+    assert!(matches!(rev, Revision::Rev3(data) if data.generation == 2));
+}
+
+#[test]
+fn peek_blocks_rev0() {
+    assert_eq!(Revision::peek_blocks(&REV0).unwrap(), (0, 0));
+}
+
+#[test]
+fn peek_blocks_rev3() {
+    let mut buf = REV3;
+
+    assert_eq!(Revision::peek_blocks(&buf).unwrap(), (0, 1));
+
+    buf[11] = 2;
+    buf[12] = 7;
+    assert_eq!(Revision::peek_blocks(&buf).unwrap(), (2, 7));
+}
+
+#[test]
+fn peek_blocks_inval_magic() {
+    let mut buf = REV3;
+
+    buf[0] = b'x';
+
+    let err = Revision::peek_blocks(&buf).unwrap_err();
+    assert!(matches!(err, HeaderError::InvalidHeader));
+}
+
+#[test]
+fn peek_blocks_inval_revision() {
+    let mut buf = REV3;
+
+    buf[10] = 4;
+
+    let err = Revision::peek_blocks(&buf).unwrap_err();
+    assert!(matches!(err, HeaderError::UnknownRevision(4)));
+}
+
+#[test]
+fn set_secret_rev0() {
+    let mut rev = Revision::get_from_buffer(&mut &REV0[..]).unwrap();
+
+    rev.set_secret(vec![4, 5]);
+    assert!(matches!(rev, Revision::Rev0(data) if data.secret == [4, 5]));
+}
+
+#[test]
+fn set_secret_rev3() {
+    let mut rev = Revision::get_from_buffer(&mut &REV3[..]).unwrap();
+
+    rev.set_secret(vec![4, 5]);
+    assert!(matches!(rev, Revision::Rev3(data) if data.secret == [4, 5]));
+}
@@ -20,17 +20,24 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use nuts_backend::Binary;
+use nuts_backend::{Binary, HEADER_MAX_SIZE};
 use nuts_memory::{MemoryBackend, Settings};
+use openssl::sha::sha256;
+use std::sync::Arc;
 
 use crate::cipher::Cipher;
+use crate::compression::Compression;
 use crate::digest::Digest;
-use crate::header::plain_secret::{PlainRev0, PlainRev1, PlainRev2, PlainSecret};
-use crate::header::{Header, HeaderError};
+use crate::header::plain_secret::{PlainRev0, PlainRev1, PlainRev2, PlainRev3, PlainSecret};
+use crate::header::revision::{KeySlot, Revision};
+use crate::header::{ExtendedBlocks, Header, HeaderError};
+use crate::info::SlotInfo;
 use crate::kdf::Kdf;
 use crate::migrate::Migrator;
 use crate::options::CreateOptionsBuilder;
 use crate::password::PasswordStore;
+use crate::recipient::Identity;
+use crate::recovery::RecoveryKey;
 
 const REV0: [u8; 79] = [
     b'n', b'u', b't', b's', b'-', b'i', b'o', // magic
@@ -75,6 +82,26 @@ const REV2: [u8; 56] = [
     0, 0, // secret: settings
 ];
 
+const REV3: [u8; 61] = [
+    b'n', b'u', b't', b's', b'-', b'i', b'o', // magic
+    0, 0, 0, 3, // revision
+    0, // extended header blocks
+    0, // generation
+    0, 0, 0, 0, // cipher
+    0, 0, 0, 0, // compression
+    0, 0, 0, 0, 0, 0, 0, 0, // iv
+    0, // number of slots
+    0, 0, 0, 0, 0, 0, 0, 23, // secret length
+    0x91, 0xc0, 0xb2, 0xcf, 0x91, 0xc0, 0xb2, 0xcf, // secret: magics
+    0,    // secret: key
+    0x00, 0x00, 0x02, 0x9a, // secret: sid
+    4, 0x00, 0x00, 0x12, 0x67, // secret: top_id
+    0, 0, // secret: settings
+    0, // secret: rekey
+    0, // secret: journal
+    0, // secret: snapshots
+];
+
 fn rev0() -> PlainRev0<MemoryBackend> {
     PlainRev0 {
         magics: 0x91c0b2cf.into(),
@@ -87,143 +114,1206 @@ fn rev0() -> PlainRev0<MemoryBackend> {
     }
 }
 
-fn rev1() -> PlainRev1<MemoryBackend> {
-    PlainRev1 {
-        magics: 0x91c0b2cf.into(),
-        key: vec![].into(),
-        iv: vec![].into(),
-        top_id: None,
-        settings: Settings,
-    }
+fn rev1() -> PlainRev1<MemoryBackend> {
+    PlainRev1 {
+        magics: 0x91c0b2cf.into(),
+        key: vec![].into(),
+        iv: vec![].into(),
+        top_id: None,
+        settings: Settings,
+    }
+}
+
+fn rev2() -> PlainRev2<MemoryBackend> {
+    PlainRev2 {
+        magics: 0x91c0b2cf.into(),
+        key: vec![].into(),
+        iv: vec![].into(),
+        sid: None,
+        top_id: None,
+        settings: Settings,
+    }
+}
+
+fn rev3() -> PlainRev3<MemoryBackend> {
+    PlainRev3 {
+        magics: 0x91c0b2cf.into(),
+        key: vec![].into(),
+        sid: None,
+        top_id: None,
+        settings: Settings,
+        rekey: None,
+        journal: None,
+        snapshots: None,
+    }
+}
+
+fn header(data: PlainSecret<MemoryBackend>) -> Header<'static, MemoryBackend> {
+    Header::<MemoryBackend> {
+        revision: 1,
+        generation: 0,
+        migrator: Migrator::default(),
+        cipher: Cipher::None,
+        compression: Compression::None,
+        kdf: Kdf::None,
+        header_key: vec![].into(),
+        slots: vec![],
+        slot: None,
+        data,
+    }
+}
+
+#[test]
+fn create() {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let header = Header::<MemoryBackend>::create(&options, Settings).unwrap();
+
+    assert_eq!(header.revision, 3);
+    assert_eq!(header.cipher, Cipher::None);
+    assert_eq!(header.compression, Compression::None);
+    assert_eq!(header.kdf, Kdf::None);
+    assert_eq!(header.data, PlainSecret::Rev3(rev3()));
+}
+
+#[test]
+fn create_guarded() {
+    let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .build::<MemoryBackend>()
+        .unwrap();
+    let header = Header::<MemoryBackend>::create(&options, Settings).unwrap();
+
+    assert!(header.header_key.is_guarded());
+    assert_eq!(header.key().len(), 16);
+    assert!(match &header.data {
+        PlainSecret::Rev3(rev3) => rev3.key.is_guarded(),
+        _ => false,
+    });
+}
+
+#[test]
+fn create_compression() {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .with_compression(Compression::Zstd)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let header = Header::<MemoryBackend>::create(&options, Settings).unwrap();
+
+    assert_eq!(header.compression, Compression::Zstd);
+}
+
+#[test]
+fn read_rev0() {
+    let migrator = Migrator::default();
+    let mut store = PasswordStore::new(None);
+
+    let header = Header::<MemoryBackend>::read(&REV0, migrator, &mut store).unwrap();
+
+    assert_eq!(header.revision, 0);
+    assert_eq!(header.cipher, Cipher::None);
+    assert_eq!(header.kdf, Kdf::None);
+    assert_eq!(header.data, PlainSecret::Rev0(rev0()));
+}
+
+#[test]
+fn read_rev1() {
+    let migrator = Migrator::default();
+    let mut store = PasswordStore::new(None);
+
+    let header = Header::<MemoryBackend>::read(&REV1, migrator, &mut store).unwrap();
+
+    assert_eq!(header.revision, 1);
+    assert_eq!(header.cipher, Cipher::None);
+    assert_eq!(header.kdf, Kdf::None);
+    assert_eq!(
+        header.data,
+        PlainSecret::Rev1(PlainRev1 {
+            top_id: Some("4711".parse().unwrap()),
+            ..rev1()
+        })
+    );
+}
+
+#[test]
+fn read_rev2() {
+    let migrator = Migrator::default();
+    let mut store = PasswordStore::new(None);
+
+    let header = Header::<MemoryBackend>::read(&REV2, migrator, &mut store).unwrap();
+
+    assert_eq!(header.revision, 2);
+    assert_eq!(header.cipher, Cipher::None);
+    assert_eq!(header.kdf, Kdf::None);
+    assert_eq!(
+        header.data,
+        PlainSecret::Rev2(PlainRev2 {
+            top_id: Some("4711".parse().unwrap()),
+            sid: Some(666),
+            ..rev2()
+        })
+    );
+}
+
+#[test]
+fn read_rev3() {
+    let migrator = Migrator::default();
+    let mut store = PasswordStore::new(None);
+
+    let header = Header::<MemoryBackend>::read(&REV3, migrator, &mut store).unwrap();
+
+    assert_eq!(header.revision, 3);
+    assert_eq!(header.cipher, Cipher::None);
+    assert_eq!(header.kdf, Kdf::None);
+    assert_eq!(
+        header.data,
+        PlainSecret::Rev3(PlainRev3 {
+            top_id: Some("4711".parse().unwrap()),
+            sid: Some(666),
+            ..rev3()
+        })
+    );
+}
+
+#[test]
+fn write_rev0() {
+    let mut store = PasswordStore::new(None);
+
+    let mut header = header(PlainSecret::Rev0(rev0()));
+
+    let buf = header.write(&mut store).unwrap();
+
+    assert_eq!(buf, REV0);
+}
+
+#[test]
+fn write_rev1() {
+    let mut store = PasswordStore::new(None);
+
+    let mut header = header(PlainSecret::Rev1(PlainRev1 {
+        top_id: Some("4711".parse().unwrap()),
+        ..rev1()
+    }));
+
+    let buf = header.write(&mut store).unwrap();
+
+    assert_eq!(buf, REV1);
+}
+
+#[test]
+fn write_rev2() {
+    let mut store = PasswordStore::new(None);
+
+    let mut header = header(PlainSecret::Rev2(PlainRev2 {
+        top_id: Some("4711".parse().unwrap()),
+        sid: Some(666),
+        ..rev2()
+    }));
+
+    let buf = header.write(&mut store).unwrap();
+
+    assert_eq!(buf, REV2);
+}
+
+#[test]
+fn write_rev3() {
+    let mut store = PasswordStore::new(None);
+
+    let mut header = header(PlainSecret::Rev3(PlainRev3 {
+        top_id: Some("4711".parse().unwrap()),
+        sid: Some(666),
+        ..rev3()
+    }));
+
+    let buf = header.write(&mut store).unwrap();
+
+    assert_eq!(buf, REV3);
+}
+
+fn header_rev3_aes128_gcm() -> Header<'static, MemoryBackend> {
+    Header::<MemoryBackend> {
+        revision: 3,
+        generation: 0,
+        migrator: Migrator::default(),
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        kdf: Kdf::pbkdf2(Digest::Sha1, 1, b"123"),
+        header_key: vec![b'y'; 16].into(),
+        slots: vec![KeySlot::password(Kdf::None, vec![], vec![])],
+        slot: Some(0),
+        data: PlainSecret::Rev3(PlainRev3 {
+            key: vec![b'x'; 16].into(),
+            ..rev3()
+        }),
+    }
+}
+
+fn store_rev3_aes128_gcm() -> PasswordStore {
+    PasswordStore::new(Some(Arc::new(|| Ok(b"abc".to_vec()))))
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm() {
+    let buf = header_rev3_aes128_gcm()
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
+
+    let header =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+
+    assert_eq!(header.revision, 3);
+    assert_eq!(header.cipher, Cipher::Aes128Gcm);
+    assert_eq!(header.kdf, Kdf::pbkdf2(Digest::Sha1, 1, b"123"));
+    assert_eq!(header.header_key, header_rev3_aes128_gcm().header_key);
+    assert_eq!(header.slot, Some(0));
+    assert_eq!(header.data, header_rev3_aes128_gcm().data);
+
+    // the keys never left guarded memory
+    assert!(header.header_key.is_guarded());
+    assert!(match &header.data {
+        PlainSecret::Rev3(rev3) => rev3.key.is_guarded(),
+        _ => false,
+    });
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_slots() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header
+        .add_slot(Kdf::pbkdf2(Digest::Sha256, 2, b"456"), b"xyz")
+        .unwrap());
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    for (password, slot, kdf) in [
+        (b"abc", 0, Kdf::pbkdf2(Digest::Sha1, 1, b"123")),
+        (b"xyz", 1, Kdf::pbkdf2(Digest::Sha256, 2, b"456")),
+    ] {
+        let mut store = PasswordStore::with_value(password);
+        let header = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap();
+
+        assert_eq!(header.kdf, kdf);
+        assert_eq!(header.header_key, header_rev3_aes128_gcm().header_key);
+        assert_eq!(header.slot, Some(slot));
+        assert_eq!(header.active_slot(), Some(slot));
+        assert_eq!(
+            header.slots(),
+            [
+                SlotInfo::Password(Kdf::pbkdf2(Digest::Sha1, 1, b"123")),
+                SlotInfo::Password(Kdf::pbkdf2(Digest::Sha256, 2, b"456"))
+            ]
+        );
+        assert_eq!(header.data, header_rev3_aes128_gcm().data);
+    }
+
+    let mut store = PasswordStore::with_value(b"xxx");
+    let err = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap_err();
+
+    assert!(matches!(err, HeaderError::NotTrustworthy));
+}
+
+#[test]
+fn write_read_rev3_aes128_ctr_wrong_password() {
+    let buf = Header {
+        cipher: Cipher::Aes128Ctr,
+        compression: Compression::None,
+        ..header_rev3_aes128_gcm()
+    }
+    .write(&mut store_rev3_aes128_gcm())
+    .unwrap();
+
+    let mut store = PasswordStore::with_value(b"xxx");
+    let err = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap_err();
+
+    assert!(matches!(err, HeaderError::WrongPassword));
+}
+
+#[test]
+fn add_slot_none() {
+    let mut header = Header {
+        revision: 3,
+        ..header(PlainSecret::Rev3(rev3()))
+    };
+
+    assert!(!header
+        .add_slot(Kdf::pbkdf2(Digest::Sha1, 1, b"123"), b"abc")
+        .unwrap());
+    assert!(header.slots.is_empty());
+}
+
+#[test]
+fn add_slot_kdf_none() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(!header.add_slot(Kdf::None, b"abc").unwrap());
+    assert_eq!(header.slots.len(), 1);
+}
+
+#[test]
+fn add_slot_rev2() {
+    let mut header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    let err = header
+        .add_slot(Kdf::pbkdf2(Digest::Sha1, 1, b"123"), b"abc")
+        .unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2));
+}
+
+#[test]
+fn remove_slot() {
+    let slot = |n| KeySlot::password(Kdf::pbkdf2(Digest::Sha1, n, b"123"), vec![], vec![]);
+    let mut header = Header {
+        slots: vec![slot(1), slot(2), slot(3)],
+        slot: Some(1),
+        ..header_rev3_aes128_gcm()
+    };
+
+    assert!(header.remove_slot(0).unwrap());
+    assert_eq!(header.slots, [slot(2), slot(3)]);
+    assert_eq!(header.slot, Some(0));
+
+    let err = header.remove_slot(0).unwrap_err();
+    assert!(matches!(err, HeaderError::ActiveKeySlot(0)));
+
+    let err = header.remove_slot(2).unwrap_err();
+    assert!(matches!(err, HeaderError::NoSuchKeySlot(2)));
+
+    assert!(header.remove_slot(1).unwrap());
+    assert_eq!(header.slots, [slot(2)]);
+    assert_eq!(header.slot, Some(0));
+}
+
+#[test]
+fn remove_slot_no_active_slot() {
+    let slot = |n| KeySlot::password(Kdf::pbkdf2(Digest::Sha1, n, b"123"), vec![], vec![]);
+    let mut header = Header {
+        slots: vec![slot(1), slot(2)],
+        slot: None,
+        ..header_rev3_aes128_gcm()
+    };
+
+    assert!(header.remove_slot(0).unwrap());
+    assert_eq!(header.slots, [slot(2)]);
+    assert_eq!(header.slot, None);
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_recipient() {
+    let mut header = header_rev3_aes128_gcm();
+    let identity = Identity::generate().unwrap();
+    let recipient = identity.recipient().unwrap();
+
+    assert!(header.add_recipient(&recipient).unwrap());
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    let mut store = PasswordStore::new(None).with_identity(Some(identity));
+    let header = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap();
+
+    assert_eq!(header.kdf, Kdf::None);
+    assert_eq!(header.header_key, header_rev3_aes128_gcm().header_key);
+    assert_eq!(header.active_slot(), Some(1));
+    assert_eq!(
+        header.slots(),
+        [
+            SlotInfo::Password(Kdf::pbkdf2(Digest::Sha1, 1, b"123")),
+            SlotInfo::Recipient(recipient)
+        ]
+    );
+    assert_eq!(header.data, header_rev3_aes128_gcm().data);
+    assert!(matches!(
+        header.password_slot_or_err().unwrap_err(),
+        HeaderError::NoPasswordSlot
+    ));
+
+    let header =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+
+    assert_eq!(header.active_slot(), Some(0));
+    header.password_slot_or_err().unwrap();
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_extended() {
+    let mut header = header_rev3_aes128_gcm();
+    let identities = (0..10)
+        .map(|_| Identity::generate().unwrap())
+        .collect::<Vec<_>>();
+
+    for identity in identities.iter() {
+        assert!(header
+            .add_recipient(&identity.recipient().unwrap())
+            .unwrap());
+    }
+
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+    let blocks = ExtendedBlocks::peek(&buf).unwrap().count;
+
+    assert!(blocks > 0);
+    assert_eq!((buf.len() - 1) / HEADER_MAX_SIZE, blocks);
+
+    for (idx, identity) in identities.into_iter().enumerate() {
+        let mut store = PasswordStore::new(None).with_identity(Some(identity));
+        let header = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap();
+
+        assert_eq!(header.active_slot(), Some(idx + 1));
+        assert_eq!(header.slots().len(), 11);
+        assert_eq!(header.data, header_rev3_aes128_gcm().data);
+    }
+
+    // the root header alone is not enough
+    let err = Header::<MemoryBackend>::read(
+        &buf[..HEADER_MAX_SIZE],
+        Migrator::default(),
+        &mut store_rev3_aes128_gcm(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, HeaderError::Buffer(_)));
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_generation() {
+    let mut header = header_rev3_aes128_gcm();
+
+    header.set_generation(7);
+
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+    assert_eq!(ExtendedBlocks::peek(&buf).unwrap().generation, 7);
+
+    let header =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+    assert_eq!(header.generation, 7);
+}
+
+#[test]
+fn extended_blocks_indices() {
+    let even = ExtendedBlocks {
+        count: 3,
+        generation: 2,
+    };
+    let odd = ExtendedBlocks {
+        count: 2,
+        generation: 3,
+    };
+
+    assert_eq!(even.indices().collect::<Vec<_>>(), [1, 3, 5]);
+    assert_eq!(odd.indices().collect::<Vec<_>>(), [2, 4]);
+    assert_eq!(ExtendedBlocks::default().indices().count(), 0);
+}
+
+#[test]
+fn extended_blocks_next_generation() {
+    let blocks = ExtendedBlocks {
+        count: 1,
+        generation: 255,
+    };
+
+    assert_eq!(blocks.next_generation(), 0);
+}
+
+#[test]
+fn write_rev2_too_large() {
+    let mut header = Header {
+        kdf: Kdf::pbkdf2(Digest::Sha1, 1, &[1; HEADER_MAX_SIZE]),
+        cipher: Cipher::Aes128Ctr,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    let err = header
+        .write(&mut PasswordStore::with_value(b"abc"))
+        .unwrap_err();
+
+    assert!(matches!(err, HeaderError::TooLarge(n) if n > HEADER_MAX_SIZE));
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_unknown_identity() {
+    let mut header = header_rev3_aes128_gcm();
+    let recipient = Identity::generate().unwrap().recipient().unwrap();
+
+    assert!(header.add_recipient(&recipient).unwrap());
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    let identity = Identity::generate().unwrap();
+    let mut store = PasswordStore::new(None).with_identity(Some(identity));
+    let err = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap_err();
+
+    assert!(matches!(err, HeaderError::UnknownIdentity));
+}
+
+#[test]
+fn write_read_rev3_recovery_key() {
+    for cipher in [Cipher::Aes128Ctr, Cipher::Aes128Gcm] {
+        let header = Header {
+            cipher,
+            ..header_rev3_aes128_gcm()
+        };
+        let recovery_key = header.recovery_key().unwrap();
+
+        assert_eq!(recovery_key.as_bytes(), [b'y'; 16]);
+
+        let buf = Header {
+            cipher,
+            ..header_rev3_aes128_gcm()
+        }
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
+
+        let mut store = PasswordStore::new(None).with_recovery_key(Some(recovery_key));
+        let header = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap();
+
+        assert_eq!(header.cipher, cipher);
+        assert_eq!(header.kdf, Kdf::None);
+        assert_eq!(header.header_key, header_rev3_aes128_gcm().header_key);
+        assert_eq!(header.active_slot(), None);
+        assert_eq!(
+            header.slots(),
+            [SlotInfo::Password(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))]
+        );
+        assert_eq!(header.data, header_rev3_aes128_gcm().data);
+    }
+}
+
+#[test]
+fn read_rev3_wrong_recovery_key() {
+    for cipher in [Cipher::Aes128Ctr, Cipher::Aes128Gcm] {
+        let buf = Header {
+            cipher,
+            ..header_rev3_aes128_gcm()
+        }
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
+
+        for key in [[b'x'; 16].as_slice(), [b'y'; 32].as_slice()] {
+            let mut store = PasswordStore::new(None).with_recovery_key(Some(RecoveryKey::new(key)));
+            let err =
+                Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap_err();
+
+            assert!(matches!(err, HeaderError::WrongRecoveryKey));
+        }
+    }
+}
+
+#[test]
+fn read_rev2_recovery_key() {
+    let mut store = PasswordStore::new(None).with_recovery_key(Some(RecoveryKey::new(&[1])));
+    let err = Header::<MemoryBackend>::read(&REV2, Migrator::default(), &mut store).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2));
+}
+
+#[test]
+fn recovery_key_none() {
+    let header = Header {
+        revision: 3,
+        ..header(PlainSecret::Rev3(rev3()))
+    };
+
+    let err = header.recovery_key().unwrap_err();
+
+    assert!(matches!(err, HeaderError::NotEncrypted));
+}
+
+#[test]
+fn recovery_key_rev2() {
+    let header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    let err = header.recovery_key().unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2));
+}
+
+#[test]
+fn add_recipient_none() {
+    let mut header = Header {
+        revision: 3,
+        ..header(PlainSecret::Rev3(rev3()))
+    };
+    let recipient = Identity::generate().unwrap().recipient().unwrap();
+
+    assert!(!header.add_recipient(&recipient).unwrap());
+    assert!(header.slots.is_empty());
+}
+
+#[test]
+fn add_recipient_rev2() {
+    let mut header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+    let recipient = Identity::generate().unwrap().recipient().unwrap();
+
+    let err = header.add_recipient(&recipient).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2));
+}
+
+#[test]
+fn password_slot_or_err_rev2() {
+    let header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    header.password_slot_or_err().unwrap();
+}
+
+#[test]
+fn write_rev3_aes128_gcm_keep_password_slot() {
+    let mut header = header_rev3_aes128_gcm();
+
+    header.write(&mut store_rev3_aes128_gcm()).unwrap();
+    let slot = header.slots[0].clone();
+
+    // the password is not asked again
+    header.write(&mut PasswordStore::new(None)).unwrap();
+    assert_eq!(header.slots[0], slot);
+
+    // wrapped again with the new password
+    header.reset_password_slot();
+    let buf = header
+        .write(&mut PasswordStore::with_value(b"xyz"))
+        .unwrap();
+
+    let mut store = PasswordStore::with_value(b"xyz");
+    let header = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap();
+
+    assert_eq!(header.slot, Some(0));
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_rekey() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.begin_rekey(Cipher::Aes128Gcm).unwrap());
+    assert!(header.set_rekey_progress(&[1], &[2], &[3]));
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    let header2 =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+
+    assert_eq!(header2.data, header.data);
+    assert_eq!(header2.rekey_cursor().unwrap(), [1]);
+}
+
+#[test]
+fn begin_rekey() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.rekey_cursor().is_none());
+    assert!(header.begin_rekey(Cipher::Aes128Gcm).unwrap());
+    assert_eq!(header.rekey_cursor().unwrap(), []);
+
+    let key = match &header.data {
+        PlainSecret::Rev3(rev3) => rev3.rekey.as_ref().unwrap().key.clone(),
+        _ => panic!("invalid data"),
+    };
+
+    assert_eq!(key.len(), 16);
+    assert_ne!(key.as_slice(), header.key());
+
+    // already pending
+    assert!(!header.begin_rekey(Cipher::Aes128Gcm).unwrap());
+}
+
+#[test]
+fn begin_rekey_none() {
+    let mut header = Header {
+        revision: 3,
+        ..header(PlainSecret::Rev3(rev3()))
+    };
+
+    assert!(!header.begin_rekey(Cipher::None).unwrap());
+    assert!(header.rekey_cursor().is_none());
+}
+
+#[test]
+fn begin_rekey_rev2() {
+    let mut header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    assert!(header.begin_rekey(Cipher::Aes128Gcm).unwrap());
+
+    assert_eq!(header.revision(), 3);
+    assert!(header.binds_blocks());
+    assert_eq!(header.rekey_cipher(), Some(Cipher::Aes128Gcm));
+    assert_eq!(header.rekey_cursor(), Some(&[][..]));
+    assert_eq!(header.iv(), Some(&[][..]));
+    assert_eq!(header.active_slot(), Some(0));
+    assert_eq!(header.header_key.len(), Cipher::Aes128Gcm.key_len());
+}
+
+#[test]
+fn begin_rekey_rev2_none() {
+    let mut header = Header {
+        revision: 2,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    // the header is upgraded, but there is nothing to re-encrypt
+    assert!(header.begin_rekey(Cipher::None).unwrap());
+
+    assert_eq!(header.revision(), 3);
+    assert!(header.rekey_cursor().is_none());
+    assert!(header.iv().is_none());
+    assert!(header.active_slot().is_none());
+}
+
+#[test]
+fn finish_rekey_rev2() {
+    let mut header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    assert!(header.begin_rekey(Cipher::Aes128Gcm).unwrap());
+    assert!(header.finish_rekey(Kdf::None).unwrap());
+
+    assert_eq!(header.revision(), 3);
+    assert!(header.rekey_cursor().is_none());
+    assert!(header.iv().is_none());
+    assert_eq!(header.key().len(), Cipher::Aes128Gcm.key_len());
+}
+
+#[test]
+fn begin_rekey_pending_conversion() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.begin_rekey(Cipher::Aes256Gcm).unwrap());
+    assert_eq!(header.rekey_cipher(), Some(Cipher::Aes256Gcm));
+
+    let err = header.begin_rekey(Cipher::Aes128Gcm).unwrap_err();
+
+    assert!(matches!(err, HeaderError::PendingConversion(cipher)
+        if cipher == Cipher::Aes256Gcm));
+}
+
+#[test]
+fn begin_rekey_inactive_slot() {
+    let mut header = header_rev3_aes128_gcm();
+
+    header
+        .add_slot(Kdf::pbkdf2(Digest::Sha256, 2, b"456"), b"xyz")
+        .unwrap();
+
+    let err = header.begin_rekey(Cipher::Aes256Gcm).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InactiveKeySlot(idx) if idx == 1));
+    assert!(header.rekey_cipher().is_none());
+
+    // the header key is replaced, even if the cipher does not change
+    let err = header.begin_rekey(Cipher::Aes128Gcm).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InactiveKeySlot(idx) if idx == 1));
+    assert!(header.rekey_cipher().is_none());
+}
+
+#[test]
+fn set_rekey_progress_not_pending() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(!header.set_rekey_progress(&[1], &[2], &[3]));
+    assert!(header.rekey_cursor().is_none());
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_journal() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.set_journal(&[1, 2], true).unwrap());
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    let header2 =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+
+    assert_eq!(header2.data, header.data);
+    assert_eq!(header2.journal(), Some((&[1, 2][..], true)));
+}
+
+#[test]
+fn set_journal() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.journal().is_none());
+
+    assert!(header.set_journal(&[1, 2], false).unwrap());
+    assert_eq!(header.journal(), Some((&[1, 2][..], false)));
+
+    assert!(header.set_journal(&[1, 2], true).unwrap());
+    assert_eq!(header.journal(), Some((&[1, 2][..], true)));
+}
+
+#[test]
+fn set_journal_rev2() {
+    let mut header = Header {
+        revision: 2,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    let err = header.set_journal(&[1, 2], false).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2));
+    assert!(header.journal().is_none());
+}
+
+#[test]
+fn clear_journal() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(!header.clear_journal());
+
+    header.set_journal(&[1, 2], true).unwrap();
+
+    assert!(header.clear_journal());
+    assert!(header.journal().is_none());
+}
+
+#[test]
+fn write_read_rev3_aes128_gcm_snapshots() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.set_snapshots(Some(&[1, 2])).unwrap());
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    let header2 =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+
+    assert_eq!(header2.data, header.data);
+    assert_eq!(header2.snapshots(), Some(&[1, 2][..]));
 }
 
-fn rev2() -> PlainRev2<MemoryBackend> {
-    PlainRev2 {
-        magics: 0x91c0b2cf.into(),
-        key: vec![].into(),
-        iv: vec![].into(),
-        sid: None,
-        top_id: None,
-        settings: Settings,
-    }
+#[test]
+fn set_snapshots() {
+    let mut header = header_rev3_aes128_gcm();
+
+    assert!(header.snapshots().is_none());
+    assert!(!header.set_snapshots(None).unwrap());
+
+    assert!(header.set_snapshots(Some(&[1, 2])).unwrap());
+    assert_eq!(header.snapshots(), Some(&[1, 2][..]));
+    assert!(!header.set_snapshots(Some(&[1, 2])).unwrap());
+
+    assert!(header.set_snapshots(None).unwrap());
+    assert!(header.snapshots().is_none());
 }
 
-fn header(data: PlainSecret<MemoryBackend>) -> Header<'static, MemoryBackend> {
-    Header::<MemoryBackend> {
-        revision: 1,
-        migrator: Migrator::default(),
-        cipher: Cipher::None,
-        kdf: Kdf::None,
-        data,
-    }
+#[test]
+fn set_snapshots_rev2() {
+    let mut header = Header {
+        revision: 2,
+        ..header(PlainSecret::Rev2(rev2()))
+    };
+
+    let err = header.set_snapshots(Some(&[1, 2])).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2));
+    assert!(header.snapshots().is_none());
 }
 
 #[test]
-fn create() {
-    let options = CreateOptionsBuilder::new(Cipher::None)
-        .build::<MemoryBackend>()
-        .unwrap();
-    let header = Header::<MemoryBackend>::create(&options, Settings).unwrap();
+fn block_key_rev2() {
+    let mut header = Header {
+        revision: 2,
+        cipher: Cipher::Aes128Gcm,
+        compression: Compression::None,
+        ..header(PlainSecret::Rev2(PlainRev2 {
+            key: vec![b'x'; 16].into(),
+            iv: vec![b'y'; 12].into(),
+            ..rev2()
+        }))
+    };
+    let old_key = (Cipher::Aes128Gcm, &[b'x'; 16][..], Some(&[b'y'; 12][..]));
 
-    assert_eq!(header.revision, 2);
-    assert_eq!(header.cipher, Cipher::None);
-    assert_eq!(header.kdf, Kdf::None);
-    assert_eq!(header.data, PlainSecret::Rev2(rev2()));
+    assert_eq!(header.block_key(&[1], None), old_key);
+
+    header.begin_rekey(Cipher::Aes128Gcm).unwrap();
+    header.set_rekey_progress(&[5], &[6], &sha256(b"abc"));
+
+    // the blocks, which are not re-encrypted yet, still use the old iv
+    let new_key = match &header.data {
+        PlainSecret::Rev3(rev3) => rev3.rekey.as_ref().unwrap().key.clone(),
+        _ => panic!("invalid data"),
+    };
+    let new_key = (Cipher::Aes128Gcm, new_key.as_slice(), None);
+
+    assert_eq!(header.block_key(&[5], None), new_key);
+    assert_eq!(header.block_key(&[7], None), old_key);
 }
 
 #[test]
-fn read_rev0() {
-    let migrator = Migrator::default();
-    let mut store = PasswordStore::new(None);
+fn block_key() {
+    let mut header = header_rev3_aes128_gcm();
+    let old_key = (Cipher::Aes128Gcm, &[b'x'; 16][..], None);
 
-    let header = Header::<MemoryBackend>::read(&REV0, migrator, &mut store).unwrap();
+    assert_eq!(header.block_key(&[1], None), old_key);
+    assert_eq!(header.block_key(&[1], Some(b"abc")), old_key);
 
-    assert_eq!(header.revision, 0);
-    assert_eq!(header.cipher, Cipher::None);
-    assert_eq!(header.kdf, Kdf::None);
-    assert_eq!(header.data, PlainSecret::Rev0(rev0()));
+    header.begin_rekey(Cipher::Aes256Ctr).unwrap();
+    header.set_rekey_progress(&[5], &[6], &sha256(b"abc"));
+
+    let new_key = match &header.data {
+        PlainSecret::Rev3(rev3) => rev3.rekey.as_ref().unwrap().key.clone(),
+        _ => panic!("invalid data"),
+    };
+    let new_key = (Cipher::Aes256Ctr, new_key.as_slice(), None);
+
+    assert_eq!(new_key.1.len(), 32);
+    assert_eq!(header.block_key(&[4], None), new_key);
+    assert_eq!(header.block_key(&[5], None), new_key);
+    assert_eq!(header.block_key(&[6], None), new_key);
+    assert_eq!(header.block_key(&[6], Some(b"abc")), old_key);
+    assert_eq!(header.block_key(&[6], Some(b"xyz")), new_key);
+    assert_eq!(header.block_key(&[7], None), old_key);
+
+    assert!(header.rekey_done(&[5], b"abc"));
+    assert!(!header.rekey_done(&[6], b"abc"));
+    assert!(header.rekey_done(&[6], b"xyz"));
+    assert!(!header.rekey_done(&[7], b"abc"));
 }
 
 #[test]
-fn read_rev1() {
-    let migrator = Migrator::default();
-    let mut store = PasswordStore::new(None);
+fn finish_rekey() {
+    let mut header = header_rev3_aes128_gcm();
 
-    let header = Header::<MemoryBackend>::read(&REV1, migrator, &mut store).unwrap();
+    assert!(!header.finish_rekey(Kdf::None).unwrap());
 
-    assert_eq!(header.revision, 1);
-    assert_eq!(header.cipher, Cipher::None);
-    assert_eq!(header.kdf, Kdf::None);
-    assert_eq!(
-        header.data,
-        PlainSecret::Rev1(PlainRev1 {
-            top_id: Some("4711".parse().unwrap()),
-            ..rev1()
-        })
-    );
+    header.begin_rekey(Cipher::Aes128Gcm).unwrap();
+
+    let new_key = match &header.data {
+        PlainSecret::Rev3(rev3) => rev3.rekey.as_ref().unwrap().key.clone(),
+        _ => panic!("invalid data"),
+    };
+
+    assert!(header.finish_rekey(Kdf::None).unwrap());
+    assert_eq!(header.cipher, Cipher::Aes128Gcm);
+    assert_eq!(header.header_key.len(), 16);
+    assert_ne!(header.header_key, header_rev3_aes128_gcm().header_key);
+    assert_eq!(header.key(), new_key.as_slice());
+    assert!(header.rekey_cursor().is_none());
+    assert!(!header.finish_rekey(Kdf::None).unwrap());
 }
 
 #[test]
-fn read_rev2() {
-    let migrator = Migrator::default();
-    let mut store = PasswordStore::new(None);
+fn finish_rekey_convert() {
+    let mut header = header_rev3_aes128_gcm();
+    let identity = Identity::generate().unwrap();
+    let recipient = identity.recipient().unwrap();
+
+    header.add_recipient(&recipient).unwrap();
+    header.begin_rekey(Cipher::Aes256Gcm).unwrap();
+    assert!(header.finish_rekey(Kdf::None).unwrap());
+
+    assert_eq!(header.cipher, Cipher::Aes256Gcm);
+    assert_eq!(header.key().len(), 32);
+    assert_eq!(header.header_key.len(), 32);
+    assert_eq!(header.slot, Some(0));
+
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    for mut store in [
+        store_rev3_aes128_gcm(),
+        PasswordStore::new(None).with_identity(Some(identity)),
+    ] {
+        let header2 = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap();
+
+        assert_eq!(header2.cipher, Cipher::Aes256Gcm);
+        assert_eq!(header2.header_key, header.header_key);
+        assert_eq!(
+            header2.slots(),
+            [
+                SlotInfo::Password(Kdf::pbkdf2(Digest::Sha1, 1, b"123")),
+                SlotInfo::Recipient(recipient.clone())
+            ]
+        );
+        assert_eq!(header2.data, header.data);
+    }
+}
 
-    let header = Header::<MemoryBackend>::read(&REV2, migrator, &mut store).unwrap();
+#[test]
+fn finish_rekey_convert_from_none() {
+    let mut header = Header {
+        revision: 3,
+        ..header(PlainSecret::Rev3(rev3()))
+    };
+    let kdf = Kdf::pbkdf2(Digest::Sha1, 1, b"123");
+
+    header.begin_rekey(Cipher::Aes128Ctr).unwrap();
+    assert!(header.finish_rekey(kdf.clone()).unwrap());
+
+    assert_eq!(header.cipher, Cipher::Aes128Ctr);
+    assert_eq!(header.kdf, kdf);
+    assert_eq!(header.key().len(), 16);
+    assert_eq!(header.slot, Some(0));
+
+    let buf = header.write(&mut store_rev3_aes128_gcm()).unwrap();
+
+    let header2 =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap();
+
+    assert_eq!(header2.cipher, Cipher::Aes128Ctr);
+    assert_eq!(header2.slots(), [SlotInfo::Password(kdf)]);
+    assert_eq!(header2.data, header.data);
+}
+
+#[test]
+fn finish_rekey_convert_into_none() {
+    let mut header = header_rev3_aes128_gcm();
+
+    header
+        .add_slot(Kdf::pbkdf2(Digest::Sha256, 2, b"456"), b"xyz")
+        .unwrap();
+    header.begin_rekey(Cipher::None).unwrap();
+    assert!(header.finish_rekey(Kdf::None).unwrap());
 
-    assert_eq!(header.revision, 2);
     assert_eq!(header.cipher, Cipher::None);
     assert_eq!(header.kdf, Kdf::None);
-    assert_eq!(
-        header.data,
-        PlainSecret::Rev2(PlainRev2 {
-            top_id: Some("4711".parse().unwrap()),
-            sid: Some(666),
-            ..rev2()
-        })
-    );
+    assert!(header.key().is_empty());
+    assert!(header.slots.is_empty());
+    assert_eq!(header.slot, None);
+
+    let buf = header.write(&mut PasswordStore::new(None)).unwrap();
+
+    let header2 =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut PasswordStore::new(None))
+            .unwrap();
+
+    assert_eq!(header2.cipher, Cipher::None);
+    assert_eq!(header2.data, header.data);
 }
 
 #[test]
-fn write_rev0() {
-    let mut buf = [b'x'; REV0.len()];
-    let mut store = PasswordStore::new(None);
+fn finish_rekey_inactive_slot() {
+    let mut header = header_rev3_aes128_gcm();
 
-    let header = header(PlainSecret::Rev0(rev0()));
+    header.begin_rekey(Cipher::Aes256Gcm).unwrap();
+    header
+        .add_slot(Kdf::pbkdf2(Digest::Sha256, 2, b"456"), b"xyz")
+        .unwrap();
 
-    header.write(&mut buf, &mut store).unwrap();
+    let err = header.finish_rekey(Kdf::None).unwrap_err();
 
-    assert_eq!(buf, REV0);
+    assert!(matches!(err, HeaderError::InactiveKeySlot(idx) if idx == 1));
+    assert_eq!(header.rekey_cipher(), Some(Cipher::Aes256Gcm));
 }
 
 #[test]
-fn write_rev1() {
-    let mut buf = [b'x'; REV1.len()];
-    let mut store = PasswordStore::new(None);
+fn read_rev3_aes128_gcm_tampered() {
+    let buf = header_rev3_aes128_gcm()
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
 
-    let header = header(PlainSecret::Rev1(PlainRev1 {
-        top_id: Some("4711".parse().unwrap()),
-        ..rev1()
-    }));
+    let mut rev = Revision::get_from_buffer(&mut &buf[..]).unwrap();
 
-    header.write(&mut buf, &mut store).unwrap();
+    match &mut rev {
+        Revision::Rev3(data) => data.slots.push(data.slots[0].clone()),
+        _ => panic!("invalid revision"),
+    }
 
-    assert_eq!(buf, REV1);
+    let mut buf = vec![];
+    rev.put_into_buffer(&mut buf).unwrap();
+
+    let err =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap_err();
+
+    assert!(matches!(err, HeaderError::NotTrustworthy));
 }
 
 #[test]
-fn write_rev2() {
-    let mut buf = [b'x'; REV2.len()];
-    let mut store = PasswordStore::new(None);
+fn read_rev3_aes128_gcm_tampered_compression() {
+    let buf = header_rev3_aes128_gcm()
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
 
-    let header = header(PlainSecret::Rev2(PlainRev2 {
-        top_id: Some("4711".parse().unwrap()),
-        sid: Some(666),
-        ..rev2()
-    }));
+    let mut rev = Revision::get_from_buffer(&mut &buf[..]).unwrap();
 
-    header.write(&mut buf, &mut store).unwrap();
+    match &mut rev {
+        Revision::Rev3(data) => data.compression = Compression::Lz4,
+        _ => panic!("invalid revision"),
+    }
 
-    assert_eq!(buf, REV2);
+    let mut buf = vec![];
+    rev.put_into_buffer(&mut buf).unwrap();
+
+    let err =
+        Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store_rev3_aes128_gcm())
+            .unwrap_err();
+
+    assert!(matches!(err, HeaderError::NotTrustworthy));
+}
+
+#[test]
+fn read_rev3_aes128_gcm_wrong_aad() {
+    let buf = header_rev3_aes128_gcm()
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
+
+    let rev = Revision::get_from_buffer(&mut &buf[..]).unwrap();
+    let mut aad = rev.aad().unwrap();
+
+    aad[0] += 1;
+
+    let data = match rev {
+        Revision::Rev3(data) => data,
+        _ => panic!("invalid revision"),
+    };
+    let err = Header::<MemoryBackend>::read_rev3(
+        data,
+        &aad,
+        Migrator::default(),
+        &mut store_rev3_aes128_gcm(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, HeaderError::NotTrustworthy));
+}
+
+#[test]
+fn read_rev3_aes128_gcm_wrong_password() {
+    let buf = header_rev3_aes128_gcm()
+        .write(&mut store_rev3_aes128_gcm())
+        .unwrap();
+
+    let mut store = PasswordStore::new(Some(Arc::new(|| Ok(b"xxx".to_vec()))));
+    let err = Header::<MemoryBackend>::read(&buf, Migrator::default(), &mut store).unwrap_err();
+
+    assert!(matches!(err, HeaderError::NotTrustworthy));
 }
 
 #[test]
@@ -236,7 +1326,7 @@ fn latest_revision_or_err_rev0() {
     let err = header.latest_revision_or_err().unwrap_err();
 
     assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
-        if expected == 2 && got == 0))
+        if expected == 3 && got == 0))
 }
 
 #[test]
@@ -249,7 +1339,7 @@ fn latest_revision_or_err_rev1() {
     let err = header.latest_revision_or_err().unwrap_err();
 
     assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
-        if expected == 2 && got == 1))
+        if expected == 3 && got == 1))
 }
 
 #[test]
@@ -259,6 +1349,19 @@ fn latest_revision_or_err_rev2() {
         ..header(PlainSecret::Rev2(rev2()))
     };
 
+    let err = header.latest_revision_or_err().unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidRevision(expected, got)
+        if expected == 3 && got == 2))
+}
+
+#[test]
+fn latest_revision_or_err_rev3() {
+    let header = Header {
+        revision: 3,
+        ..header(PlainSecret::Rev3(rev3()))
+    };
+
     header.latest_revision_or_err().unwrap();
 }
 
@@ -266,6 +1369,7 @@ fn latest_revision_or_err_rev2() {
 fn set_key_none_none() {
     let mut header = Header {
         cipher: Cipher::None,
+        compression: Compression::None,
         kdf: Kdf::None,
         ..header(PlainSecret::Rev2(rev2()))
     };
@@ -278,6 +1382,7 @@ fn set_key_none_none() {
 fn set_key_none_some() {
     let mut header = Header {
         cipher: Cipher::None,
+        compression: Compression::None,
         kdf: Kdf::None,
         ..header(PlainSecret::Rev2(rev2()))
     };
@@ -290,6 +1395,7 @@ fn set_key_none_some() {
 fn set_key_some_none() {
     let mut header = Header {
         cipher: Cipher::Aes256Gcm,
+        compression: Compression::None,
         kdf: Kdf::pbkdf2(Digest::Sha512, 4711, b"123"),
         ..header(PlainSecret::Rev2(rev2()))
     };
@@ -302,6 +1408,7 @@ fn set_key_some_none() {
 fn set_key_some_some() {
     let mut header = Header {
         cipher: Cipher::Aes256Gcm,
+        compression: Compression::None,
         kdf: Kdf::pbkdf2(Digest::Sha512, 4711, b"123"),
         ..header(PlainSecret::Rev2(rev2()))
     };
@@ -331,6 +1438,13 @@ fn settings_rev2() {
     assert_eq!(header.settings().as_bytes(), Settings.as_bytes());
 }
 
+#[test]
+fn settings_rev3() {
+    let header = header(PlainSecret::Rev3(rev3()));
+
+    assert_eq!(header.settings().as_bytes(), Settings.as_bytes());
+}
+
 #[test]
 fn key_rev0() {
     let header = header(PlainSecret::Rev0(PlainRev0 {
@@ -361,6 +1475,16 @@ fn key_rev2() {
     assert_eq!(header.key(), [1, 2, 3]);
 }
 
+#[test]
+fn key_rev3() {
+    let header = header(PlainSecret::Rev3(PlainRev3 {
+        key: vec![1, 2, 3].into(),
+        ..rev3()
+    }));
+
+    assert_eq!(header.key(), [1, 2, 3]);
+}
+
 #[test]
 fn iv_rev0() {
     let header = header(PlainSecret::Rev0(PlainRev0 {
@@ -368,7 +1492,7 @@ fn iv_rev0() {
         ..rev0()
     }));
 
-    assert_eq!(header.iv(), [1, 2, 3]);
+    assert_eq!(header.iv().unwrap(), [1, 2, 3]);
 }
 
 #[test]
@@ -378,7 +1502,7 @@ fn iv_rev1() {
         ..rev1()
     }));
 
-    assert_eq!(header.iv(), [1, 2, 3]);
+    assert_eq!(header.iv().unwrap(), [1, 2, 3]);
 }
 
 #[test]
@@ -388,7 +1512,14 @@ fn iv_rev2() {
         ..rev2()
     }));
 
-    assert_eq!(header.iv(), [1, 2, 3]);
+    assert_eq!(header.iv().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn iv_rev3() {
+    let header = header(PlainSecret::Rev3(rev3()));
+
+    assert!(header.iv().is_none());
 }
 
 #[test]
@@ -425,6 +1556,13 @@ fn accept_sid_for_create_rev2_none() {
     header.accept_sid_for_create().unwrap();
 }
 
+#[test]
+fn accept_sid_for_create_rev3_none() {
+    let header = header(PlainSecret::Rev3(rev3()));
+
+    header.accept_sid_for_create().unwrap();
+}
+
 #[test]
 fn accept_sid_for_create_rev2_some() {
     let header = header(PlainSecret::Rev2(PlainRev2 {
@@ -438,6 +1576,19 @@ fn accept_sid_for_create_rev2_some() {
         if expected.is_none() && got == Some(666)));
 }
 
+#[test]
+fn accept_sid_for_create_rev3_some() {
+    let header = header(PlainSecret::Rev3(PlainRev3 {
+        sid: Some(666),
+        ..rev3()
+    }));
+
+    let err = header.accept_sid_for_create().unwrap_err();
+
+    assert!(matches!(err,HeaderError::UnexpectedSid { expected, got }
+        if expected.is_none() && got == Some(666)));
+}
+
 #[test]
 fn accept_sid_for_open_rev0_none() {
     let header = header(PlainSecret::Rev0(rev0()));
@@ -488,6 +1639,16 @@ fn accept_sid_for_open_rev2_none() {
         if expected == Some(666) && got.is_none()));
 }
 
+#[test]
+fn accept_sid_for_open_rev3_none() {
+    let header = header(PlainSecret::Rev3(rev3()));
+
+    let err = header.accept_sid_for_open(666).unwrap_err();
+
+    assert!(matches!(err, HeaderError::UnexpectedSid{expected, got}
+        if expected == Some(666) && got.is_none()));
+}
+
 #[test]
 fn accept_sid_for_open_rev2_some_eq() {
     let header = header(PlainSecret::Rev2(PlainRev2 {
@@ -498,6 +1659,16 @@ fn accept_sid_for_open_rev2_some_eq() {
     header.accept_sid_for_open(666).unwrap();
 }
 
+#[test]
+fn accept_sid_for_open_rev3_some_eq() {
+    let header = header(PlainSecret::Rev3(PlainRev3 {
+        sid: Some(666),
+        ..rev3()
+    }));
+
+    header.accept_sid_for_open(666).unwrap();
+}
+
 #[test]
 fn accept_sid_for_open_rev2_some_neq() {
     let header = header(PlainSecret::Rev2(PlainRev2 {
@@ -511,6 +1682,19 @@ fn accept_sid_for_open_rev2_some_neq() {
         if expected == Some(666) && got == Some(4711)));
 }
 
+#[test]
+fn accept_sid_for_open_rev3_some_neq() {
+    let header = header(PlainSecret::Rev3(PlainRev3 {
+        sid: Some(4711),
+        ..rev3()
+    }));
+
+    let err = header.accept_sid_for_open(666).unwrap_err();
+
+    assert!(matches!(err, HeaderError::UnexpectedSid{expected, got}
+        if expected == Some(666) && got == Some(4711)));
+}
+
 #[test]
 #[should_panic(expected = "storing a sid into a rev0 header is not supported")]
 fn set_sid_rev0() {
@@ -544,6 +1728,15 @@ fn set_sid_rev2() {
     assert!(matches!(header.data, PlainSecret::Rev2(rev2) if rev2.sid == Some(666)));
 }
 
+#[test]
+fn set_sid_rev3() {
+    let mut header = header(PlainSecret::Rev3(rev3()));
+
+    header.set_sid(666).unwrap();
+
+    assert!(matches!(header.data, PlainSecret::Rev3(rev3) if rev3.sid == Some(666)));
+}
+
 #[test]
 fn set_sid_rev2_inval() {
     let mut header = header(PlainSecret::Rev2(rev2()));
@@ -552,6 +1745,14 @@ fn set_sid_rev2_inval() {
     assert!(matches!(err, HeaderError::InvalidSid));
 }
 
+#[test]
+fn set_sid_rev3_inval() {
+    let mut header = header(PlainSecret::Rev3(rev3()));
+    let err = header.set_sid(0).unwrap_err();
+
+    assert!(matches!(err, HeaderError::InvalidSid));
+}
+
 #[test]
 fn top_id_rev0_none() {
     let header = header(PlainSecret::Rev0(rev0()));
@@ -593,6 +1794,13 @@ fn top_id_rev2_none() {
     assert!(header.top_id().is_none());
 }
 
+#[test]
+fn top_id_rev3_none() {
+    let header = header(PlainSecret::Rev3(rev3()));
+
+    assert!(header.top_id().is_none());
+}
+
 #[test]
 fn top_id_rev2_some() {
     let header = header(PlainSecret::Rev2(PlainRev2 {
@@ -604,6 +1812,17 @@ fn top_id_rev2_some() {
     assert_eq!(top_id.to_string(), "4711");
 }
 
+#[test]
+fn top_id_rev3_some() {
+    let header = header(PlainSecret::Rev3(PlainRev3 {
+        top_id: Some("4711".parse().unwrap()),
+        ..rev3()
+    }));
+    let top_id = header.top_id().unwrap();
+
+    assert_eq!(top_id.to_string(), "4711");
+}
+
 #[test]
 #[should_panic(expected = "storing a top-id into a rev0 header is not supported")]
 fn set_top_id_rev0() {
@@ -624,3 +1843,11 @@ fn set_top_id_rev2() {
 
     assert_eq!(header.top_id().unwrap().to_string(), "4711");
 }
+#[test]
+fn set_top_id_rev3() {
+    let mut header = header(PlainSecret::Rev3(rev3()));
+
+    header.set_top_id("4711".parse().unwrap());
+
+    assert_eq!(header.top_id().unwrap().to_string(), "4711");
+}
@@ -20,14 +20,30 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
+#[cfg(doc)]
 use nuts_backend::Backend;
+use nuts_backend::BackendTypes;
 
 use crate::cipher::Cipher;
+use crate::compression::Compression;
 use crate::kdf::Kdf;
+use crate::recipient::Recipient;
+
+/// Information about a key slot.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SlotInfo {
+    /// The slot is unlocked with a password. The key is derived with the
+    /// assigned key derivation function.
+    Password(Kdf),
+
+    /// The slot is unlocked with the [identity](crate::Identity) of the
+    /// assigned recipient.
+    Recipient(Recipient),
+}
 
 /// Information from the container.
 #[derive(Debug, PartialEq)]
-pub struct Info<B: Backend> {
+pub struct Info<B: BackendTypes> {
     /// Information from the lower backend.
     pub backend: B::Info,
 
@@ -37,9 +53,27 @@ pub struct Info<B: Backend> {
     /// The cipher used for encryption.
     pub cipher: Cipher,
 
+    /// The compression of the blocks.
+    pub compression: Compression,
+
     /// The key derivation function.
+    ///
+    /// This is the key derivation function of the key slot, which was used
+    /// to open the container. [`Kdf::None`] if the container was opened with
+    /// an [identity](crate::Identity).
     pub kdf: Kdf,
 
+    /// All key slots.
+    ///
+    /// Starting with revision 3 the container can be opened with any of the
+    /// passwords or identities assigned to a key slot. The list is empty for
+    /// older revisions and if encryption is disabled.
+    pub slots: Vec<SlotInfo>,
+
+    /// Index of the key slot (in [`Info::slots`]), which was used to open the
+    /// container.
+    pub active_slot: Option<usize>,
+
     /// The gross block size is the block size specified by the
     /// [backend](Backend::block_size).
     ///
@@ -56,6 +90,7 @@ pub struct Info<B: Backend> {
     /// Depending on the selected cipher, you need to store additional data in
     /// a block. I.e. an AE-cipher results into a tag, which needs to be stored
     /// additionally. Such data must be substracted from the gross block size
-    /// and results into the net block size.
+    /// and results into the net block size. The same applies to the frame of
+    /// a compressed block.
     pub bsize_net: u32,
 }
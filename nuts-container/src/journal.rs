@@ -0,0 +1,274 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use nuts_backend::{BackendTypes, Binary, IdSize};
+use std::collections::BTreeMap;
+use std::fmt;
+use thiserror::Error;
+
+use crate::buffer::{Buffer, BufferError, BufferMut};
+use crate::svec::SecureVec;
+
+const MAGIC: [u8; 8] = *b"nuts-jnl";
+
+/// Journal related errors.
+#[derive(Debug, Error)]
+pub enum JournalError {
+    /// Errors while (de-) serializing the journal.
+    #[error(transparent)]
+    Buffer(#[from] BufferError),
+
+    /// A block of the journal index is invalid.
+    #[error("not a journal block")]
+    InvalidMagic,
+
+    /// An invalid id was read from the journal index.
+    #[error("invalid id in journal")]
+    InvalidId,
+
+    /// The block size of the container is too small to store the journal
+    /// index.
+    #[error("the block size of {0} bytes is too small for the journal")]
+    InvalidBlockSize(u32),
+
+    /// Another transaction is already pending.
+    #[error("a transaction is pending")]
+    Pending,
+
+    /// No transaction was started.
+    #[error("no transaction is pending")]
+    NotPending,
+}
+
+/// The block modifications of a pending transaction.
+///
+/// Blocks are identified by the binary representation of their ids. Written
+/// blocks are staged with their plaintext, released blocks without data.
+pub struct Transaction<Id> {
+    blocks: BTreeMap<Vec<u8>, (Id, Option<SecureVec>)>,
+    aquired: Vec<Id>,
+}
+
+impl<Id: Clone> Transaction<Id> {
+    pub fn new() -> Transaction<Id> {
+        Transaction {
+            blocks: BTreeMap::new(),
+            aquired: vec![],
+        }
+    }
+
+    /// Returns the staged plaintext of the block with the given `key`.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.blocks
+            .get(key)
+            .and_then(|(_, data)| data.as_ref().map(|data| data.as_slice()))
+    }
+
+    /// Stages a write of `data` into the block `id`.
+    pub fn write(&mut self, key: Vec<u8>, id: &Id, data: SecureVec) {
+        self.blocks.insert(key, (id.clone(), Some(data)));
+    }
+
+    /// Stages the release of block `id`.
+    pub fn release(&mut self, key: Vec<u8>, id: Id) {
+        self.blocks.insert(key, (id, None));
+    }
+
+    /// Records a block aquired during the transaction. It is released again,
+    /// if the transaction is rolled back.
+    pub fn aquired(&mut self, id: Id) {
+        self.aquired.push(id);
+    }
+
+    /// Returns the staged blocks in the order of their keys and the blocks
+    /// aquired during the transaction.
+    pub fn into_parts(self) -> (Vec<(Id, Option<SecureVec>)>, Vec<Id>) {
+        (self.blocks.into_values().collect(), self.aquired)
+    }
+}
+
+impl<Id> fmt::Debug for Transaction<Id> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Transaction")
+            .field("blocks", &self.blocks.len())
+            .field("aquired", &self.aquired.len())
+            .finish()
+    }
+}
+
+/// An entry of the journal index.
+///
+/// The data of the `target` block are stored in the `source` block of the
+/// journal. Without a `source` the `target` block is released.
+#[derive(Debug, PartialEq)]
+pub struct Entry<B: BackendTypes> {
+    pub target: B::Id,
+    pub source: Option<B::Id>,
+}
+
+impl<B: BackendTypes> Entry<B> {
+    pub fn new(target: B::Id, source: Option<B::Id>) -> Entry<B> {
+        Entry { target, source }
+    }
+}
+
+/// The journal of a transaction.
+///
+/// The journal `index` is stored in one or more index blocks, `entries` is
+/// the deserialized index.
+pub struct Journal<B: BackendTypes> {
+    pub index: Vec<B::Id>,
+    pub entries: Vec<Entry<B>>,
+}
+
+impl<B: BackendTypes> Journal<B> {
+    pub fn new(index: Vec<B::Id>, entries: Vec<Entry<B>>) -> Journal<B> {
+        Journal { index, entries }
+    }
+
+    /// Returns the ids of all blocks of the journal, these are the index
+    /// blocks and the blocks, which store the data of the entries.
+    pub fn into_ids(self) -> Vec<B::Id> {
+        let sources = self.entries.into_iter().filter_map(|entry| entry.source);
+
+        self.index.into_iter().chain(sources).collect()
+    }
+}
+
+fn get_id<B: BackendTypes, T: Buffer>(buf: &mut T) -> Result<B::Id, JournalError> {
+    let bytes = buf.get_vec::<1>()?;
+
+    <B::Id as Binary>::from_bytes(&bytes).ok_or(JournalError::InvalidId)
+}
+
+/// Serializes the journal index.
+pub fn encode_entries<B: BackendTypes>(entries: &[Entry<B>]) -> Result<Vec<u8>, JournalError> {
+    let mut buf = vec![];
+
+    buf.put_u32(entries.len() as u32)?;
+
+    for entry in entries {
+        match entry.source.as_ref() {
+            Some(source) => {
+                buf.put_u8(1)?;
+                buf.put_vec::<1>(&entry.target.as_bytes())?;
+                buf.put_vec::<1>(&source.as_bytes())?;
+            }
+            None => {
+                buf.put_u8(0)?;
+                buf.put_vec::<1>(&entry.target.as_bytes())?;
+            }
+        }
+    }
+
+    Ok(buf)
+}
+
+/// Deserializes the journal index.
+pub fn decode_entries<B: BackendTypes>(buf: &[u8]) -> Result<Vec<Entry<B>>, JournalError> {
+    let buf = &mut &buf[..];
+    let n = buf.get_u32()?;
+
+    (0..n)
+        .map(|_| {
+            let b = buf.get_u8()?;
+            let target = get_id::<B, _>(buf)?;
+
+            match b {
+                0 => Ok(Entry::new(target, None)),
+                1 => Ok(Entry::new(target, Some(get_id::<B, _>(buf)?))),
+                _ => Err(BufferError::InvalidIndex("Entry".to_string(), b as u32).into()),
+            }
+        })
+        .collect()
+}
+
+/// Returns the number of index bytes, which fit into a block of `bsize`
+/// bytes.
+fn capacity<B: BackendTypes>(bsize: u32) -> Result<usize, JournalError> {
+    // magic, id of the next index block, length of the chunk
+    let overhead = MAGIC.len() + 1 + B::Id::size() + 4;
+
+    match (bsize as usize).checked_sub(overhead) {
+        Some(n) if n > 0 => Ok(n),
+        _ => Err(JournalError::InvalidBlockSize(bsize)),
+    }
+}
+
+/// Returns the number of blocks needed to store the serialized journal
+/// `index`.
+pub fn index_blocks<B: BackendTypes>(index: &[u8], bsize: u32) -> Result<usize, JournalError> {
+    Ok(index.chunks(capacity::<B>(bsize)?).count())
+}
+
+/// Splits the serialized journal `index` into the index blocks `ids`.
+///
+/// Each index block references the next one, the last block has no
+/// successor.
+pub fn encode_index<B: BackendTypes>(
+    index: &[u8],
+    ids: &[B::Id],
+    bsize: u32,
+) -> Result<Vec<Vec<u8>>, JournalError> {
+    index
+        .chunks(capacity::<B>(bsize)?)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let mut buf = vec![];
+
+            buf.put_chunk(&MAGIC)?;
+
+            match ids.get(idx + 1) {
+                Some(id) => buf.put_vec::<1>(&id.as_bytes())?,
+                None => buf.put_vec::<1>(&[])?,
+            }
+
+            buf.put_vec::<4>(chunk)?;
+
+            Ok(buf)
+        })
+        .collect()
+}
+
+/// Decodes an index block.
+///
+/// Returns the chunk of the serialized journal index stored in the block
+/// and the id of the next index block.
+pub fn decode_index<B: BackendTypes>(buf: &[u8]) -> Result<(Vec<u8>, Option<B::Id>), JournalError> {
+    let buf = &mut &buf[..];
+    let magic = buf.get_array::<8>()?;
+
+    if magic != MAGIC {
+        return Err(JournalError::InvalidMagic);
+    }
+
+    let next = match buf.get_vec::<1>()? {
+        bytes if bytes.is_empty() => None,
+        bytes => Some(<B::Id as Binary>::from_bytes(&bytes).ok_or(JournalError::InvalidId)?),
+    };
+    let chunk = buf.get_vec::<4>()?;
+
+    Ok((chunk, next))
+}
@@ -0,0 +1,192 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_memory::{Id, MemoryBackend};
+
+use crate::journal::{
+    decode_entries, decode_index, encode_entries, encode_index, index_blocks, Entry, JournalError,
+    Transaction,
+};
+
+const ENTRIES: [u8; 20] = [
+    0, 0, 0, 2, // number of entries
+    1, // write
+    4, 0, 0, 0, 1, // target
+    4, 0, 0, 0, 2, // source
+    0, // release
+    4, 0, 0, 0, // target (incomplete)
+];
+
+fn id(n: u32) -> Id {
+    n.to_string().parse().unwrap()
+}
+
+fn entries() -> Vec<Entry<MemoryBackend>> {
+    vec![Entry::new(id(1), Some(id(2))), Entry::new(id(3), None)]
+}
+
+#[test]
+fn transaction_write() {
+    let mut txn = Transaction::new();
+
+    assert!(txn.get(&[1]).is_none());
+
+    txn.write(vec![1], &id(1), vec![1, 2, 3].into());
+    assert_eq!(txn.get(&[1]).unwrap(), [1, 2, 3]);
+
+    txn.write(vec![1], &id(1), vec![4, 5, 6].into());
+    assert_eq!(txn.get(&[1]).unwrap(), [4, 5, 6]);
+}
+
+#[test]
+fn transaction_release() {
+    let mut txn = Transaction::new();
+
+    txn.write(vec![1], &id(1), vec![1, 2, 3].into());
+    txn.release(vec![1], id(1));
+
+    assert!(txn.get(&[1]).is_none());
+}
+
+#[test]
+fn transaction_into_parts() {
+    let mut txn = Transaction::new();
+
+    txn.write(vec![3], &id(3), vec![3].into());
+    txn.release(vec![2], id(2));
+    txn.write(vec![1], &id(1), vec![1].into());
+    txn.aquired(id(4));
+
+    let (blocks, aquired) = txn.into_parts();
+    let blocks = blocks
+        .into_iter()
+        .map(|(id, data)| (id, data.map(|data| data.to_vec())))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        blocks,
+        [
+            (id(1), Some(vec![1])),
+            (id(2), None),
+            (id(3), Some(vec![3]))
+        ]
+    );
+    assert_eq!(aquired, [id(4)]);
+}
+
+#[test]
+fn encode_decode_entries() {
+    let buf = encode_entries(&entries()).unwrap();
+
+    assert_eq!(buf[..ENTRIES.len() - 4], ENTRIES[..ENTRIES.len() - 4]);
+    assert_eq!(buf[ENTRIES.len() - 4..], [4, 0, 0, 0, 3]);
+
+    assert_eq!(decode_entries::<MemoryBackend>(&buf).unwrap(), entries());
+}
+
+#[test]
+fn decode_entries_eof() {
+    let err = decode_entries::<MemoryBackend>(&ENTRIES).unwrap_err();
+
+    assert!(matches!(err, JournalError::Buffer(_)));
+}
+
+#[test]
+fn decode_entries_inval_kind() {
+    let mut buf = encode_entries(&entries()).unwrap();
+    buf[4] = 2;
+
+    let err = decode_entries::<MemoryBackend>(&buf).unwrap_err();
+
+    assert!(matches!(err, JournalError::Buffer(_)));
+}
+
+#[test]
+fn decode_entries_inval_id() {
+    let mut buf = encode_entries(&entries()).unwrap();
+    buf[5] = 3;
+
+    let err = decode_entries::<MemoryBackend>(&buf).unwrap_err();
+
+    assert!(matches!(err, JournalError::InvalidId));
+}
+
+#[test]
+fn index_single_block() {
+    let index = encode_entries(&entries()).unwrap();
+
+    assert_eq!(index_blocks::<MemoryBackend>(&index, 512).unwrap(), 1);
+
+    let blocks = encode_index::<MemoryBackend>(&index, &[id(7)], 512).unwrap();
+
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0][..9], *b"nuts-jnl\0");
+
+    let (chunk, next) = decode_index::<MemoryBackend>(&blocks[0]).unwrap();
+
+    assert_eq!(chunk, index);
+    assert!(next.is_none());
+}
+
+#[test]
+fn index_chained_blocks() {
+    let index = encode_entries(&entries()).unwrap();
+
+    // 8 bytes magic, 5 bytes next id, 4 bytes chunk length, 10 bytes chunk
+    assert_eq!(index_blocks::<MemoryBackend>(&index, 27).unwrap(), 3);
+
+    let ids = [id(7), id(8), id(9)];
+    let blocks = encode_index::<MemoryBackend>(&index, &ids, 27).unwrap();
+    let mut out = vec![];
+
+    assert_eq!(blocks.len(), 3);
+
+    for (idx, block) in blocks.iter().enumerate() {
+        let (chunk, next) = decode_index::<MemoryBackend>(block).unwrap();
+
+        assert!(block.len() <= 27);
+        assert_eq!(next.as_ref(), ids.get(idx + 1));
+
+        out.extend_from_slice(&chunk);
+    }
+
+    assert_eq!(out, index);
+}
+
+#[test]
+fn index_inval_block_size() {
+    let index = encode_entries(&entries()).unwrap();
+    let err = index_blocks::<MemoryBackend>(&index, 17).unwrap_err();
+
+    assert!(matches!(err, JournalError::InvalidBlockSize(n) if n == 17));
+}
+
+#[test]
+fn decode_index_inval_magic() {
+    let index = encode_entries(&entries()).unwrap();
+    let mut blocks = encode_index::<MemoryBackend>(&index, &[id(7)], 512).unwrap();
+    blocks[0][0] += 1;
+
+    let err = decode_index::<MemoryBackend>(&blocks[0]).unwrap_err();
+
+    assert!(matches!(err, JournalError::InvalidMagic));
+}
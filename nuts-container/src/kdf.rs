@@ -25,6 +25,7 @@ mod tests;
 
 use log::{debug, trace};
 use openssl::error::ErrorStack;
+use openssl::kdf::argon2id;
 use openssl::pkcs5::pbkdf2_hmac;
 use std::fmt;
 use std::num::ParseIntError;
@@ -36,12 +37,43 @@ use crate::digest::Digest;
 use crate::ossl;
 use crate::svec::SecureVec;
 
+// Number of bytes generated by Argon2id at once
+const ARGON2ID_KEY_LEN: usize = 32;
+
+pub(crate) const ARGON2ID_DEFAULT_MEMORY: u32 = 19456;
+pub(crate) const ARGON2ID_DEFAULT_ITERATIONS: u32 = 2;
+pub(crate) const ARGON2ID_DEFAULT_PARALLELISM: u32 = 1;
+pub(crate) const ARGON2ID_DEFAULT_SALT_LEN: u32 = 16;
+
+// Upper bounds of the Argon2id parameters. The parameters are read from the
+// unauthenticated part of the header, a modified header must not exhaust
+// memory or CPU.
+const ARGON2ID_MAX_MEMORY: u32 = 4 * 1024 * 1024; // 4 GiB
+const ARGON2ID_MAX_ITERATIONS: u32 = 64;
+const ARGON2ID_MAX_PARALLELISM: u32 = 64;
+
+// Number of bytes generated by scrypt at once
+const SCRYPT_KEY_LEN: usize = 32;
+
+const SCRYPT_DEFAULT_LOG_N: u8 = 17;
+const SCRYPT_DEFAULT_R: u32 = 8;
+const SCRYPT_DEFAULT_P: u32 = 1;
+const SCRYPT_DEFAULT_SALT_LEN: u32 = 16;
+
+// Upper bounds of the scrypt parameters, see the Argon2id limits.
+const SCRYPT_MAX_MEMORY: u64 = 4 * 1024 * 1024 * 1024; // 4 GiB
+const SCRYPT_MAX_P: u32 = 64;
+
 /// [`Kdf`] related error codes.
 #[derive(Debug, Error)]
 pub enum KdfError {
     /// An error in the OpenSSL library occured.
     #[error(transparent)]
     OpenSSL(#[from] ErrorStack),
+
+    /// A parameter of the key derivation function exceeds its upper bound.
+    #[error("the {0} of the key derivation function exceeds the limit of {1}")]
+    ExceedsLimit(&'static str, u64),
 }
 
 /// Supported key derivation functions.
@@ -54,6 +86,12 @@ pub enum KdfError {
 /// Based on a password provided by the user one of the algorithms are used to
 /// calculate a wrapping key. The wrapping key then is used for encryption of
 /// the secret in the header of the container.
+///
+/// The parameters of [`Argon2id`](Self::Argon2id) and
+/// [`Scrypt`](Self::Scrypt) are bounded: Argon2id is limited to 4 GiB of
+/// memory, 64 iterations and 64 lanes, scrypt to 4 GiB of memory and a
+/// parallelization parameter of 64. Larger values are rejected with
+/// [`KdfError::ExceedsLimit`].
 #[derive(Clone, PartialEq)]
 pub enum Kdf {
     /// No key derivation
@@ -70,6 +108,37 @@ pub enum Kdf {
         /// A salt value used by PBKDF2.
         salt: Vec<u8>,
     },
+
+    /// Argon2id
+    Argon2id {
+        /// Memory cost in KiB used by Argon2id.
+        memory: u32,
+
+        /// Number of iterations used by Argon2id.
+        iterations: u32,
+
+        /// Degree of parallelism (number of lanes) used by Argon2id.
+        parallelism: u32,
+
+        /// A salt value used by Argon2id.
+        salt: Vec<u8>,
+    },
+
+    /// scrypt
+    Scrypt {
+        /// Binary logarithm of the CPU/memory cost parameter `N` used by
+        /// scrypt.
+        log_n: u8,
+
+        /// Block size parameter used by scrypt.
+        r: u32,
+
+        /// Parallelization parameter used by scrypt.
+        p: u32,
+
+        /// A salt value used by scrypt.
+        salt: Vec<u8>,
+    },
 }
 
 impl Kdf {
@@ -82,6 +151,18 @@ impl Kdf {
                 iterations: _,
                 salt: _,
             } => false,
+            Kdf::Argon2id {
+                memory: _,
+                iterations: _,
+                parallelism: _,
+                salt: _,
+            } => false,
+            Kdf::Scrypt {
+                log_n: _,
+                r: _,
+                p: _,
+                salt: _,
+            } => false,
         }
     }
 
@@ -94,6 +175,42 @@ impl Kdf {
                 iterations: _,
                 salt: _,
             } => true,
+            Kdf::Argon2id {
+                memory: _,
+                iterations: _,
+                parallelism: _,
+                salt: _,
+            } => false,
+            Kdf::Scrypt {
+                log_n: _,
+                r: _,
+                p: _,
+                salt: _,
+            } => false,
+        }
+    }
+
+    /// Tests whether this is a [`Argon2id`](Self::Argon2id) kdf.
+    pub fn is_argon2id(&self) -> bool {
+        match self {
+            Kdf::None => false,
+            Kdf::Pbkdf2 {
+                digest: _,
+                iterations: _,
+                salt: _,
+            } => false,
+            Kdf::Argon2id {
+                memory: _,
+                iterations: _,
+                parallelism: _,
+                salt: _,
+            } => true,
+            Kdf::Scrypt {
+                log_n: _,
+                r: _,
+                p: _,
+                salt: _,
+            } => false,
         }
     }
 
@@ -179,9 +296,246 @@ impl Kdf {
         })
     }
 
+    /// Tests whether this is a [`Scrypt`](Self::Scrypt) kdf.
+    pub fn is_scrypt(&self) -> bool {
+        match self {
+            Kdf::None => false,
+            Kdf::Pbkdf2 {
+                digest: _,
+                iterations: _,
+                salt: _,
+            } => false,
+            Kdf::Argon2id {
+                memory: _,
+                iterations: _,
+                parallelism: _,
+                salt: _,
+            } => false,
+            Kdf::Scrypt {
+                log_n: _,
+                r: _,
+                p: _,
+                salt: _,
+            } => true,
+        }
+    }
+
+    /// Creates a `Kdf` instance for the Argon2id algorithm.
+    ///
+    /// The `memory` (in KiB), `iterations`, `parallelism` and the `salt`
+    /// values are used to customize the Argon2id algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nuts_container::*;
+    ///
+    /// let argon2id = Kdf::argon2id(19456, 2, 1, &[1, 2, 3]);
+    ///
+    /// match argon2id {
+    ///     Kdf::Argon2id {
+    ///         memory,
+    ///         iterations,
+    ///         parallelism,
+    ///         salt,
+    ///     } => {
+    ///         assert_eq!(memory, 19456);
+    ///         assert_eq!(iterations, 2);
+    ///         assert_eq!(parallelism, 1);
+    ///         assert_eq!(salt, [1, 2, 3]);
+    ///     }
+    ///     _ => panic!("invalid kdf"),
+    /// }
+    /// ```
+    pub fn argon2id(memory: u32, iterations: u32, parallelism: u32, salt: &[u8]) -> Kdf {
+        Kdf::Argon2id {
+            memory,
+            iterations,
+            parallelism,
+            salt: salt.to_vec(),
+        }
+    }
+
+    /// Generates a `Kdf` instance for the Argon2id algorithm.
+    ///
+    /// The `memory` (in KiB), `iterations` and `parallelism` values are used
+    /// to customize the Argon2id algorithm. For the [`salt`] `salt_len`
+    /// bytes of random data are generated.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an [`Error::OpenSSL`] error if there was an
+    /// error generating the random data. A [`KdfError::ExceedsLimit`] error
+    /// is returned, if a parameter exceeds its upper bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nuts_container::*;
+    ///
+    /// let kdf = Kdf::generate_argon2id(19456, 2, 1, 16).unwrap();
+    ///
+    /// match kdf {
+    ///     Kdf::Argon2id {
+    ///         memory,
+    ///         iterations,
+    ///         parallelism,
+    ///         salt,
+    ///     } => {
+    ///         assert_eq!(memory, 19456);
+    ///         assert_eq!(iterations, 2);
+    ///         assert_eq!(parallelism, 1);
+    ///         assert_eq!(salt.len(), 16); // salt filled with random data
+    ///     }
+    ///     _ => panic!("invalid kdf"),
+    /// }
+    /// ```
+    ///
+    /// [`salt`]: #variant.Argon2id.field.salt
+    /// [`Error::OpenSSL`]: ../error/enum.Error.html#variant.OpenSSL
+    pub fn generate_argon2id(
+        memory: u32,
+        iterations: u32,
+        parallelism: u32,
+        salt_len: u32,
+    ) -> Result<Kdf, KdfError> {
+        let mut salt = vec![0; salt_len as usize];
+        ossl::rand_bytes(&mut salt)?;
+
+        let kdf = Kdf::Argon2id {
+            memory,
+            iterations,
+            parallelism,
+            salt,
+        };
+
+        kdf.check_limits()?;
+
+        Ok(kdf)
+    }
+
+    /// Creates a `Kdf` instance for the scrypt algorithm.
+    ///
+    /// The `log_n`, `r`, `p` and the `salt` values are used to customize the
+    /// scrypt algorithm. The CPU/memory cost parameter `N` is `2^log_n`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nuts_container::*;
+    ///
+    /// let scrypt = Kdf::scrypt(17, 8, 1, &[1, 2, 3]);
+    ///
+    /// match scrypt {
+    ///     Kdf::Scrypt { log_n, r, p, salt } => {
+    ///         assert_eq!(log_n, 17);
+    ///         assert_eq!(r, 8);
+    ///         assert_eq!(p, 1);
+    ///         assert_eq!(salt, [1, 2, 3]);
+    ///     }
+    ///     _ => panic!("invalid kdf"),
+    /// }
+    /// ```
+    pub fn scrypt(log_n: u8, r: u32, p: u32, salt: &[u8]) -> Kdf {
+        Kdf::Scrypt {
+            log_n,
+            r,
+            p,
+            salt: salt.to_vec(),
+        }
+    }
+
+    /// Generates a `Kdf` instance for the scrypt algorithm.
+    ///
+    /// The `log_n`, `r` and `p` values are used to customize the scrypt
+    /// algorithm. For the [`salt`] `salt_len` bytes of random data are
+    /// generated.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an [`Error::OpenSSL`] error if there was an
+    /// error generating the random data. A [`KdfError::ExceedsLimit`] error
+    /// is returned, if a parameter exceeds its upper bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nuts_container::*;
+    ///
+    /// let kdf = Kdf::generate_scrypt(17, 8, 1, 16).unwrap();
+    ///
+    /// match kdf {
+    ///     Kdf::Scrypt { log_n, r, p, salt } => {
+    ///         assert_eq!(log_n, 17);
+    ///         assert_eq!(r, 8);
+    ///         assert_eq!(p, 1);
+    ///         assert_eq!(salt.len(), 16); // salt filled with random data
+    ///     }
+    ///     _ => panic!("invalid kdf"),
+    /// }
+    /// ```
+    ///
+    /// [`salt`]: #variant.Scrypt.field.salt
+    /// [`Error::OpenSSL`]: ../error/enum.Error.html#variant.OpenSSL
+    pub fn generate_scrypt(log_n: u8, r: u32, p: u32, salt_len: u32) -> Result<Kdf, KdfError> {
+        let mut salt = vec![0; salt_len as usize];
+        ossl::rand_bytes(&mut salt)?;
+
+        let kdf = Kdf::Scrypt { log_n, r, p, salt };
+
+        kdf.check_limits()?;
+
+        Ok(kdf)
+    }
+
+    fn check_limits(&self) -> Result<(), KdfError> {
+        fn check(name: &'static str, value: u64, max: u64) -> Result<(), KdfError> {
+            if value > max {
+                Err(KdfError::ExceedsLimit(name, max))
+            } else {
+                Ok(())
+            }
+        }
+
+        match self {
+            Kdf::None | Kdf::Pbkdf2 { .. } => Ok(()),
+            Kdf::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                salt: _,
+            } => {
+                check("memory", *memory as u64, ARGON2ID_MAX_MEMORY as u64)?;
+                check(
+                    "iterations",
+                    *iterations as u64,
+                    ARGON2ID_MAX_ITERATIONS as u64,
+                )?;
+                check(
+                    "parallelism",
+                    *parallelism as u64,
+                    ARGON2ID_MAX_PARALLELISM as u64,
+                )
+            }
+            Kdf::Scrypt {
+                log_n,
+                r,
+                p,
+                salt: _,
+            } => {
+                check("p", *p as u64, SCRYPT_MAX_P as u64)?;
+                check(
+                    "memory",
+                    ossl::scrypt_memory(*log_n, *r, *p),
+                    SCRYPT_MAX_MEMORY,
+                )
+            }
+        }
+    }
+
     fn create_key_internal(&self, password: &[u8]) -> Result<SecureVec, KdfError> {
         match self {
-            Kdf::None => Ok(vec![].into()),
+            Kdf::None => Ok(SecureVec::new()),
             Kdf::Pbkdf2 {
                 digest,
                 iterations,
@@ -196,11 +550,56 @@ impl Kdf {
                 }
 
                 let md = digest.as_openssl();
-                let mut key = vec![0; digest.size()];
+                let mut key = SecureVec::zeroed_guarded(digest.size());
 
                 pbkdf2_hmac(password, salt, *iterations as usize, md, &mut key)?;
 
-                Ok(key.into())
+                Ok(key)
+            }
+            Kdf::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                salt,
+            } => {
+                if password.is_empty() {
+                    panic!("invalid password, cannot be empty");
+                }
+
+                if salt.is_empty() {
+                    panic!("invalid salt, cannot be empty");
+                }
+
+                let mut key = SecureVec::zeroed_guarded(ARGON2ID_KEY_LEN);
+
+                argon2id(
+                    None,
+                    password,
+                    salt,
+                    None,
+                    None,
+                    *iterations,
+                    *parallelism,
+                    *memory,
+                    &mut key,
+                )?;
+
+                Ok(key)
+            }
+            Kdf::Scrypt { log_n, r, p, salt } => {
+                if password.is_empty() {
+                    panic!("invalid password, cannot be empty");
+                }
+
+                if salt.is_empty() {
+                    panic!("invalid salt, cannot be empty");
+                }
+
+                let mut key = SecureVec::zeroed_guarded(SCRYPT_KEY_LEN);
+
+                ossl::scrypt(password, salt, *log_n, *r, *p, &mut key)?;
+
+                Ok(key)
             }
         }
     }
@@ -210,12 +609,30 @@ impl Kdf {
         password: &[u8],
         min_len: usize,
     ) -> Result<SecureVec, KdfError> {
-        let mut key = self.create_key_internal(password)?;
+        // The parameters might come from a modified header, check them before
+        // any memory is spent.
+        self.check_limits()?;
+
+        // The key wraps the header key, keep it guarded while it grows.
+        let mut key = self.create_key_internal(password)?.into_guarded();
 
         // ignore min_len for None
+        if !self.is_none() && key.len() < min_len {
+            // Each step appends a key of the same length. Reserve the final
+            // length, so the key is not moved while it grows.
+            let step = key.len();
+            let mut len = step;
+
+            while len < min_len {
+                len += step;
+            }
+
+            key.reserve(len - step);
+        }
+
         while !self.is_none() && key.len() < min_len {
             let xxx = self.create_key_internal(&key)?;
-            key.extend(xxx.as_ref());
+            key.extend_from_slice(&xxx);
 
             trace!("create_key (step): len = {}", key.len());
         }
@@ -237,6 +654,22 @@ impl Kdf {
 
                 Ok(Kdf::pbkdf2(digest, iterations, &salt))
             }
+            2 => {
+                let memory = buf.get_u32()?;
+                let iterations = buf.get_u32()?;
+                let parallelism = buf.get_u32()?;
+                let salt = buf.get_vec::<8>()?;
+
+                Ok(Kdf::argon2id(memory, iterations, parallelism, &salt))
+            }
+            3 => {
+                let log_n = buf.get_u8()?;
+                let r = buf.get_u32()?;
+                let p = buf.get_u32()?;
+                let salt = buf.get_vec::<8>()?;
+
+                Ok(Kdf::scrypt(log_n, r, p, &salt))
+            }
             _ => Err(BufferError::InvalidIndex("Kdf".to_string(), b)),
         }
     }
@@ -254,6 +687,29 @@ impl Kdf {
                 buf.put_u32(*iterations)?;
                 buf.put_vec::<8>(salt.as_slice())?;
 
+                Ok(())
+            }
+            Kdf::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                salt,
+            } => {
+                buf.put_u32(2)?;
+                buf.put_u32(*memory)?;
+                buf.put_u32(*iterations)?;
+                buf.put_u32(*parallelism)?;
+                buf.put_vec::<8>(salt.as_slice())?;
+
+                Ok(())
+            }
+            Kdf::Scrypt { log_n, r, p, salt } => {
+                buf.put_u32(3)?;
+                buf.put_u8(*log_n)?;
+                buf.put_u32(*r)?;
+                buf.put_u32(*p)?;
+                buf.put_vec::<8>(salt.as_slice())?;
+
                 Ok(())
             }
         }
@@ -271,6 +727,24 @@ impl fmt::Display for Kdf {
             } => {
                 write!(fmt, "pbkdf2:{}:{}:{}", digest, iterations, salt.len())
             }
+            Kdf::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                salt,
+            } => {
+                write!(
+                    fmt,
+                    "argon2id:{}:{}:{}:{}",
+                    memory,
+                    iterations,
+                    parallelism,
+                    salt.len()
+                )
+            }
+            Kdf::Scrypt { log_n, r, p, salt } => {
+                write!(fmt, "scrypt:{}:{}:{}:{}", log_n, r, p, salt.len())
+            }
         }
     }
 }
@@ -291,6 +765,29 @@ impl fmt::Debug for Kdf {
                     .field("salt", &salt)
                     .finish()
             }
+            Kdf::Argon2id {
+                memory,
+                iterations,
+                parallelism,
+                salt,
+            } => {
+                let salt = format!("<{} bytes>", salt.len());
+                fmt.debug_struct("Argon2id")
+                    .field("memory", &memory)
+                    .field("iterations", &iterations)
+                    .field("parallelism", &parallelism)
+                    .field("salt", &salt)
+                    .finish()
+            }
+            Kdf::Scrypt { log_n, r, p, salt } => {
+                let salt = format!("<{} bytes>", salt.len());
+                fmt.debug_struct("Scrypt")
+                    .field("log_n", &log_n)
+                    .field("r", &r)
+                    .field("p", &p)
+                    .field("salt", &salt)
+                    .finish()
+            }
         }
     }
 }
@@ -336,6 +833,81 @@ fn parse_pbkdf2(v: &[&str]) -> Result<Kdf, ParseKdfPbkdf2Error> {
     Ok(Kdf::generate_pbkdf2(digest, iterations, salt_len)?)
 }
 
+fn parse_argon2id(v: &[&str]) -> Result<Kdf, ParseKdfArgon2idError> {
+    if !v.is_empty() && v.len() != 4 {
+        return Err(ParseKdfArgon2idError::InvalidNumberOfArguments(v.len()));
+    }
+
+    let memory = if v.is_empty() || v[0].is_empty() {
+        ARGON2ID_DEFAULT_MEMORY
+    } else {
+        v[0].parse::<u32>()
+            .map_err(ParseKdfArgon2idError::InvalidMemory)?
+    };
+
+    let iterations = if v.is_empty() || v[1].is_empty() {
+        ARGON2ID_DEFAULT_ITERATIONS
+    } else {
+        v[1].parse::<u32>()
+            .map_err(ParseKdfArgon2idError::InvalidIterations)?
+    };
+
+    let parallelism = if v.is_empty() || v[2].is_empty() {
+        ARGON2ID_DEFAULT_PARALLELISM
+    } else {
+        v[2].parse::<u32>()
+            .map_err(ParseKdfArgon2idError::InvalidParallelism)?
+    };
+
+    let salt_len = if v.is_empty() || v[3].is_empty() {
+        ARGON2ID_DEFAULT_SALT_LEN
+    } else {
+        v[3].parse::<u32>()
+            .map_err(ParseKdfArgon2idError::InvalidSaltLen)?
+    };
+
+    Ok(Kdf::generate_argon2id(
+        memory,
+        iterations,
+        parallelism,
+        salt_len,
+    )?)
+}
+
+fn parse_scrypt(v: &[&str]) -> Result<Kdf, ParseKdfScryptError> {
+    if !v.is_empty() && v.len() != 4 {
+        return Err(ParseKdfScryptError::InvalidNumberOfArguments(v.len()));
+    }
+
+    let log_n = if v.is_empty() || v[0].is_empty() {
+        SCRYPT_DEFAULT_LOG_N
+    } else {
+        v[0].parse::<u8>()
+            .map_err(ParseKdfScryptError::InvalidLogN)?
+    };
+
+    let r = if v.is_empty() || v[1].is_empty() {
+        SCRYPT_DEFAULT_R
+    } else {
+        v[1].parse::<u32>().map_err(ParseKdfScryptError::InvalidR)?
+    };
+
+    let p = if v.is_empty() || v[2].is_empty() {
+        SCRYPT_DEFAULT_P
+    } else {
+        v[2].parse::<u32>().map_err(ParseKdfScryptError::InvalidP)?
+    };
+
+    let salt_len = if v.is_empty() || v[3].is_empty() {
+        SCRYPT_DEFAULT_SALT_LEN
+    } else {
+        v[3].parse::<u32>()
+            .map_err(ParseKdfScryptError::InvalidSaltLen)?
+    };
+
+    Ok(Kdf::generate_scrypt(log_n, r, p, salt_len)?)
+}
+
 #[derive(Debug, Error)]
 pub enum ParseKdfNoneError {
     #[error("invalid number of arguments for the none-kdf, expected none but got {0}")]
@@ -360,6 +932,48 @@ pub enum ParseKdfPbkdf2Error {
     Kdf(#[from] KdfError),
 }
 
+#[derive(Debug, Error)]
+pub enum ParseKdfArgon2idError {
+    #[error("invalid number of arguments for Argon2id, got {0} but none or four are expected")]
+    InvalidNumberOfArguments(usize),
+
+    #[error("invalid memory: {0}")]
+    InvalidMemory(#[source] ParseIntError),
+
+    #[error("invalid iterations: {0}")]
+    InvalidIterations(#[source] ParseIntError),
+
+    #[error("invalid parallelism: {0}")]
+    InvalidParallelism(#[source] ParseIntError),
+
+    #[error("invalid salt length: {0}")]
+    InvalidSaltLen(#[source] ParseIntError),
+
+    #[error(transparent)]
+    Kdf(#[from] KdfError),
+}
+
+#[derive(Debug, Error)]
+pub enum ParseKdfScryptError {
+    #[error("invalid number of arguments for scrypt, got {0} but none or four are expected")]
+    InvalidNumberOfArguments(usize),
+
+    #[error("invalid log_n: {0}")]
+    InvalidLogN(#[source] ParseIntError),
+
+    #[error("invalid r: {0}")]
+    InvalidR(#[source] ParseIntError),
+
+    #[error("invalid p: {0}")]
+    InvalidP(#[source] ParseIntError),
+
+    #[error("invalid salt length: {0}")]
+    InvalidSaltLen(#[source] ParseIntError),
+
+    #[error(transparent)]
+    Kdf(#[from] KdfError),
+}
+
 #[derive(Debug, Error)]
 pub enum ParseKdfError {
     #[error(transparent)]
@@ -368,6 +982,12 @@ pub enum ParseKdfError {
     #[error(transparent)]
     Pbkdf2(ParseKdfPbkdf2Error),
 
+    #[error(transparent)]
+    Argon2id(ParseKdfArgon2idError),
+
+    #[error(transparent)]
+    Scrypt(ParseKdfScryptError),
+
     #[error("unknown kdf: {0}")]
     Unknown(String),
 }
@@ -388,6 +1008,8 @@ impl FromStr for Kdf {
         match v[0] {
             "none" => parse_none(&v[1..]).map_err(ParseKdfError::None),
             "pbkdf2" => parse_pbkdf2(&v[1..]).map_err(ParseKdfError::Pbkdf2),
+            "argon2id" => parse_argon2id(&v[1..]).map_err(ParseKdfError::Argon2id),
+            "scrypt" => parse_scrypt(&v[1..]).map_err(ParseKdfError::Scrypt),
             _ => Err(ParseKdfError::Unknown(v[0].to_string())),
         }
     }
@@ -20,7 +20,9 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
+mod argon2id;
 mod bytes;
 mod none;
 mod pbkdf2;
+mod scrypt;
 mod string;
@@ -0,0 +1,195 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::kdf::{Kdf, KdfError};
+use crate::tests::RND;
+
+#[test]
+fn is_none() {
+    assert!(!Kdf::argon2id(8, 1, 1, &[1, 2, 3]).is_none());
+}
+
+#[test]
+fn is_pbkdf2() {
+    assert!(!Kdf::argon2id(8, 1, 1, &[1, 2, 3]).is_pbkdf2());
+}
+
+#[test]
+fn is_argon2id() {
+    assert!(Kdf::argon2id(8, 1, 1, &[1, 2, 3]).is_argon2id());
+}
+
+#[test]
+fn is_scrypt() {
+    assert!(!Kdf::argon2id(8, 1, 1, &[1, 2, 3]).is_scrypt());
+}
+
+#[test]
+fn ok() {
+    match Kdf::argon2id(8, 1, 2, &[1, 2, 3]) {
+        Kdf::Argon2id {
+            memory,
+            iterations,
+            parallelism,
+            salt,
+        } => {
+            assert_eq!(memory, 8);
+            assert_eq!(iterations, 1);
+            assert_eq!(parallelism, 2);
+            assert_eq!(salt, [1, 2, 3]);
+        }
+        _ => panic!("invalid kdf"),
+    }
+}
+
+#[test]
+fn generate_empty_salt() {
+    let kdf = Kdf::generate_argon2id(8, 1, 2, 0).unwrap();
+
+    match kdf {
+        Kdf::Argon2id {
+            memory,
+            iterations,
+            parallelism,
+            salt,
+        } => {
+            assert_eq!(memory, 8);
+            assert_eq!(iterations, 1);
+            assert_eq!(parallelism, 2);
+            assert_eq!(salt, [0; 0]);
+        }
+        _ => panic!("invalid kdf"),
+    }
+}
+
+#[test]
+fn generate_with_salt() {
+    let kdf = Kdf::generate_argon2id(8, 1, 2, 3).unwrap();
+
+    match kdf {
+        Kdf::Argon2id {
+            memory,
+            iterations,
+            parallelism,
+            salt,
+        } => {
+            assert_eq!(memory, 8);
+            assert_eq!(iterations, 1);
+            assert_eq!(parallelism, 2);
+            assert_eq!(salt.len(), 3); // salt filled with random data
+            assert_eq!(salt, &RND[..3]);
+        }
+        _ => panic!("invalid kdf"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid password, cannot be empty")]
+fn create_key_empty_password() {
+    Kdf::argon2id(8, 1, 1, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .create_key(b"", 0)
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid password, cannot be empty")]
+fn create_key_empty_password_min_len() {
+    Kdf::argon2id(8, 1, 1, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .create_key(b"", 40)
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid salt, cannot be empty")]
+fn create_key_empty_salt() {
+    Kdf::argon2id(8, 1, 1, &[]).create_key(b"123", 0).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid salt, cannot be empty")]
+fn create_key_empty_salt_min_len() {
+    Kdf::argon2id(8, 1, 1, &[]).create_key(b"123", 40).unwrap();
+}
+
+#[test]
+fn create_key_inval_memory() {
+    Kdf::argon2id(0, 1, 1, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .create_key(b"123", 0)
+        .unwrap_err();
+}
+
+#[test]
+fn create_key_limits() {
+    for (kdf, name) in [
+        (
+            Kdf::argon2id(4 * 1024 * 1024 + 1, 1, 1, &[1, 2, 3]),
+            "memory",
+        ),
+        (Kdf::argon2id(8, 65, 1, &[1, 2, 3]), "iterations"),
+        (Kdf::argon2id(8, 1, 65, &[1, 2, 3]), "parallelism"),
+        (
+            Kdf::argon2id(u32::MAX, u32::MAX, u32::MAX, &[1, 2, 3]),
+            "memory",
+        ),
+    ] {
+        let err = kdf.create_key(b"123", 0).unwrap_err();
+        assert!(matches!(err, KdfError::ExceedsLimit(n, _) if n == name));
+    }
+}
+
+#[test]
+fn generate_limits() {
+    let err = Kdf::generate_argon2id(8, 65, 1, 3).unwrap_err();
+    assert!(matches!(err, KdfError::ExceedsLimit("iterations", 64)));
+}
+
+#[test]
+fn create_key() {
+    let wkey = Kdf::argon2id(8, 1, 1, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .create_key(b"123", 0)
+        .unwrap();
+
+    assert_eq!(
+        *wkey,
+        vec![
+            246, 243, 248, 158, 36, 84, 47, 6, 177, 145, 97, 59, 129, 192, 231, 11, 175, 37, 144,
+            146, 29, 81, 70, 92, 203, 182, 184, 15, 27, 240, 179, 168
+        ]
+    );
+}
+
+#[test]
+fn create_key_min_len() {
+    let wkey = Kdf::argon2id(8, 1, 1, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .create_key(b"123", 40)
+        .unwrap();
+
+    assert_eq!(
+        *wkey,
+        vec![
+            246, 243, 248, 158, 36, 84, 47, 6, 177, 145, 97, 59, 129, 192, 231, 11, 175, 37, 144,
+            146, 29, 81, 70, 92, 203, 182, 184, 15, 27, 240, 179, 168, 217, 217, 152, 49, 36, 184,
+            195, 67, 187, 241, 3, 10, 49, 61, 110, 143, 215, 172, 73, 54, 90, 8, 223, 228, 140, 9,
+            226, 247, 42, 8, 142, 147
+        ]
+    );
+}
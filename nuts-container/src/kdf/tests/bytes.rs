@@ -50,6 +50,48 @@ fn de_pbkdf2() {
     );
 }
 
+#[test]
+fn de_argon2id() {
+    let buf = [
+        0x00, 0x00, 0x00, 0x02, // argon2id variant
+        0x00, 0x00, 0x4c, 0x00, // memory
+        0x00, 0x00, 0x00, 0x02, // iterations
+        0x00, 0x00, 0x00, 0x01, // parallelism
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 1, 2, 3, // salt
+    ];
+
+    assert_eq!(
+        Kdf::get_from_buffer(&mut &buf[..]).unwrap(),
+        Kdf::Argon2id {
+            memory: 19456,
+            iterations: 2,
+            parallelism: 1,
+            salt: vec![1, 2, 3]
+        }
+    );
+}
+
+#[test]
+fn de_scrypt() {
+    let buf = [
+        0x00, 0x00, 0x00, 0x03, // scrypt variant
+        0x11, // log_n
+        0x00, 0x00, 0x00, 0x08, // r
+        0x00, 0x00, 0x00, 0x01, // p
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 1, 2, 3, // salt
+    ];
+
+    assert_eq!(
+        Kdf::get_from_buffer(&mut &buf[..]).unwrap(),
+        Kdf::Scrypt {
+            log_n: 17,
+            r: 8,
+            p: 1,
+            salt: vec![1, 2, 3]
+        }
+    );
+}
+
 #[test]
 fn de_eof() {
     let buf = [0x00, 0x00, 0x00];
@@ -60,10 +102,10 @@ fn de_eof() {
 
 #[test]
 fn de_invalid() {
-    let buf = [0x00, 0x00, 0x00, 0x02];
+    let buf = [0x00, 0x00, 0x00, 0x04];
     let err = Kdf::get_from_buffer(&mut &buf[..]).unwrap_err();
 
-    assert_eq!(err.to_string(), "no Kdf at 2");
+    assert_eq!(err.to_string(), "no Kdf at 4");
 }
 
 #[test]
@@ -97,6 +139,56 @@ fn ser_pbkdf2() {
     );
 }
 
+#[test]
+fn ser_argon2id() {
+    let mut buf = vec![];
+
+    Kdf::Argon2id {
+        memory: 19456,
+        iterations: 2,
+        parallelism: 1,
+        salt: vec![1, 2, 3],
+    }
+    .put_into_buffer(&mut buf)
+    .unwrap();
+
+    assert_eq!(
+        buf,
+        [
+            0x00, 0x00, 0x00, 0x02, // argon2id variant
+            0x00, 0x00, 0x4c, 0x00, // memory
+            0x00, 0x00, 0x00, 0x02, // iterations
+            0x00, 0x00, 0x00, 0x01, // parallelism
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 1, 2, 3 // salt
+        ]
+    );
+}
+
+#[test]
+fn ser_scrypt() {
+    let mut buf = vec![];
+
+    Kdf::Scrypt {
+        log_n: 17,
+        r: 8,
+        p: 1,
+        salt: vec![1, 2, 3],
+    }
+    .put_into_buffer(&mut buf)
+    .unwrap();
+
+    assert_eq!(
+        buf,
+        [
+            0x00, 0x00, 0x00, 0x03, // scrypt variant
+            0x11, // log_n
+            0x00, 0x00, 0x00, 0x08, // r
+            0x00, 0x00, 0x00, 0x01, // p
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 1, 2, 3 // salt
+        ]
+    );
+}
+
 #[test]
 fn ser_write_zero() {
     let mut buf = [0; 3];
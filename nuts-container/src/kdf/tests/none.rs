@@ -32,6 +32,16 @@ fn is_pbkdf2() {
     assert!(!Kdf::None.is_pbkdf2());
 }
 
+#[test]
+fn is_argon2id() {
+    assert!(!Kdf::None.is_argon2id());
+}
+
+#[test]
+fn is_scrypt() {
+    assert!(!Kdf::None.is_scrypt());
+}
+
 #[test]
 fn create_key_empty_password() {
     let key = Kdf::None.create_key(b"", 0).unwrap();
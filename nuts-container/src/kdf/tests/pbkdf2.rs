@@ -34,6 +34,16 @@ fn is_pbkdf2() {
     assert!(Kdf::pbkdf2(Digest::Sha1, 5, &[1, 2, 3]).is_pbkdf2());
 }
 
+#[test]
+fn is_argon2id() {
+    assert!(!Kdf::pbkdf2(Digest::Sha1, 5, &[1, 2, 3]).is_argon2id());
+}
+
+#[test]
+fn is_scrypt() {
+    assert!(!Kdf::pbkdf2(Digest::Sha1, 5, &[1, 2, 3]).is_scrypt());
+}
+
 #[test]
 fn ok() {
     match Kdf::pbkdf2(Digest::Sha1, 5, &[1, 2, 3]) {
@@ -172,3 +182,13 @@ fn create_key_min_len() {
         ]
     );
 }
+
+#[test]
+fn create_key_guarded() {
+    let key = Kdf::pbkdf2(Digest::Sha1, 1, &[1, 2, 3])
+        .create_key(b"123", 40)
+        .unwrap();
+
+    assert_eq!(key.len(), 40);
+    assert!(key.is_guarded());
+}
@@ -0,0 +1,174 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::kdf::{Kdf, KdfError};
+use crate::tests::RND;
+
+#[test]
+fn is_none() {
+    assert!(!Kdf::scrypt(4, 1, 1, &[1, 2, 3]).is_none());
+}
+
+#[test]
+fn is_pbkdf2() {
+    assert!(!Kdf::scrypt(4, 1, 1, &[1, 2, 3]).is_pbkdf2());
+}
+
+#[test]
+fn is_argon2id() {
+    assert!(!Kdf::scrypt(4, 1, 1, &[1, 2, 3]).is_argon2id());
+}
+
+#[test]
+fn is_scrypt() {
+    assert!(Kdf::scrypt(4, 1, 1, &[1, 2, 3]).is_scrypt());
+}
+
+#[test]
+fn ok() {
+    match Kdf::scrypt(4, 1, 2, &[1, 2, 3]) {
+        Kdf::Scrypt { log_n, r, p, salt } => {
+            assert_eq!(log_n, 4);
+            assert_eq!(r, 1);
+            assert_eq!(p, 2);
+            assert_eq!(salt, [1, 2, 3]);
+        }
+        _ => panic!("invalid kdf"),
+    }
+}
+
+#[test]
+fn generate_empty_salt() {
+    let kdf = Kdf::generate_scrypt(4, 1, 2, 0).unwrap();
+
+    match kdf {
+        Kdf::Scrypt { log_n, r, p, salt } => {
+            assert_eq!(log_n, 4);
+            assert_eq!(r, 1);
+            assert_eq!(p, 2);
+            assert_eq!(salt, [0; 0]);
+        }
+        _ => panic!("invalid kdf"),
+    }
+}
+
+#[test]
+fn generate_with_salt() {
+    let kdf = Kdf::generate_scrypt(4, 1, 2, 3).unwrap();
+
+    match kdf {
+        Kdf::Scrypt { log_n, r, p, salt } => {
+            assert_eq!(log_n, 4);
+            assert_eq!(r, 1);
+            assert_eq!(p, 2);
+            assert_eq!(salt.len(), 3); // salt filled with random data
+            assert_eq!(salt, &RND[..3]);
+        }
+        _ => panic!("invalid kdf"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid password, cannot be empty")]
+fn create_key_empty_password() {
+    Kdf::scrypt(4, 1, 1, &[1, 2, 3]).create_key(b"", 0).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid password, cannot be empty")]
+fn create_key_empty_password_min_len() {
+    Kdf::scrypt(4, 1, 1, &[1, 2, 3])
+        .create_key(b"", 40)
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid salt, cannot be empty")]
+fn create_key_empty_salt() {
+    Kdf::scrypt(4, 1, 1, &[]).create_key(b"123", 0).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid salt, cannot be empty")]
+fn create_key_empty_salt_min_len() {
+    Kdf::scrypt(4, 1, 1, &[]).create_key(b"123", 40).unwrap();
+}
+
+#[test]
+fn create_key_inval_log_n() {
+    for log_n in [0, 64, 255] {
+        Kdf::scrypt(log_n, 1, 1, &[1, 2, 3])
+            .create_key(b"123", 0)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn create_key_limits() {
+    for (kdf, name) in [
+        (Kdf::scrypt(4, 1, 65, &[1, 2, 3]), "p"),
+        (Kdf::scrypt(25, 8, 1, &[1, 2, 3]), "memory"),
+        (Kdf::scrypt(4, u32::MAX, 1, &[1, 2, 3]), "memory"),
+        (Kdf::scrypt(63, 1, 1, &[1, 2, 3]), "memory"),
+    ] {
+        let err = kdf.create_key(b"123", 0).unwrap_err();
+        assert!(matches!(err, KdfError::ExceedsLimit(n, _) if n == name));
+    }
+}
+
+#[test]
+fn generate_limits() {
+    let err = Kdf::generate_scrypt(25, 8, 1, 3).unwrap_err();
+    assert!(matches!(err, KdfError::ExceedsLimit("memory", _)));
+}
+
+#[test]
+fn create_key() {
+    let wkey = Kdf::scrypt(4, 1, 1, &[1, 2, 3])
+        .create_key(b"123", 0)
+        .unwrap();
+
+    assert_eq!(
+        *wkey,
+        vec![
+            39, 41, 246, 94, 214, 104, 124, 37, 10, 140, 1, 98, 16, 203, 155, 207, 137, 10, 183,
+            56, 54, 249, 114, 79, 116, 80, 135, 19, 176, 219, 126, 165
+        ]
+    );
+}
+
+#[test]
+fn create_key_min_len() {
+    let wkey = Kdf::scrypt(4, 1, 1, &[1, 2, 3])
+        .create_key(b"123", 40)
+        .unwrap();
+
+    assert_eq!(
+        *wkey,
+        vec![
+            39, 41, 246, 94, 214, 104, 124, 37, 10, 140, 1, 98, 16, 203, 155, 207, 137, 10, 183,
+            56, 54, 249, 114, 79, 116, 80, 135, 19, 176, 219, 126, 165, 243, 124, 111, 126, 164,
+            227, 220, 39, 216, 183, 143, 54, 195, 87, 34, 17, 192, 241, 59, 184, 67, 201, 172, 86,
+            42, 121, 133, 114, 181, 62, 102, 68
+        ]
+    );
+}
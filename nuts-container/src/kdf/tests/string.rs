@@ -20,7 +20,10 @@
 // IN THE SOFTWARE.
 
 use crate::digest::Digest;
-use crate::kdf::{Kdf, ParseKdfError, ParseKdfNoneError, ParseKdfPbkdf2Error};
+use crate::kdf::{
+    Kdf, ParseKdfArgon2idError, ParseKdfError, ParseKdfNoneError, ParseKdfPbkdf2Error,
+    ParseKdfScryptError,
+};
 use crate::tests::RND;
 
 #[test]
@@ -122,6 +125,224 @@ fn from_str_pbkdf2_default_salt_len() {
     )
 }
 
+#[test]
+fn from_str_argon2id_inval_args() {
+    for (str, args) in [("argon2id::", 2), ("argon2id:::::", 5)] {
+        let err = str.parse::<Kdf>().unwrap_err();
+        assert!(matches!(err, ParseKdfError::Argon2id(cause)
+                if matches!(cause, ParseKdfArgon2idError::InvalidNumberOfArguments(num)
+                    if num == args)));
+    }
+}
+
+#[test]
+fn from_str_argon2id_inval_memory() {
+    let err = "argon2id:x:::".parse::<Kdf>().unwrap_err();
+    assert!(matches!(
+        err,
+        ParseKdfError::Argon2id(ParseKdfArgon2idError::InvalidMemory(_))
+    ));
+}
+
+#[test]
+fn from_str_argon2id_no_args() {
+    let kdf = "argon2id".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Argon2id {
+            memory: 19456,
+            iterations: 2,
+            parallelism: 1,
+            salt: RND[..16].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_argon2id_all_args() {
+    let kdf = "argon2id:1024:3:4:8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Argon2id {
+            memory: 1024,
+            iterations: 3,
+            parallelism: 4,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_argon2id_default_memory() {
+    let kdf = "argon2id::3:4:8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Argon2id {
+            memory: 19456,
+            iterations: 3,
+            parallelism: 4,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_argon2id_default_iterations() {
+    let kdf = "argon2id:1024::4:8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Argon2id {
+            memory: 1024,
+            iterations: 2,
+            parallelism: 4,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_argon2id_default_parallelism() {
+    let kdf = "argon2id:1024:3::8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Argon2id {
+            memory: 1024,
+            iterations: 3,
+            parallelism: 1,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_argon2id_default_salt_len() {
+    let kdf = "argon2id:1024:3:4:".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Argon2id {
+            memory: 1024,
+            iterations: 3,
+            parallelism: 4,
+            salt: RND[..16].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_scrypt_inval_args() {
+    for (str, args) in [("scrypt::", 2), ("scrypt:::::", 5)] {
+        let err = str.parse::<Kdf>().unwrap_err();
+        assert!(matches!(err, ParseKdfError::Scrypt(cause)
+                if matches!(cause, ParseKdfScryptError::InvalidNumberOfArguments(num)
+                    if num == args)));
+    }
+}
+
+#[test]
+fn from_str_scrypt_inval_log_n() {
+    let err = "scrypt:256:::".parse::<Kdf>().unwrap_err();
+    assert!(matches!(
+        err,
+        ParseKdfError::Scrypt(ParseKdfScryptError::InvalidLogN(_))
+    ));
+}
+
+#[test]
+fn from_str_scrypt_no_args() {
+    let kdf = "scrypt".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Scrypt {
+            log_n: 17,
+            r: 8,
+            p: 1,
+            salt: RND[..16].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_scrypt_all_args() {
+    let kdf = "scrypt:10:4:2:8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Scrypt {
+            log_n: 10,
+            r: 4,
+            p: 2,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_scrypt_default_log_n() {
+    let kdf = "scrypt::4:2:8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Scrypt {
+            log_n: 17,
+            r: 4,
+            p: 2,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_scrypt_default_r() {
+    let kdf = "scrypt:10::2:8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Scrypt {
+            log_n: 10,
+            r: 8,
+            p: 2,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_scrypt_default_p() {
+    let kdf = "scrypt:10:4::8".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Scrypt {
+            log_n: 10,
+            r: 4,
+            p: 1,
+            salt: RND[..8].to_vec()
+        }
+    )
+}
+
+#[test]
+fn from_str_scrypt_default_salt_len() {
+    let kdf = "scrypt:10:4:2:".parse::<Kdf>().unwrap();
+
+    assert_eq!(
+        kdf,
+        Kdf::Scrypt {
+            log_n: 10,
+            r: 4,
+            p: 2,
+            salt: RND[..16].to_vec()
+        }
+    )
+}
+
 #[test]
 fn to_string_none() {
     assert_eq!(Kdf::None.to_string(), "none");
@@ -136,3 +357,25 @@ fn to_string_pbkdf2() {
     };
     assert_eq!(kdf.to_string(), "pbkdf2:sha1:1:3");
 }
+
+#[test]
+fn to_string_argon2id() {
+    let kdf = Kdf::Argon2id {
+        memory: 19456,
+        iterations: 2,
+        parallelism: 1,
+        salt: vec![1, 2, 3],
+    };
+    assert_eq!(kdf.to_string(), "argon2id:19456:2:1:3");
+}
+
+#[test]
+fn to_string_scrypt() {
+    let kdf = Kdf::Scrypt {
+        log_n: 17,
+        r: 8,
+        p: 1,
+        salt: vec![1, 2, 3],
+    };
+    assert_eq!(kdf.to_string(), "scrypt:17:8:1:3");
+}
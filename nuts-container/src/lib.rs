@@ -201,6 +201,109 @@
 //! assert_eq!(buf, [b'x'; 512]);
 //! ```
 //!
+//! ## Share a container between threads
+//!
+//! A [`Container`] is [`Send`], but reading needs a mutable reference. Convert
+//! it into a [`SharedContainer`], which can be cloned and sent to other
+//! threads. Each clone reads blocks concurrently.
+//!
+//! ```rust
+//! use nuts_container::*;
+//! use nuts_memory::MemoryBackend;
+//! use std::thread;
+//!
+//! let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+//!     .with_password_callback(|| Ok(b"abc".to_vec()))
+//!     .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+//!     .build::<MemoryBackend>()
+//!     .unwrap();
+//! let mut container = Container::<MemoryBackend>::create(MemoryBackend::new(), options).unwrap();
+//!
+//! let id = container.aquire().unwrap();
+//! container.write(&id, b"xyz").unwrap();
+//!
+//! let shared = container.into_shared().unwrap();
+//! let handles = (0..4)
+//!     .map(|_| {
+//!         let shared = shared.clone();
+//!         let id = id.clone();
+//!
+//!         thread::spawn(move || {
+//!             let mut buf = [0; 3];
+//!             assert_eq!(shared.read(&id, &mut buf).unwrap(), 3);
+//!             assert_eq!(buf, *b"xyz");
+//!         })
+//!     })
+//!     .collect::<Vec<_>>();
+//!
+//! for handle in handles {
+//!     handle.join().unwrap();
+//! }
+//! ```
+//!
+//! ## Store a byte stream
+//!
+//! The [`Stream`] service presents a container as a resizable stream of
+//! bytes, which implements [`Read`](std::io::Read),
+//! [`Write`](std::io::Write) and [`Seek`](std::io::Seek). The index of the
+//! data blocks is managed by the stream itself.
+//!
+//! ```rust
+//! use nuts_container::*;
+//! use nuts_memory::MemoryBackend;
+//! use std::io::{Read, Seek, SeekFrom, Write};
+//!
+//! let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+//!     .with_password_callback(|| Ok(b"abc".to_vec()))
+//!     .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+//!     .build::<MemoryBackend>()
+//!     .unwrap();
+//! let container = Container::<MemoryBackend>::create(MemoryBackend::new(), options).unwrap();
+//! let mut stream = Container::create_service::<Stream<MemoryBackend>>(container).unwrap();
+//!
+//! stream.write_all(&[b'x'; 2000]).unwrap();
+//! stream.seek(SeekFrom::Start(1000)).unwrap();
+//!
+//! let mut buf = vec![];
+//! assert_eq!(stream.read_to_end(&mut buf).unwrap(), 1000);
+//! assert_eq!(buf, [b'x'; 1000]);
+//! ```
+//!
+//! ## Asynchronous container
+//!
+//! With the `async` feature enabled the `AsyncContainer` is available. It
+//! is the asynchronous variant of the [`Container`] and runs on top of an
+//! [`AsyncBackend`](nuts_backend::AsyncBackend). The header and the blocks
+//! are encrypted in the same way, thus a container can be opened by both
+//! types. A blocking [`Backend`] is turned into an asynchronous backend with
+//! the [`Blocking`](nuts_backend::Blocking) adapter.
+//!
+//! The `AsyncContainer` does not depend on a specific async runtime:
+//!
+//! ```rust
+//! # #[cfg(feature = "async")]
+//! # pollster::block_on(async {
+//! use nuts_container::*;
+//! use nuts_memory::MemoryBackend;
+//!
+//! let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+//!     .with_password_callback(|| Ok(b"abc".to_vec()))
+//!     .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+//!     .build::<MemoryBackend>()
+//!     .unwrap();
+//! let mut container = AsyncContainer::create(MemoryBackend::new(), options)
+//!     .await
+//!     .unwrap();
+//!
+//! let id = container.aquire().await.unwrap();
+//! container.write(&id, b"xyz").await.unwrap();
+//!
+//! let mut buf = [0; 3];
+//! assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+//! assert_eq!(buf, *b"xyz");
+//! # });
+//! ```
+//!
 //! ## The header of a container
 //!
 //! The header of the container stores all data necessary to open the container
@@ -220,17 +323,50 @@
 //!   ([`Kdf`]). So, with a user supplied passphrase you are derivating the
 //!   wrapping-key, which decrypts the _secret_ part of the header, where the
 //!   master-key (used for en-/decryption of the data blocks) is stored.
+//!   For AE-ciphers the unencrypted part of the header (cipher, kdf, ...) is
+//!   authenticated together with the _secret_, a modified header is rejected
+//!   with [`HeaderError::NotTrustworthy`].
+//!
+//!   Each block is encrypted with its own random nonce, which is stored in
+//!   front of the ciphertext of the block. Thus, the same nonce is never
+//!   re-used for different blocks or different versions of a block.
+//!
+//!   For AE-ciphers the [id](Backend::Id) of the block is authenticated
+//!   together with the block. A block which was moved to another id or
+//!   copied over another block is detected and rejected with
+//!   [`CipherError::NotTrustworthy`]. Note that rolling back a block to an
+//!   older version of the _same_ block is not detected! An older ciphertext
+//!   of the block is still authentic for its id, the container does not
+//!   track a version counter per block. Protection against rollbacks must be
+//!   provided by the backend resp. the storage below it.
 //!
 //! * The key derivation function ([`Kdf`]) defines a way to create a key from
 //!   a user supplied passphrase. In the next step this key is used to encrypt resp.
-//!   decrypt the _secret_ part of the header.
+//!   decrypt the _secret_ part of the header. Instead of (or in addition
+//!   to) the passphrase the contents of a _keyfile_ can be fed into the
+//!   [`Kdf`], see [`OpenOptionsBuilder::with_keyfile`].
+//!
+//! * The _key slots_: Starting with revision 3 the _secret_ is encrypted with
+//!   a random header-key. Each key slot stores this header-key, wrapped with
+//!   the key derived from its own passphrase and [`Kdf`]. The container can
+//!   be opened with the passphrase of any key slot, all slots are tried one
+//!   after another. Slots are added and removed with the
+//!   [`ModifyOptionsBuilder`], [`Info::slots`] lists them.
+//!
+//!   Instead of a passphrase a key slot can wrap the header-key to the public
+//!   key of a [`Recipient`] (X25519). Such a slot is unlocked with the
+//!   private key, the [`Identity`] of the recipient.
+//!
+//!   The header-key itself can be exported as a [`RecoveryKey`], which opens
+//!   the container without any key slot, if all passphrases are lost.
 //!
 //! * The _secret_ is the encrypted part of the header and contains sensible
 //!   data of the container. The secret is encrypted with a wrapping-key, which
 //!   is the output of the [`Kdf`]. The _secret_ contains:
 //!
 //!   * _master-key_: The master-key is used for encryption of the blocks of
-//!     the container.
+//!     the container. It can be replaced with [`Container::rekey`], which
+//!     re-encrypts all blocks.
 //!   * _top-id_: The _top-id_ points to some kind of super-block. During
 //!     [service-creation](Container::create_service) the super-block is
 //!     aquired (if requested by the service) and its id (the _top-id_) is
@@ -238,46 +374,77 @@
 //!   * _settings of the backend_: The backend of the container stores its
 //!     runtime information in the secret. It gets it back when opening the
 //!     backend again. See [`Backend::Settings`] for more information.
+//!
+//! Without its header the data of a container are lost. Use
+//! [`Container::export_header`] to create a backup of the (encrypted) header
+//! and [`Container::restore_header`] to write it back into the container.
 
+#[cfg(feature = "async")]
+mod asynchronous;
 mod buffer;
+mod cache;
 mod cipher;
+mod compression;
 mod digest;
 mod error;
 mod header;
 mod info;
+mod journal;
 mod kdf;
 mod migrate;
 mod options;
 mod ossl;
 mod password;
+mod recipient;
+mod recovery;
 mod service;
+mod shared;
+mod snapshot;
+mod stream;
 mod svec;
 #[cfg(test)]
 mod tests;
+mod verify;
 
-use log::debug;
-use nuts_backend::{Backend, Create, Open, ReceiveHeader, HEADER_MAX_SIZE};
-use std::{any, cmp};
+use log::{debug, error};
+use nuts_backend::{Backend, BackendTypes, Binary, Create, Open, ReceiveHeader, HEADER_MAX_SIZE};
+use openssl::sha::sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{any, cmp, mem};
 
+use crate::cache::BlockCache;
 use crate::cipher::CipherContext;
-use crate::header::Header;
+use crate::header::{ExtendedBlocks, Header};
+use crate::journal::{Entry, Journal, Transaction};
 use crate::migrate::Migrator;
 use crate::password::PasswordStore;
+use crate::snapshot::Snapshots;
+use crate::svec::SecureVec;
 
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncContainer, AsyncServiceFactory};
 pub use buffer::BufferError;
 pub use cipher::{Cipher, CipherError};
+pub use compression::{Compression, CompressionError};
 pub use digest::Digest;
-pub use error::{ContainerResult, Error};
+pub use error::{ContainerResult, Error, IntoContainerResult};
 pub use header::{HeaderError, LATEST_REVISION};
-pub use info::Info;
+pub use info::{Info, SlotInfo};
+pub use journal::JournalError;
 pub use kdf::{Kdf, KdfError};
 pub use migrate::{Migration, MigrationError};
 pub use options::{
-    CreateOptions, CreateOptionsBuilder, ModifyOptions, ModifyOptionsBuilder, OpenOptions,
-    OpenOptionsBuilder,
+    ConvertOptions, ConvertOptionsBuilder, CreateOptions, CreateOptionsBuilder, ModifyOptions,
+    ModifyOptionsBuilder, OpenOptions, OpenOptionsBuilder,
 };
 pub use password::PasswordError;
+pub use recipient::{Identity, Recipient, RecipientError};
+pub use recovery::{RecoveryError, RecoveryKey};
 pub use service::{Service, ServiceFactory};
+pub use shared::SharedContainer;
+pub use snapshot::{SnapshotError, SnapshotInfo};
+pub use stream::{Stream, StreamError, StreamMigration, StreamResult};
+pub use verify::VerifyReport;
 
 macro_rules! map_err {
     ($result:expr) => {
@@ -300,6 +467,11 @@ pub struct Container<B: Backend> {
     store: PasswordStore,
     header: Header<'static, B>,
     ctx: CipherContext,
+    extended: ExtendedBlocks,
+    cache: BlockCache<B::Id>,
+    txn: Option<Transaction<B::Id>>,
+    snapshots: Snapshots<B>,
+    view: Option<usize>,
 }
 
 impl<B: Backend> Container<B> {
@@ -330,16 +502,14 @@ impl<B: Backend> Container<B> {
         backend_options: C,
         options: CreateOptions,
     ) -> ContainerResult<Container<B>, B> {
-        let mut header_bytes = [0; HEADER_MAX_SIZE];
         let settings = backend_options.settings();
-        let header = Header::create(&options, settings)?;
+        let mut header = Header::create(&options, settings)?;
 
         let callback = options.callback.clone();
-        let mut store = PasswordStore::new(callback);
-
-        header.write(&mut header_bytes, &mut store)?;
+        let mut store = PasswordStore::new(callback).with_keyfile(options.keyfile.clone());
 
-        let backend = map_err!(backend_options.build(header_bytes, options.overwrite))?;
+        let blocks = split_header(&header.write(&mut store)?);
+        let backend = map_err!(backend_options.build(blocks[0], options.overwrite))?;
 
         debug!(
             "Container created, backend: {}, header: {:?}",
@@ -348,13 +518,23 @@ impl<B: Backend> Container<B> {
         );
 
         let ctx = CipherContext::new(header.cipher());
-
-        Ok(Container {
+        let mut container = Container {
             backend,
             store,
             header,
             ctx,
-        })
+            extended: ExtendedBlocks::default(),
+            cache: BlockCache::new(0, false),
+            txn: None,
+            snapshots: Snapshots::new(),
+            view: None,
+        };
+
+        if blocks.len() > 1 {
+            container.write_header_blocks(&blocks)?;
+        }
+
+        Ok(container)
     }
 
     /// Creates a [service](Service) running on top of the given `container`.
@@ -416,6 +596,10 @@ impl<B: Backend> Container<B> {
     /// [password callback](OpenOptionsBuilder::with_password_callback). The
     /// returned password is then used to decrypt the secure part of the header.
     ///
+    /// If a [commit](Container::commit) was interrupted, the container is
+    /// recovered: A committed transaction is replayed, an incomplete
+    /// transaction is discarded.
+    ///
     /// # Errors
     ///
     /// Errors are listed in the [`Error`] type.
@@ -423,11 +607,11 @@ impl<B: Backend> Container<B> {
         mut backend_options: O,
         options: OpenOptions,
     ) -> ContainerResult<Container<B>, B> {
-        let callback = options.callback.clone();
-        let mut store = PasswordStore::new(callback);
+        let mut store = open_store(&options);
         let migrator = Migrator::default();
 
-        let mut header = Self::read_header(&mut backend_options, migrator, &mut store)?;
+        let header_bytes = Self::read_header_bytes(&mut backend_options)?;
+        let mut header = Header::<B>::read(&header_bytes, migrator, &mut store)?;
         let settings = header.settings().clone();
         let backend = map_err!(backend_options.build(settings))?;
 
@@ -440,13 +624,109 @@ impl<B: Backend> Container<B> {
         );
 
         let ctx = CipherContext::new(header.cipher());
-
-        Ok(Container {
+        let mut container = Container {
             backend,
             store,
             header,
             ctx,
-        })
+            extended: ExtendedBlocks::peek(&header_bytes)?,
+            cache: BlockCache::new(options.cache_size, options.write_back),
+            txn: None,
+            snapshots: Snapshots::new(),
+            view: None,
+        };
+
+        container.load_snapshots()?;
+
+        match options.snapshot.as_ref() {
+            Some(name) => container.view = Some(container.snapshots.position(name)?),
+            None => container.recover()?,
+        }
+
+        Ok(container)
+    }
+
+    /// Restores the header of an existing container.
+    ///
+    /// The container is opened with the given `header`, which was created by
+    /// [`Container::export_header`], instead of the header stored in the
+    /// backend. Like in [`Container::open`] the `header` is decrypted with the
+    /// credentials passed to `options`. This detects a damaged or modified
+    /// backup.
+    ///
+    /// Next, the backup is verified against the container: a block of the
+    /// container (the _top-id_ or the first aquired block) is decrypted with
+    /// the restored master-key. For AE-ciphers this detects a header, which
+    /// belongs to another container or to another master-key (e.g. before a
+    /// [rekey](Container::rekey)), an [`Error::HeaderMismatch`] error is
+    /// returned. Other ciphers cannot detect a mismatch!
+    ///
+    /// Finally, the restored header (including its extended header blocks)
+    /// is written back into the backend and the opened container is returned.
+    /// Note that the key slots of the backup are restored as well, passwords
+    /// changed after the backup are lost.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    #[allow(clippy::manual_is_multiple_of)] // is_multiple_of() needs rust 1.87
+    pub fn restore_header<O: Open<B>>(
+        backend_options: O,
+        options: OpenOptions,
+        header: &[u8],
+    ) -> ContainerResult<Container<B>, B> {
+        if header.is_empty()
+            || header.len() % HEADER_MAX_SIZE != 0
+            || ExtendedBlocks::peek(header)?.count + 1 != header.len() / HEADER_MAX_SIZE
+        {
+            return Err(HeaderError::InvalidHeader.into());
+        }
+
+        let mut store = open_store(&options);
+        let mut parsed = Header::<B>::read(header, Migrator::default(), &mut store)?;
+        let settings = parsed.settings().clone();
+        let backend = map_err!(backend_options.build(settings))?;
+
+        parsed.migrate()?;
+
+        let ctx = CipherContext::new(parsed.cipher());
+        let mut container = Container {
+            backend,
+            store,
+            header: parsed,
+            ctx,
+            extended: ExtendedBlocks::default(),
+            cache: BlockCache::new(options.cache_size, options.write_back),
+            txn: None,
+            snapshots: Snapshots::new(),
+            view: None,
+        };
+
+        container.verify_header()?;
+        container.load_snapshots()?;
+
+        // The extended header blocks of the replaced header, if any, must
+        // not be overwritten before the restored root header is written.
+        let mut buf = [0; HEADER_MAX_SIZE];
+
+        if container.backend.get_header_bytes(&mut buf).is_ok() {
+            container.extended = ExtendedBlocks::peek(&buf).unwrap_or_default();
+        }
+
+        container
+            .header
+            .set_generation(container.extended.next_generation());
+
+        let blocks = split_header(&container.header.write(&mut container.store)?);
+        container.write_header_blocks(&blocks)?;
+
+        debug!(
+            "Header restored, backend: {}, header: {:?}",
+            any::type_name::<B>(),
+            container.header
+        );
+
+        Ok(container)
     }
 
     /// Opens a [service](Service) running on top of an existing container.
@@ -455,6 +735,13 @@ impl<B: Backend> Container<B> {
     /// the service instance.
     ///
     /// This should be the preferred way to open a nuts-service!
+    ///
+    /// If `migrate` is set, a revision 0 or 1 header is converted into a
+    /// revision 2 header, which stores the service identifier. Note that a
+    /// conversion into revision 3 by [`Container::rekey`] is only possible,
+    /// if the block size of the service does not change (e.g. for an
+    /// unencrypted container), because each block stores its own nonce since
+    /// revision 3. Otherwise the data must be copied into a new container.
     pub fn open_service<F: ServiceFactory<B>>(
         mut container: Container<B>,
         migrate: bool,
@@ -471,7 +758,7 @@ impl<B: Backend> Container<B> {
 
         if migrate {
             container.update_header(|header| {
-                let changed = header.convert_to_latest(F::Service::sid());
+                let changed = header.convert_to_rev2(F::Service::sid());
                 Ok(changed)
             })?;
         }
@@ -485,10 +772,58 @@ impl<B: Backend> Container<B> {
     }
 
     /// Consumes this container, returning the inner backend.
-    pub fn into_backend(self) -> B {
+    ///
+    /// Blocks buffered by the [write-back cache](OpenOptionsBuilder::with_write_back)
+    /// are flushed on a best-effort basis, errors are logged only and the
+    /// buffered blocks are lost. Use [`Container::try_into_backend`] to
+    /// handle errors.
+    pub fn into_backend(mut self) -> B {
+        self.flush_or_log();
         self.backend
     }
 
+    /// Consumes this container, returning the inner backend.
+    ///
+    /// Blocks buffered by the [write-back cache](OpenOptionsBuilder::with_write_back)
+    /// are flushed before.
+    ///
+    /// # Errors
+    ///
+    /// If the blocks cannot be flushed, the error is returned together with
+    /// the unchanged container, see [`IntoContainerResult`]. Errors are
+    /// listed in the [`Error`] type.
+    pub fn try_into_backend(mut self) -> IntoContainerResult<B, B> {
+        match self.flush() {
+            Ok(()) => Ok(self.backend),
+            Err(err) => Err(Box::new((err, self))),
+        }
+    }
+
+    /// Consumes this container, returning a [`SharedContainer`].
+    ///
+    /// The [`SharedContainer`] can be cloned and sent to other threads, where
+    /// blocks are read concurrently. The block cache is not shared, blocks
+    /// buffered by the [write-back cache](OpenOptionsBuilder::with_write_back)
+    /// are flushed before.
+    ///
+    /// # Errors
+    ///
+    /// A pending [transaction](Container::begin) cannot be shared, a
+    /// [`JournalError::Pending`] error is returned. If the blocks of the
+    /// cache cannot be flushed, the error of the backend is returned. In both
+    /// cases the unchanged container is returned together with the error,
+    /// see [`IntoContainerResult`].
+    pub fn into_shared(mut self) -> IntoContainerResult<SharedContainer<B>, B> {
+        if self.txn.is_some() {
+            return Err(Box::new((JournalError::Pending.into(), self)));
+        }
+
+        match self.flush() {
+            Ok(()) => Ok(SharedContainer::new(self)),
+            Err(err) => Err(Box::new((err, self))),
+        }
+    }
+
     /// Returns information from the container.
     ///
     /// # Errors
@@ -501,7 +836,10 @@ impl<B: Backend> Container<B> {
             backend,
             revision: self.header.revision(),
             cipher: self.header.cipher(),
+            compression: self.header.compression(),
             kdf: self.header.kdf().clone(),
+            slots: self.header.slots(),
+            active_slot: self.header.active_slot(),
             bsize_gross: self.backend.block_size(),
             bsize_net: self.block_size(),
         })
@@ -524,12 +862,19 @@ impl<B: Backend> Container<B> {
     ///
     /// Depending on the selected cipher, you need to store additional data in
     /// a block. I.e. an AE-cipher results into a tag, which needs to be stored
-    /// additionally. Such data must be substracted from the gross block size
-    /// and results into the net block size.
+    /// additionally. Starting with revision 3 each block also stores its own
+    /// random nonce. Such data must be substracted from the gross block size
+    /// and results into the net block size. With [compression](Compression)
+    /// enabled, the compression frame of each block is substracted as well.
+    ///
+    /// While a [conversion](Container::convert) into another cipher is
+    /// pending, the smaller block size of both ciphers is returned.
     pub fn block_size(&self) -> u32 {
-        self.backend
-            .block_size()
-            .saturating_sub(self.header.cipher().tag_size())
+        block_size(&self.header, self.backend.block_size())
+    }
+
+    fn net_block_size(&self, cipher: Cipher, iv: bool) -> u32 {
+        net_block_size(self.backend.block_size(), cipher, iv)
     }
 
     /// Modifies the container with the given options.
@@ -541,22 +886,92 @@ impl<B: Backend> Container<B> {
     ///
     /// Errors are listed in the [`Error`] type.
     pub fn modify(&mut self, options: ModifyOptions) -> ContainerResult<(), B> {
+        self.writable_or_err()?;
+        self.no_transaction_or_err()?;
+
         let mut changed = false;
 
+        if options.password.is_some() || options.kdf.is_some() {
+            self.header.password_slot_or_err()?;
+        }
+
         if options.password.is_some() {
-            self.store = PasswordStore::new(options.password.clone());
+            // a keyfile stays part of the password
+            self.store.change_callback(options.password.clone());
             changed = true;
         }
 
         self.update_header(|header| {
+            if options.password.is_some() {
+                header.reset_password_slot();
+            }
+
             if let Some(kdf) = options.kdf {
                 changed |= header.set_kdf(kdf);
             }
 
+            // remove from the end, the indices of the remaining slots are
+            // not shifted
+            let mut remove_slots = options.remove_slots.clone();
+            remove_slots.sort_unstable();
+            remove_slots.dedup();
+
+            for idx in remove_slots.into_iter().rev() {
+                changed |= header.remove_slot(idx)?;
+            }
+
+            for (kdf, callback) in options.add_slots.iter() {
+                if header.cipher() != Cipher::None {
+                    let mut store = PasswordStore::new(Some(callback.clone()));
+                    changed |= header.add_slot(kdf.build()?, store.value()?)?;
+                }
+            }
+
+            for recipient in options.add_recipients.iter() {
+                changed |= header.add_recipient(recipient)?;
+            }
+
             Ok(changed)
         })
     }
 
+    /// Returns the recovery key of the container.
+    ///
+    /// The [`RecoveryKey`] opens the container without a password, see
+    /// [`OpenOptionsBuilder::with_recovery_key`]. Its textual representation
+    /// can be written down on paper and kept in a safe place. Anybody with
+    /// the recovery key has full access to the container!
+    ///
+    /// The recovery key stays valid, when passwords or key slots are
+    /// changed. A [conversion](Container::convert) into another cipher
+    /// invalidates the recovery key, export it again afterwards.
+    ///
+    /// # Errors
+    ///
+    /// The recovery key is available starting with revision 3, for older
+    /// revisions an [`HeaderError::InvalidRevision`] error is returned. An
+    /// unencrypted container has no recovery key, a
+    /// [`HeaderError::NotEncrypted`] error is returned.
+    pub fn recovery_key(&self) -> ContainerResult<RecoveryKey, B> {
+        Ok(self.header.recovery_key()?)
+    }
+
+    /// Exports the header of the container.
+    ///
+    /// Returns the raw (encrypted) header as it is stored in the backend. The
+    /// root header is followed by its extended header blocks, thus the size
+    /// of the backup is a multiple of [`HEADER_MAX_SIZE`]. The backup can be
+    /// written back with [`Container::restore_header`], e.g. when the header
+    /// of the container is damaged. The backup is protected by the key slots,
+    /// which were active when exporting it.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub fn export_header(&mut self) -> ContainerResult<Vec<u8>, B> {
+        Self::read_header_bytes(&mut self.backend)
+    }
+
     /// Aquires a new block in the backend.
     ///
     /// Once aquired you should be able to [read](Container::read) and
@@ -571,13 +986,91 @@ impl<B: Backend> Container<B> {
     ///
     /// Errors are listed in the [`Error`] type.
     pub fn aquire(&mut self) -> ContainerResult<B::Id, B> {
+        self.writable_or_err()?;
+
+        let cipher = self.header.cipher();
+
+        self.ctx.set_cipher(cipher);
+        self.ctx.copy_from_slice(
+            self.net_block_size(cipher, self.header.iv().is_some()) as usize,
+            &[],
+        );
+
+        let key = self.header.key();
+        let ctext = match self.header.iv() {
+            Some(iv) => self.ctx.encrypt(key, iv)?,
+            None => self.ctx.encrypt_with_nonce(&[], key)?,
+        };
+
+        let id = map_err!(self.backend.aquire(ctext))?;
+
+        if self.header.binds_blocks() {
+            // The block is bound to its id, which is not known before the
+            // block was aquired. Write it again, now with the final id. If
+            // this write fails, the id is not passed to the caller, the
+            // unbound block is leaked.
+            self.write_block(&id, &[])?;
+        }
+
+        if let Some(txn) = self.txn.as_mut() {
+            txn.aquired(id.clone());
+        }
+
+        Ok(id)
+    }
+
+    /// Aquires `n` new blocks in the container.
+    ///
+    /// This is the batch variant of [`Container::aquire`]. The blocks are
+    /// aquired with a single [`Backend::aquire_many`] call.
+    ///
+    /// Returns the [ids](Backend::Id) of the new blocks.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub fn aquire_many(&mut self, n: usize) -> ContainerResult<Vec<B::Id>, B> {
+        self.writable_or_err()?;
+
+        let ids = self.aquire_blocks(n)?;
+
+        if let Some(txn) = self.txn.as_mut() {
+            for id in ids.iter() {
+                txn.aquired(id.clone());
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// Aquires `n` new blocks in the backend. Unlike
+    /// [`Container::aquire_many`] the blocks are not recorded by a pending
+    /// transaction.
+    fn aquire_blocks(&mut self, n: usize) -> ContainerResult<Vec<B::Id>, B> {
+        let cipher = self.header.cipher();
+
+        self.ctx.set_cipher(cipher);
+        self.ctx.copy_from_slice(
+            self.net_block_size(cipher, self.header.iv().is_some()) as usize,
+            &[],
+        );
+
         let key = self.header.key();
-        let iv = self.header.iv();
+        let ctext = match self.header.iv() {
+            Some(iv) => self.ctx.encrypt(key, iv)?,
+            None => self.ctx.encrypt_with_nonce(&[], key)?,
+        }
+        .to_vec();
+
+        let ids = map_err!(self.backend.aquire_many(&vec![ctext.as_slice(); n]))?;
 
-        self.ctx.copy_from_slice(self.block_size() as usize, &[]);
-        let ctext = self.ctx.encrypt(key, iv)?;
+        if self.header.binds_blocks() {
+            // Bind the blocks to their final ids, see Container::aquire.
+            let blocks = ids.iter().map(|id| (id, &[][..])).collect::<Vec<_>>();
+            self.write_blocks(&blocks)?;
+        }
 
-        map_err!(self.backend.aquire(ctext))
+        Ok(ids)
     }
 
     /// Releases a block again.
@@ -586,11 +1079,36 @@ impl<B: Backend> Container<B> {
     /// [written](Container::write), the [id](Backend::Id) cannot be used
     /// afterwards.
     ///
+    /// Within a [transaction](Container::begin) the block is released, when
+    /// the transaction is committed.
+    ///
     /// # Errors
     ///
     /// Errors are listed in the [`Error`] type.
     pub fn release(&mut self, id: B::Id) -> ContainerResult<(), B> {
-        map_err!(self.backend.release(id))
+        self.writable_or_err()?;
+
+        if let Some(txn) = self.txn.as_mut() {
+            txn.release(id.as_bytes(), id);
+            return Ok(());
+        }
+
+        self.cache.remove(&id.as_bytes());
+
+        if !self.snapshots.contains(&id) {
+            return map_err!(self.backend.release(id));
+        }
+
+        // The block (or its copy) is kept, as long as it belongs to a
+        // snapshot.
+        let unused = self.snapshots.release(id);
+        self.save_snapshots()?;
+
+        for id in unused {
+            map_err!(self.backend.release(id))?;
+        }
+
+        Ok(())
     }
 
     /// Reads a block from the container.
@@ -606,24 +1124,146 @@ impl<B: Backend> Container<B> {
     /// The methods returns the number of bytes actually read, which cannot be
     /// greater than the [block-size](Backend::block_size).
     ///
+    /// Within a [transaction](Container::begin) you read the data written by
+    /// the transaction.
+    ///
     /// # Errors
     ///
-    /// Errors are listed in the [`Error`] type.
+    /// For AE-ciphers a block, which was moved resp. copied from another id,
+    /// is rejected with a [`CipherError::NotTrustworthy`] error. A block,
+    /// which was rolled back to an older version of itself, is _not_
+    /// detected. Further errors are listed in the [`Error`] type.
     pub fn read(&mut self, id: &B::Id, buf: &mut [u8]) -> ContainerResult<usize, B> {
+        let key = id.as_bytes();
+
+        if let Some(data) = self.txn.as_ref().and_then(|txn| txn.get(&key)) {
+            let n = cmp::min(data.len(), buf.len());
+            buf[..n].copy_from_slice(&data[..n]);
+
+            return Ok(n);
+        }
+
+        if let Some(data) = self.cache.get(&key) {
+            let n = cmp::min(data.len(), buf.len());
+            buf[..n].copy_from_slice(&data[..n]);
+
+            return Ok(n);
+        }
+
+        let target = self.snapshots.resolve(self.view, id)?;
         let ctext = self.ctx.inp_mut(self.backend.block_size() as usize);
-        map_err!(self.backend.read(id, ctext))?;
+        let n = map_err!(self.backend.read(&target, ctext))?;
+        self.ctx.truncate_inp(n);
 
-        let key = self.header.key();
-        let iv = self.header.iv();
+        self.decrypt_and_cache(key, id, &target, buf)
+    }
+
+    /// Reads several blocks from the container.
+    ///
+    /// This is the batch variant of [`Container::read`]. For each
+    /// `(id, buf)` pair of `blocks` the block with the given `id` is read and
+    /// decrypted into `buf`. Blocks, which are not served by the block cache
+    /// or a pending transaction, are read with a single
+    /// [`Backend::read_many`] call.
+    ///
+    /// Returns the number of bytes actually read for each block in the order
+    /// of `blocks`.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub fn read_many(
+        &mut self,
+        blocks: &mut [(&B::Id, &mut [u8])],
+    ) -> ContainerResult<Vec<usize>, B> {
+        let mut result = vec![0; blocks.len()];
+        let mut missing = vec![];
 
-        let ptext = self.ctx.decrypt(key, iv)?;
+        for (idx, (id, buf)) in blocks.iter_mut().enumerate() {
+            let key = id.as_bytes();
+            let data = match self.txn.as_ref().and_then(|txn| txn.get(&key)) {
+                Some(data) => Some(data),
+                None => self.cache.get(&key),
+            };
 
-        let n = cmp::min(ptext.len(), buf.len());
-        buf[..n].copy_from_slice(&ptext[..n]);
+            match data {
+                Some(data) => {
+                    let n = cmp::min(data.len(), buf.len());
+                    buf[..n].copy_from_slice(&data[..n]);
+                    result[idx] = n;
+                }
+                None => missing.push(idx),
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(result);
+        }
+
+        let targets = missing
+            .iter()
+            .map(|idx| self.snapshots.resolve(self.view, blocks[*idx].0))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let gross = self.backend.block_size() as usize;
+        let mut ctexts = vec![vec![0; gross]; missing.len()];
+        let mut batch = targets
+            .iter()
+            .zip(ctexts.iter_mut())
+            .map(|(target, ctext)| (target, ctext.as_mut_slice()))
+            .collect::<Vec<_>>();
+
+        let lens = map_err!(self.backend.read_many(&mut batch))?;
+        let blocks_read = missing
+            .into_iter()
+            .zip(targets.iter())
+            .zip(ctexts.iter().zip(lens));
+
+        for ((idx, target), (ctext, len)) in blocks_read {
+            let (id, buf) = &mut blocks[idx];
+
+            self.ctx.inp_mut(len).copy_from_slice(&ctext[..len]);
+            result[idx] = self.decrypt_and_cache(id.as_bytes(), id, target, buf)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Decrypts the raw block data, which was read into the cipher context,
+    /// into `buf` and puts the block into the cache.
+    ///
+    /// The data of block `id` were read from the block `target`, see
+    /// [`Container::create_snapshot`].
+    fn decrypt_and_cache(
+        &mut self,
+        key: Vec<u8>,
+        id: &B::Id,
+        target: &B::Id,
+        buf: &mut [u8],
+    ) -> ContainerResult<usize, B> {
+        if !self.cache.is_enabled() {
+            return self.decrypt_block(target, buf);
+        }
+
+        let mut data = SecureVec::zeroed(self.block_size() as usize);
+        let n = self.decrypt_block(target, &mut data)?;
+        data.truncate(n);
+
+        let n = cmp::min(n, buf.len());
+        buf[..n].copy_from_slice(&data[..n]);
+
+        self.cache_block(key, id, data, false)?;
 
         Ok(n)
     }
 
+    /// Decrypts the raw block data, which was read into the cipher context.
+    fn decrypt_block(&mut self, id: &B::Id, buf: &mut [u8]) -> ContainerResult<usize, B> {
+        let bsize = self.block_size() as usize;
+
+        decrypt_block(&self.header, &mut self.ctx, bsize, id, buf)
+    }
+
     /// Writes a block into the container.
     ///
     /// Encrypts the plain data from `buf` and writes the encrypted data into
@@ -640,54 +1280,981 @@ impl<B: Backend> Container<B> {
     ///
     /// The method returns the number of bytes actually written.
     ///
+    /// With [write-back](OpenOptionsBuilder::with_write_back) buffering
+    /// enabled, the block is written into the block cache only, see
+    /// [`Container::flush`]. Within a [transaction](Container::begin) the
+    /// block is written, when the transaction is committed.
+    ///
     /// # Errors
     ///
     /// Errors are listed in the [`Error`] type.
     pub fn write(&mut self, id: &B::Id, buf: &[u8]) -> ContainerResult<usize, B> {
-        let len = self.ctx.copy_from_slice(self.block_size() as usize, buf);
-
-        let key = self.header.key();
-        let iv = self.header.iv();
+        self.writable_or_err()?;
 
-        let ctext = self.ctx.encrypt(key, iv)?;
+        if self.txn.is_none() && !self.cache.is_enabled() {
+            return self.write_block(id, buf);
+        }
 
-        map_err!(self.backend.write(id, ctext)).map(|_| len)
-    }
+        let bsize = self.block_size() as usize;
+        let len = cmp::min(buf.len(), bsize);
+        let mut data = SecureVec::zeroed(bsize);
 
-    fn read_header<H: ReceiveHeader<B>>(
-        reader: &mut H,
-        migrator: Migrator<'static>,
-        store: &mut PasswordStore,
-    ) -> ContainerResult<Header<'static, B>, B> {
-        let mut buf = [0; HEADER_MAX_SIZE];
+        data[..len].copy_from_slice(&buf[..len]);
 
-        match reader.get_header_bytes(&mut buf) {
-            Ok(_) => {
-                debug!("got {} header bytes", buf.len());
-                Ok(Header::read(&buf, migrator, store)?)
-            }
-            Err(cause) => Err(Error::Backend(cause)),
+        if let Some(txn) = self.txn.as_mut() {
+            txn.write(id.as_bytes(), id, data);
+            return Ok(len);
         }
-    }
 
-    fn update_header<F: FnOnce(&mut Header<B>) -> Result<bool, HeaderError>>(
-        &mut self,
-        f: F,
-    ) -> ContainerResult<(), B> {
-        debug!("header before update: {:?}", self.header);
+        let dirty = self.cache.is_write_back();
 
-        let changed = f(&mut self.header)?;
+        if !dirty {
+            self.write_block(id, &data)?;
+        }
 
-        debug!(
-            "header after update: {:?}, changed: {}",
-            self.header, changed
-        );
+        self.cache_block(id.as_bytes(), id, data, dirty)?;
 
-        if changed {
-            let mut header_bytes = [0; HEADER_MAX_SIZE];
+        Ok(len)
+    }
 
-            self.header.write(&mut header_bytes, &mut self.store)?;
-            map_err!(self.backend.write_header(&header_bytes))?;
+    /// Writes several blocks into the container.
+    ///
+    /// This is the batch variant of [`Container::write`]. For each
+    /// `(id, buf)` pair of `blocks` the data from `buf` are encrypted and
+    /// written into the block with the given `id`. The blocks are written
+    /// with a single [`Backend::write_many`] call.
+    ///
+    /// Returns the number of bytes actually written for each block in the
+    /// order of `blocks`.
+    ///
+    /// With [write-back](OpenOptionsBuilder::with_write_back) buffering
+    /// enabled, the blocks are written into the block cache only, see
+    /// [`Container::flush`]. Within a [transaction](Container::begin) the
+    /// blocks are written, when the transaction is committed.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub fn write_many(&mut self, blocks: &[(&B::Id, &[u8])]) -> ContainerResult<Vec<usize>, B> {
+        self.writable_or_err()?;
+
+        if self.cache.is_write_back() || self.txn.is_some() {
+            return blocks.iter().map(|(id, buf)| self.write(id, buf)).collect();
+        }
+
+        self.write_through(blocks)
+    }
+
+    /// Writes the `blocks` into the backend. If the block cache is enabled,
+    /// the blocks are cached as clean blocks.
+    fn write_through(&mut self, blocks: &[(&B::Id, &[u8])]) -> ContainerResult<Vec<usize>, B> {
+        let lens = self.write_blocks(blocks)?;
+
+        if self.cache.is_enabled() {
+            let bsize = self.block_size() as usize;
+
+            for ((id, buf), len) in blocks.iter().zip(lens.iter()) {
+                let mut data = SecureVec::zeroed(bsize);
+
+                data[..*len].copy_from_slice(&buf[..*len]);
+                self.cache_block(id.as_bytes(), id, data, false)?;
+            }
+        }
+
+        Ok(lens)
+    }
+
+    /// Writes all blocks buffered by the block cache into the backend.
+    ///
+    /// With [write-back](OpenOptionsBuilder::with_write_back) buffering
+    /// enabled, written blocks are kept in the cache. Call this method to
+    /// make sure that they are actually stored in the backend. Without
+    /// write-back buffering nothing happens.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub fn flush(&mut self) -> ContainerResult<(), B> {
+        for (key, id, data) in self.cache.dirty() {
+            self.write_block(&id, &data)?;
+            self.cache.set_clean(&key);
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the block cache, errors are logged only.
+    fn flush_or_log(&mut self) {
+        if let Err(err) = self.flush() {
+            error!("failed to flush the block cache: {}", err);
+        }
+    }
+
+    /// Starts a transaction.
+    ///
+    /// Blocks [written](Container::write) and [released](Container::release)
+    /// within the transaction are staged in memory until the transaction is
+    /// [committed](Container::commit). Reading a staged block returns the
+    /// data written by the transaction. Blocks [aquired](Container::aquire)
+    /// within the transaction are aquired immediately, they are released
+    /// again, if the transaction is [rolled back](Container::rollback).
+    ///
+    /// Use a transaction to update several blocks at once, e.g. the blocks of
+    /// an index together with the header block of a service. Either all or
+    /// none of the updates are stored in the container.
+    ///
+    /// A transaction, which is neither committed nor rolled back, is lost,
+    /// when the container is dropped. Blocks aquired by the transaction are
+    /// not released then.
+    ///
+    /// # Errors
+    ///
+    /// Transactions are available starting with revision 3, for older
+    /// revisions an [`HeaderError::InvalidRevision`] error is returned. If
+    /// another transaction is pending, a [`JournalError::Pending`] error is
+    /// returned.
+    pub fn begin(&mut self) -> ContainerResult<(), B> {
+        self.header.latest_revision_or_err()?;
+        self.writable_or_err()?;
+        self.no_transaction_or_err()?;
+
+        self.txn = Some(Transaction::new());
+
+        Ok(())
+    }
+
+    /// Commits the pending transaction.
+    ///
+    /// The staged blocks are written into an encrypted journal first. The
+    /// journal is stored in blocks of the container and referenced by the
+    /// header. Once the journal is complete, it is marked as committed and
+    /// the blocks are written to their final location. Finally the journal
+    /// is removed again.
+    ///
+    /// If the commit is interrupted, the container is recovered by the next
+    /// [`Container::open`]: A committed journal is replayed, otherwise the
+    /// journal is discarded and the blocks stay unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Without a pending transaction a [`JournalError::NotPending`] error is
+    /// returned. Further errors are listed in the [`Error`] type.
+    pub fn commit(&mut self) -> ContainerResult<(), B> {
+        let txn = self.txn.take().ok_or(JournalError::NotPending)?;
+        let (blocks, _) = txn.into_parts();
+
+        if blocks.is_empty() {
+            return Ok(());
+        }
+
+        // Blocks aquired for the journal are lost, if the commit is
+        // interrupted before the journal is referenced by the header.
+        let nwrites = blocks.iter().filter(|(_, data)| data.is_some()).count();
+        let mut sources = self.aquire_many(nwrites)?.into_iter();
+        let entries = blocks
+            .iter()
+            .map(|(id, data)| Entry::new(id.clone(), data.as_ref().and_then(|_| sources.next())))
+            .collect::<Vec<Entry<B>>>();
+
+        let bsize = self.block_size();
+        let index = journal::encode_entries(&entries)?;
+        let index_ids = self.aquire_many(journal::index_blocks::<B>(&index, bsize)?)?;
+        let index_blocks = journal::encode_index::<B>(&index, &index_ids, bsize)?;
+        let first = index_ids[0].as_bytes();
+
+        let batch = index_ids
+            .iter()
+            .zip(index_blocks.iter())
+            .map(|(id, buf)| (id, buf.as_slice()))
+            .collect::<Vec<_>>();
+
+        self.write_blocks(&batch)?;
+        self.update_header(|header| header.set_journal(&first, false))?;
+
+        let batch = entries
+            .iter()
+            .zip(blocks.iter())
+            .filter_map(|(entry, (_, data))| {
+                Some((entry.source.as_ref()?, data.as_ref()?.as_slice()))
+            })
+            .collect::<Vec<_>>();
+
+        self.write_blocks(&batch)?;
+        self.update_header(|header| header.set_journal(&first, true))?;
+
+        debug!("transaction committed, {} blocks", blocks.len());
+
+        let batch = blocks
+            .iter()
+            .filter_map(|(id, data)| Some((id, data.as_ref()?.as_slice())))
+            .collect::<Vec<_>>();
+
+        self.write_through(&batch)?;
+
+        for (id, data) in blocks {
+            if data.is_none() {
+                self.release(id)?;
+            }
+        }
+
+        self.finish_journal(Journal::new(index_ids, entries))
+    }
+
+    /// Rolls back the pending transaction.
+    ///
+    /// The staged blocks are discarded, blocks aquired within the
+    /// transaction are released again.
+    ///
+    /// # Errors
+    ///
+    /// Without a pending transaction a [`JournalError::NotPending`] error is
+    /// returned. Further errors are listed in the [`Error`] type.
+    pub fn rollback(&mut self) -> ContainerResult<(), B> {
+        let txn = self.txn.take().ok_or(JournalError::NotPending)?;
+        let (_, aquired) = txn.into_parts();
+
+        for id in aquired {
+            self.release(id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replays or discards the journal of an interrupted transaction.
+    fn recover(&mut self) -> ContainerResult<(), B> {
+        let (first, committed) = match self.header.journal() {
+            Some((id, committed)) => (id.to_vec(), committed),
+            None => return Ok(()),
+        };
+
+        let first = <B::Id as Binary>::from_bytes(&first).ok_or(JournalError::InvalidId)?;
+        let journal = self.read_journal(first)?;
+
+        if committed {
+            debug!("replay journal, {} blocks", journal.entries.len());
+
+            let ids = map_err!(self.backend.ids())?;
+            let mut buf = vec![0; self.block_size() as usize];
+
+            for entry in journal.entries.iter() {
+                match entry.source.as_ref() {
+                    Some(source) => {
+                        self.read(source, &mut buf)?;
+                        self.write_block(&entry.target, &buf)?;
+                    }
+                    // the block was already released before the interruption
+                    None if !self.block_exists(ids.as_deref(), &entry.target) => {}
+                    None => self.release(entry.target.clone())?,
+                }
+            }
+        } else {
+            debug!("discard journal, {} blocks", journal.entries.len());
+        }
+
+        self.finish_journal(journal)
+    }
+
+    /// Tests whether the block `id` is still aquired.
+    ///
+    /// `ids` is the [list of blocks](Backend::ids) of the backend. If the
+    /// backend cannot list its blocks, the block is read instead, a block
+    /// which cannot be read is considered released. At worst the block is
+    /// leaked.
+    fn block_exists(&mut self, ids: Option<&[B::Id]>, id: &B::Id) -> bool {
+        match ids {
+            Some(ids) => ids.contains(id),
+            None => {
+                let mut buf = vec![0; self.backend.block_size() as usize];
+                self.backend.read(id, &mut buf).is_ok()
+            }
+        }
+    }
+
+    /// Reads the journal, which starts at the index block `id`.
+    fn read_journal(&mut self, id: B::Id) -> ContainerResult<Journal<B>, B> {
+        let mut buf = vec![0; self.block_size() as usize];
+        let mut ids = vec![];
+        let mut index = vec![];
+        let mut next = Some(id);
+
+        while let Some(id) = next {
+            self.read(&id, &mut buf)?;
+
+            let (chunk, id_next) = journal::decode_index::<B>(&buf)?;
+
+            index.extend_from_slice(&chunk);
+            ids.push(id);
+            next = id_next;
+        }
+
+        Ok(Journal::new(ids, journal::decode_entries(&index)?))
+    }
+
+    /// Removes the `journal` from the header and releases its blocks.
+    fn finish_journal(&mut self, journal: Journal<B>) -> ContainerResult<(), B> {
+        // The blocks are released after the header was updated, an
+        // interruption leaves unused blocks but never a journal, which
+        // references released blocks.
+        self.update_header(|header| Ok(header.clear_journal()))?;
+
+        for id in journal.into_ids() {
+            self.release(id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Tests whether no transaction is pending.
+    fn no_transaction_or_err(&self) -> ContainerResult<(), B> {
+        if self.txn.is_some() || self.header.journal().is_some() {
+            Err(JournalError::Pending.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Creates a snapshot of the container.
+    ///
+    /// The snapshot `name` preserves the current state of all blocks of the
+    /// container. Blocks of a snapshot are copied on write: When such a block
+    /// is [written](Container::write) afterwards, the data are written into a
+    /// new block, which replaces the block in the live container. The block
+    /// of the snapshot stays unchanged. A [released](Container::release)
+    /// block is kept as well. Thus, a snapshot needs additional blocks only
+    /// for blocks modified after its creation.
+    ///
+    /// The snapshot table, which assigns the blocks to the live container
+    /// and to the snapshots, is stored encrypted in blocks of the container
+    /// and referenced by the header. Blocks buffered by the
+    /// [write-back cache](OpenOptionsBuilder::with_write_back) are flushed
+    /// before the snapshot is created.
+    ///
+    /// A snapshot is opened read-only with
+    /// [`OpenOptionsBuilder::with_snapshot`].
+    ///
+    /// # Errors
+    ///
+    /// Snapshots are available starting with revision 3, for older revisions
+    /// an [`HeaderError::InvalidRevision`] error is returned. If a snapshot
+    /// with the same name already exists, a [`SnapshotError::Exists`] error
+    /// is returned. While a transaction is pending, a
+    /// [`JournalError::Pending`] error is returned. Further errors are listed
+    /// in the [`Error`] type.
+    pub fn create_snapshot(&mut self, name: &str) -> ContainerResult<(), B> {
+        self.header.latest_revision_or_err()?;
+        self.writable_or_err()?;
+        self.no_transaction_or_err()?;
+        self.flush()?;
+
+        let ids = self.backend_ids()?;
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        self.snapshots.create(name, created, ids)?;
+        self.save_snapshots()?;
+
+        debug!("snapshot {} created", name);
+
+        Ok(())
+    }
+
+    /// Returns information about all snapshots of the container in the order
+    /// of their creation.
+    pub fn snapshots(&self) -> Vec<SnapshotInfo> {
+        self.snapshots.list()
+    }
+
+    /// Deletes a snapshot.
+    ///
+    /// Blocks, which are referenced by no other snapshot, are released.
+    /// Copies of blocks, which were created because of the snapshot, are
+    /// moved back into their original blocks.
+    ///
+    /// # Errors
+    ///
+    /// If no snapshot `name` exists, a [`SnapshotError::NotFound`] error is
+    /// returned. While a transaction is pending, a [`JournalError::Pending`]
+    /// error is returned. Further errors are listed in the [`Error`] type.
+    pub fn delete_snapshot(&mut self, name: &str) -> ContainerResult<(), B> {
+        self.writable_or_err()?;
+        self.no_transaction_or_err()?;
+
+        // The blocks are released after the snapshot table was updated, an
+        // interruption leaves unused blocks but never a table, which
+        // references released blocks.
+        let unused = self.snapshots.remove(name)?;
+        self.save_snapshots()?;
+
+        for id in unused {
+            map_err!(self.backend.release(id))?;
+        }
+
+        let moves = self.snapshots.unfrozen();
+
+        if !moves.is_empty() {
+            let mut buf = vec![0; self.block_size() as usize];
+
+            for (id, target) in moves.iter() {
+                let n = self.read_block(target, &mut buf)?;
+                self.store_block(id, &buf[..n])?;
+            }
+
+            let unused = moves
+                .iter()
+                .filter_map(|(id, _)| self.snapshots.unmap(id))
+                .collect::<Vec<_>>();
+
+            self.save_snapshots()?;
+
+            for id in unused {
+                map_err!(self.backend.release(id))?;
+            }
+        }
+
+        debug!("snapshot {} deleted, {} blocks moved", name, moves.len());
+
+        Ok(())
+    }
+
+    /// Loads the snapshot table referenced by the header.
+    fn load_snapshots(&mut self) -> ContainerResult<(), B> {
+        let first = match self.header.snapshots() {
+            Some(id) => <B::Id as Binary>::from_bytes(id).ok_or(SnapshotError::InvalidId)?,
+            None => return Ok(()),
+        };
+
+        let mut buf = vec![0; self.block_size() as usize];
+        let mut ids = vec![];
+        let mut table = vec![];
+        let mut next = Some(first);
+
+        while let Some(id) = next {
+            self.read_block(&id, &mut buf)?;
+
+            let (chunk, id_next) = snapshot::decode_index::<B>(&buf)?;
+
+            table.extend_from_slice(&chunk);
+            ids.push(id);
+            next = id_next;
+        }
+
+        self.snapshots = Snapshots::decode(&table)?;
+        self.snapshots.set_index(ids);
+
+        debug!("snapshots loaded: {:?}", self.snapshots);
+
+        Ok(())
+    }
+
+    /// Writes the snapshot table into new blocks, which are referenced by
+    /// the header. The blocks of the previous table are released afterwards.
+    fn save_snapshots(&mut self) -> ContainerResult<(), B> {
+        let ids = if self.snapshots.is_empty() {
+            vec![]
+        } else {
+            let bsize = self.block_size();
+            let table = self.snapshots.encode()?;
+            let ids = self.aquire_blocks(snapshot::index_blocks::<B>(&table, bsize)?)?;
+            let blocks = snapshot::encode_index::<B>(&table, &ids, bsize)?;
+
+            let batch = ids
+                .iter()
+                .zip(blocks.iter())
+                .map(|(id, buf)| (id, buf.as_slice()))
+                .collect::<Vec<_>>();
+
+            self.store_blocks(&batch)?;
+
+            ids
+        };
+
+        let first = ids.first().map(|id| id.as_bytes());
+        self.update_header(|header| header.set_snapshots(first.as_deref()))?;
+
+        for id in self.snapshots.set_index(ids) {
+            map_err!(self.backend.release(id))?;
+        }
+
+        Ok(())
+    }
+
+    /// Tests whether the container is not a read-only snapshot.
+    fn writable_or_err(&self) -> ContainerResult<(), B> {
+        match self.view {
+            Some(_) => Err(SnapshotError::ReadOnly.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads and decrypts the block `id` of the backend, bypassing the block
+    /// cache and the snapshot table.
+    fn read_block(&mut self, id: &B::Id, buf: &mut [u8]) -> ContainerResult<usize, B> {
+        let ctext = self.ctx.inp_mut(self.backend.block_size() as usize);
+        let n = map_err!(self.backend.read(id, ctext))?;
+        self.ctx.truncate_inp(n);
+
+        self.decrypt_block(id, buf)
+    }
+
+    /// Puts the plaintext `data` of block `id` into the cache. A dirty block
+    /// evicted from the cache is written into the backend.
+    fn cache_block(
+        &mut self,
+        key: Vec<u8>,
+        id: &B::Id,
+        data: SecureVec,
+        dirty: bool,
+    ) -> ContainerResult<(), B> {
+        if let Some((id, data)) = self.cache.insert(key, id.clone(), data, dirty) {
+            self.write_block(&id, &data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encrypts `buf` and writes it into the block `id` of the backend,
+    /// bypassing the block cache.
+    ///
+    /// See [`Container::write_blocks`] for blocks, which belong to a
+    /// snapshot.
+    fn write_block(&mut self, id: &B::Id, buf: &[u8]) -> ContainerResult<usize, B> {
+        let target = self.snapshots.target(id);
+
+        if self.snapshots.is_frozen(&target) {
+            self.write_blocks(&[(id, buf)]).map(|lens| lens[0])
+        } else {
+            self.store_block(&target, buf)
+        }
+    }
+
+    /// Encrypts `buf` and writes it into the block `id` of the backend.
+    /// Unlike [`Container::write_block`] the block is written in place, even
+    /// if it belongs to a snapshot.
+    fn store_block(&mut self, id: &B::Id, buf: &[u8]) -> ContainerResult<usize, B> {
+        let gross = self.backend.block_size();
+        let variable = self.backend.variable_block_size();
+        let (len, ctext) = encrypt_block(&self.header, &mut self.ctx, gross, variable, id, buf)?;
+
+        map_err!(self.backend.write(id, ctext)).map(|_| len)
+    }
+
+    /// Encrypts the `blocks` and writes them with a single
+    /// [`Backend::write_many`] call into the backend, bypassing the block
+    /// cache.
+    ///
+    /// A block, which belongs to a [snapshot](Container::create_snapshot),
+    /// is not overwritten. Its data are written into a new block, which
+    /// replaces the block from now on.
+    fn write_blocks(&mut self, blocks: &[(&B::Id, &[u8])]) -> ContainerResult<Vec<usize>, B> {
+        let mut targets = blocks
+            .iter()
+            .map(|(id, _)| self.snapshots.target(id))
+            .collect::<Vec<_>>();
+        let copies = targets
+            .iter()
+            .enumerate()
+            .filter(|(_, target)| self.snapshots.is_frozen(target))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        if copies.is_empty() {
+            let batch = targets
+                .iter()
+                .zip(blocks.iter())
+                .map(|(target, (_, buf))| (target, *buf))
+                .collect::<Vec<_>>();
+
+            return self.store_blocks(&batch);
+        }
+
+        for (idx, id) in copies.iter().zip(self.aquire_blocks(copies.len())?) {
+            targets[*idx] = id;
+        }
+
+        let batch = targets
+            .iter()
+            .zip(blocks.iter())
+            .map(|(target, (_, buf))| (target, *buf))
+            .collect::<Vec<_>>();
+        let lens = self.store_blocks(&batch)?;
+
+        // The copies are referenced, after their data are written.
+        let unused = copies
+            .into_iter()
+            .filter_map(|idx| self.snapshots.remap(blocks[idx].0, targets[idx].clone()))
+            .collect::<Vec<_>>();
+
+        self.save_snapshots()?;
+
+        for id in unused {
+            map_err!(self.backend.release(id))?;
+        }
+
+        Ok(lens)
+    }
+
+    /// Encrypts the `blocks` and writes them with a single
+    /// [`Backend::write_many`] call into the backend. Unlike
+    /// [`Container::write_blocks`] the blocks are written in place, even if
+    /// they belong to a snapshot.
+    fn store_blocks(&mut self, blocks: &[(&B::Id, &[u8])]) -> ContainerResult<Vec<usize>, B> {
+        let gross = self.backend.block_size();
+        let variable = self.backend.variable_block_size();
+        let mut lens = Vec::with_capacity(blocks.len());
+        let mut ctexts = Vec::with_capacity(blocks.len());
+
+        for (id, buf) in blocks {
+            let (len, ctext) =
+                encrypt_block(&self.header, &mut self.ctx, gross, variable, id, buf)?;
+
+            lens.push(len);
+            ctexts.push(ctext.to_vec());
+        }
+
+        let batch = blocks
+            .iter()
+            .zip(ctexts.iter())
+            .map(|((id, _), ctext)| (*id, ctext.as_slice()))
+            .collect::<Vec<_>>();
+
+        map_err!(self.backend.write_many(&batch))?;
+
+        Ok(lens)
+    }
+
+    /// Rotates the master-key of the container.
+    ///
+    /// A new master-key is generated and every block of the container is
+    /// re-encrypted with it. Finally the new key replaces the old master-key
+    /// in the header.
+    ///
+    /// The header key, which is wrapped by the key slots, is replaced as
+    /// well. Like on a [conversion](Container::convert) the password slot,
+    /// which unlocked the container, is wrapped again with the current
+    /// password and recipient slots are created again. The old
+    /// [recovery key](Container::recovery_key) becomes invalid, fetch the new
+    /// one after the rotation.
+    ///
+    /// The progress of the rotation is recorded in the header. If the
+    /// rotation is interrupted, the container stays usable. Calling this
+    /// method again resumes the rotation at the point, where it was
+    /// interrupted.
+    ///
+    /// Nothing happens for an unencrypted container of revision 3. A pending
+    /// [conversion](Container::convert) into another cipher is resumed.
+    ///
+    /// A container of revision 0 to 2 is upgraded into revision 3, see
+    /// [`Container::convert`].
+    ///
+    /// # Errors
+    ///
+    /// Another password slot results into [`HeaderError::InactiveKeySlot`],
+    /// because its password is not known; remove it before. A backend, which
+    /// cannot [list its blocks](Backend::ids), results into an
+    /// [`Error::IdsUnsupported`] error. Further errors are listed in the
+    /// [`Error`] type.
+    pub fn rekey(&mut self) -> ContainerResult<(), B> {
+        let cipher = match self.header.rekey_cipher() {
+            Some(cipher) => cipher,
+            None => self.header.cipher(),
+        };
+
+        self.convert(ConvertOptionsBuilder::new(cipher).build())
+    }
+
+    /// Converts the container into another cipher.
+    ///
+    /// Use [`ConvertOptionsBuilder`] to create a [`ConvertOptions`] instance,
+    /// which selects the new cipher. Like [`Container::rekey`] a new
+    /// master-key is generated and every block is re-encrypted, but now with
+    /// the new cipher. An interrupted conversion is resumed by calling the
+    /// method again with the same cipher.
+    ///
+    /// The net [block size](Container::block_size) depends on the cipher.
+    /// While the conversion is pending, the smaller block size of both
+    /// ciphers is reported. A service (like an archive) lays out its data
+    /// according to the block size, thus the block size of a container, which
+    /// hosts a service, cannot change. Migrate the data of the service into a
+    /// new container instead. If the block size of a plain container shrinks,
+    /// the data of every block must fit into the new block size.
+    ///
+    /// The key slots are converted as well. The password slot, which
+    /// unlocked the container, is wrapped again with the current password,
+    /// recipient slots are created again. Other password slots cannot be
+    /// converted, because their passwords are not known; remove them before.
+    /// When converting into [`Cipher::None`] all key slots are removed. An
+    /// unencrypted container gets a new password slot, which uses the
+    /// [`Kdf`] and password callback of the options. Without a callback the
+    /// callback passed to [`Container::open`] is used.
+    ///
+    /// A container of revision 0 to 2 is upgraded into revision 3. Its
+    /// header is converted into revision 3 with a password slot for the
+    /// current password, the blocks are re-encrypted into the layout of
+    /// revision 3, where each block stores its own nonce. Like any
+    /// conversion the upgrade is resumed, if it was interrupted. The nonce
+    /// reduces the block size of an encrypted container, thus a container,
+    /// which hosts a service, cannot be upgraded in place; copy the data of
+    /// the service into a new container instead.
+    ///
+    /// # Errors
+    ///
+    /// If the block size of a service changes, an [`Error::ServiceBlockSize`]
+    /// error is returned, if the data of a block does not fit into the new
+    /// block size an [`Error::BlockTooLarge`] error. Another password slot
+    /// results into [`HeaderError::InactiveKeySlot`], a pending conversion
+    /// into another cipher into [`HeaderError::PendingConversion`]. Further
+    /// errors are listed in the [`Error`] type.
+    pub fn convert(&mut self, options: ConvertOptions) -> ContainerResult<(), B> {
+        self.writable_or_err()?;
+        self.no_transaction_or_err()?;
+
+        // Blocks are read from and written into the backend directly, the
+        // block size can change as well.
+        self.flush()?;
+
+        if self.header.rekey_cipher().is_none() {
+            self.block_size_convertible_or_err(options.cipher)?;
+        }
+
+        self.cache.clear();
+
+        // List the blocks before the rotation is recorded in the header, a
+        // backend, which cannot list its blocks, leaves the header untouched.
+        let all_ids = self.backend_ids()?;
+
+        self.update_header(|header| header.begin_rekey(options.cipher))?;
+
+        let mut cursor = match self.header.rekey_cursor() {
+            Some(cursor) => cursor.to_vec(),
+            None => return Ok(()),
+        };
+
+        // Blocks are re-encrypted in the order of their binary ids, the
+        // blocks up to the cursor are already done.
+        let mut ids = all_ids
+            .into_iter()
+            .map(|id| (id.as_bytes(), id))
+            .filter(|(bytes, _)| *bytes > cursor)
+            .collect::<Vec<_>>();
+        ids.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        debug!("rekey {} blocks, cursor: {:?}", ids.len(), cursor);
+
+        let mut buf = vec![0; self.block_size() as usize];
+
+        for (bytes, id) in ids {
+            let ctext = self.ctx.inp_mut(self.backend.block_size() as usize);
+            let n = map_err!(self.backend.read(&id, ctext))?;
+            self.ctx.truncate_inp(n);
+
+            // Skip the inflight block, if it was already re-encrypted before
+            // the rotation was interrupted.
+            if !self.header.rekey_done(&bytes, self.ctx.inp()) {
+                let digest = sha256(self.ctx.inp());
+
+                self.decrypt_block(&id, &mut buf)?;
+                self.update_header(|header| {
+                    Ok(header.set_rekey_progress(&cursor, &bytes, &digest))
+                })?;
+                self.store_block(&id, &buf)?;
+            }
+
+            cursor = bytes;
+        }
+
+        if self.header.cipher() == Cipher::None && options.callback.is_some() {
+            self.store = PasswordStore::new(options.callback.clone());
+        }
+
+        self.update_header(|header| header.finish_rekey(options.kdf.build()?))
+    }
+
+    /// Tests whether the data of the container fit into the net block size
+    /// of `cipher`.
+    fn block_size_convertible_or_err(&mut self, cipher: Cipher) -> ContainerResult<(), B> {
+        let old_size = self.block_size();
+        let new_size = self
+            .net_block_size(cipher, false)
+            .saturating_sub(self.header.compression().overhead());
+
+        if old_size == new_size {
+            return Ok(());
+        }
+
+        if self.header.sid().is_some() {
+            return Err(Error::ServiceBlockSize(old_size, new_size));
+        }
+
+        if new_size < old_size {
+            let mut buf = vec![0; old_size as usize];
+
+            for id in self.backend_ids()? {
+                self.read_block(&id, &mut buf)?;
+
+                if buf[new_size as usize..].iter().any(|n| *n != 0) {
+                    return Err(Error::BlockTooLarge(id.to_string(), new_size));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the integrity of the container.
+    ///
+    /// The header is read again from the backend and decrypted. Next, every
+    /// [aquired block](Backend::ids) is read from the backend and decrypted.
+    /// Problems are collected in the returned [`VerifyReport`], the
+    /// verification does not stop at the first damaged block.
+    ///
+    /// The authenticity of a block can only be verified by an AE-cipher.
+    /// Other ciphers detect read errors only. The block cache is bypassed,
+    /// blocks buffered by the
+    /// [write-back cache](OpenOptionsBuilder::with_write_back) are flushed
+    /// before.
+    ///
+    /// # Errors
+    ///
+    /// Errors, which prevent the verification (like a failure to flush the
+    /// cache or to list the blocks), are listed in the [`Error`] type. A
+    /// backend, which cannot [list its blocks](Backend::ids), results into an
+    /// [`Error::IdsUnsupported`] error.
+    pub fn verify(&mut self) -> ContainerResult<VerifyReport<B>, B> {
+        self.flush()?;
+
+        let header = Self::read_header_bytes(&mut self.backend)
+            .and_then(|bytes| {
+                Header::<B>::read(&bytes, Migrator::default(), &mut self.store)
+                    .map_err(Error::Header)
+            })
+            .err();
+
+        let ids = self.backend_ids()?;
+        let bsize = self.block_size() as usize;
+        let mut buf = SecureVec::zeroed(bsize);
+        let mut damaged = vec![];
+
+        for id in ids.iter() {
+            let ctext = self.ctx.inp_mut(self.backend.block_size() as usize);
+
+            let result = map_err!(self.backend.read(id, ctext)).and_then(|n| {
+                self.ctx.truncate_inp(n);
+                decrypt_block(&self.header, &mut self.ctx, bsize, id, &mut buf)
+            });
+
+            if let Err(err) = result {
+                debug!("block {} is damaged: {}", id, err);
+                damaged.push((id.clone(), err));
+            }
+        }
+
+        Ok(VerifyReport {
+            header,
+            blocks: ids.len(),
+            damaged,
+        })
+    }
+
+    /// Returns the ids of all blocks of the backend.
+    fn backend_ids(&mut self) -> ContainerResult<Vec<B::Id>, B> {
+        map_err!(self.backend.ids())?.ok_or(Error::IdsUnsupported)
+    }
+
+    /// Decrypts a block of the container to verify the master-key of the
+    /// header.
+    fn verify_header(&mut self) -> ContainerResult<(), B> {
+        let id = match self.header.top_id() {
+            Some(id) => Some(id.clone()),
+            None => map_err!(self.backend.ids())?.and_then(|ids| ids.into_iter().next()),
+        };
+
+        if let Some(id) = id {
+            let mut buf = vec![0; self.block_size() as usize];
+
+            match self.read(&id, &mut buf) {
+                Ok(_) => {}
+                Err(Error::Cipher(CipherError::NotTrustworthy)) => {
+                    return Err(Error::HeaderMismatch)
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the root header followed by its extended header blocks.
+    fn read_header_bytes<H: ReceiveHeader<B>>(reader: &mut H) -> ContainerResult<Vec<u8>, B> {
+        let mut buf = [0; HEADER_MAX_SIZE];
+
+        map_err!(reader.get_header_bytes(&mut buf))?;
+
+        let extended = ExtendedBlocks::peek(&buf)?;
+        let mut header_bytes = buf.to_vec();
+
+        for idx in extended.indices() {
+            if !map_err!(reader.get_extended_header_bytes(idx, &mut buf))? {
+                return Err(HeaderError::ExtendedHeaderUnsupported.into());
+            }
+
+            header_bytes.extend_from_slice(&buf);
+        }
+
+        debug!("got {} header bytes", header_bytes.len());
+
+        Ok(header_bytes)
+    }
+
+    /// Writes the header `blocks` into the backend.
+    ///
+    /// The extended header blocks are written first into the slots of the
+    /// generation of the new root header, which is written afterwards. The
+    /// generation must be the successor of the current generation, thus the
+    /// blocks of the current root header are left untouched until the new
+    /// root header is written. Afterwards, the extended header blocks of the
+    /// replaced generation are wiped because they can contain outdated key
+    /// slots.
+    fn write_header_blocks(&mut self, blocks: &[[u8; HEADER_MAX_SIZE]]) -> ContainerResult<(), B> {
+        let extended = ExtendedBlocks::peek(&blocks[0])?;
+
+        for (idx, block) in extended.indices().zip(&blocks[1..]) {
+            if !map_err!(self.backend.write_extended_header(idx, block))? {
+                return Err(HeaderError::ExtendedHeaderUnsupported.into());
+            }
+        }
+
+        map_err!(self.backend.write_header(&blocks[0]))?;
+
+        let replaced = mem::replace(&mut self.extended, extended);
+
+        for idx in replaced.indices() {
+            map_err!(self
+                .backend
+                .write_extended_header(idx, &[0; HEADER_MAX_SIZE]))?;
+        }
+
+        Ok(())
+    }
+
+    fn update_header<F: FnOnce(&mut Header<B>) -> Result<bool, HeaderError>>(
+        &mut self,
+        f: F,
+    ) -> ContainerResult<(), B> {
+        debug!("header before update: {:?}", self.header);
+
+        let changed = f(&mut self.header)?;
+
+        debug!(
+            "header after update: {:?}, changed: {}",
+            self.header, changed
+        );
+
+        if changed {
+            self.header.set_generation(self.extended.next_generation());
+
+            let blocks = split_header(&self.header.write(&mut self.store)?);
+            self.write_header_blocks(&blocks)?;
         }
 
         Ok(())
@@ -700,3 +2267,125 @@ impl<B: Backend> Container<B> {
         self.backend.delete()
     }
 }
+
+fn open_store(options: &OpenOptions) -> PasswordStore {
+    PasswordStore::new(options.callback.clone())
+        .with_keyfile(options.keyfile.clone())
+        .with_identity(options.identity.clone())
+        .with_recovery_key(options.recovery_key.clone())
+}
+
+/// Splits the serialized header into the root header and its extended
+/// header blocks. The last block is padded with zeros.
+fn split_header(header: &[u8]) -> Vec<[u8; HEADER_MAX_SIZE]> {
+    header
+        .chunks(HEADER_MAX_SIZE)
+        .map(|chunk| {
+            let mut block = [0; HEADER_MAX_SIZE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            block
+        })
+        .collect()
+}
+
+/// Evaluates the (net) block size of a container with the given `header`.
+/// `gross` is the block size of the backend.
+fn block_size<B: BackendTypes>(header: &Header<B>, gross: u32) -> u32 {
+    let size = net_block_size(gross, header.cipher(), header.iv().is_some());
+    let size = match header.rekey_cipher() {
+        Some(cipher) => cmp::min(size, net_block_size(gross, cipher, false)),
+        None => size,
+    };
+
+    size.saturating_sub(header.compression().overhead())
+}
+
+/// Returns the net block size of `cipher`. `iv` tells whether the blocks
+/// use the container-wide [iv](Header::iv), otherwise each block stores its
+/// own nonce.
+fn net_block_size(gross: u32, cipher: Cipher, iv: bool) -> u32 {
+    let overhead = if iv {
+        cipher.tag_size()
+    } else {
+        cipher.iv_len() as u32 + cipher.tag_size()
+    };
+
+    gross.saturating_sub(overhead)
+}
+
+/// Encrypts `buf` for the block `id`. `gross` is the block size of the
+/// backend, `variable` tells whether the backend has a variable block size.
+///
+/// Returns the number of plaintext bytes taken from `buf` together with the
+/// ciphertext. A compressed block is padded to the net block size unless
+/// the backend has a variable block size.
+fn encrypt_block<'a, B: BackendTypes>(
+    header: &Header<B>,
+    ctx: &'a mut CipherContext,
+    gross: u32,
+    variable: bool,
+    id: &B::Id,
+    buf: &[u8],
+) -> ContainerResult<(usize, &'a [u8]), B> {
+    let id_bytes = id.as_bytes();
+    let (cipher, key, iv) = header.block_key(&id_bytes, None);
+
+    // While a conversion is pending, the net block size of the cipher can
+    // be larger than the block size of the container. The remaining bytes
+    // are padded.
+    let len = cmp::min(buf.len(), block_size(header, gross) as usize);
+    let net_size = net_block_size(gross, cipher, iv.is_some()) as usize;
+
+    ctx.set_cipher(cipher);
+
+    match header.compression() {
+        Compression::None => ctx.copy_from_slice(net_size, &buf[..len]),
+        compression => {
+            let frame = compression.compress(&buf[..len]);
+            let size = if variable { frame.len() } else { net_size };
+
+            ctx.copy_from_slice(size, &frame)
+        }
+    };
+
+    let ctext = match iv {
+        Some(iv) => ctx.encrypt(key, iv)?,
+        None => ctx.encrypt_with_nonce(&id_bytes, key)?,
+    };
+
+    Ok((len, ctext))
+}
+
+/// Decrypts the raw block data, which was read into the cipher context.
+///
+/// Up to `bsize` bytes are copied into `buf`.
+fn decrypt_block<B: BackendTypes>(
+    header: &Header<B>,
+    ctx: &mut CipherContext,
+    bsize: usize,
+    id: &B::Id,
+    buf: &mut [u8],
+) -> ContainerResult<usize, B> {
+    let id_bytes = id.as_bytes();
+    let (cipher, key, iv) = header.block_key(&id_bytes, Some(ctx.inp()));
+
+    ctx.set_cipher(cipher);
+
+    let ptext = match iv {
+        Some(iv) => ctx.decrypt(key, iv)?,
+        None => ctx.decrypt_with_nonce(&id_bytes, key)?,
+    };
+
+    if header.compression() == Compression::None {
+        let n = cmp::min(cmp::min(ptext.len(), bsize), buf.len());
+        buf[..n].copy_from_slice(&ptext[..n]);
+
+        return Ok(n);
+    }
+
+    let data = Compression::decompress(ptext, bsize)?;
+    let n = cmp::min(bsize, buf.len());
+    buf[..n].copy_from_slice(&data[..n]);
+
+    Ok(n)
+}
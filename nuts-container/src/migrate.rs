@@ -35,7 +35,11 @@ pub enum MigrationError {
     Rev0(String),
 }
 
-pub trait Migration {
+/// Migration of outdated header revisions.
+///
+/// The migration is stored in the header of the container, thus it must be
+/// [`Send`] and [`Sync`] like the container itself.
+pub trait Migration: Send + Sync {
     /// Migration of a revision 0 header.
     ///
     /// You you have a revision 0 header, then this function is called. You
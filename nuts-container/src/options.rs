@@ -20,28 +20,34 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use nuts_backend::Backend;
-use std::rc::Rc;
+use nuts_backend::BackendTypes;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use crate::cipher::Cipher;
-use crate::digest::Digest;
+use crate::compression::Compression;
 use crate::error::ContainerResult;
-use crate::kdf::{Kdf, KdfError};
+use crate::kdf::{
+    Kdf, KdfError, ARGON2ID_DEFAULT_ITERATIONS, ARGON2ID_DEFAULT_MEMORY,
+    ARGON2ID_DEFAULT_PARALLELISM, ARGON2ID_DEFAULT_SALT_LEN,
+};
 use crate::password::CallbackFn;
+use crate::recipient::{Identity, Recipient};
+use crate::recovery::RecoveryKey;
 #[cfg(doc)]
 use crate::{error::Error, Container};
 
 #[derive(Debug)]
 pub(crate) enum KdfBuilder {
-    Pbkdf2(Digest, u32, u32),
+    Argon2id(u32, u32, u32, u32),
     Kdf(Kdf),
 }
 
 impl KdfBuilder {
     pub(crate) fn build(&self) -> Result<Kdf, KdfError> {
         match self {
-            KdfBuilder::Pbkdf2(digest, iterations, salt_len) => {
-                Kdf::generate_pbkdf2(*digest, *iterations, *salt_len)
+            KdfBuilder::Argon2id(memory, iterations, parallelism, salt_len) => {
+                Kdf::generate_argon2id(*memory, *iterations, *parallelism, *salt_len)
             }
             KdfBuilder::Kdf(ref kdf) => Ok(kdf.clone()),
         }
@@ -53,8 +59,11 @@ impl KdfBuilder {
 /// Use the [`CreateOptionsBuilder`] utility to create a `CreateOptions`
 /// instance.
 pub struct CreateOptions {
-    pub(crate) callback: Option<Rc<CallbackFn>>,
+    pub(crate) callback: Option<Arc<CallbackFn>>,
+    pub(crate) keyfile: Option<PathBuf>,
+    pub(crate) recipients: Vec<Recipient>,
     pub(crate) cipher: Cipher,
+    pub(crate) compression: Compression,
     pub(crate) kdf: KdfBuilder,
     pub(crate) overwrite: bool,
 }
@@ -70,12 +79,20 @@ impl CreateOptionsBuilder {
         let kdf = if cipher == Cipher::None {
             KdfBuilder::Kdf(Kdf::None)
         } else {
-            KdfBuilder::Pbkdf2(Digest::Sha256, 65536, 16)
+            KdfBuilder::Argon2id(
+                ARGON2ID_DEFAULT_MEMORY,
+                ARGON2ID_DEFAULT_ITERATIONS,
+                ARGON2ID_DEFAULT_PARALLELISM,
+                ARGON2ID_DEFAULT_SALT_LEN,
+            )
         };
 
         CreateOptionsBuilder(CreateOptions {
             callback: None,
+            keyfile: None,
+            recipients: vec![],
             cipher,
+            compression: Compression::None,
             kdf,
             overwrite: false,
         })
@@ -96,11 +113,45 @@ impl CreateOptionsBuilder {
     /// returned.
     ///
     /// [`Error::NoPassword`]: enum.Error.html#variant.NoPassword
-    pub fn with_password_callback<Cb: Fn() -> Result<Vec<u8>, String> + 'static>(
+    pub fn with_password_callback<Cb: Fn() -> Result<Vec<u8>, String> + Send + Sync + 'static>(
         mut self,
         callback: Cb,
     ) -> Self {
-        self.0.callback = Some(Rc::new(callback));
+        self.0.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Assigns a keyfile to the container.
+    ///
+    /// The contents of the file at `path` are fed into the key derivation
+    /// function. If a [password callback](Self::with_password_callback) is
+    /// assigned as well, the container is protected by both, the keyfile and
+    /// the password. Otherwise the keyfile alone is enough to open the
+    /// container.
+    ///
+    /// The keyfile is read, when the key is actually derived. If the file
+    /// cannot be read, the creation fails.
+    pub fn with_keyfile<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.0.keyfile = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Wraps the header key to the given `recipient`.
+    ///
+    /// The container can then be opened with the [`Identity`] of the
+    /// recipient (see [`OpenOptionsBuilder::with_identity`]). Call the method
+    /// several times to add more than one recipient. If neither a
+    /// [password callback](Self::with_password_callback) nor a
+    /// [keyfile](Self::with_keyfile) is assigned, the container can only be
+    /// opened by the recipients.
+    ///
+    /// If the cipher is set to [`Cipher::None`], then the setting is
+    /// discarded.
+    pub fn with_recipient(mut self, recipient: Recipient) -> Self {
+        if self.0.cipher != Cipher::None {
+            self.0.recipients.push(recipient);
+        }
+
         self
     }
 
@@ -116,6 +167,22 @@ impl CreateOptionsBuilder {
         self
     }
 
+    /// Compresses the blocks of the container with the given `compression`
+    /// algorithm.
+    ///
+    /// The data of a block are compressed before they are encrypted. Blocks,
+    /// which do not compress, are stored as-is. Defaults to
+    /// [`Compression::None`].
+    ///
+    /// **Note:** On a backend with a [variable block
+    /// size](nuts_backend::Backend::variable_block_size) the size of a stored
+    /// block leaks the compressed size of its plaintext. See the security
+    /// notes of [`Compression`].
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.0.compression = compression;
+        self
+    }
+
     /// Assigns a new overwrite flag to the options.
     ///
     /// If set to `true` an already existing backend is overwritten. If
@@ -134,7 +201,7 @@ impl CreateOptionsBuilder {
     /// # Errors
     ///
     /// If validation has failed an [`Error`] is returned.
-    pub fn build<B: Backend>(self) -> ContainerResult<CreateOptions, B> {
+    pub fn build<B: BackendTypes>(self) -> ContainerResult<CreateOptions, B> {
         Ok(self.0)
     }
 }
@@ -143,7 +210,13 @@ impl CreateOptionsBuilder {
 ///
 /// Use the [`OpenOptionsBuilder`] utility to create a `OpenOptions` instance.
 pub struct OpenOptions {
-    pub(crate) callback: Option<Rc<CallbackFn>>,
+    pub(crate) callback: Option<Arc<CallbackFn>>,
+    pub(crate) keyfile: Option<PathBuf>,
+    pub(crate) identity: Option<Identity>,
+    pub(crate) recovery_key: Option<RecoveryKey>,
+    pub(crate) cache_size: usize,
+    pub(crate) write_back: bool,
+    pub(crate) snapshot: Option<String>,
 }
 
 /// Utility used to create a [`OpenOptions`] instance.
@@ -152,7 +225,15 @@ pub struct OpenOptionsBuilder(OpenOptions);
 impl OpenOptionsBuilder {
     /// Creates a builder instance.
     pub fn new() -> Self {
-        OpenOptionsBuilder(OpenOptions { callback: None })
+        OpenOptionsBuilder(OpenOptions {
+            callback: None,
+            keyfile: None,
+            identity: None,
+            recovery_key: None,
+            cache_size: 0,
+            write_back: false,
+            snapshot: None,
+        })
     }
 
     /// Assigns a password callback to the container.
@@ -170,11 +251,91 @@ impl OpenOptionsBuilder {
     /// returned.
     ///
     /// [`Error::NoPassword`]: enum.Error.html#variant.NoPassword
-    pub fn with_password_callback<Cb: Fn() -> Result<Vec<u8>, String> + 'static>(
+    pub fn with_password_callback<Cb: Fn() -> Result<Vec<u8>, String> + Send + Sync + 'static>(
         mut self,
         callback: Cb,
     ) -> Self {
-        self.0.callback = Some(Rc::new(callback));
+        self.0.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Assigns a keyfile to the container.
+    ///
+    /// The contents of the file at `path` are fed into the key derivation
+    /// function. If a [password callback](Self::with_password_callback) is
+    /// assigned as well, the container is protected by both, the keyfile and
+    /// the password. Otherwise the keyfile alone is enough to open the
+    /// container.
+    ///
+    /// The keyfile is read, when the key is actually derived. If the file
+    /// cannot be read, the operation fails.
+    pub fn with_keyfile<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.0.keyfile = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Assigns an identity to the container.
+    ///
+    /// The identity opens the container, if the header key is wrapped to
+    /// its [recipient](Identity::recipient). If no
+    /// [password callback](Self::with_password_callback) and no
+    /// [keyfile](Self::with_keyfile) is assigned, only the key slots of the
+    /// identity are tried.
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.0.identity = Some(identity);
+        self
+    }
+
+    /// Assigns a recovery key to the container.
+    ///
+    /// The [`RecoveryKey`] unlocks the container directly, no key slot and no
+    /// [key derivation function][Kdf] is involved. The password callback,
+    /// keyfile and identity are not used in this case. Use it, if the
+    /// password of the container is lost; then add a new key slot and remove
+    /// the old one.
+    pub fn with_recovery_key(mut self, recovery_key: RecoveryKey) -> Self {
+        self.0.recovery_key = Some(recovery_key);
+        self
+    }
+
+    /// Enables a cache of decrypted blocks.
+    ///
+    /// Up to `size` blocks are kept in memory, the least recently used block
+    /// is evicted first. Reading a cached block needs neither a round trip to
+    /// the backend nor a decryption. The plaintext of an evicted block is
+    /// wiped from memory.
+    ///
+    /// A `size` of `0` disables the cache, which is the default.
+    pub fn with_cache(mut self, size: usize) -> Self {
+        self.0.cache_size = size;
+        self
+    }
+
+    /// Enables write-back buffering of the block cache.
+    ///
+    /// A written block is kept in the [cache](Self::with_cache) only. It is
+    /// written into the backend, when it is evicted from the cache or on
+    /// [`Container::flush`](crate::Container::flush).
+    /// [`Container::into_backend`](crate::Container::into_backend) flushes on
+    /// a best-effort basis, use
+    /// [`Container::try_into_backend`](crate::Container::try_into_backend) to
+    /// handle errors. Blocks, which are not flushed, are lost when the
+    /// container is dropped, an error is logged then!
+    ///
+    /// Without a cache the option has no effect.
+    pub fn with_write_back(mut self, write_back: bool) -> Self {
+        self.0.write_back = write_back;
+        self
+    }
+
+    /// Opens a snapshot of the container.
+    ///
+    /// Instead of the live container the
+    /// [snapshot](crate::Container::create_snapshot) `name` is opened. The
+    /// snapshot is read-only, modifying it results into a
+    /// [`SnapshotError::ReadOnly`](crate::SnapshotError::ReadOnly) error.
+    pub fn with_snapshot(mut self, name: &str) -> Self {
+        self.0.snapshot = Some(name.to_string());
         self
     }
 
@@ -186,7 +347,7 @@ impl OpenOptionsBuilder {
     /// # Errors
     ///
     /// If validation has failed an [`Error`] is returned.
-    pub fn build<B: Backend>(self) -> ContainerResult<OpenOptions, B> {
+    pub fn build<B: BackendTypes>(self) -> ContainerResult<OpenOptions, B> {
         Ok(self.0)
     }
 }
@@ -203,7 +364,10 @@ impl Default for OpenOptionsBuilder {
 /// instance.
 pub struct ModifyOptions {
     pub(crate) kdf: Option<Kdf>,
-    pub(crate) password: Option<Rc<CallbackFn>>,
+    pub(crate) password: Option<Arc<CallbackFn>>,
+    pub(crate) add_slots: Vec<(KdfBuilder, Arc<CallbackFn>)>,
+    pub(crate) remove_slots: Vec<usize>,
+    pub(crate) add_recipients: Vec<Recipient>,
 }
 
 /// Utility used to create a [`ModifyOptions`] instance.
@@ -228,6 +392,11 @@ impl ModifyOptionsBuilder {
     ///   set to [`Kdf::None`].
     ///
     /// For both exceptions the `kdf` passed to the method is ignored.
+    ///
+    /// If the container was opened with an [`Identity`], there is no password
+    /// slot to change and [`HeaderError::NoPasswordSlot`] is raised.
+    ///
+    /// [`HeaderError::NoPasswordSlot`]: crate::HeaderError::NoPasswordSlot
     pub fn change_kdf(mut self, kdf: Kdf) -> Self {
         self.0.kdf = Some(kdf);
         self
@@ -236,17 +405,82 @@ impl ModifyOptionsBuilder {
     /// Change the password of the container.
     ///
     /// Use the given callback which returns the new password on success.
-    /// If encryption is disabled the callback is not invoked.
+    /// If encryption is disabled the callback is not invoked. If the
+    /// container was opened with an [`Identity`], there is no password slot
+    /// to change and [`HeaderError::NoPasswordSlot`] is raised. A keyfile,
+    /// which was passed to [`OpenOptionsBuilder::with_keyfile`], is kept: The
+    /// container still needs the keyfile and the new password.
     ///
     /// On success the callback returns the password (represented as an
     /// [`Vec<u8>`](`Vec`)) wrapped into an [`Ok`](`Result::Ok`). On any
     /// failure an [`Err`](`Result::Err`) with an error message must be
     /// returned.
-    pub fn change_password<Cb: Fn() -> Result<Vec<u8>, String> + 'static>(
+    ///
+    /// [`HeaderError::NoPasswordSlot`]: crate::HeaderError::NoPasswordSlot
+    pub fn change_password<Cb: Fn() -> Result<Vec<u8>, String> + Send + Sync + 'static>(
+        mut self,
+        callback: Cb,
+    ) -> Self {
+        self.0.password = Some(Arc::new(callback));
+        self
+    }
+
+    /// Adds a new key slot to the container.
+    ///
+    /// The container can then also be opened with the password returned by
+    /// the given callback. The new slot uses Argon2id as
+    /// [key derivation function][Kdf], use
+    /// [`ModifyOptionsBuilder::add_slot_with_kdf`] to choose another one. If
+    /// encryption is disabled the callback is not invoked.
+    ///
+    /// Key slots are available starting with
+    /// [revision 3](crate::LATEST_REVISION) of the container.
+    pub fn add_slot<Cb: Fn() -> Result<Vec<u8>, String> + Send + Sync + 'static>(
         mut self,
         callback: Cb,
     ) -> Self {
-        self.0.password = Some(Rc::new(callback));
+        let kdf = KdfBuilder::Argon2id(
+            ARGON2ID_DEFAULT_MEMORY,
+            ARGON2ID_DEFAULT_ITERATIONS,
+            ARGON2ID_DEFAULT_PARALLELISM,
+            ARGON2ID_DEFAULT_SALT_LEN,
+        );
+
+        self.0.add_slots.push((kdf, Arc::new(callback)));
+        self
+    }
+
+    /// Adds a new key slot, which uses the given
+    /// [key derivation function][Kdf].
+    ///
+    /// See [`ModifyOptionsBuilder::add_slot`] for details.
+    pub fn add_slot_with_kdf<Cb: Fn() -> Result<Vec<u8>, String> + Send + Sync + 'static>(
+        mut self,
+        kdf: Kdf,
+        callback: Cb,
+    ) -> Self {
+        self.0
+            .add_slots
+            .push((KdfBuilder::Kdf(kdf), Arc::new(callback)));
+        self
+    }
+
+    /// Adds a new key slot, which is wrapped to the given `recipient`.
+    ///
+    /// The container can then also be opened with the [`Identity`] of the
+    /// recipient. If encryption is disabled, no slot is added.
+    pub fn add_recipient(mut self, recipient: Recipient) -> Self {
+        self.0.add_recipients.push(recipient);
+        self
+    }
+
+    /// Removes the key slot with the given index.
+    ///
+    /// The index refers to [`Info::slots`](crate::Info::slots). Slots are
+    /// removed before new slots are added. The slot, which was used to open
+    /// the container, cannot be removed.
+    pub fn remove_slot(mut self, index: usize) -> Self {
+        self.0.remove_slots.push(index);
         self
     }
 
@@ -261,6 +495,82 @@ impl Default for ModifyOptionsBuilder {
         Self(ModifyOptions {
             kdf: None,
             password: None,
+            add_slots: vec![],
+            remove_slots: vec![],
+            add_recipients: vec![],
         })
     }
 }
+
+/// Options used to convert a container into another cipher.
+///
+/// Use the [`ConvertOptionsBuilder`] utility to create a `ConvertOptions`
+/// instance.
+pub struct ConvertOptions {
+    pub(crate) callback: Option<Arc<CallbackFn>>,
+    pub(crate) cipher: Cipher,
+    pub(crate) kdf: KdfBuilder,
+}
+
+/// Utility used to create a [`ConvertOptions`] instance.
+///
+/// The following example creates a [`ConvertOptions`] instance, which
+/// converts the container into [`Cipher::Aes256Gcm`]:
+///
+/// ```
+/// use nuts_container::{Cipher, ConvertOptionsBuilder};
+///
+/// let options = ConvertOptionsBuilder::new(Cipher::Aes256Gcm).build();
+/// ```
+pub struct ConvertOptionsBuilder(ConvertOptions);
+
+impl ConvertOptionsBuilder {
+    /// Creates a builder instance.
+    ///
+    /// The container is converted into the given `cipher`.
+    pub fn new(cipher: Cipher) -> Self {
+        ConvertOptionsBuilder(ConvertOptions {
+            callback: None,
+            cipher,
+            kdf: KdfBuilder::Argon2id(
+                ARGON2ID_DEFAULT_MEMORY,
+                ARGON2ID_DEFAULT_ITERATIONS,
+                ARGON2ID_DEFAULT_PARALLELISM,
+                ARGON2ID_DEFAULT_SALT_LEN,
+            ),
+        })
+    }
+
+    /// Assigns a password callback to the converted container.
+    ///
+    /// A password is only needed, if an unencrypted container is converted
+    /// into an encrypted one. An already encrypted container keeps its
+    /// password and an assigned callback is never called.
+    ///
+    /// On success the callback returns the password (represented as an
+    /// [`Vec<u8>`](`Vec`)) wrapped into an [`Ok`](`Result::Ok`). On any
+    /// failure an [`Err`](`Result::Err`) with an error message must be
+    /// returned.
+    pub fn with_password_callback<Cb: Fn() -> Result<Vec<u8>, String> + Send + Sync + 'static>(
+        mut self,
+        callback: Cb,
+    ) -> Self {
+        self.0.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Uses the given key derivation function.
+    ///
+    /// Like the [password callback](Self::with_password_callback) the `kdf`
+    /// is only used, if an unencrypted container is converted into an
+    /// encrypted one. By default Argon2id is used.
+    pub fn with_kdf(mut self, kdf: Kdf) -> Self {
+        self.0.kdf = KdfBuilder::Kdf(kdf);
+        self
+    }
+
+    /// Finally, creates the [`ConvertOptions`] instance.
+    pub fn build(self) -> ConvertOptions {
+        self.0
+    }
+}
@@ -21,6 +21,9 @@
 // IN THE SOFTWARE.
 
 use openssl::error::ErrorStack;
+use openssl::md::Md;
+use openssl::pkey::Id;
+use openssl::pkey_ctx::PkeyCtx;
 
 #[cfg(test)]
 pub use test::rand_bytes;
@@ -36,6 +39,56 @@ pub fn rand_u32() -> Result<u32, ErrorStack> {
     Ok(u32::from_be_bytes(bytes))
 }
 
+/// Returns the number of bytes of memory needed by scrypt.
+///
+/// The CPU/memory cost parameter `N` is calculated as `2^log_n`.
+pub fn scrypt_memory(log_n: u8, r: u32, p: u32) -> u64 {
+    // an invalid n (0) is rejected by OpenSSL
+    let n = 1u64.checked_shl(log_n as u32).unwrap_or(0);
+
+    128u64
+        .saturating_mul(r as u64)
+        .saturating_mul(n.saturating_add(p as u64).saturating_add(2))
+}
+
+/// Derives a key from `password` and `salt` using the scrypt algorithm.
+///
+/// The CPU/memory cost parameter `N` is calculated as `2^log_n`. The key is
+/// written into `key`.
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+    key: &mut [u8],
+) -> Result<(), ErrorStack> {
+    let n = 1u64.checked_shl(log_n as u32).unwrap_or(0);
+
+    // OpenSSL limits the memory to 32 MB by default, which is not enough for
+    // larger cost parameters. Calculate the memory actually needed.
+    let maxmem = scrypt_memory(log_n, r, p);
+
+    openssl::pkcs5::scrypt(password, salt, n, r as u64, p as u64, maxmem, key)
+}
+
+/// Derives a key from the input keying material `ikm` using HKDF with
+/// SHA-256.
+///
+/// The key is written into `key`.
+pub fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8], key: &mut [u8]) -> Result<(), ErrorStack> {
+    let mut ctx = PkeyCtx::new_id(Id::HKDF)?;
+
+    ctx.derive_init()?;
+    ctx.set_hkdf_md(Md::sha256())?;
+    ctx.set_hkdf_key(ikm)?;
+    ctx.set_hkdf_salt(salt)?;
+    ctx.add_hkdf_info(info)?;
+    ctx.derive(Some(key))?;
+
+    Ok(())
+}
+
 #[cfg(not(test))]
 mod production {
     use openssl::error::ErrorStack;
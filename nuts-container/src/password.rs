@@ -23,10 +23,16 @@
 #[cfg(test)]
 mod tests;
 
-use std::fmt;
-use std::rc::Rc;
+use openssl::sha::Sha256;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fmt, result};
 use thiserror::Error;
 
+use crate::recipient::Identity;
+use crate::recovery::RecoveryKey;
 use crate::svec::SecureVec;
 
 /// Password related error codes.
@@ -41,28 +47,112 @@ pub enum PasswordError {
     /// variant.
     #[error("failed to receive the password: {0}")]
     PasswordCallback(String),
+
+    /// Failed to read the keyfile, the underlaying IO error is passed to the
+    /// variant.
+    #[error("failed to read the keyfile: {0}")]
+    Keyfile(#[source] io::Error),
 }
 
-pub type CallbackFn = dyn Fn() -> Result<Vec<u8>, String>;
+pub type CallbackFn = dyn Fn() -> Result<Vec<u8>, String> + Send + Sync;
+
+fn keyfile_digest(path: &Path) -> result::Result<SecureVec, io::Error> {
+    let mut file = File::open(path)?;
+    let mut sha256 = Sha256::new();
+    let mut buf = [0; 4096];
+
+    loop {
+        let n = file.read(&mut buf)?;
+
+        if n == 0 {
+            break;
+        }
+
+        sha256.update(&buf[..n]);
+    }
+
+    Ok(SecureVec::from(&sha256.finish()[..]))
+}
 
 pub struct PasswordStore {
-    callback: Option<Rc<CallbackFn>>,
+    callback: Option<Arc<CallbackFn>>,
+    keyfile: Option<PathBuf>,
+    identity: Option<Identity>,
+    recovery_key: Option<RecoveryKey>,
     value: Option<SecureVec>,
 }
 
 impl PasswordStore {
-    pub fn new(callback: Option<Rc<CallbackFn>>) -> PasswordStore {
+    pub fn new(callback: Option<Arc<CallbackFn>>) -> PasswordStore {
         PasswordStore {
             callback,
+            keyfile: None,
+            identity: None,
+            recovery_key: None,
             value: None,
         }
     }
 
+    /// Assigns a keyfile to the store.
+    ///
+    /// The SHA-256 digest of the keyfile becomes part of the value of the
+    /// store. If a callback is assigned as well, the password returned by
+    /// the callback is appended to the digest.
+    pub fn with_keyfile(mut self, keyfile: Option<PathBuf>) -> PasswordStore {
+        self.keyfile = keyfile;
+        self
+    }
+
+    /// Replaces the callback of the store.
+    ///
+    /// A keyfile and identity are kept, the value is evaluated again with the
+    /// new callback.
+    pub fn change_callback(&mut self, callback: Option<Arc<CallbackFn>>) {
+        self.callback = callback;
+        self.value = None;
+    }
+
+    /// Assigns an identity to the store.
+    ///
+    /// The identity unlocks key slots, which are wrapped to its recipient.
+    pub fn with_identity(mut self, identity: Option<Identity>) -> PasswordStore {
+        self.identity = identity;
+        self
+    }
+
+    pub fn identity(&self) -> Option<&Identity> {
+        self.identity.as_ref()
+    }
+
+    /// Assigns a recovery key to the store.
+    ///
+    /// The recovery key unlocks the header directly, the key slots are
+    /// skipped.
+    pub fn with_recovery_key(mut self, recovery_key: Option<RecoveryKey>) -> PasswordStore {
+        self.recovery_key = recovery_key;
+        self
+    }
+
+    pub fn recovery_key(&self) -> Option<&RecoveryKey> {
+        self.recovery_key.as_ref()
+    }
+
+    /// Tests whether a password is available.
+    ///
+    /// This is the case, if a callback or keyfile is assigned or the value
+    /// is already known.
+    pub fn has_password(&self) -> bool {
+        self.callback.is_some() || self.keyfile.is_some() || self.value.is_some()
+    }
+
     #[cfg(test)]
     pub fn with_value(value: &[u8]) -> PasswordStore {
         PasswordStore {
             callback: None,
-            value: Some(value.to_vec().into()),
+            keyfile: None,
+            identity: None,
+            recovery_key: None,
+            value: Some(value.into()),
         }
     }
 
@@ -70,10 +160,26 @@ impl PasswordStore {
         match self.value {
             Some(ref v) => Ok(v),
             None => {
-                let callback = self.callback.as_ref().ok_or(PasswordError::NoPassword)?;
-                let value = callback().map_err(PasswordError::PasswordCallback)?;
+                let value = match self.keyfile.as_ref() {
+                    Some(path) => {
+                        let mut value = keyfile_digest(path).map_err(PasswordError::Keyfile)?;
+
+                        if let Some(callback) = self.callback.as_ref() {
+                            let password: SecureVec =
+                                callback().map_err(PasswordError::PasswordCallback)?.into();
+
+                            value.extend_from_slice(&password);
+                        }
+
+                        value
+                    }
+                    None => {
+                        let callback = self.callback.as_ref().ok_or(PasswordError::NoPassword)?;
+                        callback().map_err(PasswordError::PasswordCallback)?.into()
+                    }
+                };
 
-                Ok(self.value.insert(value.into()))
+                Ok(self.value.insert(value.into_guarded()))
             }
         }
     }
@@ -90,6 +196,9 @@ impl fmt::Debug for PasswordStore {
 
         fmt.debug_struct("PasswordStore")
             .field("callback", &callback)
+            .field("keyfile", &self.keyfile)
+            .field("identity", &self.identity)
+            .field("recovery_key", &self.recovery_key)
             .field("value", &value)
             .finish()
     }
@@ -20,7 +20,8 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use std::rc::Rc;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::password::{PasswordError, PasswordStore};
 
@@ -39,7 +40,7 @@ fn no_callback() {
 
 #[test]
 fn error_from_callback() {
-    let mut store = PasswordStore::new(Some(Rc::new(|| Err(String::from("some error")))));
+    let mut store = PasswordStore::new(Some(Arc::new(|| Err(String::from("some error")))));
 
     let err = store.value().unwrap_err();
     assert!(matches!(err, PasswordError::PasswordCallback(msg) if msg == "some error"));
@@ -47,7 +48,7 @@ fn error_from_callback() {
 
 #[test]
 fn value_from_callback() {
-    let mut store = PasswordStore::new(Some(Rc::new(|| Ok(vec![1, 2, 3]))));
+    let mut store = PasswordStore::new(Some(Arc::new(|| Ok(vec![1, 2, 3]))));
 
     let value1 = store.value().unwrap();
     assert_eq!(value1, [1, 2, 3]);
@@ -57,3 +58,64 @@ fn value_from_callback() {
         assert_eq!(value2, [1, 2, 3]);
     }
 }
+
+const SAMPLE_DIGEST: [u8; 32] = [
+    48, 85, 226, 14, 237, 84, 114, 125, 227, 104, 180, 4, 31, 92, 171, 72, 131, 18, 199, 124, 3,
+    190, 236, 195, 91, 199, 252, 210, 199, 16, 178, 138,
+];
+
+fn keyfile(name: &str) -> Option<PathBuf> {
+    Some(
+        [env!("CARGO_MANIFEST_DIR"), "data", "keyfile", name]
+            .iter()
+            .collect(),
+    )
+}
+
+#[test]
+fn value_from_keyfile() {
+    let mut store = PasswordStore::new(None).with_keyfile(keyfile("sample"));
+    assert_eq!(store.value().unwrap(), SAMPLE_DIGEST);
+}
+
+#[test]
+fn value_from_keyfile_and_callback() {
+    let mut store =
+        PasswordStore::new(Some(Arc::new(|| Ok(vec![1, 2, 3])))).with_keyfile(keyfile("sample"));
+
+    let value = store.value().unwrap();
+    assert_eq!(value[..32], SAMPLE_DIGEST);
+    assert_eq!(value[32..], [1, 2, 3]);
+}
+
+#[test]
+fn no_such_keyfile() {
+    let mut store = PasswordStore::new(Some(Arc::new(|| Ok(vec![1, 2, 3]))))
+        .with_keyfile(keyfile("no-such-file"));
+
+    let err = store.value().unwrap_err();
+    assert!(matches!(err, PasswordError::Keyfile(_)));
+}
+
+#[test]
+fn error_from_callback_with_keyfile() {
+    let mut store = PasswordStore::new(Some(Arc::new(|| Err(String::from("some error")))))
+        .with_keyfile(keyfile("sample"));
+
+    let err = store.value().unwrap_err();
+    assert!(matches!(err, PasswordError::PasswordCallback(msg) if msg == "some error"));
+}
+
+#[test]
+fn change_callback_with_keyfile() {
+    let mut store =
+        PasswordStore::new(Some(Arc::new(|| Ok(vec![1, 2, 3])))).with_keyfile(keyfile("sample"));
+
+    assert_eq!(store.value().unwrap()[32..], [1, 2, 3]);
+
+    store.change_callback(Some(Arc::new(|| Ok(vec![4, 5, 6]))));
+
+    let value = store.value().unwrap();
+    assert_eq!(value[..32], SAMPLE_DIGEST);
+    assert_eq!(value[32..], [4, 5, 6]);
+}
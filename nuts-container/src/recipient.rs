@@ -0,0 +1,213 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use openssl::derive::Deriver;
+use openssl::error::ErrorStack;
+use openssl::pkey::{HasPublic, Id, PKey, PKeyRef, Private, Public};
+use std::convert::TryInto;
+use std::fmt;
+use thiserror::Error;
+
+use crate::ossl;
+use crate::svec::SecureVec;
+
+const KEY_LEN: usize = 32;
+const HKDF_INFO: &[u8] = b"nuts-x25519";
+
+/// Recipient related error codes.
+#[derive(Debug, Error)]
+pub enum RecipientError {
+    /// The key is not a X25519 key.
+    #[error("invalid X25519 key")]
+    InvalidKey,
+
+    /// An error in the OpenSSL library occured.
+    #[error(transparent)]
+    OpenSSL(#[from] ErrorStack),
+}
+
+fn derive_wrapping_key<T: HasPublic>(
+    secret: &PKeyRef<Private>,
+    peer: &PKeyRef<T>,
+    ephemeral: &[u8],
+    recipient: &[u8],
+    key_len: usize,
+) -> Result<SecureVec, ErrorStack> {
+    let mut deriver = Deriver::new(secret)?;
+
+    deriver.set_peer(peer)?;
+
+    let shared = SecureVec::from(deriver.derive_to_vec()?).into_guarded();
+    let salt = [ephemeral, recipient].concat();
+    let mut key = SecureVec::zeroed_guarded(key_len);
+
+    ossl::hkdf_sha256(&shared, &salt, HKDF_INFO, &mut key)?;
+
+    Ok(key)
+}
+
+/// The public key of a recipient.
+///
+/// The header key of a container can be wrapped to a recipient. Only the
+/// owner of the related [`Identity`] is able to unwrap it again. A X25519
+/// key agreement with an ephemeral key is used to derive the wrapping key.
+#[derive(Clone, PartialEq)]
+pub struct Recipient([u8; KEY_LEN]);
+
+impl Recipient {
+    /// Creates a recipient from the raw 32 bytes of a X25519 public key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Recipient, RecipientError> {
+        bytes
+            .try_into()
+            .map(Recipient)
+            .map_err(|_| RecipientError::InvalidKey)
+    }
+
+    /// Reads a recipient from a PEM encoded X25519 public key.
+    ///
+    /// Such a key is created with
+    /// `openssl pkey -in <IDENTITY> -pubout`.
+    pub fn from_pem(pem: &[u8]) -> Result<Recipient, RecipientError> {
+        let pkey = PKey::public_key_from_pem(pem)?;
+
+        if pkey.id() == Id::X25519 {
+            Self::from_bytes(&pkey.raw_public_key()?)
+        } else {
+            Err(RecipientError::InvalidKey)
+        }
+    }
+
+    /// Returns the PEM encoding of the recipient.
+    pub fn to_pem(&self) -> Result<Vec<u8>, RecipientError> {
+        Ok(self.pkey()?.public_key_to_pem()?)
+    }
+
+    /// Returns the raw 32 bytes of the public key.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn pkey(&self) -> Result<PKey<Public>, ErrorStack> {
+        PKey::public_key_from_raw_bytes(&self.0, Id::X25519)
+    }
+
+    /// Creates a new wrapping key for the recipient.
+    ///
+    /// Returns the public key of the ephemeral key together with the
+    /// wrapping key. The ephemeral public key must be stored to re-create
+    /// the wrapping key with [`Identity::wrapping_key`].
+    pub(crate) fn create_wrapping_key(
+        &self,
+        key_len: usize,
+    ) -> Result<(Vec<u8>, SecureVec), RecipientError> {
+        let ephemeral = PKey::generate_x25519()?;
+        let ephemeral_pub = ephemeral.raw_public_key()?;
+        let peer = self.pkey()?;
+        let key = derive_wrapping_key(&ephemeral, &peer, &ephemeral_pub, &self.0, key_len)?;
+
+        Ok((ephemeral_pub, key))
+    }
+}
+
+impl fmt::Display for Recipient {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("x25519:")?;
+
+        for n in self.0.iter() {
+            write!(fmt, "{:02x}", n)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Recipient {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("Recipient")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+/// The private key of a [`Recipient`].
+///
+/// An identity unlocks all key slots, which were wrapped to its
+/// [recipient](Identity::recipient).
+#[derive(Clone)]
+pub struct Identity(PKey<Private>);
+
+impl Identity {
+    /// Generates a new random identity.
+    pub fn generate() -> Result<Identity, RecipientError> {
+        Ok(Identity(PKey::generate_x25519()?))
+    }
+
+    /// Reads an identity from a PEM encoded X25519 private key.
+    ///
+    /// Such a key is created with `openssl genpkey -algorithm X25519`.
+    pub fn from_pem(pem: &[u8]) -> Result<Identity, RecipientError> {
+        let pkey = PKey::private_key_from_pem(pem)?;
+
+        if pkey.id() == Id::X25519 {
+            Ok(Identity(pkey))
+        } else {
+            Err(RecipientError::InvalidKey)
+        }
+    }
+
+    /// Returns the PEM encoding (PKCS#8) of the identity.
+    pub fn to_pem(&self) -> Result<Vec<u8>, RecipientError> {
+        Ok(self.0.private_key_to_pem_pkcs8()?)
+    }
+
+    /// Returns the recipient (public key) of the identity.
+    pub fn recipient(&self) -> Result<Recipient, RecipientError> {
+        Recipient::from_bytes(&self.0.raw_public_key()?)
+    }
+
+    /// Re-creates the wrapping key from the public key of the ephemeral key.
+    pub(crate) fn wrapping_key(
+        &self,
+        ephemeral: &[u8],
+        key_len: usize,
+    ) -> Result<SecureVec, RecipientError> {
+        let peer = PKey::public_key_from_raw_bytes(ephemeral, Id::X25519)?;
+        let recipient = self.0.raw_public_key()?;
+
+        Ok(derive_wrapping_key(
+            &self.0, &peer, ephemeral, &recipient, key_len,
+        )?)
+    }
+}
+
+impl fmt::Debug for Identity {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let recipient = self.recipient().ok();
+
+        fmt.debug_struct("Identity")
+            .field("recipient", &recipient)
+            .finish()
+    }
+}
@@ -0,0 +1,203 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use openssl::sha::sha256;
+use std::str::{self, FromStr};
+use std::{fmt, io};
+use thiserror::Error;
+
+use crate::svec::SecureVec;
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const CHECKSUM_LEN: usize = 4;
+const GROUP_LEN: usize = 4;
+
+/// Recovery key related error codes.
+#[derive(Debug, Error)]
+pub enum RecoveryError {
+    /// The recovery key contains a character, which is not part of the
+    /// alphabet.
+    #[error("invalid character in recovery key: {0}")]
+    InvalidCharacter(char),
+
+    /// The checksum of the recovery key does not match, the key was not
+    /// transcribed correctly.
+    #[error("invalid recovery key, the checksum does not match")]
+    InvalidChecksum,
+}
+
+fn checksum(key: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut sum = [0; CHECKSUM_LEN];
+
+    sum.copy_from_slice(&sha256(key)[..CHECKSUM_LEN]);
+
+    sum
+}
+
+fn decode_char(c: char) -> Result<u8, RecoveryError> {
+    // Characters, which are easily confused with letters of the alphabet,
+    // are mapped to these letters.
+    let c = match c.to_ascii_uppercase() {
+        '0' => 'O',
+        '1' => 'I',
+        '8' => 'B',
+        c => c,
+    };
+
+    ALPHABET
+        .iter()
+        .position(|n| *n as char == c)
+        .map(|n| n as u8)
+        .ok_or(RecoveryError::InvalidCharacter(c))
+}
+
+/// A key, which opens a container without a password.
+///
+/// The recovery key is the header key of the container. It unlocks the
+/// secret of the header directly, no key slot and no
+/// [key derivation function](crate::Kdf) is involved. Use
+/// [`Container::recovery_key`](crate::Container::recovery_key) to export it
+/// and [`OpenOptionsBuilder::with_recovery_key`] to open the container with
+/// it.
+///
+/// The textual representation is meant to be written down on paper: The key
+/// followed by a checksum is base32 encoded and split into groups of four
+/// characters. When parsing a recovery key, the case of the characters and
+/// any whitespace are ignored, `0`, `1` and `8` are read as `O`, `I` and `B`.
+///
+/// [`OpenOptionsBuilder::with_recovery_key`]: crate::OpenOptionsBuilder::with_recovery_key
+#[derive(Clone, PartialEq)]
+pub struct RecoveryKey(SecureVec);
+
+impl RecoveryKey {
+    pub(crate) fn new(key: &[u8]) -> RecoveryKey {
+        RecoveryKey(key.into())
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Writes the textual representation of the key into `writer`.
+    ///
+    /// Unlike the [`Display`](fmt::Display) implementation no `String` is
+    /// involved, which would keep a copy of the key in memory. The text is
+    /// encoded into a buffer, which is wiped afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Errors of the `writer` are passed to the caller.
+    pub fn write_to<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.encode())
+    }
+
+    /// Encodes the key followed by its checksum.
+    ///
+    /// The key is base32 encoded and split into groups of [`GROUP_LEN`]
+    /// characters, which are separated by a dash.
+    #[allow(clippy::manual_div_ceil)] // div_ceil() needs rust 1.73
+    fn encode(&self) -> SecureVec {
+        let nchars = ((self.0.len() + CHECKSUM_LEN) * 8 + 4) / 5;
+        let mut buf = SecureVec::with_capacity(nchars + nchars / GROUP_LEN);
+        let mut idx = 0;
+        let mut acc = 0u16;
+        let mut nbits = 0;
+        let mut push = |buf: &mut SecureVec, c: u8| {
+            if idx > 0 && idx % GROUP_LEN == 0 {
+                buf.push(b'-');
+            }
+
+            buf.push(c);
+            idx += 1;
+        };
+
+        for n in self.0.iter().chain(checksum(&self.0).iter()) {
+            acc = (acc << 8) | *n as u16;
+            nbits += 8;
+
+            while nbits >= 5 {
+                nbits -= 5;
+                push(&mut buf, ALPHABET[(acc >> nbits) as usize & 0x1F]);
+            }
+
+            acc &= (1 << nbits) - 1;
+        }
+
+        if nbits > 0 {
+            push(&mut buf, ALPHABET[(acc << (5 - nbits)) as usize & 0x1F]);
+        }
+
+        buf
+    }
+}
+
+impl FromStr for RecoveryKey {
+    type Err = RecoveryError;
+
+    fn from_str(s: &str) -> Result<Self, RecoveryError> {
+        let mut bytes = SecureVec::with_capacity(s.len());
+        let mut acc = 0u16;
+        let mut nbits = 0;
+
+        for c in s.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+            acc = (acc << 5) | decode_char(c)? as u16;
+            nbits += 5;
+
+            if nbits >= 8 {
+                nbits -= 8;
+                bytes.push((acc >> nbits) as u8);
+                acc &= (1 << nbits) - 1;
+            }
+        }
+
+        if bytes.len() < CHECKSUM_LEN || nbits >= 5 || acc != 0 {
+            return Err(RecoveryError::InvalidChecksum);
+        }
+
+        let (key, sum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+
+        if checksum(key) == sum {
+            Ok(RecoveryKey::new(key))
+        } else {
+            Err(RecoveryError::InvalidChecksum)
+        }
+    }
+}
+
+impl fmt::Display for RecoveryKey {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let buf = self.encode();
+
+        fmt.write_str(str::from_utf8(&buf).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl fmt::Debug for RecoveryKey {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("RecoveryKey")
+            .field(&format!("<{} bytes>", self.0.len()))
+            .finish()
+    }
+}
@@ -0,0 +1,114 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::recovery::{RecoveryError, RecoveryKey};
+
+const KEY16: &str = "AAAQ-EAYE-AUDA-OCAJ-BIFQ-YDIO-B67E-LSZG";
+const KEY32: &str = "AAAQ-EAYE-AUDA-OCAJ-BIFQ-YDIO-B4IB-CEQT-CQKR-MFYY-DENB-WHA5-DYPW-GDON-FE";
+
+fn key(len: u8) -> RecoveryKey {
+    RecoveryKey::new(&(0..len).collect::<Vec<u8>>())
+}
+
+#[test]
+fn to_string_16() {
+    assert_eq!(key(16).to_string(), KEY16);
+}
+
+#[test]
+fn to_string_32() {
+    assert_eq!(key(32).to_string(), KEY32);
+}
+
+#[test]
+fn from_str_16() {
+    assert_eq!(KEY16.parse::<RecoveryKey>().unwrap(), key(16));
+}
+
+#[test]
+fn from_str_32() {
+    assert_eq!(KEY32.parse::<RecoveryKey>().unwrap(), key(32));
+}
+
+#[test]
+fn from_str_relaxed() {
+    let s = "aaaq eaye audA-0CAJ\nb1fq ydi0 b67e lszg";
+
+    assert_eq!(s.parse::<RecoveryKey>().unwrap(), key(16));
+}
+
+#[test]
+fn from_str_invalid_char() {
+    let err = "AAAQ-EAY9".parse::<RecoveryKey>().unwrap_err();
+
+    assert!(matches!(err, RecoveryError::InvalidCharacter('9')));
+}
+
+#[test]
+fn from_str_invalid_checksum() {
+    let s = KEY16.replace("B67E", "B67F");
+    let err = s.parse::<RecoveryKey>().unwrap_err();
+
+    assert!(matches!(err, RecoveryError::InvalidChecksum));
+}
+
+#[test]
+fn from_str_truncated() {
+    for s in [
+        "",
+        "AAAQ",
+        &KEY16[..KEY16.len() - 1],
+        &KEY16[..KEY16.len() - 5],
+    ] {
+        let err = s.parse::<RecoveryKey>().unwrap_err();
+
+        assert!(matches!(err, RecoveryError::InvalidChecksum), "{}", s);
+    }
+}
+
+#[test]
+fn from_str_trailing() {
+    let err = format!("{}A", KEY16).parse::<RecoveryKey>().unwrap_err();
+
+    assert!(matches!(err, RecoveryError::InvalidChecksum));
+}
+
+#[test]
+fn debug() {
+    assert_eq!(format!("{:?}", key(16)), "RecoveryKey(\"<16 bytes>\")");
+}
+
+#[test]
+fn write_to_16() {
+    let mut buf = vec![];
+
+    key(16).write_to(&mut buf).unwrap();
+    assert_eq!(buf, KEY16.as_bytes());
+}
+
+#[test]
+fn write_to_32() {
+    let mut buf = vec![];
+
+    key(32).write_to(&mut buf).unwrap();
+    assert_eq!(buf, KEY32.as_bytes());
+}
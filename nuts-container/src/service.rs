@@ -20,7 +20,7 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use nuts_backend::Backend;
+use nuts_backend::{Backend, BackendTypes};
 
 use crate::error::Error;
 use crate::migrate::Migration;
@@ -31,7 +31,7 @@ use crate::Container;
 /// A concrete service should implement this trait. Later, such a service can
 /// be instantiated with [`Container::open_service`] resp.
 /// [`Container::create_service`].
-pub trait Service<B: Backend> {
+pub trait Service<B: BackendTypes> {
     /// The migration assiciated with this service.
     type Migration: Migration + 'static;
 
@@ -0,0 +1,180 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::Backend;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::cache::BlockCache;
+use crate::cipher::CipherContext;
+use crate::header::{ExtendedBlocks, Header};
+use crate::password::PasswordStore;
+use crate::snapshot::Snapshots;
+use crate::{block_size, decrypt_block, Container, ContainerResult, Error};
+
+#[derive(Debug)]
+struct Inner<B: Backend> {
+    backend: Mutex<B>,
+    store: PasswordStore,
+    header: Header<'static, B>,
+    extended: ExtendedBlocks,
+    snapshots: Snapshots<B>,
+    view: Option<usize>,
+    contexts: Mutex<Vec<CipherContext>>,
+}
+
+/// A shareable, read-only handle of a [`Container`].
+///
+/// A `SharedContainer` is created with [`Container::into_shared`]. It can be
+/// cloned and sent to other threads, all clones read from the same
+/// container.
+///
+/// Unlike [`Container::read`], [`SharedContainer::read`] only needs a shared
+/// reference. Each read uses its own cipher context, only the access to the
+/// backend is serialized. Thus, blocks are decrypted concurrently. The
+/// contexts are kept for subsequent reads, there are not more contexts than
+/// concurrent reads.
+///
+/// Use [`SharedContainer::try_into_container`] to get back the container
+/// (e.g. to modify it), once all other clones are dropped.
+#[derive(Debug)]
+pub struct SharedContainer<B: Backend>(Arc<Inner<B>>);
+
+impl<B: Backend> SharedContainer<B> {
+    pub(crate) fn new(container: Container<B>) -> SharedContainer<B> {
+        SharedContainer(Arc::new(Inner {
+            backend: Mutex::new(container.backend),
+            store: container.store,
+            header: container.header,
+            extended: container.extended,
+            snapshots: container.snapshots,
+            view: container.view,
+            contexts: Mutex::new(vec![container.ctx]),
+        }))
+    }
+
+    /// Returns the _top-id_ of the container.
+    ///
+    /// See [`Container::top_id`] for details.
+    pub fn top_id(&self) -> Option<&B::Id> {
+        self.0.header.top_id()
+    }
+
+    /// Returns the (net) block size of the container.
+    ///
+    /// See [`Container::block_size`] for details.
+    pub fn block_size(&self) -> u32 {
+        block_size(&self.0.header, self.backend().block_size())
+    }
+
+    /// Reads a block from the container.
+    ///
+    /// Reads the block with the given `id` and places the decrypted data in
+    /// `buf`. See [`Container::read`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`Error`] type.
+    pub fn read(&self, id: &B::Id, buf: &mut [u8]) -> ContainerResult<usize, B> {
+        let target = self.0.snapshots.resolve(self.0.view, id)?;
+        let mut ctx = lock(&self.0.contexts)
+            .pop()
+            .unwrap_or_else(|| CipherContext::new(self.0.header.cipher()));
+
+        let result = self.read_with(&mut ctx, &target, buf);
+
+        lock(&self.0.contexts).push(ctx);
+
+        result
+    }
+
+    fn read_with(
+        &self,
+        ctx: &mut CipherContext,
+        target: &B::Id,
+        buf: &mut [u8],
+    ) -> ContainerResult<usize, B> {
+        let bsize = {
+            let mut backend = self.backend();
+            let ctext = ctx.inp_mut(backend.block_size() as usize);
+
+            let n = backend.read(target, ctext).map_err(Error::Backend)?;
+            ctx.truncate_inp(n);
+
+            block_size(&self.0.header, backend.block_size()) as usize
+        };
+
+        decrypt_block(&self.0.header, ctx, bsize, target, buf)
+    }
+
+    /// Converts the handle back into a [`Container`].
+    ///
+    /// This succeeds only, if this is the last handle of the container,
+    /// otherwise the handle is returned unchanged. The returned container
+    /// has no block cache.
+    pub fn try_into_container(self) -> Result<Container<B>, SharedContainer<B>> {
+        match Arc::try_unwrap(self.0) {
+            Ok(inner) => {
+                let backend = inner
+                    .backend
+                    .into_inner()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let cipher = inner.header.cipher();
+                let ctx = inner
+                    .contexts
+                    .into_inner()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .pop()
+                    .unwrap_or_else(|| CipherContext::new(cipher));
+
+                Ok(Container {
+                    backend,
+                    store: inner.store,
+                    header: inner.header,
+                    ctx,
+                    extended: inner.extended,
+                    cache: BlockCache::new(0, false),
+                    txn: None,
+                    snapshots: inner.snapshots,
+                    view: inner.view,
+                })
+            }
+            Err(inner) => Err(SharedContainer(inner)),
+        }
+    }
+
+    fn backend(&self) -> MutexGuard<'_, B> {
+        lock(&self.0.backend)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // A panic while reading from the backend does not leave the backend (or
+    // a cipher context) in an inconsistent state, continue with the poisoned
+    // instance.
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<B: Backend> Clone for SharedContainer<B> {
+    fn clone(&self) -> Self {
+        SharedContainer(Arc::clone(&self.0))
+    }
+}
@@ -0,0 +1,526 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use nuts_backend::{BackendTypes, Binary, IdSize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::buffer::{Buffer, BufferError, BufferMut};
+
+const MAGIC: [u8; 8] = *b"nuts-snp";
+
+/// Snapshot related errors.
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    /// Errors while (de-) serializing the snapshot table.
+    #[error(transparent)]
+    Buffer(#[from] BufferError),
+
+    /// A block of the snapshot table is invalid.
+    #[error("not a snapshot block")]
+    InvalidMagic,
+
+    /// An invalid id was read from the snapshot table.
+    #[error("invalid id in snapshot table")]
+    InvalidId,
+
+    /// The name of a snapshot must not be empty and cannot be longer than
+    /// 255 bytes.
+    #[error("invalid snapshot name: {0}")]
+    InvalidName(String),
+
+    /// The block size of the container is too small to store the snapshot
+    /// table.
+    #[error("the block size of {0} bytes is too small for the snapshot table")]
+    InvalidBlockSize(u32),
+
+    /// A snapshot with the given name already exists.
+    #[error("the snapshot {0} already exists")]
+    Exists(String),
+
+    /// No snapshot with the given name exists.
+    #[error("no such snapshot: {0}")]
+    NotFound(String),
+
+    /// The block was not aquired, when the snapshot was created.
+    #[error("no such block in snapshot: {0}")]
+    NoSuchBlock(String),
+
+    /// A snapshot is opened read-only, it cannot be modified. The
+    /// `AsyncContainer` (feature `async`) opens a container with snapshots
+    /// read-only as well.
+    #[error("the snapshot is read-only")]
+    ReadOnly,
+}
+
+/// Information about a snapshot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotInfo {
+    /// The name of the snapshot.
+    pub name: String,
+
+    /// The point in time, when the snapshot was created.
+    pub created: SystemTime,
+
+    /// The number of blocks, which belong to the snapshot.
+    pub blocks: usize,
+}
+
+/// A snapshot of the container.
+///
+/// `blocks` maps the ids of all blocks, which were aquired when the snapshot
+/// was created, to the blocks, where their data are stored.
+struct Snapshot<B: BackendTypes> {
+    name: String,
+    created: u64,
+    blocks: BTreeMap<Vec<u8>, B::Id>,
+}
+
+impl<B: BackendTypes> Snapshot<B> {
+    fn info(&self) -> SnapshotInfo {
+        SnapshotInfo {
+            name: self.name.clone(),
+            created: UNIX_EPOCH + Duration::from_secs(self.created),
+            blocks: self.blocks.len(),
+        }
+    }
+}
+
+/// The snapshot table.
+///
+/// The blocks of a snapshot are _frozen_, they are never written again.
+/// Writing a frozen block copies the block: the data are written into a new
+/// block, which replaces the frozen block in the live container (`live`).
+/// Frozen blocks, which are not used by the live container anymore, are
+/// `retained` until the last snapshot, which references them, is deleted.
+///
+/// Blocks are identified by the binary representation of their ids. The
+/// table itself is stored in the `index` blocks.
+pub struct Snapshots<B: BackendTypes> {
+    index: Vec<B::Id>,
+    live: BTreeMap<Vec<u8>, B::Id>,
+    retained: BTreeMap<Vec<u8>, B::Id>,
+    list: Vec<Snapshot<B>>,
+    frozen: BTreeSet<Vec<u8>>,
+}
+
+impl<B: BackendTypes> Snapshots<B> {
+    pub fn new() -> Snapshots<B> {
+        Snapshots {
+            index: vec![],
+            live: BTreeMap::new(),
+            retained: BTreeMap::new(),
+            list: vec![],
+            frozen: BTreeSet::new(),
+        }
+    }
+
+    /// Tests whether the table is empty. An empty table is not stored.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty() && self.live.is_empty() && self.retained.is_empty()
+    }
+
+    /// Replaces the blocks, where the table is stored, returning the blocks
+    /// of the previous table.
+    pub fn set_index(&mut self, index: Vec<B::Id>) -> Vec<B::Id> {
+        std::mem::replace(&mut self.index, index)
+    }
+
+    /// Returns information about all snapshots in the order of their
+    /// creation.
+    pub fn list(&self) -> Vec<SnapshotInfo> {
+        self.list.iter().map(|snapshot| snapshot.info()).collect()
+    }
+
+    /// Returns the index of the snapshot `name`.
+    pub fn position(&self, name: &str) -> Result<usize, SnapshotError> {
+        self.list
+            .iter()
+            .position(|snapshot| snapshot.name == name)
+            .ok_or_else(|| SnapshotError::NotFound(name.to_string()))
+    }
+
+    /// Returns the block, which stores the data of the live block `id`.
+    pub fn target(&self, id: &B::Id) -> B::Id {
+        self.live
+            .get(&id.as_bytes())
+            .cloned()
+            .unwrap_or_else(|| id.clone())
+    }
+
+    /// Returns the block, which stores the data of block `id`.
+    ///
+    /// With a `view` the block is looked up in the snapshot at index `view`,
+    /// otherwise in the live container.
+    pub fn resolve(&self, view: Option<usize>, id: &B::Id) -> Result<B::Id, SnapshotError> {
+        match view {
+            Some(idx) => self.list[idx]
+                .blocks
+                .get(&id.as_bytes())
+                .cloned()
+                .ok_or_else(|| SnapshotError::NoSuchBlock(id.to_string())),
+            None => Ok(self.target(id)),
+        }
+    }
+
+    /// Tests whether the block `id` belongs to a snapshot.
+    pub fn is_frozen(&self, id: &B::Id) -> bool {
+        self.frozen.contains(&id.as_bytes())
+    }
+
+    /// Tests whether the live block `id` is referenced by the table.
+    pub fn contains(&self, id: &B::Id) -> bool {
+        let key = id.as_bytes();
+
+        self.live.contains_key(&key) || self.frozen.contains(&key)
+    }
+
+    /// Stores the data of the live block `id` in the block `target` from now
+    /// on.
+    ///
+    /// Returns the previous target, if it is not used anymore and can be
+    /// released.
+    pub fn remap(&mut self, id: &B::Id, target: B::Id) -> Option<B::Id> {
+        self.live
+            .insert(id.as_bytes(), target)
+            .and_then(|prev| self.retain(prev))
+    }
+
+    /// Removes the live block `id`.
+    ///
+    /// Returns the blocks, which are not used anymore and can be released.
+    pub fn release(&mut self, id: B::Id) -> Vec<B::Id> {
+        let target = self.live.remove(&id.as_bytes());
+
+        target
+            .into_iter()
+            .chain(Some(id))
+            .filter_map(|id| self.retain(id))
+            .collect()
+    }
+
+    /// Returns the live blocks, which store their data in another block,
+    /// although they are not frozen anymore. The data can be moved back.
+    pub fn unfrozen(&self) -> Vec<(B::Id, B::Id)> {
+        self.live
+            .iter()
+            .filter(|(key, _)| !self.frozen.contains(*key))
+            .filter_map(|(key, target)| {
+                let id = <B::Id as Binary>::from_bytes(key)?;
+                Some((id, target.clone()))
+            })
+            .collect()
+    }
+
+    /// Stores the data of the live block `id` in the block itself again.
+    ///
+    /// Returns the previous target, if it is not used anymore and can be
+    /// released.
+    pub fn unmap(&mut self, id: &B::Id) -> Option<B::Id> {
+        self.live
+            .remove(&id.as_bytes())
+            .and_then(|target| self.retain(target))
+    }
+
+    /// Creates the snapshot `name`.
+    ///
+    /// `ids` are all blocks aquired in the backend. Blocks used internally
+    /// by the table are skipped, the remaining blocks are frozen.
+    pub fn create(
+        &mut self,
+        name: &str,
+        created: u64,
+        ids: Vec<B::Id>,
+    ) -> Result<(), SnapshotError> {
+        if name.is_empty() || name.len() > u8::MAX as usize {
+            return Err(SnapshotError::InvalidName(name.to_string()));
+        }
+
+        if self.position(name).is_ok() {
+            return Err(SnapshotError::Exists(name.to_string()));
+        }
+
+        let internal = self
+            .index
+            .iter()
+            .chain(self.live.values())
+            .map(|id| id.as_bytes())
+            .chain(self.retained.keys().cloned())
+            .collect::<BTreeSet<_>>();
+
+        let blocks = ids
+            .into_iter()
+            .map(|id| (id.as_bytes(), id))
+            .filter(|(key, _)| !internal.contains(key))
+            .map(|(key, id)| {
+                let target = self.target(&id);
+                (key, target)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        self.frozen
+            .extend(blocks.values().map(|target| target.as_bytes()));
+        self.list.push(Snapshot {
+            name: name.to_string(),
+            created,
+            blocks,
+        });
+
+        Ok(())
+    }
+
+    /// Removes the snapshot `name`.
+    ///
+    /// Returns the retained blocks, which are not referenced by another
+    /// snapshot and can be released.
+    pub fn remove(&mut self, name: &str) -> Result<Vec<B::Id>, SnapshotError> {
+        let idx = self.position(name)?;
+
+        self.list.remove(idx);
+        self.update_frozen();
+
+        let keys = self
+            .retained
+            .keys()
+            .filter(|key| !self.frozen.contains(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| self.retained.remove(key))
+            .collect())
+    }
+
+    /// Keeps the block `id`, if it is frozen. Otherwise the block is
+    /// returned, it can be released.
+    fn retain(&mut self, id: B::Id) -> Option<B::Id> {
+        let key = id.as_bytes();
+
+        if self.frozen.contains(&key) {
+            self.retained.insert(key, id);
+            None
+        } else {
+            Some(id)
+        }
+    }
+
+    fn update_frozen(&mut self) {
+        self.frozen = self
+            .list
+            .iter()
+            .flat_map(|snapshot| snapshot.blocks.values())
+            .map(|id| id.as_bytes())
+            .collect();
+    }
+
+    /// Serializes the table.
+    pub fn encode(&self) -> Result<Vec<u8>, SnapshotError> {
+        let mut buf = vec![];
+
+        buf.put_u32(self.live.len() as u32)?;
+
+        for (key, target) in self.live.iter() {
+            buf.put_vec::<1>(key)?;
+            buf.put_vec::<1>(&target.as_bytes())?;
+        }
+
+        buf.put_u32(self.retained.len() as u32)?;
+
+        for key in self.retained.keys() {
+            buf.put_vec::<1>(key)?;
+        }
+
+        buf.put_u32(self.list.len() as u32)?;
+
+        for snapshot in self.list.iter() {
+            buf.put_vec::<1>(snapshot.name.as_bytes())?;
+            buf.put_u64(snapshot.created)?;
+            buf.put_u32(snapshot.blocks.len() as u32)?;
+
+            for (key, target) in snapshot.blocks.iter() {
+                let target = target.as_bytes();
+
+                buf.put_vec::<1>(key)?;
+
+                if *key == target {
+                    buf.put_u8(0)?;
+                } else {
+                    buf.put_u8(1)?;
+                    buf.put_vec::<1>(&target)?;
+                }
+            }
+        }
+
+        Ok(buf)
+    }
+
+    /// Deserializes the table.
+    pub fn decode(buf: &[u8]) -> Result<Snapshots<B>, SnapshotError> {
+        let buf = &mut &buf[..];
+        let mut table = Snapshots::new();
+
+        for _ in 0..buf.get_u32()? {
+            let (key, _) = get_id::<B, _>(buf)?;
+            let (_, target) = get_id::<B, _>(buf)?;
+
+            table.live.insert(key, target);
+        }
+
+        for _ in 0..buf.get_u32()? {
+            let (key, id) = get_id::<B, _>(buf)?;
+
+            table.retained.insert(key, id);
+        }
+
+        for _ in 0..buf.get_u32()? {
+            let name = String::from_utf8(buf.get_vec::<1>()?)
+                .map_err(|err| SnapshotError::InvalidName(err.to_string()))?;
+            let created = buf.get_u64()?;
+            let mut blocks = BTreeMap::new();
+
+            for _ in 0..buf.get_u32()? {
+                let (key, id) = get_id::<B, _>(buf)?;
+                let b = buf.get_u8()?;
+
+                let target = match b {
+                    0 => id,
+                    1 => get_id::<B, _>(buf)?.1,
+                    _ => {
+                        return Err(
+                            BufferError::InvalidIndex("Snapshot".to_string(), b as u32).into()
+                        )
+                    }
+                };
+
+                blocks.insert(key, target);
+            }
+
+            table.list.push(Snapshot {
+                name,
+                created,
+                blocks,
+            });
+        }
+
+        table.update_frozen();
+
+        Ok(table)
+    }
+}
+
+impl<B: BackendTypes> fmt::Debug for Snapshots<B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self
+            .list
+            .iter()
+            .map(|snapshot| snapshot.name.as_str())
+            .collect::<Vec<_>>();
+
+        fmt.debug_struct("Snapshots")
+            .field("snapshots", &names)
+            .field("live", &self.live.len())
+            .field("retained", &self.retained.len())
+            .finish()
+    }
+}
+
+fn get_id<B: BackendTypes, T: Buffer>(buf: &mut T) -> Result<(Vec<u8>, B::Id), SnapshotError> {
+    let bytes = buf.get_vec::<1>()?;
+    let id = <B::Id as Binary>::from_bytes(&bytes).ok_or(SnapshotError::InvalidId)?;
+
+    Ok((bytes, id))
+}
+
+/// Returns the number of table bytes, which fit into a block of `bsize`
+/// bytes.
+fn capacity<B: BackendTypes>(bsize: u32) -> Result<usize, SnapshotError> {
+    // magic, id of the next table block, length of the chunk
+    let overhead = MAGIC.len() + 1 + B::Id::size() + 4;
+
+    match (bsize as usize).checked_sub(overhead) {
+        Some(n) if n > 0 => Ok(n),
+        _ => Err(SnapshotError::InvalidBlockSize(bsize)),
+    }
+}
+
+/// Returns the number of blocks needed to store the serialized `table`.
+pub fn index_blocks<B: BackendTypes>(table: &[u8], bsize: u32) -> Result<usize, SnapshotError> {
+    Ok(table.chunks(capacity::<B>(bsize)?).count())
+}
+
+/// Splits the serialized `table` into the table blocks `ids`.
+///
+/// Each table block references the next one, the last block has no
+/// successor.
+pub fn encode_index<B: BackendTypes>(
+    table: &[u8],
+    ids: &[B::Id],
+    bsize: u32,
+) -> Result<Vec<Vec<u8>>, SnapshotError> {
+    table
+        .chunks(capacity::<B>(bsize)?)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let mut buf = vec![];
+
+            buf.put_chunk(&MAGIC)?;
+
+            match ids.get(idx + 1) {
+                Some(id) => buf.put_vec::<1>(&id.as_bytes())?,
+                None => buf.put_vec::<1>(&[])?,
+            }
+
+            buf.put_vec::<4>(chunk)?;
+
+            Ok(buf)
+        })
+        .collect()
+}
+
+/// Decodes a table block.
+///
+/// Returns the chunk of the serialized table stored in the block and the id
+/// of the next table block.
+pub fn decode_index<B: BackendTypes>(
+    buf: &[u8],
+) -> Result<(Vec<u8>, Option<B::Id>), SnapshotError> {
+    let buf = &mut &buf[..];
+    let magic = buf.get_array::<8>()?;
+
+    if magic != MAGIC {
+        return Err(SnapshotError::InvalidMagic);
+    }
+
+    let next = match buf.get_vec::<1>()? {
+        bytes if bytes.is_empty() => None,
+        bytes => Some(<B::Id as Binary>::from_bytes(&bytes).ok_or(SnapshotError::InvalidId)?),
+    };
+    let chunk = buf.get_vec::<4>()?;
+
+    Ok((chunk, next))
+}
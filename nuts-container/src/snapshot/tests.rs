@@ -0,0 +1,297 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_memory::{Id, MemoryBackend};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::snapshot::{
+    decode_index, encode_index, index_blocks, SnapshotError, SnapshotInfo, Snapshots,
+};
+
+fn id(n: u32) -> Id {
+    n.to_string().parse().unwrap()
+}
+
+fn ids(ids: &[u32]) -> Vec<Id> {
+    ids.iter().map(|n| id(*n)).collect()
+}
+
+fn table(blocks: &[u32]) -> Snapshots<MemoryBackend> {
+    let mut table = Snapshots::new();
+
+    table.create("s1", 1, ids(blocks)).unwrap();
+
+    table
+}
+
+#[test]
+fn create() {
+    let table = table(&[1, 2]);
+
+    assert!(!table.is_empty());
+    assert!(table.is_frozen(&id(1)));
+    assert!(table.is_frozen(&id(2)));
+    assert!(!table.is_frozen(&id(3)));
+
+    assert_eq!(
+        table.list(),
+        [SnapshotInfo {
+            name: "s1".to_string(),
+            created: UNIX_EPOCH + Duration::from_secs(1),
+            blocks: 2
+        }]
+    );
+    assert_eq!(table.position("s1").unwrap(), 0);
+}
+
+#[test]
+fn create_exists() {
+    let mut table = table(&[1, 2]);
+    let err = table.create("s1", 2, ids(&[1, 2])).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::Exists(name) if name == "s1"));
+    assert_eq!(table.list().len(), 1);
+}
+
+#[test]
+fn create_inval_name() {
+    let mut table = Snapshots::<MemoryBackend>::new();
+
+    let err = table.create("", 1, ids(&[1])).unwrap_err();
+    assert!(matches!(err, SnapshotError::InvalidName(name) if name.is_empty()));
+
+    let err = table.create(&"x".repeat(256), 1, ids(&[1])).unwrap_err();
+    assert!(matches!(err, SnapshotError::InvalidName(name) if name.len() == 256));
+
+    assert!(table.is_empty());
+}
+
+#[test]
+fn create_skip_internal() {
+    let mut table = table(&[1, 2, 3]);
+
+    table.set_index(ids(&[9]));
+    assert!(table.remap(&id(1), id(10)).is_none());
+    assert!(table.release(id(3)).is_empty());
+
+    table.create("s2", 2, ids(&[1, 2, 3, 9, 10])).unwrap();
+
+    assert_eq!(table.list()[1].blocks, 2);
+    assert_eq!(table.resolve(Some(1), &id(1)).unwrap(), id(10));
+    assert_eq!(table.resolve(Some(1), &id(2)).unwrap(), id(2));
+    assert!(table.resolve(Some(1), &id(3)).is_err());
+    assert!(table.resolve(Some(1), &id(9)).is_err());
+}
+
+#[test]
+fn resolve() {
+    let mut table = table(&[1, 2]);
+
+    assert!(table.remap(&id(1), id(10)).is_none());
+
+    assert_eq!(table.resolve(None, &id(1)).unwrap(), id(10));
+    assert_eq!(table.resolve(None, &id(2)).unwrap(), id(2));
+    assert_eq!(table.resolve(None, &id(3)).unwrap(), id(3));
+
+    assert_eq!(table.resolve(Some(0), &id(1)).unwrap(), id(1));
+    assert_eq!(table.resolve(Some(0), &id(2)).unwrap(), id(2));
+
+    let err = table.resolve(Some(0), &id(3)).unwrap_err();
+    assert!(matches!(err, SnapshotError::NoSuchBlock(id) if id == "3"));
+}
+
+#[test]
+fn remap() {
+    let mut table = table(&[1]);
+
+    assert!(table.remap(&id(1), id(10)).is_none());
+    assert_eq!(table.remap(&id(1), id(11)), Some(id(10)));
+    assert_eq!(table.target(&id(1)), id(11));
+}
+
+#[test]
+fn remap_retained() {
+    let mut table = table(&[1]);
+
+    assert!(table.remap(&id(1), id(10)).is_none());
+    table.create("s2", 2, ids(&[1, 10])).unwrap();
+
+    // 10 belongs to s2 and is retained
+    assert!(table.remap(&id(1), id(11)).is_none());
+    assert_eq!(table.remove("s2").unwrap(), [id(10)]);
+}
+
+#[test]
+fn release() {
+    let mut table = table(&[1, 2]);
+
+    assert!(table.contains(&id(1)));
+    assert!(!table.contains(&id(3)));
+
+    assert!(table.release(id(1)).is_empty());
+    assert_eq!(table.release(id(3)), [id(3)]);
+}
+
+#[test]
+fn release_remapped() {
+    let mut table = table(&[1, 2]);
+
+    assert!(table.remap(&id(1), id(10)).is_none());
+    assert!(table.contains(&id(1)));
+
+    assert_eq!(table.release(id(1)), [id(10)]);
+    assert_eq!(table.target(&id(1)), id(1));
+}
+
+#[test]
+fn remove() {
+    let mut table = table(&[1, 2]);
+
+    assert!(table.release(id(2)).is_empty());
+    assert_eq!(table.remove("s1").unwrap(), [id(2)]);
+    assert!(table.is_empty());
+
+    let err = table.remove("s1").unwrap_err();
+    assert!(matches!(err, SnapshotError::NotFound(name) if name == "s1"));
+}
+
+#[test]
+fn remove_shared() {
+    let mut table = table(&[1, 2]);
+
+    table.create("s2", 2, ids(&[1, 2])).unwrap();
+    assert!(table.release(id(2)).is_empty());
+
+    assert!(table.remove("s1").unwrap().is_empty());
+    assert!(table.is_frozen(&id(2)));
+    assert_eq!(table.remove("s2").unwrap(), [id(2)]);
+}
+
+#[test]
+fn unfrozen() {
+    let mut table = table(&[1, 2]);
+
+    assert!(table.remap(&id(1), id(10)).is_none());
+    assert!(table.unfrozen().is_empty());
+
+    assert!(table.remove("s1").unwrap().is_empty());
+    assert_eq!(table.unfrozen(), [(id(1), id(10))]);
+
+    assert_eq!(table.unmap(&id(1)), Some(id(10)));
+    assert!(table.unfrozen().is_empty());
+    assert!(table.is_empty());
+}
+
+#[test]
+fn encode_decode() {
+    let mut table = table(&[1, 2, 3]);
+
+    assert!(table.remap(&id(1), id(10)).is_none());
+    assert!(table.release(id(3)).is_empty());
+    table.create("s2", 2, ids(&[1, 2, 3, 10])).unwrap();
+
+    let buf = table.encode().unwrap();
+    let table2 = Snapshots::<MemoryBackend>::decode(&buf).unwrap();
+
+    assert_eq!(table2.list(), table.list());
+    assert_eq!(table2.target(&id(1)), id(10));
+    assert_eq!(table2.resolve(Some(0), &id(1)).unwrap(), id(1));
+    assert_eq!(table2.resolve(Some(1), &id(1)).unwrap(), id(10));
+    assert!(table2.is_frozen(&id(3)));
+    assert_eq!(table2.encode().unwrap(), buf);
+}
+
+#[test]
+fn decode_eof() {
+    let buf = table(&[1, 2]).encode().unwrap();
+    let err = Snapshots::<MemoryBackend>::decode(&buf[..buf.len() - 1]).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::Buffer(_)));
+}
+
+#[test]
+fn decode_inval_kind() {
+    let mut buf = table(&[1]).encode().unwrap();
+    let n = buf.len();
+    buf[n - 1] = 2;
+
+    let err = Snapshots::<MemoryBackend>::decode(&buf).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::Buffer(_)));
+}
+
+#[test]
+fn decode_inval_id() {
+    let mut buf = table(&[1]).encode().unwrap();
+    let n = buf.len();
+    buf[n - 6] = 3;
+
+    let err = Snapshots::<MemoryBackend>::decode(&buf).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::InvalidId));
+}
+
+#[test]
+fn index_chained_blocks() {
+    let table = table(&[1, 2]).encode().unwrap();
+    let n = index_blocks::<MemoryBackend>(&table, 27).unwrap();
+
+    // 8 bytes magic, 5 bytes next id, 4 bytes chunk length, 10 bytes chunk
+    assert_eq!(n, 4);
+
+    let ids = (0..n as u32).map(|n| id(n + 7)).collect::<Vec<_>>();
+    let blocks = encode_index::<MemoryBackend>(&table, &ids, 27).unwrap();
+    let mut out = vec![];
+
+    assert_eq!(blocks.len(), n);
+    assert_eq!(blocks[0][..8], *b"nuts-snp");
+
+    for (idx, block) in blocks.iter().enumerate() {
+        let (chunk, next) = decode_index::<MemoryBackend>(block).unwrap();
+
+        assert!(block.len() <= 27);
+        assert_eq!(next.as_ref(), ids.get(idx + 1));
+
+        out.extend_from_slice(&chunk);
+    }
+
+    assert_eq!(out, table);
+}
+
+#[test]
+fn index_inval_block_size() {
+    let table = table(&[1]).encode().unwrap();
+    let err = index_blocks::<MemoryBackend>(&table, 17).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::InvalidBlockSize(n) if n == 17));
+}
+
+#[test]
+fn decode_index_inval_magic() {
+    let table = table(&[1]).encode().unwrap();
+    let mut blocks = encode_index::<MemoryBackend>(&table, &[id(7)], 512).unwrap();
+    blocks[0][0] += 1;
+
+    let err = decode_index::<MemoryBackend>(&blocks[0]).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::InvalidMagic));
+}
@@ -0,0 +1,427 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+mod tree;
+
+use log::debug;
+use nuts_backend::{Backend, IdSize};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::{cmp, mem};
+use thiserror::Error;
+
+use crate::buffer::{Buffer, BufferError, BufferMut};
+use crate::error::Error;
+use crate::migrate::Migration;
+use crate::service::{Service, ServiceFactory};
+use crate::stream::tree::{get_id, ids_per_node, put_id, Tree, NUM_DIRECT};
+use crate::Container;
+
+const SID: u32 = 0x73 << 24 | 0x74 << 16 | 0x72 << 8 | 0x6D; // "strm"
+const MAGIC: [u8; 11] = *b"nuts-stream";
+
+/// Number of ids stored in the super-block: the direct blocks and the
+/// indirect, double and triple indirect nodes.
+const NUM_IDS: usize = NUM_DIRECT + 3;
+
+/// Error type of the [`Stream`].
+#[derive(Debug, Error)]
+pub enum StreamError<B: Backend> {
+    /// An error occured in the underlaying container.
+    #[error(transparent)]
+    Container(#[from] Error<B>),
+
+    /// Errors while (de-) serializing the super-block or a node.
+    #[error(transparent)]
+    Buffer(#[from] BufferError),
+
+    /// The container has no top-id, where the super-block is stored.
+    #[error("no top-id available")]
+    NoTopId,
+
+    /// The super-block of the stream is invalid.
+    #[error("the super-block of the stream is invalid")]
+    InvalidSuperBlock,
+
+    /// A node of the block index is invalid.
+    #[error("not a stream node: {0}")]
+    InvalidNode(String),
+
+    /// An invalid id was read from the super-block or a node.
+    #[error("invalid id")]
+    InvalidId,
+
+    /// The block size of the container is too small to store the
+    /// super-block or the nodes of the block index.
+    #[error("the block size of {0} bytes is too small")]
+    InvalidBlockSize(u32),
+
+    /// Cannot aquire another block, the stream is full.
+    #[error("the stream is full")]
+    Full,
+}
+
+pub type StreamResult<T, B> = Result<T, StreamError<B>>;
+
+/// The [migration](Migration) of the [`Stream`] service.
+///
+/// A stream was never stored in a revision 0 container, the migration
+/// fails.
+pub struct StreamMigration;
+
+impl Migration for StreamMigration {
+    fn migrate_rev0(&self, _userdata: &[u8]) -> Result<(u32, Vec<u8>), String> {
+        Err("a revision 0 container does not store a stream".to_string())
+    }
+}
+
+/// A byte stream stored in a container.
+///
+/// The `Stream` is a [service](Service), which presents a [`Container`] as a
+/// resizable stream of bytes. It implements the [`Read`], [`Write`] and
+/// [`Seek`] traits, thus it can be used like a [file](std::fs::File).
+///
+/// The stream keeps its own index of data blocks. The _super-block_, which is
+/// referenced by the _top-id_ of the container, stores the length of the
+/// stream and references the first data blocks directly. Further data blocks
+/// are referenced by an indirect, a double and a triple indirect node.
+///
+/// Create a stream with [`Container::create_service`] and open it again with
+/// [`Container::open_service`]. All changes are written immediately into the
+/// container, [`Write::flush`] flushes the
+/// [write-back cache](crate::OpenOptionsBuilder::with_write_back) of the
+/// container.
+#[derive(Debug)]
+pub struct Stream<B: Backend> {
+    container: Container<B>,
+    top_id: B::Id,
+    tree: Tree<B>,
+    len: u64,
+    pos: u64,
+    buf: Vec<u8>,
+}
+
+impl<B: Backend> Stream<B> {
+    fn new(container: Container<B>, tree: Tree<B>, len: u64) -> StreamResult<Stream<B>, B> {
+        let top_id = container.top_id().cloned().ok_or(StreamError::NoTopId)?;
+        let bsize = container.block_size();
+
+        if (bsize as usize) < Self::super_block_size() || ids_per_node::<B>(bsize) < 2 {
+            return Err(StreamError::InvalidBlockSize(bsize));
+        }
+
+        Ok(Stream {
+            container,
+            top_id,
+            tree,
+            len,
+            pos: 0,
+            buf: vec![0; bsize as usize],
+        })
+    }
+
+    fn super_block_size() -> usize {
+        MAGIC.len() + 2 * mem::size_of::<u64>() + 1 + NUM_IDS * B::Id::size()
+    }
+
+    /// Returns the length of the stream in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the stream has a length of zero bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Truncates or extends the stream.
+    ///
+    /// If `len` is less than the current length, the stream is truncated and
+    /// data blocks, which are not needed anymore, are released. If `len` is
+    /// greater than the current length, the stream is extended and the new
+    /// bytes are filled with zeros.
+    ///
+    /// Like [`File::set_len`](std::fs::File::set_len) the position of the
+    /// stream is not changed.
+    ///
+    /// # Errors
+    ///
+    /// Errors are listed in the [`StreamError`] type.
+    pub fn set_len(&mut self, len: u64) -> StreamResult<(), B> {
+        let bsize = self.buf.len() as u64;
+        let nblocks = match len % bsize {
+            0 => len / bsize,
+            _ => len / bsize + 1,
+        };
+
+        while self.tree.nblocks() > nblocks {
+            self.tree.release(&mut self.container)?;
+        }
+
+        while self.tree.nblocks() < nblocks {
+            // aquired blocks are filled with zeros
+            self.tree.aquire(&mut self.container)?;
+        }
+
+        let off = (len % bsize) as usize;
+
+        if len < self.len && off > 0 {
+            // wipe the truncated bytes, they appear again when extending
+            // the stream later
+            let id = self.tree.lookup(&mut self.container, len / bsize)?;
+
+            self.container.read(&id, &mut self.buf)?;
+            self.buf[off..].iter_mut().for_each(|n| *n = 0);
+            self.container.write(&id, &self.buf)?;
+        }
+
+        debug!("set_len: {} => {}", self.len, len);
+
+        self.len = len;
+        self.flush_super_block()
+    }
+
+    /// Returns the underlaying container.
+    pub fn container(&self) -> &Container<B> {
+        &self.container
+    }
+
+    /// Consumes the stream, returning the underlaying container.
+    pub fn into_container(self) -> Container<B> {
+        self.container
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> StreamResult<usize, B> {
+        let bsize = self.buf.len() as u64;
+        let mut nread = 0;
+
+        while nread < buf.len() && self.pos < self.len {
+            let off = (self.pos % bsize) as usize;
+            let n = cmp::min(buf.len() - nread, self.buf.len() - off);
+            let n = cmp::min(n as u64, self.len - self.pos) as usize;
+
+            let id = self.tree.lookup(&mut self.container, self.pos / bsize)?;
+            self.container.read(&id, &mut self.buf)?;
+
+            buf[nread..nread + n].copy_from_slice(&self.buf[off..off + n]);
+
+            nread += n;
+            self.pos += n as u64;
+        }
+
+        Ok(nread)
+    }
+
+    fn write_bytes(&mut self, buf: &[u8]) -> StreamResult<usize, B> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.pos > self.len {
+            // fill the gap with zeros
+            self.set_len(self.pos)?;
+        }
+
+        let bsize = self.buf.len() as u64;
+        let mut nwritten = 0;
+
+        while nwritten < buf.len() {
+            let idx = self.pos / bsize;
+            let off = (self.pos % bsize) as usize;
+            let n = cmp::min(buf.len() - nwritten, self.buf.len() - off);
+            let data = &buf[nwritten..nwritten + n];
+
+            let id = if idx < self.tree.nblocks() {
+                let id = self.tree.lookup(&mut self.container, idx)?;
+
+                if n < self.buf.len() {
+                    self.container.read(&id, &mut self.buf)?;
+                }
+
+                id
+            } else {
+                self.buf.iter_mut().for_each(|n| *n = 0);
+                self.tree.aquire(&mut self.container)?
+            };
+
+            if n < self.buf.len() {
+                self.buf[off..off + n].copy_from_slice(data);
+                self.container.write(&id, &self.buf)?;
+            } else {
+                self.container.write(&id, data)?;
+            }
+
+            nwritten += n;
+            self.pos += n as u64;
+        }
+
+        if self.pos > self.len {
+            self.len = self.pos;
+            self.flush_super_block()?;
+        }
+
+        Ok(nwritten)
+    }
+
+    fn seek_to(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        fn add(base: u64, off: i64) -> Option<u64> {
+            if off >= 0 {
+                base.checked_add(off as u64)
+            } else {
+                base.checked_sub(off.unsigned_abs())
+            }
+        }
+
+        let pos = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(off) => add(self.len, off),
+            SeekFrom::Current(off) => add(self.pos, off),
+        };
+
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn load_super_block(container: &mut Container<B>) -> StreamResult<(Tree<B>, u64), B> {
+        let top_id = container.top_id().cloned().ok_or(StreamError::NoTopId)?;
+        let mut buf = vec![0; container.block_size() as usize];
+
+        container.read(&top_id, &mut buf)?;
+
+        let buf = &mut buf.as_slice();
+
+        if buf.get_array::<11>()? != MAGIC {
+            return Err(StreamError::InvalidSuperBlock);
+        }
+
+        let len = buf.get_u64()?;
+        let nblocks = buf.get_u64()?;
+        let nids = buf.get_u8()? as usize;
+
+        if nids > NUM_IDS {
+            return Err(StreamError::InvalidSuperBlock);
+        }
+
+        let ids = (0..nids)
+            .map(|_| get_id::<B, _>(buf))
+            .collect::<Result<_, _>>()?;
+
+        Ok((Tree::new(ids, nblocks), len))
+    }
+
+    fn flush_super_block(&mut self) -> StreamResult<(), B> {
+        let mut buf = vec![];
+
+        buf.put_chunk(&MAGIC)?;
+        buf.put_u64(self.len)?;
+        buf.put_u64(self.tree.nblocks())?;
+        buf.put_u8(self.tree.ids().len() as u8)?;
+
+        for id in self.tree.ids() {
+            put_id::<B, _>(&mut buf, id)?;
+        }
+
+        self.container.write(&self.top_id, &buf)?;
+
+        Ok(())
+    }
+}
+
+impl<B: Backend> Read for Stream<B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_bytes(buf).map_err(into_io_error)
+    }
+}
+
+impl<B: Backend> Write for Stream<B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_bytes(buf).map_err(into_io_error)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.container
+            .flush()
+            .map_err(|err| into_io_error(err.into()))
+    }
+}
+
+impl<B: Backend> Seek for Stream<B> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.seek_to(pos)
+    }
+}
+
+fn into_io_error<B: Backend>(err: StreamError<B>) -> io::Error {
+    let kind = match err {
+        StreamError::Buffer(_)
+        | StreamError::InvalidSuperBlock
+        | StreamError::InvalidNode(_)
+        | StreamError::InvalidId => io::ErrorKind::InvalidData,
+        _ => io::ErrorKind::Other,
+    };
+
+    io::Error::new(kind, err.to_string())
+}
+
+impl<B: Backend> Service<B> for Stream<B> {
+    type Migration = StreamMigration;
+
+    fn sid() -> u32 {
+        SID
+    }
+
+    fn need_top_id() -> bool {
+        true
+    }
+
+    fn migration() -> StreamMigration {
+        StreamMigration
+    }
+}
+
+impl<B: Backend> ServiceFactory<B> for Stream<B> {
+    type Service = Self;
+    type Err = StreamError<B>;
+
+    fn create(container: Container<B>) -> StreamResult<Self, B> {
+        let mut stream = Stream::new(container, Tree::new(vec![], 0), 0)?;
+
+        stream.flush_super_block()?;
+
+        Ok(stream)
+    }
+
+    fn open(mut container: Container<B>) -> StreamResult<Self, B> {
+        let (tree, len) = Self::load_super_block(&mut container)?;
+
+        Stream::new(container, tree, len)
+    }
+}
@@ -0,0 +1,129 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_memory::MemoryBackend;
+use std::io::{Seek, SeekFrom};
+
+use crate::stream::{Stream, StreamError, MAGIC};
+use crate::{Cipher, Container, CreateOptionsBuilder, ServiceFactory};
+
+fn setup_container(bsize: u32) -> Container<MemoryBackend> {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::create(MemoryBackend::new_with_bsize(bsize), options).unwrap();
+
+    Container::create_service::<Stream<MemoryBackend>>(container)
+        .unwrap()
+        .into_container()
+}
+
+fn super_block(container: &mut Container<MemoryBackend>) -> Vec<u8> {
+    let top_id = *container.top_id().unwrap();
+    let mut buf = vec![0; container.block_size() as usize];
+
+    container.read(&top_id, &mut buf).unwrap();
+
+    buf
+}
+
+#[test]
+fn super_block_size() {
+    // magic + len + nblocks + nids + 15 ids
+    assert_eq!(
+        Stream::<MemoryBackend>::super_block_size(),
+        11 + 8 + 8 + 1 + 60
+    );
+}
+
+#[test]
+fn create() {
+    let mut container = setup_container(512);
+    let buf = super_block(&mut container);
+
+    assert_eq!(buf[..11], MAGIC);
+    assert_eq!(buf[11..], [0; 501]);
+}
+
+#[test]
+fn create_inval_block_size() {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::create(MemoryBackend::new_with_bsize(87), options).unwrap();
+    let err = Container::create_service::<Stream<MemoryBackend>>(container).unwrap_err();
+
+    assert!(matches!(err, StreamError::InvalidBlockSize(87)));
+}
+
+#[test]
+fn open() {
+    let container = setup_container(512);
+    let stream = Stream::open(container).unwrap();
+
+    assert_eq!(stream.len(), 0);
+    assert!(stream.is_empty());
+    assert!(stream.tree.ids().is_empty());
+}
+
+#[test]
+fn open_inval_magic() {
+    let mut container = setup_container(512);
+    let top_id = *container.top_id().unwrap();
+
+    container.write(&top_id, b"nuts-strean").unwrap();
+
+    let err = Stream::open(container).unwrap_err();
+    assert!(matches!(err, StreamError::InvalidSuperBlock));
+}
+
+#[test]
+fn open_inval_nids() {
+    let mut container = setup_container(512);
+    let top_id = *container.top_id().unwrap();
+    let mut buf = super_block(&mut container);
+
+    buf[27] = 16;
+    container.write(&top_id, &buf).unwrap();
+
+    let err = Stream::open(container).unwrap_err();
+    assert!(matches!(err, StreamError::InvalidSuperBlock));
+}
+
+#[test]
+fn seek() {
+    let container = setup_container(512);
+    let mut stream = Stream::open(container).unwrap();
+
+    stream.set_len(10).unwrap();
+
+    assert_eq!(stream.seek(SeekFrom::Start(3)).unwrap(), 3);
+    assert_eq!(stream.seek(SeekFrom::Current(2)).unwrap(), 5);
+    assert_eq!(stream.seek(SeekFrom::Current(-5)).unwrap(), 0);
+    assert_eq!(stream.seek(SeekFrom::End(0)).unwrap(), 10);
+    assert_eq!(stream.seek(SeekFrom::End(-4)).unwrap(), 6);
+    assert_eq!(stream.seek(SeekFrom::End(5)).unwrap(), 15);
+
+    stream.seek(SeekFrom::Current(-16)).unwrap_err();
+    stream.seek(SeekFrom::End(-11)).unwrap_err();
+    assert_eq!(stream.stream_position().unwrap(), 15);
+}
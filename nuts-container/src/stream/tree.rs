@@ -0,0 +1,310 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use log::debug;
+use nuts_backend::{Backend, Binary, IdSize};
+use std::mem;
+
+use crate::buffer::{Buffer, BufferMut};
+use crate::stream::{StreamError, StreamResult};
+use crate::Container;
+
+const NODE_MAGIC: [u8; 4] = *b"node";
+
+/// Number of blocks directly referenced by the tree.
+pub const NUM_DIRECT: usize = 12;
+
+/// Number of indirection levels (indirect, double and triple indirect).
+const NUM_LEVELS: usize = 3;
+
+/// Number of ids, which fit into a node.
+pub fn ids_per_node<B: Backend>(bsize: u32) -> usize {
+    (bsize as usize).saturating_sub(NODE_MAGIC.len() + mem::size_of::<u32>()) / B::Id::size()
+}
+
+/// Reads an id from `buf`.
+pub fn get_id<B: Backend, T: Buffer>(buf: &mut T) -> StreamResult<B::Id, B> {
+    let bytes = buf.get_chunk(B::Id::size())?;
+
+    B::Id::from_bytes(bytes).ok_or(StreamError::InvalidId)
+}
+
+/// Writes `id` into `buf`.
+pub fn put_id<B: Backend, T: BufferMut>(buf: &mut T, id: &B::Id) -> StreamResult<(), B> {
+    Ok(buf.put_chunk(&id.as_bytes())?)
+}
+
+/// A node of the tree, which stores the ids of its children.
+#[derive(Debug)]
+struct Node<B: Backend> {
+    id: B::Id,
+    ids: Vec<B::Id>,
+}
+
+impl<B: Backend> Node<B> {
+    fn load(container: &mut Container<B>, id: &B::Id) -> StreamResult<Node<B>, B> {
+        let mut buf = vec![0; container.block_size() as usize];
+
+        container.read(id, &mut buf)?;
+
+        let buf = &mut buf.as_slice();
+
+        if buf.get_array::<4>()? != NODE_MAGIC {
+            return Err(StreamError::InvalidNode(id.to_string()));
+        }
+
+        let count = buf.get_u32()?;
+        let ids = (0..count)
+            .map(|_| get_id::<B, _>(buf))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Node {
+            id: id.clone(),
+            ids,
+        })
+    }
+
+    fn flush(&self, container: &mut Container<B>) -> StreamResult<(), B> {
+        let mut buf = vec![];
+
+        buf.put_chunk(&NODE_MAGIC)?;
+        buf.put_u32(self.ids.len() as u32)?;
+
+        for id in self.ids.iter() {
+            put_id::<B, _>(&mut buf, id)?;
+        }
+
+        container.write(&self.id, &buf)?;
+
+        Ok(())
+    }
+}
+
+/// Index of the data blocks of a stream.
+///
+/// The first [`NUM_DIRECT`] blocks are referenced directly. The following
+/// blocks are referenced by an indirect node, the next ones by a double and
+/// finally by a triple indirect node. Blocks are always appended to resp.
+/// removed from the end of the tree.
+#[derive(Debug)]
+pub struct Tree<B: Backend> {
+    ids: Vec<B::Id>,
+    nblocks: u64,
+    cache: Vec<Option<Node<B>>>,
+}
+
+impl<B: Backend> Tree<B> {
+    pub fn new(ids: Vec<B::Id>, nblocks: u64) -> Tree<B> {
+        Tree {
+            ids,
+            nblocks,
+            cache: (0..NUM_LEVELS).map(|_| None).collect(),
+        }
+    }
+
+    /// The ids of the direct blocks followed by the ids of the indirect
+    /// nodes.
+    pub fn ids(&self) -> &[B::Id] {
+        &self.ids
+    }
+
+    pub fn nblocks(&self) -> u64 {
+        self.nblocks
+    }
+
+    /// Looks up the id of the data block `idx`.
+    pub fn lookup(&mut self, container: &mut Container<B>, idx: u64) -> StreamResult<B::Id, B> {
+        assert!(idx < self.nblocks);
+
+        let (slot, path) = self.path(container, idx)?;
+        let mut id = self.ids[slot].clone();
+
+        for (level, pos) in path.iter().enumerate() {
+            let node = self.node(container, level, &id)?;
+
+            id = match node.ids.get(*pos) {
+                Some(id) => id.clone(),
+                None => return Err(StreamError::InvalidNode(node.id.to_string())),
+            };
+        }
+
+        Ok(id)
+    }
+
+    /// Appends a new data block to the tree.
+    pub fn aquire(&mut self, container: &mut Container<B>) -> StreamResult<B::Id, B> {
+        let (slot, path) = self.path(container, self.nblocks)?;
+
+        if slot == self.ids.len() {
+            let id = match path.is_empty() {
+                true => container.aquire()?,
+                false => Self::aquire_node(container)?,
+            };
+
+            self.ids.push(id);
+        }
+
+        let mut id = self.ids[slot].clone();
+
+        for (level, pos) in path.iter().enumerate() {
+            let child = match self.node(container, level, &id)?.ids.get(*pos) {
+                Some(child) => child.clone(),
+                None => {
+                    let child = match level + 1 == path.len() {
+                        true => container.aquire()?,
+                        false => Self::aquire_node(container)?,
+                    };
+
+                    let node = self.node(container, level, &id)?;
+
+                    node.ids.push(child.clone());
+                    node.flush(container)?;
+
+                    child
+                }
+            };
+
+            id = child;
+        }
+
+        self.nblocks += 1;
+
+        debug!("aquire: nblocks={} => {}", self.nblocks, id);
+
+        Ok(id)
+    }
+
+    /// Removes the last data block from the tree and releases it. Nodes,
+    /// which become empty, are released as well.
+    pub fn release(&mut self, container: &mut Container<B>) -> StreamResult<(), B> {
+        assert!(self.nblocks > 0);
+
+        let (slot, path) = self.path(container, self.nblocks - 1)?;
+        let mut ids = vec![self.ids[slot].clone()];
+
+        for (level, pos) in path.iter().enumerate() {
+            let node = self.node(container, level, &ids[level])?;
+
+            match node.ids.get(*pos) {
+                Some(id) => ids.push(id.clone()),
+                None => return Err(StreamError::InvalidNode(node.id.to_string())),
+            }
+        }
+
+        // the data block is the last id
+        let mut release = ids.pop();
+
+        for level in (0..path.len()).rev() {
+            if let Some(id) = release.take() {
+                container.release(id)?;
+            }
+
+            let node = self.node(container, level, &ids[level])?;
+
+            node.ids.pop();
+
+            if node.ids.is_empty() {
+                release = Some(node.id.clone());
+                self.cache[level] = None;
+            } else {
+                node.flush(container)?;
+                break;
+            }
+        }
+
+        if let Some(id) = release {
+            container.release(id)?;
+            self.ids.pop();
+        }
+
+        self.nblocks -= 1;
+
+        debug!("release: nblocks={}", self.nblocks);
+
+        Ok(())
+    }
+
+    fn aquire_node(container: &mut Container<B>) -> StreamResult<B::Id, B> {
+        let id = container.aquire()?;
+
+        Node {
+            id: id.clone(),
+            ids: vec![],
+        }
+        .flush(container)?;
+
+        Ok(id)
+    }
+
+    /// Returns the node `id` of the given `level`.
+    fn node(
+        &mut self,
+        container: &mut Container<B>,
+        level: usize,
+        id: &B::Id,
+    ) -> StreamResult<&mut Node<B>, B> {
+        let cached = matches!(&self.cache[level], Some(node) if node.id == *id);
+
+        if !cached {
+            self.cache[level] = Some(Node::load(container, id)?);
+        }
+
+        Ok(self.cache[level].as_mut().unwrap())
+    }
+
+    /// Evaluates the path to the data block `idx`.
+    ///
+    /// Returns the slot in [`Self::ids`] followed by the positions in the
+    /// nodes on the way to the data block.
+    fn path(&self, container: &Container<B>, idx: u64) -> StreamResult<(usize, Vec<usize>), B> {
+        let ipn = ids_per_node::<B>(container.block_size()) as u64;
+        let mut idx = idx;
+
+        if idx < NUM_DIRECT as u64 {
+            return Ok((idx as usize, vec![]));
+        }
+
+        idx -= NUM_DIRECT as u64;
+
+        let mut nids = 1;
+
+        for level in 1..=NUM_LEVELS {
+            nids *= ipn;
+
+            if idx < nids {
+                let path = (0..level)
+                    .rev()
+                    .map(|n| ((idx / ipn.pow(n as u32)) % ipn) as usize)
+                    .collect();
+
+                return Ok((NUM_DIRECT + level - 1, path));
+            }
+
+            idx -= nids;
+        }
+
+        Err(StreamError::Full)
+    }
+}
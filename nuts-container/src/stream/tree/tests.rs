@@ -0,0 +1,160 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::Backend;
+use nuts_memory::{Id, MemoryBackend};
+
+use crate::stream::tree::{ids_per_node, Tree};
+use crate::stream::StreamError;
+use crate::{Cipher, Container, CreateOptionsBuilder};
+
+// 12 direct, 22 indirect, 484 double indirect blocks
+const BSIZE: u32 = 96;
+const IPN: u64 = 22;
+
+fn setup_container() -> Container<MemoryBackend> {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::create(MemoryBackend::new_with_bsize(BSIZE), options).unwrap()
+}
+
+fn nblocks(container: Container<MemoryBackend>) -> usize {
+    container.into_backend().ids().unwrap().unwrap().len()
+}
+
+#[test]
+fn ids_per_node_bsize() {
+    assert_eq!(ids_per_node::<MemoryBackend>(BSIZE), IPN as usize);
+    assert_eq!(ids_per_node::<MemoryBackend>(12), 1);
+    assert_eq!(ids_per_node::<MemoryBackend>(11), 0);
+    assert_eq!(ids_per_node::<MemoryBackend>(0), 0);
+}
+
+#[test]
+fn path() {
+    let container = setup_container();
+    let tree = Tree::<MemoryBackend>::new(vec![], 0);
+
+    assert_eq!(tree.path(&container, 0).unwrap(), (0, vec![]));
+    assert_eq!(tree.path(&container, 11).unwrap(), (11, vec![]));
+    assert_eq!(tree.path(&container, 12).unwrap(), (12, vec![0]));
+    assert_eq!(tree.path(&container, 12 + IPN - 1).unwrap(), (12, vec![21]));
+    assert_eq!(tree.path(&container, 12 + IPN).unwrap(), (13, vec![0, 0]));
+    assert_eq!(
+        tree.path(&container, 12 + IPN + 23).unwrap(),
+        (13, vec![1, 1])
+    );
+    assert_eq!(
+        tree.path(&container, 12 + IPN + IPN * IPN - 1).unwrap(),
+        (13, vec![21, 21])
+    );
+    assert_eq!(
+        tree.path(&container, 12 + IPN + IPN * IPN).unwrap(),
+        (14, vec![0, 0, 0])
+    );
+    assert_eq!(
+        tree.path(&container, 12 + IPN + IPN * IPN + IPN * IPN * IPN - 1)
+            .unwrap(),
+        (14, vec![21, 21, 21])
+    );
+
+    let err = tree
+        .path(&container, 12 + IPN + IPN * IPN + IPN * IPN * IPN)
+        .unwrap_err();
+    assert!(matches!(err, StreamError::Full));
+}
+
+#[test]
+fn aquire_lookup() {
+    let mut container = setup_container();
+    let mut tree = Tree::<MemoryBackend>::new(vec![], 0);
+    let n = 12 + IPN + IPN * IPN + 3;
+
+    let ids = (0..n)
+        .map(|_| tree.aquire(&mut container).unwrap())
+        .collect::<Vec<Id>>();
+
+    assert_eq!(tree.nblocks(), n);
+    assert_eq!(tree.ids().len(), 15);
+
+    for (idx, id) in ids.iter().enumerate() {
+        assert_eq!(tree.lookup(&mut container, idx as u64).unwrap(), *id);
+    }
+
+    // reload the tree without any cached nodes
+    let mut tree = Tree::<MemoryBackend>::new(tree.ids().to_vec(), n);
+
+    for (idx, id) in ids.iter().enumerate().rev() {
+        assert_eq!(tree.lookup(&mut container, idx as u64).unwrap(), *id);
+    }
+
+    // data blocks + 1 indirect + (1 + 22) double indirect + 3 triple indirect
+    assert_eq!(nblocks(container), n as usize + 1 + 23 + 3);
+}
+
+#[test]
+fn release() {
+    let mut container = setup_container();
+    let mut tree = Tree::<MemoryBackend>::new(vec![], 0);
+    let n = 12 + IPN + IPN * IPN + 3;
+
+    let ids = (0..n)
+        .map(|_| tree.aquire(&mut container).unwrap())
+        .collect::<Vec<Id>>();
+
+    while tree.nblocks() > 12 + IPN + 1 {
+        tree.release(&mut container).unwrap();
+    }
+
+    assert_eq!(tree.ids().len(), 14);
+
+    for (idx, id) in ids.iter().take(12 + IPN as usize + 1).enumerate() {
+        assert_eq!(tree.lookup(&mut container, idx as u64).unwrap(), *id);
+    }
+
+    while tree.nblocks() > 0 {
+        tree.release(&mut container).unwrap();
+    }
+
+    assert!(tree.ids().is_empty());
+    assert_eq!(nblocks(container), 0);
+}
+
+#[test]
+fn invalid_node() {
+    let mut container = setup_container();
+    let mut tree = Tree::<MemoryBackend>::new(vec![], 0);
+
+    for _ in 0..13 {
+        tree.aquire(&mut container).unwrap();
+    }
+
+    let indirect = tree.ids()[12];
+    container.write(&indirect, b"xxx").unwrap();
+
+    let mut tree = Tree::<MemoryBackend>::new(tree.ids().to_vec(), 13);
+    let err = tree.lookup(&mut container, 12).unwrap_err();
+
+    assert!(matches!(err, StreamError::InvalidNode(id) if id == indirect.to_string()));
+}
@@ -20,45 +20,238 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use std::fmt;
+mod region;
+#[cfg(test)]
+mod tests;
+
 use std::ops::{Deref, DerefMut};
+use std::{cmp, fmt};
+
+use crate::svec::region::{wipe_vec, Region};
+
+/// A byte buffer for secrets.
+///
+/// The data are stored in a dedicated memory region, which is wiped, when
+/// the buffer is dropped. By default the region is allocated on the heap.
+/// Long-lived key material should be moved into a
+/// [guarded](SecureVec::into_guarded) region: its pages are locked into
+/// memory and surrounded by guard pages (see the `region` module).
+///
+/// Unlike a `Vec<u8>` the buffer is never reallocated behind your back. If
+/// the buffer needs to grow beyond its [capacity](SecureVec::capacity), the
+/// data are moved into a new region and the old region is wiped
+/// immediately. Reserve the final size with [`SecureVec::with_capacity`] to
+/// avoid the move at all.
+///
+/// Converting a `Vec<u8>` into a `SecureVec` copies the data into a heap
+/// region and wipes the buffer of the vector. Only
+/// [`SecureVec::into_guarded`] moves the data into a locked region.
+pub struct SecureVec {
+    region: Region,
+    len: usize,
+}
+
+impl SecureVec {
+    /// Creates an empty buffer, nothing is allocated.
+    pub const fn new() -> SecureVec {
+        SecureVec {
+            region: Region::empty(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty buffer with room for at least `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> SecureVec {
+        SecureVec {
+            region: Region::new(capacity),
+            len: 0,
+        }
+    }
+
+    /// Creates a buffer of `len` zeros.
+    pub fn zeroed(len: usize) -> SecureVec {
+        SecureVec {
+            region: Region::new(len),
+            len,
+        }
+    }
+
+    /// Creates a buffer of `len` zeros in a guarded region.
+    ///
+    /// Use it for key material, which is generated resp. decrypted in place,
+    /// thus it never touches the heap. See [`SecureVec::into_guarded`] for
+    /// details about guarded regions.
+    pub fn zeroed_guarded(len: usize) -> SecureVec {
+        SecureVec {
+            region: Region::mapped(len),
+            len,
+        }
+    }
+
+    /// Copies `buf` into a guarded region.
+    pub fn from_guarded(buf: &[u8]) -> SecureVec {
+        let mut vec = SecureVec::zeroed_guarded(buf.len());
+
+        vec.copy_from_slice(buf);
+        vec
+    }
+
+    /// Moves the data into a guarded region, the previous region is wiped.
+    ///
+    /// A guarded region is locked into memory and surrounded by guard
+    /// pages. Every guarded region occupies kernel mappings of its own,
+    /// thus only long-lived key material should be guarded. If the region
+    /// cannot be created, the data stay on the heap and a warning is
+    /// logged.
+    ///
+    /// An empty buffer stays guarded: once it grows, its data are placed
+    /// into a guarded region.
+    pub fn into_guarded(self) -> SecureVec {
+        if self.is_guarded() {
+            return self;
+        }
+
+        self.copy_into(Region::mapped(self.len))
+    }
+
+    /// Tests whether the data are stored in a guarded region.
+    pub fn is_guarded(&self) -> bool {
+        self.region.is_mapped()
+    }
+
+    /// Copies the data into `region`, which must be large enough.
+    fn copy_into(&self, mut region: Region) -> SecureVec {
+        region.as_mut_slice()[..self.len].copy_from_slice(self.as_slice());
+
+        SecureVec {
+            region,
+            len: self.len,
+        }
+    }
+
+    /// Returns the number of bytes the buffer can hold without moving into
+    /// a new region.
+    pub fn capacity(&self) -> usize {
+        self.region.len()
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.region.as_slice()[..self.len]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.region.as_mut_slice()[..self.len]
+    }
 
-#[derive(Clone, PartialEq)]
-pub struct SecureVec(Vec<u8>);
+    /// Makes sure, that `additional` more bytes fit into the buffer.
+    ///
+    /// If the capacity is exhausted, the data are moved into a new region,
+    /// the old region is wiped.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len + additional;
+
+        if required > self.capacity() {
+            let mut region = self
+                .region
+                .new_like(cmp::max(required, 2 * self.capacity()));
+
+            region.as_mut_slice()[..self.len].copy_from_slice(self.as_slice());
+            self.region = region;
+        }
+    }
+
+    /// Resizes the buffer to `new_len` bytes. New bytes are set to `value`.
+    pub fn resize(&mut self, new_len: usize, value: u8) {
+        if new_len > self.len {
+            self.reserve(new_len - self.len);
+            self.region.as_mut_slice()[self.len..new_len].fill(value);
+        } else {
+            self.region.as_mut_slice()[new_len..self.len].fill(0);
+        }
+
+        self.len = new_len;
+    }
+
+    /// Shortens the buffer to `len` bytes. The removed bytes are wiped.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.resize(len, 0);
+        }
+    }
+
+    /// Removes and wipes all bytes of the buffer. The capacity is kept.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Appends `value` to the buffer.
+    pub fn push(&mut self, value: u8) {
+        self.extend_from_slice(&[value]);
+    }
+
+    /// Appends `buf` to the buffer.
+    pub fn extend_from_slice(&mut self, buf: &[u8]) {
+        let len = self.len;
+
+        self.reserve(buf.len());
+        self.region.as_mut_slice()[len..len + buf.len()].copy_from_slice(buf);
+        self.len += buf.len();
+    }
+}
+
+impl Default for SecureVec {
+    fn default() -> Self {
+        SecureVec::new()
+    }
+}
+
+impl Clone for SecureVec {
+    fn clone(&self) -> Self {
+        self.copy_into(self.region.new_like(self.len))
+    }
+}
+
+impl PartialEq for SecureVec {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
 
 impl AsRef<[u8]> for SecureVec {
     fn as_ref(&self) -> &[u8] {
-        &self.0
+        self.as_slice()
     }
 }
 
 impl Deref for SecureVec {
-    type Target = Vec<u8>;
+    type Target = [u8];
 
-    fn deref(&self) -> &Vec<u8> {
-        &self.0
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
     }
 }
 
 impl DerefMut for SecureVec {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
     }
 }
 
-impl From<Vec<u8>> for SecureVec {
-    fn from(inner: Vec<u8>) -> Self {
-        SecureVec(inner)
+impl From<&[u8]> for SecureVec {
+    fn from(buf: &[u8]) -> Self {
+        let mut vec = SecureVec::with_capacity(buf.len());
+
+        vec.extend_from_slice(buf);
+        vec
     }
 }
 
-impl Drop for SecureVec {
-    fn drop(&mut self) {
-        self.0.resize(self.0.capacity(), 0);
+impl From<Vec<u8>> for SecureVec {
+    fn from(inner: Vec<u8>) -> Self {
+        let vec = SecureVec::from(inner.as_slice());
 
-        for elem in self.0.iter_mut() {
-            *elem = 0;
-        }
+        wipe_vec(inner);
+        vec
     }
 }
 
@@ -67,9 +260,9 @@ impl fmt::Debug for SecureVec {
         let mut dbg = fmt.debug_tuple("SecureVec");
 
         if cfg!(feature = "debug-plain-keys") {
-            dbg.field(&format!("<{} bytes>", self.0.len())).finish()
+            dbg.field(&self.as_slice()).finish()
         } else {
-            dbg.field(&self.0).finish()
+            dbg.field(&format!("<{} bytes>", self.len)).finish()
         }
     }
 }
@@ -0,0 +1,270 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+//! Memory regions for secrets.
+//!
+//! A region is either allocated on the heap or mapped separately from the
+//! heap. Both kinds are wiped, when the region is dropped.
+//!
+//! A mapped region is reserved for long-lived key material. On unix
+//! platforms the pages holding the data are locked into memory, thus they
+//! are never swapped to disk. The data pages are surrounded by inaccessible
+//! guard pages, an access beyond the region aborts the process instead of
+//! reading or overwriting neighbouring memory. Every mapped region costs
+//! several kernel mappings, which are limited (`vm.max_map_count` on
+//! Linux), so short-lived and bulk buffers like data blocks are allocated
+//! on the heap.
+//!
+//! Locking can fail, e.g. if `RLIMIT_MEMLOCK` is exceeded. The region is
+//! still usable then, a warning is logged once. If the region cannot be
+//! mapped at all, a warning is logged and the heap is used instead.
+//!
+//! Other platforms always use the heap.
+
+#[cfg(unix)]
+use log::warn;
+use std::alloc::{self, handle_alloc_error, Layout};
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::sync::atomic::{self, Ordering};
+
+/// Overwrites `buf` with zeros.
+///
+/// Volatile writes are used, so the compiler cannot optimize the wipe away.
+fn wipe(buf: &mut [u8]) {
+    for elem in buf.iter_mut() {
+        unsafe { ptr::write_volatile(elem, 0) };
+    }
+
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+mod sys {
+    use log::warn;
+    use std::io;
+    use std::ptr::{self, NonNull};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static LOCK_WARNING: AtomicBool = AtomicBool::new(false);
+
+    pub fn page_size() -> usize {
+        unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+    }
+
+    /// Maps `len` bytes of data pages surrounded by guard pages. `len` must
+    /// be a multiple of the page size. Returns a pointer to the first data
+    /// page.
+    pub fn map(len: usize) -> io::Result<NonNull<u8>> {
+        let page = page_size();
+        let total = len + 2 * page;
+
+        unsafe {
+            let base = libc::mmap(
+                ptr::null_mut(),
+                total,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+
+            if base == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            let base = base as *mut u8;
+            let data = base.add(page);
+
+            if libc::mprotect(base as *mut _, page, libc::PROT_NONE) != 0
+                || libc::mprotect(data.add(len) as *mut _, page, libc::PROT_NONE) != 0
+            {
+                let err = io::Error::last_os_error();
+
+                libc::munmap(base as *mut _, total);
+                return Err(err);
+            }
+
+            if libc::mlock(data as *const _, len) != 0
+                && !LOCK_WARNING.swap(true, Ordering::Relaxed)
+            {
+                warn!(
+                    "failed to lock memory, secrets might be swapped: {}",
+                    io::Error::last_os_error()
+                );
+            }
+
+            #[cfg(target_os = "linux")]
+            libc::madvise(data as *mut _, len, libc::MADV_DONTDUMP);
+
+            Ok(NonNull::new_unchecked(data))
+        }
+    }
+
+    /// Unmaps a region created with [`map`].
+    pub fn unmap(data: NonNull<u8>, len: usize) {
+        let page = page_size();
+
+        unsafe {
+            libc::munlock(data.as_ptr() as *const _, len);
+            libc::munmap(data.as_ptr().sub(page) as *mut _, len + 2 * page);
+        }
+    }
+}
+
+/// A fixed-size memory region for secrets.
+///
+/// The memory is wiped, when the region is dropped.
+pub struct Region {
+    data: NonNull<u8>,
+    len: usize,
+    mapped: bool,
+}
+
+impl Region {
+    /// Creates an empty region, nothing is allocated.
+    pub const fn empty() -> Region {
+        Region {
+            data: NonNull::dangling(),
+            len: 0,
+            mapped: false,
+        }
+    }
+
+    /// Creates a zero-filled region of `len` bytes on the heap.
+    pub fn new(len: usize) -> Region {
+        if len == 0 {
+            return Region::empty();
+        }
+
+        let layout = Layout::from_size_align(len, 1).unwrap();
+        let data = NonNull::new(unsafe { alloc::alloc_zeroed(layout) })
+            .unwrap_or_else(|| handle_alloc_error(layout));
+
+        Region {
+            data,
+            len,
+            mapped: false,
+        }
+    }
+
+    /// Creates a zero-filled, mapped region with room for at least `len`
+    /// bytes.
+    ///
+    /// The size of the region is a multiple of the page size. Falls back to
+    /// [`Region::new`], if the region cannot be mapped. An empty region is
+    /// not mapped, but a region created with [`Region::new_like`] is mapped
+    /// again.
+    #[cfg(unix)]
+    pub fn mapped(len: usize) -> Region {
+        if len == 0 {
+            return Region {
+                mapped: true,
+                ..Region::empty()
+            };
+        }
+
+        let len = match len % sys::page_size() {
+            0 => len,
+            n => len + sys::page_size() - n,
+        };
+
+        match sys::map(len) {
+            Ok(data) => Region {
+                data,
+                len,
+                mapped: true,
+            },
+            Err(err) => {
+                warn!(
+                    "failed to map memory for secrets, falling back to the heap: {}",
+                    err
+                );
+                Region::new(len)
+            }
+        }
+    }
+
+    /// Creates a zero-filled region of `len` bytes on the heap, mapped
+    /// regions are not supported on this platform.
+    #[cfg(not(unix))]
+    pub fn mapped(len: usize) -> Region {
+        Region::new(len)
+    }
+
+    /// Creates a zero-filled region of the same kind as `self` with room
+    /// for at least `len` bytes.
+    pub fn new_like(&self, len: usize) -> Region {
+        if self.mapped {
+            Region::mapped(len)
+        } else {
+            Region::new(len)
+        }
+    }
+
+    /// Tests whether the region is mapped separately from the heap.
+    pub fn is_mapped(&self) -> bool {
+        self.mapped
+    }
+
+    /// Returns the size of the region.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.data.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for Region {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        wipe(self.as_mut_slice());
+
+        #[cfg(unix)]
+        if self.mapped {
+            sys::unmap(self.data, self.len);
+            return;
+        }
+
+        let layout = Layout::from_size_align(self.len, 1).unwrap();
+        unsafe { alloc::dealloc(self.data.as_ptr(), layout) };
+    }
+}
+
+// The region is owned exclusively, like the buffer of a `Vec`.
+unsafe impl Send for Region {}
+unsafe impl Sync for Region {}
+
+/// Wipes the whole buffer of `vec` including its spare capacity.
+pub fn wipe_vec(mut vec: Vec<u8>) {
+    vec.resize(vec.capacity(), 0);
+    wipe(&mut vec);
+}
@@ -0,0 +1,237 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use crate::svec::SecureVec;
+
+fn page_size() -> usize {
+    SecureVec::from(&[1][..]).into_guarded().capacity()
+}
+
+#[test]
+fn new() {
+    let vec = SecureVec::new();
+
+    assert!(vec.is_empty());
+    assert_eq!(vec.capacity(), 0);
+}
+
+#[test]
+fn with_capacity() {
+    let vec = SecureVec::with_capacity(1);
+
+    assert!(vec.is_empty());
+    assert_eq!(vec.capacity(), 1);
+    assert!(!vec.is_guarded());
+}
+
+#[test]
+fn zeroed() {
+    let vec = SecureVec::zeroed(3);
+
+    assert_eq!(vec.as_slice(), [0; 3]);
+    assert_eq!(vec.capacity(), 3);
+    assert!(!vec.is_guarded());
+}
+
+#[test]
+fn zeroed_many() {
+    // Far more buffers than a process could map with guard pages
+    // (vm.max_map_count defaults to 65530 on Linux).
+    let vecs: Vec<SecureVec> = (0..70000).map(|_| SecureVec::zeroed(512)).collect();
+
+    assert!(vecs.iter().all(|vec| vec.as_slice() == [0; 512]));
+}
+
+#[test]
+fn into_guarded() {
+    let vec = SecureVec::from(&[1, 2, 3][..]).into_guarded();
+
+    assert_eq!(vec.as_slice(), [1, 2, 3]);
+    assert!(vec.is_guarded());
+    assert_eq!(vec.capacity(), page_size());
+}
+
+#[test]
+fn zeroed_guarded() {
+    let vec = SecureVec::zeroed_guarded(3);
+
+    assert_eq!(vec.as_slice(), [0; 3]);
+    assert!(vec.is_guarded());
+    assert_eq!(vec.capacity(), page_size());
+}
+
+#[test]
+fn from_guarded() {
+    let vec = SecureVec::from_guarded(&[1, 2, 3]);
+
+    assert_eq!(vec.as_slice(), [1, 2, 3]);
+    assert!(vec.is_guarded());
+}
+
+#[test]
+fn into_guarded_pages() {
+    let vec = SecureVec::zeroed(page_size() + 1).into_guarded();
+
+    assert_eq!(vec.len(), page_size() + 1);
+    assert_eq!(vec.capacity(), 2 * page_size());
+}
+
+#[test]
+fn into_guarded_empty() {
+    let mut vec = SecureVec::new().into_guarded();
+
+    assert!(vec.is_empty());
+    assert_eq!(vec.capacity(), 0);
+
+    vec.push(1);
+
+    assert!(vec.is_guarded());
+    assert_eq!(vec.capacity(), page_size());
+}
+
+#[test]
+fn from_slice() {
+    let vec = SecureVec::from(&[1, 2, 3][..]);
+
+    assert_eq!(vec.as_slice(), [1, 2, 3]);
+}
+
+#[test]
+fn from_vec() {
+    let vec = SecureVec::from(vec![1, 2, 3]);
+
+    assert_eq!(vec.as_slice(), [1, 2, 3]);
+}
+
+#[test]
+fn extend_in_place() {
+    let mut vec = SecureVec::with_capacity(7);
+    let ptr = vec.region.as_slice().as_ptr();
+
+    vec.extend_from_slice(&[1, 2, 3]);
+    vec.extend_from_slice(&[4, 5, 6]);
+    vec.push(7);
+
+    assert_eq!(vec.as_slice(), [1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(vec.region.as_slice().as_ptr(), ptr);
+}
+
+#[test]
+fn extend_move() {
+    let mut vec = SecureVec::zeroed(3);
+
+    vec[0] = 1;
+    vec.extend_from_slice(&[2]);
+
+    assert_eq!(vec.as_slice(), [1, 0, 0, 2]);
+    assert_eq!(vec.capacity(), 6);
+}
+
+#[test]
+fn extend_move_guarded() {
+    let mut vec = SecureVec::zeroed(page_size()).into_guarded();
+
+    vec[0] = 1;
+    vec.extend_from_slice(&[2]);
+
+    assert!(vec.is_guarded());
+    assert_eq!(vec.len(), page_size() + 1);
+    assert_eq!(vec.capacity(), 2 * page_size());
+    assert_eq!(vec[0], 1);
+    assert_eq!(vec[page_size()], 2);
+}
+
+#[test]
+fn reserve() {
+    let mut vec = SecureVec::from(&[1, 2, 3][..]);
+
+    vec.reserve(4);
+
+    assert_eq!(vec.as_slice(), [1, 2, 3]);
+    assert_eq!(vec.capacity(), 7);
+}
+
+#[test]
+fn resize() {
+    let mut vec = SecureVec::from(&[1, 2, 3][..]);
+
+    vec.resize(5, 9);
+    assert_eq!(vec.as_slice(), [1, 2, 3, 9, 9]);
+
+    vec.resize(2, 9);
+    assert_eq!(vec.as_slice(), [1, 2]);
+}
+
+#[test]
+fn truncate_wipes() {
+    let mut vec = SecureVec::from(&[1, 2, 3][..]);
+
+    vec.truncate(1);
+
+    assert_eq!(vec.as_slice(), [1]);
+    assert_eq!(vec.region.as_slice()[..3], [1, 0, 0]);
+}
+
+#[test]
+fn clear_wipes() {
+    let mut vec = SecureVec::from(&[1, 2, 3][..]);
+    let capacity = vec.capacity();
+
+    vec.clear();
+
+    assert!(vec.is_empty());
+    assert_eq!(vec.capacity(), capacity);
+    assert_eq!(vec.region.as_slice()[..3], [0, 0, 0]);
+}
+
+#[test]
+fn clone() {
+    let vec = SecureVec::from(&[1, 2, 3][..]);
+    let other = vec.clone();
+
+    assert_eq!(vec, other);
+    assert!(!other.is_guarded());
+    assert_ne!(
+        vec.region.as_slice().as_ptr(),
+        other.region.as_slice().as_ptr()
+    );
+}
+
+#[test]
+fn clone_guarded() {
+    let vec = SecureVec::from(&[1, 2, 3][..]).into_guarded();
+    let other = vec.clone();
+
+    assert_eq!(vec, other);
+    assert!(other.is_guarded());
+}
+
+#[test]
+fn debug() {
+    let vec = SecureVec::from(&[1, 2, 3][..]);
+
+    if cfg!(feature = "debug-plain-keys") {
+        assert_eq!(format!("{:?}", vec), "SecureVec([1, 2, 3])");
+    } else {
+        assert_eq!(format!("{:?}", vec), "SecureVec(\"<3 bytes>\")");
+    }
+}
@@ -20,11 +20,14 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
+mod cache;
 mod info;
 mod read;
+mod verify;
 mod write;
 
 const CTEXT_AES128_CTR: [u8; 512] = [
+    0x91, 0xc0, 0xb2, 0xcf, 0xe7, 0xd1, 0x1e, 0xe3, 0x19, 0x17, 0xc4, 0x48, 0xfa, 0xd5, 0x2f, 0x30,
     0xfb, 0x7b, 0x8e, 0xef, 0x3e, 0xff, 0xde, 0x50, 0x70, 0xaa, 0x93, 0x9c, 0xf1, 0x44, 0xa1, 0x12,
     0xad, 0x3f, 0x96, 0xb8, 0x26, 0xab, 0xb4, 0xd, 0x51, 0xad, 0x11, 0xdf, 0x16, 0x5d, 0x79, 0x5a,
     0xf4, 0x99, 0x3, 0x2a, 0x8c, 0x8d, 0x1, 0x2a, 0x7f, 0x67, 0x65, 0x9d, 0xab, 0xab, 0x9d, 0x5c,
@@ -56,42 +59,41 @@ const CTEXT_AES128_CTR: [u8; 512] = [
     0x58, 0x9c, 0xe6, 0x9c, 0x30, 0x7f, 0x33, 0x10, 0xf7, 0x8a, 0xd3, 0xc3, 0x2d, 0xff, 0x12, 0x9b,
     0x51, 0x8a, 0xa2, 0x5, 0xb4, 0x90, 0x66, 0xec, 0x4e, 0x0, 0x8c, 0x33, 0xcf, 0x1b, 0xae, 0xb7,
     0xe7, 0xd8, 0x5d, 0xcc, 0x8a, 0x88, 0xb9, 0x9d, 0x10, 0xf3, 0x68, 0x2f, 0xf2, 0x82, 0xf8, 0xd0,
-    0xc, 0x2b, 0x98, 0x3d, 0xae, 0xc5, 0x68, 0x1a, 0xc2, 0xca, 0xc9, 0xcf, 0x7d, 0x16, 0xe8, 0xe3,
 ];
 
 const CTEXT_AES128_GCM: [u8; 512] = [
-    0x49, 0x6a, 0x82, 0xb1, 0x3d, 0x3d, 0x37, 0x5f, 0x8e, 0x9b, 0x18, 0x1c, 0x5b, 0x99, 0x64, 0x25,
-    0x12, 0xf3, 0x33, 0x94, 0x82, 0x5e, 0x12, 0x21, 0x23, 0xb8, 0xb9, 0x95, 0x56, 0x7f, 0xc, 0x3a,
-    0x4b, 0x7, 0x68, 0x1c, 0x36, 0xfa, 0x2f, 0xb5, 0x3b, 0xaf, 0xc1, 0xf3, 0x83, 0xba, 0x1a, 0x14,
-    0x9d, 0xd3, 0x3f, 0x3a, 0x8c, 0x92, 0xb, 0x58, 0x8b, 0x37, 0xd0, 0x39, 0x2c, 0xfe, 0xa8, 0xeb,
-    0xe3, 0x6c, 0x83, 0xf1, 0x9, 0x41, 0x9f, 0x6c, 0x50, 0xc6, 0x12, 0xb4, 0xce, 0x13, 0x79, 0xb6,
-    0x12, 0x4f, 0x28, 0xf5, 0x79, 0xce, 0x29, 0x1f, 0x53, 0x92, 0xd5, 0x51, 0xf8, 0xed, 0x5f, 0x74,
-    0xfe, 0xfe, 0x6c, 0x19, 0x5e, 0x86, 0x3c, 0xfc, 0x6b, 0x1f, 0xe3, 0xb6, 0xdb, 0x50, 0xdf, 0x6d,
-    0x62, 0xd1, 0xf2, 0x4e, 0xdf, 0xbc, 0xcc, 0x74, 0x47, 0xf8, 0x40, 0xe9, 0x4d, 0x39, 0x15, 0xa0,
-    0x90, 0x16, 0xc, 0x2c, 0xa1, 0x8b, 0x42, 0xc2, 0x0, 0x6a, 0xa7, 0xe3, 0x83, 0xf0, 0xa3, 0x74,
-    0x8b, 0x87, 0x76, 0xa1, 0xdd, 0x3c, 0xdb, 0xf6, 0x6e, 0xaa, 0x45, 0xd8, 0x4, 0x1a, 0x3b, 0x30,
-    0x1c, 0xb, 0xe3, 0xa5, 0x57, 0x36, 0xc6, 0x2b, 0x23, 0x7d, 0x79, 0x99, 0x45, 0x89, 0xfa, 0xce,
-    0xc8, 0xdf, 0x61, 0x11, 0xce, 0xd7, 0xa0, 0x29, 0xe5, 0xec, 0xf4, 0xff, 0x4, 0xeb, 0xed, 0xad,
-    0x0, 0xd3, 0xc1, 0x20, 0x44, 0x22, 0x5f, 0x73, 0x3e, 0xa2, 0x7e, 0xe6, 0xfa, 0x52, 0xec, 0x27,
-    0x37, 0xf0, 0x72, 0x18, 0xe7, 0x3c, 0x4d, 0x7c, 0x7c, 0xb8, 0x6d, 0xa9, 0x59, 0x13, 0xdd, 0x2f,
-    0xb6, 0x30, 0xf8, 0x2c, 0x4f, 0x4e, 0xda, 0x29, 0x3, 0x64, 0x98, 0x6e, 0xa9, 0x5d, 0x6b, 0xa5,
-    0xc6, 0x33, 0xb8, 0x10, 0x32, 0x24, 0x4b, 0xe6, 0xa0, 0x15, 0x89, 0x6f, 0x7a, 0x7b, 0x51, 0xca,
-    0xf4, 0xba, 0x2e, 0xcb, 0x80, 0xd0, 0x6c, 0x5c, 0x3b, 0x95, 0x11, 0xc6, 0x8e, 0x9b, 0x33, 0x11,
-    0xf4, 0x59, 0xde, 0xae, 0x45, 0x45, 0xb7, 0x6c, 0xfe, 0xc8, 0x9d, 0x75, 0xd, 0xa5, 0xfa, 0x4b,
-    0x93, 0x2e, 0x46, 0xc4, 0x5d, 0x4f, 0x86, 0x41, 0x2e, 0x12, 0x1c, 0xb7, 0xf4, 0x92, 0xf2, 0x1,
-    0x36, 0x95, 0x75, 0x3e, 0x1b, 0x8a, 0x71, 0x17, 0x9f, 0xe8, 0xa4, 0x8b, 0xcd, 0xe3, 0x23, 0x66,
-    0x42, 0xb6, 0x48, 0xbd, 0xfc, 0xf7, 0x77, 0x71, 0x77, 0x78, 0xf6, 0x6a, 0xfe, 0xfd, 0x0, 0x0,
-    0x8a, 0x29, 0xc0, 0x59, 0x41, 0xb4, 0xf1, 0xf7, 0xec, 0xd8, 0x5, 0x28, 0xa8, 0xe3, 0xbe, 0x16,
-    0x6d, 0x81, 0x35, 0xc9, 0x6e, 0x6f, 0xb6, 0x0, 0xbc, 0xd5, 0x3c, 0x36, 0x7c, 0x94, 0x36, 0x1f,
-    0x5c, 0x25, 0x80, 0xca, 0x1d, 0xe5, 0xec, 0x30, 0x47, 0xbc, 0x10, 0x6e, 0x54, 0x7a, 0x51, 0x56,
-    0x6d, 0x6, 0x1b, 0x86, 0x8, 0x9f, 0x9b, 0x1f, 0x38, 0x29, 0x17, 0xed, 0x61, 0xeb, 0xbb, 0xe8,
-    0xb2, 0xe5, 0xcb, 0xef, 0x58, 0x8b, 0x4b, 0x9e, 0xc4, 0xe7, 0x85, 0xb, 0xb5, 0xe7, 0x5a, 0x7d,
-    0x2c, 0xfe, 0x9, 0x81, 0xd4, 0xcc, 0x66, 0xde, 0x13, 0xbb, 0x4c, 0x9d, 0x83, 0x6b, 0x71, 0xf1,
-    0xc5, 0xe1, 0xf2, 0x92, 0x8f, 0xf9, 0x38, 0x7, 0xfd, 0x28, 0x42, 0xcf, 0x6b, 0x1d, 0xe8, 0x23,
-    0x89, 0xe6, 0xe3, 0x50, 0xa1, 0x5e, 0xac, 0x3c, 0x31, 0xe3, 0xb0, 0x74, 0xcc, 0x2d, 0xd6, 0xb8,
-    0xa3, 0x22, 0x6c, 0xbd, 0x44, 0xd, 0x2c, 0xfe, 0x16, 0xd2, 0x79, 0x33, 0xb5, 0x6c, 0xcc, 0x72,
-    0xe0, 0xd1, 0x6a, 0x8, 0x83, 0x89, 0x11, 0xc8, 0x72, 0x85, 0xc0, 0x14, 0x8f, 0x20, 0xc9, 0x17,
-    0x4c, 0xe5, 0xba, 0xd8, 0x18, 0x6c, 0xdf, 0xaa, 0xfa, 0xe6, 0xa7, 0xc9, 0x60, 0xa1, 0xcd, 0x56,
+    0x91, 0xc0, 0xb2, 0xcf, 0xe7, 0xd1, 0x1e, 0xe3, 0x19, 0x17, 0xc4, 0x48, 0x49, 0x6a, 0x82, 0xb1,
+    0x3d, 0x3d, 0x37, 0x5f, 0x8e, 0x9b, 0x18, 0x1c, 0x5b, 0x99, 0x64, 0x25, 0x12, 0xf3, 0x33, 0x94,
+    0x82, 0x5e, 0x12, 0x21, 0x23, 0xb8, 0xb9, 0x95, 0x56, 0x7f, 0xc, 0x3a, 0x4b, 0x7, 0x68, 0x1c,
+    0x36, 0xfa, 0x2f, 0xb5, 0x3b, 0xaf, 0xc1, 0xf3, 0x83, 0xba, 0x1a, 0x14, 0x9d, 0xd3, 0x3f, 0x3a,
+    0x8c, 0x92, 0xb, 0x58, 0x8b, 0x37, 0xd0, 0x39, 0x2c, 0xfe, 0xa8, 0xeb, 0xe3, 0x6c, 0x83, 0xf1,
+    0x9, 0x41, 0x9f, 0x6c, 0x50, 0xc6, 0x12, 0xb4, 0xce, 0x13, 0x79, 0xb6, 0x12, 0x4f, 0x28, 0xf5,
+    0x79, 0xce, 0x29, 0x1f, 0x53, 0x92, 0xd5, 0x51, 0xf8, 0xed, 0x5f, 0x74, 0xfe, 0xfe, 0x6c, 0x19,
+    0x5e, 0x86, 0x3c, 0xfc, 0x6b, 0x1f, 0xe3, 0xb6, 0xdb, 0x50, 0xdf, 0x6d, 0x62, 0xd1, 0xf2, 0x4e,
+    0xdf, 0xbc, 0xcc, 0x74, 0x47, 0xf8, 0x40, 0xe9, 0x4d, 0x39, 0x15, 0xa0, 0x90, 0x16, 0xc, 0x2c,
+    0xa1, 0x8b, 0x42, 0xc2, 0x0, 0x6a, 0xa7, 0xe3, 0x83, 0xf0, 0xa3, 0x74, 0x8b, 0x87, 0x76, 0xa1,
+    0xdd, 0x3c, 0xdb, 0xf6, 0x6e, 0xaa, 0x45, 0xd8, 0x4, 0x1a, 0x3b, 0x30, 0x1c, 0xb, 0xe3, 0xa5,
+    0x57, 0x36, 0xc6, 0x2b, 0x23, 0x7d, 0x79, 0x99, 0x45, 0x89, 0xfa, 0xce, 0xc8, 0xdf, 0x61, 0x11,
+    0xce, 0xd7, 0xa0, 0x29, 0xe5, 0xec, 0xf4, 0xff, 0x4, 0xeb, 0xed, 0xad, 0x0, 0xd3, 0xc1, 0x20,
+    0x44, 0x22, 0x5f, 0x73, 0x3e, 0xa2, 0x7e, 0xe6, 0xfa, 0x52, 0xec, 0x27, 0x37, 0xf0, 0x72, 0x18,
+    0xe7, 0x3c, 0x4d, 0x7c, 0x7c, 0xb8, 0x6d, 0xa9, 0x59, 0x13, 0xdd, 0x2f, 0xb6, 0x30, 0xf8, 0x2c,
+    0x4f, 0x4e, 0xda, 0x29, 0x3, 0x64, 0x98, 0x6e, 0xa9, 0x5d, 0x6b, 0xa5, 0xc6, 0x33, 0xb8, 0x10,
+    0x32, 0x24, 0x4b, 0xe6, 0xa0, 0x15, 0x89, 0x6f, 0x7a, 0x7b, 0x51, 0xca, 0xf4, 0xba, 0x2e, 0xcb,
+    0x80, 0xd0, 0x6c, 0x5c, 0x3b, 0x95, 0x11, 0xc6, 0x8e, 0x9b, 0x33, 0x11, 0xf4, 0x59, 0xde, 0xae,
+    0x45, 0x45, 0xb7, 0x6c, 0xfe, 0xc8, 0x9d, 0x75, 0xd, 0xa5, 0xfa, 0x4b, 0x93, 0x2e, 0x46, 0xc4,
+    0x5d, 0x4f, 0x86, 0x41, 0x2e, 0x12, 0x1c, 0xb7, 0xf4, 0x92, 0xf2, 0x1, 0x36, 0x95, 0x75, 0x3e,
+    0x1b, 0x8a, 0x71, 0x17, 0x9f, 0xe8, 0xa4, 0x8b, 0xcd, 0xe3, 0x23, 0x66, 0x42, 0xb6, 0x48, 0xbd,
+    0xfc, 0xf7, 0x77, 0x71, 0x77, 0x78, 0xf6, 0x6a, 0xfe, 0xfd, 0x0, 0x0, 0x8a, 0x29, 0xc0, 0x59,
+    0x41, 0xb4, 0xf1, 0xf7, 0xec, 0xd8, 0x5, 0x28, 0xa8, 0xe3, 0xbe, 0x16, 0x6d, 0x81, 0x35, 0xc9,
+    0x6e, 0x6f, 0xb6, 0x0, 0xbc, 0xd5, 0x3c, 0x36, 0x7c, 0x94, 0x36, 0x1f, 0x5c, 0x25, 0x80, 0xca,
+    0x1d, 0xe5, 0xec, 0x30, 0x47, 0xbc, 0x10, 0x6e, 0x54, 0x7a, 0x51, 0x56, 0x6d, 0x6, 0x1b, 0x86,
+    0x8, 0x9f, 0x9b, 0x1f, 0x38, 0x29, 0x17, 0xed, 0x61, 0xeb, 0xbb, 0xe8, 0xb2, 0xe5, 0xcb, 0xef,
+    0x58, 0x8b, 0x4b, 0x9e, 0xc4, 0xe7, 0x85, 0xb, 0xb5, 0xe7, 0x5a, 0x7d, 0x2c, 0xfe, 0x9, 0x81,
+    0xd4, 0xcc, 0x66, 0xde, 0x13, 0xbb, 0x4c, 0x9d, 0x83, 0x6b, 0x71, 0xf1, 0xc5, 0xe1, 0xf2, 0x92,
+    0x8f, 0xf9, 0x38, 0x7, 0xfd, 0x28, 0x42, 0xcf, 0x6b, 0x1d, 0xe8, 0x23, 0x89, 0xe6, 0xe3, 0x50,
+    0xa1, 0x5e, 0xac, 0x3c, 0x31, 0xe3, 0xb0, 0x74, 0xcc, 0x2d, 0xd6, 0xb8, 0xa3, 0x22, 0x6c, 0xbd,
+    0x44, 0xd, 0x2c, 0xfe, 0x16, 0xd2, 0x79, 0x33, 0xb5, 0x6c, 0xcc, 0x72, 0xe0, 0xd1, 0x6a, 0x8,
+    0x68, 0xc0, 0xdb, 0xe1, 0x90, 0x7a, 0xed, 0x79, 0x38, 0x96, 0x82, 0xd7, 0x74, 0x43, 0xa6, 0x82,
 ];
 
 pub const RND: [u8; 1536] = [
@@ -0,0 +1,284 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::Backend;
+use nuts_memory::{Id, MemoryBackend};
+
+use crate::cache::BlockCache;
+use crate::{
+    Cipher, CipherError, Container, CreateOptionsBuilder, Digest, Error, Kdf, OpenOptionsBuilder,
+};
+
+fn setup_backend() -> (MemoryBackend, Id, Id) {
+    let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let id1 = container.aquire().unwrap();
+    let id2 = container.aquire().unwrap();
+
+    container.write(&id1, b"abc").unwrap();
+    container.write(&id2, b"def").unwrap();
+
+    (container.into_backend(), id1, id2)
+}
+
+fn open(backend: MemoryBackend, size: usize, write_back: bool) -> Container<MemoryBackend> {
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_cache(size)
+        .with_write_back(write_back)
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::open(backend, options).unwrap()
+}
+
+fn setup_container(size: usize, write_back: bool) -> (Container<MemoryBackend>, Id, Id) {
+    let (backend, id1, id2) = setup_backend();
+
+    (open(backend, size, write_back), id1, id2)
+}
+
+fn corrupt(container: &mut Container<MemoryBackend>, id: &Id) {
+    container.backend.write(id, &[0; 512]).unwrap();
+}
+
+fn assert_block(container: &mut Container<MemoryBackend>, id: &Id, data: &[u8]) {
+    let mut buf = [b'x'; 4];
+
+    assert_eq!(container.read(id, &mut buf).unwrap(), 4);
+    assert_eq!(buf[..3], *data);
+    assert_eq!(buf[3], 0);
+}
+
+fn assert_not_trustworthy(container: &mut Container<MemoryBackend>, id: &Id) {
+    let err = container.read(id, &mut [0; 3]).unwrap_err();
+
+    assert!(matches!(err, Error::Cipher(CipherError::NotTrustworthy)));
+}
+
+#[test]
+fn disabled() {
+    let (mut container, id1, _) = setup_container(0, true);
+
+    assert_block(&mut container, &id1, b"abc");
+    corrupt(&mut container, &id1);
+    assert_not_trustworthy(&mut container, &id1);
+
+    // no write-back without a cache
+    let ctext = container.backend().get(&id1).unwrap().to_vec();
+    container.write(&id1, b"xyz").unwrap();
+    assert_ne!(container.backend().get(&id1).unwrap(), ctext);
+}
+
+#[test]
+fn read_hit() {
+    let (mut container, id1, _) = setup_container(2, false);
+
+    assert_block(&mut container, &id1, b"abc");
+
+    // served from the cache, the backend is not touched
+    corrupt(&mut container, &id1);
+    assert_block(&mut container, &id1, b"abc");
+}
+
+#[test]
+fn read_small_buf() {
+    let (mut container, id1, _) = setup_container(2, false);
+    let mut buf = [0; 2];
+
+    assert_eq!(container.read(&id1, &mut buf).unwrap(), 2);
+    assert_eq!(buf, *b"ab");
+
+    // the whole block is cached
+    corrupt(&mut container, &id1);
+    assert_block(&mut container, &id1, b"abc");
+}
+
+#[test]
+fn read_evicted() {
+    let (mut container, id1, id2) = setup_container(1, false);
+
+    assert_block(&mut container, &id1, b"abc");
+    assert_block(&mut container, &id2, b"def");
+
+    corrupt(&mut container, &id1);
+    assert_not_trustworthy(&mut container, &id1);
+}
+
+#[test]
+fn write_through() {
+    let (mut container, id1, _) = setup_container(2, false);
+    let ctext = container.backend().get(&id1).unwrap().to_vec();
+
+    assert_eq!(container.write(&id1, b"xyz").unwrap(), 3);
+    assert_ne!(container.backend().get(&id1).unwrap(), ctext);
+
+    corrupt(&mut container, &id1);
+    assert_block(&mut container, &id1, b"xyz");
+}
+
+#[test]
+fn write_back() {
+    let (mut container, id1, _) = setup_container(2, true);
+    let ctext = container.backend().get(&id1).unwrap().to_vec();
+
+    assert_eq!(container.write(&id1, b"xyz").unwrap(), 3);
+    assert_eq!(container.backend().get(&id1).unwrap(), ctext);
+    assert_block(&mut container, &id1, b"xyz");
+
+    container.flush().unwrap();
+    assert_ne!(container.backend().get(&id1).unwrap(), ctext);
+
+    // nothing left to flush
+    let ctext = container.backend().get(&id1).unwrap().to_vec();
+    container.flush().unwrap();
+    assert_eq!(container.backend().get(&id1).unwrap(), ctext);
+
+    let mut container = open(container.into_backend(), 0, false);
+    assert_block(&mut container, &id1, b"xyz");
+}
+
+#[test]
+fn write_back_into_backend() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    // flushed without an explicit flush
+    container.write(&id1, b"xyz").unwrap();
+
+    let mut container = open(container.into_backend(), 0, false);
+    assert_block(&mut container, &id1, b"xyz");
+}
+
+#[test]
+fn write_back_into_shared() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+
+    let shared = container.into_shared().unwrap();
+    let mut buf = [0; 3];
+
+    assert_eq!(shared.read(&id1, &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"xyz");
+}
+
+#[test]
+fn write_back_into_shared_failed() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+    container.backend.release(id1).unwrap();
+
+    let (err, container) = *container.into_shared().unwrap_err();
+
+    assert!(matches!(err, Error::Backend(_)));
+    assert_eq!(container.cache.num_dirty(), 1);
+}
+
+#[test]
+fn write_back_try_into_backend() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+
+    let backend = container.try_into_backend().unwrap();
+
+    let mut container = open(backend, 0, false);
+    assert_block(&mut container, &id1, b"xyz");
+}
+
+#[test]
+fn write_back_try_into_backend_failed() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+    container.backend.release(id1).unwrap();
+
+    let (err, container) = *container.try_into_backend().unwrap_err();
+
+    assert!(matches!(err, Error::Backend(_)));
+    assert_eq!(container.cache.num_dirty(), 1);
+}
+
+#[test]
+fn write_back_dropped() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+    assert_eq!(container.cache.num_dirty(), 1);
+
+    // the dirty block is discarded (and logged) when the cache is dropped
+    container.cache = BlockCache::new(0, false);
+
+    let mut container = open(container.into_backend(), 0, false);
+    assert_block(&mut container, &id1, b"abc");
+}
+
+#[test]
+fn write_back_evicted() {
+    let (mut container, id1, id2) = setup_container(1, true);
+    let ctext1 = container.backend().get(&id1).unwrap().to_vec();
+    let ctext2 = container.backend().get(&id2).unwrap().to_vec();
+
+    container.write(&id1, b"xyz").unwrap();
+    container.write(&id2, b"uvw").unwrap();
+
+    assert_ne!(container.backend().get(&id1).unwrap(), ctext1);
+    assert_eq!(container.backend().get(&id2).unwrap(), ctext2);
+
+    // id2 is still dirty, it is flushed by into_backend()
+    let mut container = open(container.into_backend(), 0, false);
+    assert_block(&mut container, &id1, b"xyz");
+    assert_block(&mut container, &id2, b"uvw");
+}
+
+#[test]
+fn release() {
+    let (mut container, id1, _) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+    container.release(id1).unwrap();
+
+    assert!(container.cache.dirty().is_empty());
+    assert!(container.read(&id1, &mut [0; 3]).is_err());
+}
+
+#[test]
+fn rekey() {
+    let (mut container, id1, id2) = setup_container(2, true);
+
+    container.write(&id1, b"xyz").unwrap();
+    container.rekey().unwrap();
+
+    assert!(container.cache.dirty().is_empty());
+    assert_block(&mut container, &id1, b"xyz");
+    assert_block(&mut container, &id2, b"def");
+
+    let mut container = open(container.into_backend(), 0, false);
+    assert_block(&mut container, &id1, b"xyz");
+    assert_block(&mut container, &id2, b"def");
+}
@@ -22,7 +22,7 @@
 
 use nuts_memory::MemoryBackend;
 
-use crate::{Cipher, Container, CreateOptionsBuilder, Digest, Info, Kdf};
+use crate::{Cipher, Compression, Container, CreateOptionsBuilder, Digest, Info, Kdf, SlotInfo};
 
 #[test]
 fn none() {
@@ -35,9 +35,12 @@ fn none() {
         container.info().unwrap(),
         Info {
             backend: (),
-            revision: 2,
+            revision: 3,
             cipher: Cipher::None,
+            compression: Compression::None,
             kdf: Kdf::None,
+            slots: vec![],
+            active_slot: None,
             bsize_gross: 512,
             bsize_net: 512,
         }
@@ -58,11 +61,14 @@ fn aes128_ctr() {
         container.info().unwrap(),
         Info {
             backend: (),
-            revision: 2,
+            revision: 3,
             cipher: Cipher::Aes128Ctr,
-            kdf,
+            compression: Compression::None,
+            kdf: kdf.clone(),
+            slots: vec![SlotInfo::Password(kdf)],
+            active_slot: Some(0),
             bsize_gross: 512,
-            bsize_net: 512,
+            bsize_net: 496,
         }
     );
 }
@@ -81,11 +87,41 @@ fn aes128_gcm() {
         container.info().unwrap(),
         Info {
             backend: (),
-            revision: 2,
+            revision: 3,
             cipher: Cipher::Aes128Gcm,
-            kdf,
+            compression: Compression::None,
+            kdf: kdf.clone(),
+            slots: vec![SlotInfo::Password(kdf)],
+            active_slot: Some(0),
             bsize_gross: 512,
-            bsize_net: 496,
+            bsize_net: 484,
+        }
+    );
+}
+
+#[test]
+fn aes128_gcm_zstd() {
+    let kdf = Kdf::pbkdf2(Digest::Sha1, 65536, b"123");
+    let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(kdf.clone())
+        .with_compression(Compression::Zstd)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::<MemoryBackend>::create(MemoryBackend::new(), options).unwrap();
+
+    assert_eq!(
+        container.info().unwrap(),
+        Info {
+            backend: (),
+            revision: 3,
+            cipher: Cipher::Aes128Gcm,
+            compression: Compression::Zstd,
+            kdf: kdf.clone(),
+            slots: vec![SlotInfo::Password(kdf)],
+            active_slot: Some(0),
+            bsize_gross: 512,
+            bsize_net: 479,
         }
     );
 }
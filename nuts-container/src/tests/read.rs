@@ -118,10 +118,11 @@ mod aes128_ctr {
         (container, id)
     }
 
-    read_tests!(512);
+    read_tests!(496);
 }
 
 mod aes128_gcm {
+    use nuts_backend::Backend;
     use nuts_memory::{Error as MemoryError, Id, MemoryBackend};
 
     use crate::tests::{CTEXT_AES128_GCM, RND};
@@ -142,13 +143,74 @@ mod aes128_gcm {
         (container, id)
     }
 
-    read_tests!(496, &CTEXT_AES128_GCM);
+    read_tests!(484, &CTEXT_AES128_GCM);
 
     #[test]
     fn not_trustworthy() {
         let data = [&[CTEXT_AES128_GCM[0] + 1], &CTEXT_AES128_GCM[1..]].concat();
         let (mut container, id) = setup_container(&data);
-        let mut buf = [0; 496];
+        let mut buf = [0; 484];
+
+        let err = container.read(&id, &mut buf).unwrap_err();
+        assert!(matches!(err, Error::Cipher(CipherError::NotTrustworthy)));
+    }
+
+    #[test]
+    fn wrong_id() {
+        let mut backend = MemoryBackend::new();
+
+        backend.insert().unwrap();
+        let id = backend.insert_data(&CTEXT_AES128_GCM).unwrap();
+
+        let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+            .with_password_callback(|| Ok(b"abc".to_vec()))
+            .with_kdf(Kdf::pbkdf2(Digest::Sha1, 65536, b"123"))
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = Container::<MemoryBackend>::create(backend, options).unwrap();
+        let mut buf = [0; 484];
+
+        let err = container.read(&id, &mut buf).unwrap_err();
+        assert!(matches!(err, Error::Cipher(CipherError::NotTrustworthy)));
+    }
+
+    /// Replaces the block `id` with `data`, which is not bound to the id
+    /// (like the first write of Container::aquire).
+    fn write_unbound(container: &mut Container<MemoryBackend>, id: &Id, data: &[u8]) {
+        let bsize = container.net_block_size(Cipher::Aes128Gcm, false) as usize;
+        let key = container.header.key().to_vec();
+
+        container.ctx.set_cipher(Cipher::Aes128Gcm);
+        container.ctx.copy_from_slice(bsize, data);
+
+        let ctext = container
+            .ctx
+            .encrypt_with_nonce(&[], &key)
+            .unwrap()
+            .to_vec();
+        container.backend.write(id, &ctext).unwrap();
+    }
+
+    #[test]
+    fn unbound_zero_block() {
+        let (mut container, _) = setup_container(&CTEXT_AES128_GCM);
+        let id = container.aquire().unwrap();
+        let mut buf = [0; 3];
+
+        // an unbound all-zero block could be copied over any block
+        write_unbound(&mut container, &id, &[]);
+
+        let err = container.read(&id, &mut buf).unwrap_err();
+        assert!(matches!(err, Error::Cipher(CipherError::NotTrustworthy)));
+    }
+
+    #[test]
+    fn unbound_block() {
+        let (mut container, _) = setup_container(&CTEXT_AES128_GCM);
+        let id = container.aquire().unwrap();
+        let mut buf = [0; 3];
+
+        write_unbound(&mut container, &id, b"abc");
 
         let err = container.read(&id, &mut buf).unwrap_err();
         assert!(matches!(err, Error::Cipher(CipherError::NotTrustworthy)));
@@ -0,0 +1,157 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::{Backend, HEADER_MAX_SIZE};
+use nuts_memory::{Id, MemoryBackend};
+
+use crate::{
+    Cipher, CipherError, Container, CreateOptionsBuilder, Digest, Error, HeaderError, Kdf,
+    OpenOptionsBuilder,
+};
+
+fn setup_container(cipher: Cipher, write_back: bool) -> (Container<MemoryBackend>, Vec<Id>) {
+    let options = CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+    let ids = (0..3).map(|_| container.aquire().unwrap()).collect();
+
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_cache(8)
+        .with_write_back(write_back)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::open(container.into_backend(), options).unwrap();
+
+    (container, ids)
+}
+
+fn corrupt(container: &mut Container<MemoryBackend>, id: &Id) {
+    let mut buf = container.backend.get(id).unwrap().to_vec();
+
+    buf[0] ^= 1;
+    container.backend.write(id, &buf).unwrap();
+}
+
+#[test]
+fn intact() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes128Gcm] {
+        let (mut container, _) = setup_container(cipher, false);
+        let report = container.verify().unwrap();
+
+        assert!(report.is_ok());
+        assert!(report.header.is_none());
+        assert_eq!(report.blocks, 3);
+        assert!(report.damaged.is_empty());
+    }
+}
+
+#[test]
+fn empty() {
+    let (mut container, ids) = setup_container(Cipher::Aes128Gcm, false);
+
+    for id in ids {
+        container.release(id).unwrap();
+    }
+
+    let report = container.verify().unwrap();
+
+    assert!(report.is_ok());
+    assert_eq!(report.blocks, 0);
+}
+
+#[test]
+fn damaged_block() {
+    let (mut container, ids) = setup_container(Cipher::Aes128Gcm, false);
+
+    corrupt(&mut container, &ids[1]);
+
+    let report = container.verify().unwrap();
+
+    assert!(!report.is_ok());
+    assert!(report.header.is_none());
+    assert_eq!(report.blocks, 3);
+    assert_eq!(report.damaged.len(), 1);
+    assert_eq!(report.damaged[0].0, ids[1]);
+    assert!(matches!(
+        report.damaged[0].1,
+        Error::Cipher(CipherError::NotTrustworthy)
+    ));
+}
+
+#[test]
+fn damaged_block_cached() {
+    let (mut container, ids) = setup_container(Cipher::Aes128Gcm, false);
+    let mut buf = [0; 3];
+
+    container.read(&ids[1], &mut buf).unwrap();
+    corrupt(&mut container, &ids[1]);
+
+    // the cache is bypassed
+    let report = container.verify().unwrap();
+
+    assert_eq!(report.damaged.len(), 1);
+    assert_eq!(report.damaged[0].0, ids[1]);
+}
+
+#[test]
+fn damaged_block_ctr() {
+    let (mut container, ids) = setup_container(Cipher::Aes128Ctr, false);
+
+    corrupt(&mut container, &ids[1]);
+
+    // not detected without an AE-cipher
+    assert!(container.verify().unwrap().is_ok());
+}
+
+#[test]
+fn damaged_header() {
+    let (mut container, _) = setup_container(Cipher::Aes128Gcm, false);
+
+    container
+        .backend
+        .write_header(&[0; HEADER_MAX_SIZE])
+        .unwrap();
+
+    let report = container.verify().unwrap();
+
+    assert!(!report.is_ok());
+    assert!(matches!(
+        report.header,
+        Some(Error::Header(HeaderError::InvalidHeader))
+    ));
+    assert_eq!(report.blocks, 3);
+    assert!(report.damaged.is_empty());
+}
+
+#[test]
+fn write_back() {
+    let (mut container, ids) = setup_container(Cipher::Aes128Gcm, true);
+
+    container.write(&ids[0], b"abc").unwrap();
+
+    assert!(container.verify().unwrap().is_ok());
+    assert!(container.cache.dirty().is_empty());
+}
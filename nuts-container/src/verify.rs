@@ -0,0 +1,54 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::Backend;
+
+use crate::error::Error;
+
+/// The result of a [verification](crate::Container::verify) of the
+/// container.
+#[derive(Debug)]
+pub struct VerifyReport<B: Backend> {
+    /// The error, if the header stored in the backend cannot be read.
+    pub header: Option<Error<B>>,
+
+    /// The number of verified blocks.
+    pub blocks: usize,
+
+    /// Blocks, which failed the verification.
+    ///
+    /// An [`Error::Backend`] error means that the block cannot be read from
+    /// the backend. An [`Error::Cipher`] error with
+    /// [`CipherError::NotTrustworthy`](crate::CipherError::NotTrustworthy)
+    /// means that the authentication of the block failed, its content was
+    /// modified.
+    pub damaged: Vec<(B::Id, Error<B>)>,
+}
+
+impl<B: Backend> VerifyReport<B> {
+    /// Tests whether the container is intact.
+    ///
+    /// Returns `true` if neither the header nor a block is damaged.
+    pub fn is_ok(&self) -> bool {
+        self.header.is_none() && self.damaged.is_empty()
+    }
+}
@@ -0,0 +1,462 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#![cfg(feature = "async")]
+
+mod common;
+
+use async_trait::async_trait;
+use nuts_backend::{Blocking, HEADER_MAX_SIZE};
+use nuts_container::{
+    AsyncContainer, AsyncServiceFactory, Cipher, Compression, Container, ContainerResult,
+    CreateOptionsBuilder, Digest, Error, HeaderError, Identity, Kdf, Migration, OpenOptionsBuilder,
+    Service, SnapshotError,
+};
+use nuts_memory::MemoryBackend;
+use pollster::block_on;
+use thiserror::Error;
+
+use crate::common::FailingBackend;
+
+const NUM_RECIPIENTS: usize = 10;
+
+#[derive(Debug, Error)]
+#[error(transparent)]
+struct SampleError(#[from] Error<MemoryBackend>);
+
+struct SampleMigration;
+
+impl Migration for SampleMigration {
+    fn migrate_rev0(&self, _userdata: &[u8]) -> Result<(u32, Vec<u8>), String> {
+        Ok((666, vec![]))
+    }
+}
+
+#[derive(Debug)]
+struct SampleService(AsyncContainer<MemoryBackend>);
+
+impl Service<MemoryBackend> for SampleService {
+    type Migration = SampleMigration;
+
+    fn sid() -> u32 {
+        666
+    }
+
+    fn need_top_id() -> bool {
+        true
+    }
+
+    fn migration() -> SampleMigration {
+        SampleMigration
+    }
+}
+
+#[async_trait]
+impl AsyncServiceFactory<MemoryBackend> for SampleService {
+    type Service = Self;
+    type Err = SampleError;
+
+    async fn create(container: AsyncContainer<MemoryBackend>) -> Result<Self, SampleError> {
+        Ok(SampleService(container))
+    }
+
+    async fn open(container: AsyncContainer<MemoryBackend>) -> Result<Self, SampleError> {
+        Ok(SampleService(container))
+    }
+}
+
+fn create_options(cipher: Cipher) -> CreateOptionsBuilder {
+    CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+}
+
+fn open_options(password: &'static [u8]) -> OpenOptionsBuilder {
+    OpenOptionsBuilder::new().with_password_callback(move || Ok(password.to_vec()))
+}
+
+async fn create_container(cipher: Cipher) -> AsyncContainer<MemoryBackend> {
+    let options = create_options(cipher).build::<MemoryBackend>().unwrap();
+
+    AsyncContainer::create(MemoryBackend::new(), options)
+        .await
+        .unwrap()
+}
+
+async fn open_container(
+    backend: MemoryBackend,
+    password: &'static [u8],
+) -> ContainerResult<AsyncContainer<MemoryBackend>, MemoryBackend> {
+    let options = open_options(password).build::<MemoryBackend>().unwrap();
+
+    AsyncContainer::open(backend, options).await
+}
+
+#[test]
+fn create_open() {
+    block_on(async {
+        for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+            let mut container = create_container(cipher).await;
+            let id = container.aquire().await.unwrap();
+
+            assert_eq!(container.write(&id, b"xyz").await.unwrap(), 3);
+
+            let backend = container.into_backend();
+            let mut container = open_container(backend, b"abc").await.unwrap();
+            let mut buf = [0; 4];
+
+            assert_eq!(container.read(&id, &mut buf).await.unwrap(), 4);
+            assert_eq!(buf, *b"xyz\0");
+        }
+    });
+}
+
+#[test]
+fn open_wrong_password() {
+    block_on(async {
+        let backend = create_container(Cipher::Aes128Gcm).await.into_backend();
+        let err = open_container(backend, b"xxx").await.unwrap_err();
+
+        assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+    });
+}
+
+#[test]
+fn info() {
+    block_on(async {
+        let container = create_container(Cipher::Aes128Gcm).await;
+        let info = container.info().await.unwrap();
+
+        assert_eq!(info.revision, 3);
+        assert_eq!(info.cipher, Cipher::Aes128Gcm);
+        assert_eq!(info.compression, Compression::None);
+        assert_eq!(info.kdf, Kdf::pbkdf2(Digest::Sha1, 1, b"123"));
+        assert_eq!(info.bsize_gross, 512);
+        assert_eq!(info.bsize_net, 484);
+        assert_eq!(container.block_size(), 484);
+    });
+}
+
+#[test]
+fn release() {
+    block_on(async {
+        let mut container = create_container(Cipher::Aes128Gcm).await;
+        let id = container.aquire().await.unwrap();
+
+        container.release(id).await.unwrap();
+
+        let err = container.read(&id, &mut [0; 3]).await.unwrap_err();
+        assert!(matches!(err, Error::Backend(_)));
+    });
+}
+
+#[test]
+fn compression() {
+    block_on(async {
+        let options = create_options(Cipher::Aes128Gcm)
+            .with_compression(Compression::Zstd)
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = AsyncContainer::create(MemoryBackend::new(), options)
+            .await
+            .unwrap();
+        let data = b"xyz".repeat(100);
+        let id = container.aquire().await.unwrap();
+
+        assert_eq!(container.write(&id, &data).await.unwrap(), data.len());
+
+        let mut buf = vec![0; data.len()];
+
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), data.len());
+        assert_eq!(buf, data);
+    });
+}
+
+#[test]
+fn sync_to_async() {
+    let backend = {
+        let options = create_options(Cipher::Aes128Gcm)
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+        let id = container.aquire().unwrap();
+
+        container.write(&id, b"xyz").unwrap();
+
+        container.into_backend()
+    };
+
+    block_on(async {
+        let mut container = open_container(backend, b"abc").await.unwrap();
+        let id = "1".parse().unwrap();
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+    });
+}
+
+#[test]
+fn async_to_sync() {
+    let backend = block_on(async {
+        let mut container = create_container(Cipher::Aes128Gcm).await;
+        let id = container.aquire().await.unwrap();
+
+        container.write(&id, b"xyz").await.unwrap();
+
+        container.into_backend()
+    });
+
+    let options = open_options(b"abc").build::<MemoryBackend>().unwrap();
+    let mut container = Container::open(backend, options).unwrap();
+    let id = "1".parse().unwrap();
+    let mut buf = [0; 3];
+
+    assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"xyz");
+}
+
+#[test]
+fn recover_journal() {
+    let (backend, id) = {
+        let options = create_options(Cipher::Aes128Gcm)
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+        let id = container.aquire().unwrap();
+
+        container.write(&id, b"abc").unwrap();
+
+        (container.into_backend(), id)
+    };
+
+    // interrupt the commit after the journal is committed, the block is
+    // updated by the next open
+    let options = open_options(b"abc").build::<FailingBackend>().unwrap();
+    let mut container = Container::open(FailingBackend::new(backend, Some(6)), options).unwrap();
+
+    container.begin().unwrap();
+    container.write(&id, b"xyz").unwrap();
+    container.commit().unwrap_err();
+
+    block_on(async {
+        let backend = container.into_backend().inner;
+        let mut container = open_container(backend, b"abc").await.unwrap();
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+
+        // the blocks of the journal are released
+        let mut backend = container.into_backend();
+        assert_eq!(
+            nuts_backend::Backend::ids(&mut backend).unwrap(),
+            Some(vec![id])
+        );
+    });
+}
+
+#[test]
+fn snapshot_read() {
+    let (backend, id) = {
+        let options = create_options(Cipher::Aes128Gcm)
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+        let id = container.aquire().unwrap();
+
+        container.write(&id, b"abc").unwrap();
+        container.create_snapshot("s1").unwrap();
+        container.write(&id, b"xyz").unwrap();
+
+        (container.into_backend(), id)
+    };
+
+    block_on(async {
+        let mut container = open_container(backend, b"abc").await.unwrap();
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+
+        // without copy on write the container is read-only
+        let err = container.write(&id, b"123").await.unwrap_err();
+        assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+        let err = container.aquire().await.unwrap_err();
+        assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+        let err = container.release(id).await.unwrap_err();
+        assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+        let options = open_options(b"abc")
+            .with_snapshot("s1")
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = AsyncContainer::open(container.into_backend(), options)
+            .await
+            .unwrap();
+
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, *b"abc");
+
+        let err = container.write(&id, b"123").await.unwrap_err();
+        assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+    });
+}
+
+#[test]
+fn snapshot_not_found() {
+    let options = create_options(Cipher::Aes128Gcm)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    container.create_snapshot("s1").unwrap();
+
+    block_on(async {
+        let options = open_options(b"abc")
+            .with_snapshot("s2")
+            .build::<MemoryBackend>()
+            .unwrap();
+        let err = AsyncContainer::open(container.into_backend(), options)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Snapshot(SnapshotError::NotFound(name)) if name == "s2"));
+    });
+}
+
+#[test]
+fn extended_header() {
+    let identities = (0..NUM_RECIPIENTS)
+        .map(|_| Identity::generate().unwrap())
+        .collect::<Vec<_>>();
+
+    block_on(async {
+        let mut builder = create_options(Cipher::Aes128Gcm);
+
+        for identity in identities.iter() {
+            builder = builder.with_recipient(identity.recipient().unwrap());
+        }
+
+        let options = builder.build::<MemoryBackend>().unwrap();
+        let mut container = AsyncContainer::create(MemoryBackend::new(), options)
+            .await
+            .unwrap();
+        let id = container.aquire().await.unwrap();
+
+        container.write(&id, b"xyz").await.unwrap();
+
+        let mut backend = container.into_backend();
+        let mut buf = [0; HEADER_MAX_SIZE];
+
+        nuts_backend::ReceiveHeader::get_extended_header_bytes(&mut backend, 1, &mut buf).unwrap();
+
+        let options = OpenOptionsBuilder::new()
+            .with_identity(identities[NUM_RECIPIENTS - 1].clone())
+            .build::<MemoryBackend>()
+            .unwrap();
+        let mut container = AsyncContainer::open(backend, options).await.unwrap();
+        let mut buf = [0; 3];
+
+        assert_eq!(
+            container.info().await.unwrap().slots.len(),
+            NUM_RECIPIENTS + 1
+        );
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+    });
+}
+
+#[test]
+fn service() {
+    block_on(async {
+        let container = create_container(Cipher::Aes128Gcm).await;
+        let service = AsyncContainer::create_service::<SampleService>(container)
+            .await
+            .unwrap();
+        let top_id = service.0.top_id().cloned().unwrap();
+
+        let backend = service.0.into_backend();
+        let container = open_container(backend, b"abc").await.unwrap();
+        let service = AsyncContainer::open_service::<SampleService>(container, false)
+            .await
+            .unwrap();
+
+        assert_eq!(service.0.top_id(), Some(&top_id));
+    });
+}
+
+#[test]
+fn service_already_attached() {
+    block_on(async {
+        let container = create_container(Cipher::None).await;
+        let service = AsyncContainer::create_service::<SampleService>(container)
+            .await
+            .unwrap();
+
+        let backend = service.0.into_backend();
+        let container = open_container(backend, b"abc").await.unwrap();
+        let err = AsyncContainer::create_service::<SampleService>(container)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err.0, Error::Header(HeaderError::UnexpectedSid { expected, got })
+            if expected.is_none() && got == Some(666))
+        );
+    });
+}
+
+#[test]
+fn blocking() {
+    block_on(async {
+        let options = create_options(Cipher::Aes128Gcm)
+            .build::<Blocking<MemoryBackend>>()
+            .unwrap();
+        let mut container = AsyncContainer::create(Blocking::new(MemoryBackend::new()), options)
+            .await
+            .unwrap();
+        let id = container.aquire().await.unwrap();
+
+        assert_eq!(container.write(&id, b"xyz").await.unwrap(), 3);
+
+        let backend = container.into_backend();
+        let options = open_options(b"abc")
+            .build::<Blocking<MemoryBackend>>()
+            .unwrap();
+        let mut container = AsyncContainer::open(backend, options).await.unwrap();
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+
+        let backend = container.into_backend().into_inner();
+        let options = open_options(b"abc").build::<MemoryBackend>().unwrap();
+        let mut container = Container::open(backend, options).unwrap();
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+    });
+}
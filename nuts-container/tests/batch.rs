@@ -0,0 +1,286 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::{Backend, Open, ReceiveHeader, HEADER_MAX_SIZE};
+use nuts_container::{
+    Cipher, Container, CreateOptionsBuilder, Digest, Error, Kdf, OpenOptionsBuilder,
+};
+use nuts_memory::{Error as MemoryError, Id, MemoryBackend, Settings};
+
+const BLOCKS: [&[u8]; 4] = [b"abc", b"def", b"ghi", b"jkl"];
+
+/// A memory backend, which counts the calls of the single-block and batch
+/// methods.
+#[derive(Debug, Default)]
+struct CountingBackend {
+    inner: MemoryBackend,
+    single: usize,
+    batch: usize,
+}
+
+impl ReceiveHeader<Self> for CountingBackend {
+    fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), MemoryError> {
+        self.inner.get_header_bytes(bytes)
+    }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, MemoryError> {
+        self.inner.get_extended_header_bytes(idx, bytes)
+    }
+}
+
+impl Open<Self> for CountingBackend {
+    fn build(self, _settings: Settings) -> Result<CountingBackend, MemoryError> {
+        Ok(self)
+    }
+}
+
+impl Backend for CountingBackend {
+    type Settings = Settings;
+    type Err = MemoryError;
+    type Id = Id;
+    type Info = ();
+
+    fn info(&self) -> Result<(), MemoryError> {
+        self.inner.info()
+    }
+
+    fn block_size(&self) -> u32 {
+        Backend::block_size(&self.inner)
+    }
+
+    fn aquire(&mut self, buf: &[u8]) -> Result<Id, MemoryError> {
+        self.single += 1;
+        self.inner.aquire(buf)
+    }
+
+    fn aquire_many(&mut self, bufs: &[&[u8]]) -> Result<Vec<Id>, MemoryError> {
+        self.batch += 1;
+        self.inner.aquire_many(bufs)
+    }
+
+    fn release(&mut self, id: Id) -> Result<(), MemoryError> {
+        self.inner.release(id)
+    }
+
+    fn ids(&mut self) -> Result<Option<Vec<Id>>, MemoryError> {
+        self.inner.ids()
+    }
+
+    fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize, MemoryError> {
+        self.single += 1;
+        self.inner.read(id, buf)
+    }
+
+    fn read_many(&mut self, blocks: &mut [(&Id, &mut [u8])]) -> Result<Vec<usize>, MemoryError> {
+        self.batch += 1;
+        self.inner.read_many(blocks)
+    }
+
+    fn write(&mut self, id: &Id, buf: &[u8]) -> Result<usize, MemoryError> {
+        self.single += 1;
+        self.inner.write(id, buf)
+    }
+
+    fn write_many(&mut self, blocks: &[(&Id, &[u8])]) -> Result<Vec<usize>, MemoryError> {
+        self.batch += 1;
+        self.inner.write_many(blocks)
+    }
+
+    fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), MemoryError> {
+        self.inner.write_header(buf)
+    }
+
+    fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, MemoryError> {
+        self.inner.write_extended_header(idx, buf)
+    }
+
+    fn delete(self) {
+        self.inner.delete()
+    }
+}
+
+fn create_backend(cipher: Cipher) -> MemoryBackend {
+    let options = CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::create(MemoryBackend::new(), options)
+        .unwrap()
+        .into_backend()
+}
+
+fn open_container(cipher: Cipher, cache: usize, write_back: bool) -> Container<CountingBackend> {
+    let backend = CountingBackend {
+        inner: create_backend(cipher),
+        ..Default::default()
+    };
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_cache(cache)
+        .with_write_back(write_back)
+        .build::<CountingBackend>()
+        .unwrap();
+
+    Container::open(backend, options).unwrap()
+}
+
+fn counters(container: &Container<CountingBackend>) -> (usize, usize) {
+    (container.backend().single, container.backend().batch)
+}
+
+fn read_blocks(container: &mut Container<CountingBackend>, ids: &[Id]) -> Vec<Vec<u8>> {
+    let mut bufs = vec![vec![0; 3]; ids.len()];
+    let mut blocks = ids
+        .iter()
+        .zip(bufs.iter_mut())
+        .map(|(id, buf)| (id, buf.as_mut_slice()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(container.read_many(&mut blocks).unwrap(), [3; 4]);
+
+    bufs
+}
+
+fn write_blocks(container: &mut Container<CountingBackend>, ids: &[Id]) {
+    let blocks = ids.iter().zip(BLOCKS.iter().copied()).collect::<Vec<_>>();
+
+    assert_eq!(container.write_many(&blocks).unwrap(), vec![3; ids.len()]);
+}
+
+#[test]
+fn aquire_many() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        let mut container = open_container(cipher, 0, false);
+        let ids = container.aquire_many(4).unwrap();
+
+        assert_eq!(ids.len(), 4);
+        assert_eq!(container.backend().single, 0);
+
+        for id in ids.iter() {
+            let mut buf = [1; 3];
+
+            assert_eq!(container.read(id, &mut buf).unwrap(), 3);
+            assert_eq!(buf, [0; 3]);
+        }
+    }
+}
+
+#[test]
+fn aquire_many_none() {
+    let mut container = open_container(Cipher::Aes128Gcm, 0, false);
+
+    assert!(container.aquire_many(0).unwrap().is_empty());
+}
+
+#[test]
+fn write_many_read_many() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        let mut container = open_container(cipher, 0, false);
+        let ids = container.aquire_many(4).unwrap();
+        let (single, batch) = counters(&container);
+
+        write_blocks(&mut container, &ids);
+        assert_eq!(counters(&container), (single, batch + 1));
+
+        assert_eq!(read_blocks(&mut container, &ids), BLOCKS);
+        assert_eq!(counters(&container), (single, batch + 2));
+
+        for (id, expected) in ids.iter().zip(BLOCKS.iter()) {
+            let mut buf = [0; 3];
+
+            assert_eq!(container.read(id, &mut buf).unwrap(), 3);
+            assert_eq!(buf, **expected);
+        }
+    }
+}
+
+#[test]
+fn read_many_cached() {
+    let mut container = open_container(Cipher::Aes128Gcm, 8, false);
+    let ids = container.aquire_many(4).unwrap();
+
+    write_blocks(&mut container, &ids[..2]);
+    let (single, batch) = counters(&container);
+
+    let mut bufs = vec![vec![0; 3]; 4];
+    let mut blocks = ids
+        .iter()
+        .zip(bufs.iter_mut())
+        .map(|(id, buf)| (id, buf.as_mut_slice()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(container.read_many(&mut blocks).unwrap(), [3; 4]);
+    assert_eq!(bufs, [BLOCKS[0], BLOCKS[1], &[0; 3], &[0; 3]]);
+    assert_eq!(counters(&container), (single, batch + 1));
+
+    // All blocks are cached now.
+    assert_eq!(read_blocks(&mut container, &ids), bufs);
+    assert_eq!(counters(&container), (single, batch + 1));
+}
+
+#[test]
+fn write_many_write_back() {
+    let mut container = open_container(Cipher::Aes128Gcm, 8, true);
+    let ids = container.aquire_many(4).unwrap();
+    let (single, batch) = counters(&container);
+
+    write_blocks(&mut container, &ids);
+    assert_eq!(read_blocks(&mut container, &ids), BLOCKS);
+    assert_eq!(counters(&container), (single, batch));
+
+    container.flush().unwrap();
+    assert_eq!(counters(&container), (single + 4, batch));
+
+    for (id, expected) in ids.iter().zip(BLOCKS.iter()) {
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(id, &mut buf).unwrap(), 3);
+        assert_eq!(buf, **expected);
+    }
+}
+
+#[test]
+fn read_many_no_such_block() {
+    let mut container = open_container(Cipher::Aes128Gcm, 0, false);
+    let ids = container.aquire_many(2).unwrap();
+    let id = "4711".parse::<Id>().unwrap();
+    let mut bufs = [[0; 3]; 3];
+    let [buf1, buf2, buf3] = &mut bufs;
+    let mut blocks = [
+        (&ids[0], &mut buf1[..]),
+        (&id, &mut buf2[..]),
+        (&ids[1], &mut buf3[..]),
+    ];
+
+    let err = container.read_many(&mut blocks).unwrap_err();
+    assert!(matches!(err, Error::Backend(MemoryError::NoSuchId(n)) if n == id));
+}
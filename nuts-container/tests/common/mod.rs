@@ -20,8 +20,13 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use nuts_container::{Container, Error, Migration, Service, ServiceFactory};
-use nuts_memory::MemoryBackend;
+use nuts_backend::{Backend, Open, ReceiveHeader, HEADER_MAX_SIZE};
+use nuts_container::{
+    Cipher, Container, ContainerResult, CreateOptionsBuilder, Digest, Error, Kdf, Migration,
+    OpenOptionsBuilder, Service, ServiceFactory,
+};
+use nuts_memory::{Error as MemoryError, Id, MemoryBackend, Settings};
+use std::fmt;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -38,6 +43,135 @@ pub fn fixture_path(dir: &str, name: &str) -> PathBuf {
     [MANIFEST_DIR, "data", dir, name].iter().collect()
 }
 
+/// The blocks written by [`create_blocks`].
+#[allow(dead_code)]
+pub const BLOCKS: [&[u8]; 4] = [b"abc", b"def", b"ghi", b"jkl"];
+
+#[allow(dead_code)]
+pub fn kdf() -> Kdf {
+    kdf_with_iterations(1)
+}
+
+#[allow(dead_code)]
+pub fn kdf_with_iterations(iterations: u32) -> Kdf {
+    Kdf::pbkdf2(Digest::Sha1, iterations, b"123")
+}
+
+/// Options for a container protected by the password `abc`.
+#[allow(dead_code)]
+pub fn password_options(cipher: Cipher) -> CreateOptionsBuilder {
+    CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(kdf())
+}
+
+/// Creates a container and writes `xyz` into its first block.
+///
+/// The block is checked by [`assert_block`].
+#[allow(dead_code)]
+pub fn create_container(builder: CreateOptionsBuilder) -> Container<MemoryBackend> {
+    let options = builder.build::<MemoryBackend>().unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let id = container.aquire().unwrap();
+    container.write(&id, b"xyz").unwrap();
+
+    container
+}
+
+/// Creates a container protected by the password `abc` and writes
+/// [`BLOCKS`] into it.
+#[allow(dead_code)]
+pub fn create_blocks(cipher: Cipher) -> (Container<MemoryBackend>, Vec<Id>) {
+    let options = password_options(cipher).build::<MemoryBackend>().unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+    let mut ids = vec![];
+
+    for buf in BLOCKS.iter() {
+        let id = container.aquire().unwrap();
+
+        container.write(&id, buf).unwrap();
+        ids.push(id);
+    }
+
+    (container, ids)
+}
+
+#[allow(dead_code)]
+pub fn open_container<B: Backend + fmt::Debug, O: Open<B>>(
+    backend: O,
+    builder: OpenOptionsBuilder,
+) -> ContainerResult<Container<B>, B> {
+    Container::open(backend, builder.build::<B>().unwrap())
+}
+
+#[allow(dead_code)]
+pub fn open_with_password<B: Backend + fmt::Debug, O: Open<B>>(
+    backend: O,
+    password: &'static [u8],
+) -> ContainerResult<Container<B>, B> {
+    let builder = OpenOptionsBuilder::new().with_password_callback(move || Ok(password.to_vec()));
+
+    open_container(backend, builder)
+}
+
+/// Opens a container created by [`create_blocks`].
+#[allow(dead_code)]
+pub fn open_blocks<B: Backend + fmt::Debug, O: Open<B>>(backend: O) -> Container<B> {
+    open_with_password(backend, b"abc").unwrap()
+}
+
+#[allow(dead_code)]
+pub fn clone_backend(backend: &MemoryBackend) -> MemoryBackend {
+    serde_json::from_value(serde_json::to_value(backend).unwrap()).unwrap()
+}
+
+/// Checks the block written by [`create_container`].
+#[allow(dead_code)]
+pub fn assert_block(container: &mut Container<MemoryBackend>) {
+    let id = "1".parse().unwrap();
+    let mut buf = [0; 3];
+
+    assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"xyz");
+}
+
+#[allow(dead_code)]
+pub fn raw_blocks(backend: &MemoryBackend, ids: &[Id]) -> Vec<Vec<u8>> {
+    ids.iter()
+        .map(|id| backend.get(id).unwrap().to_vec())
+        .collect()
+}
+
+#[allow(dead_code)]
+pub fn backend_ids<B: Backend<Id = Id>>(backend: &mut B) -> Vec<Id> {
+    let mut ids = backend.ids().unwrap().unwrap();
+
+    ids.sort_by_key(|id| id.to_string().parse::<u32>().unwrap());
+
+    ids
+}
+
+#[allow(dead_code)]
+pub fn read_block<B: Backend + fmt::Debug>(container: &mut Container<B>, id: &B::Id) -> Vec<u8> {
+    let mut buf = [0; 3];
+
+    assert_eq!(container.read(id, &mut buf).unwrap(), 3);
+
+    buf.to_vec()
+}
+
+#[allow(dead_code)]
+pub fn assert_blocks<B: Backend<Id = Id> + fmt::Debug>(
+    container: &mut Container<B>,
+    ids: &[Id],
+    blocks: &[&[u8]],
+) {
+    for (id, expected) in ids.iter().zip(blocks.iter()) {
+        assert_eq!(read_block(container, id), *expected);
+    }
+}
+
 #[derive(Debug, Error)]
 #[error(transparent)]
 pub struct SampleError(#[from] pub Error<MemoryBackend>);
@@ -88,3 +222,117 @@ impl ServiceFactory<MemoryBackend> for SampleService {
         Ok(SampleService(container))
     }
 }
+
+/// A memory backend, which fails after the given number of writes.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FailingBackend {
+    pub inner: MemoryBackend,
+    writes: Option<usize>,
+    ids: bool,
+}
+
+#[allow(dead_code)]
+impl FailingBackend {
+    pub fn new(inner: MemoryBackend, writes: Option<usize>) -> FailingBackend {
+        FailingBackend {
+            inner,
+            writes,
+            ids: true,
+        }
+    }
+
+    /// The backend cannot list its blocks anymore.
+    pub fn without_ids(mut self) -> FailingBackend {
+        self.ids = false;
+        self
+    }
+
+    fn count_write(&mut self, id: &Id) -> Result<(), MemoryError> {
+        match self.writes.as_mut() {
+            Some(0) => Err(MemoryError::NoSuchId(*id)),
+            Some(n) => {
+                *n -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl ReceiveHeader<Self> for FailingBackend {
+    fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), MemoryError> {
+        self.inner.get_header_bytes(bytes)
+    }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, MemoryError> {
+        self.inner.get_extended_header_bytes(idx, bytes)
+    }
+}
+
+impl Open<Self> for FailingBackend {
+    fn build(self, _settings: Settings) -> Result<FailingBackend, MemoryError> {
+        Ok(self)
+    }
+}
+
+impl Backend for FailingBackend {
+    type Settings = Settings;
+    type Err = MemoryError;
+    type Id = Id;
+    type Info = ();
+
+    fn info(&self) -> Result<(), MemoryError> {
+        self.inner.info()
+    }
+
+    fn block_size(&self) -> u32 {
+        Backend::block_size(&self.inner)
+    }
+
+    fn aquire(&mut self, buf: &[u8]) -> Result<Id, MemoryError> {
+        self.inner.aquire(buf)
+    }
+
+    fn release(&mut self, id: Id) -> Result<(), MemoryError> {
+        self.inner.release(id)
+    }
+
+    fn ids(&mut self) -> Result<Option<Vec<Id>>, MemoryError> {
+        if self.ids {
+            self.inner.ids()
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize, MemoryError> {
+        self.inner.read(id, buf)
+    }
+
+    fn write(&mut self, id: &Id, buf: &[u8]) -> Result<usize, MemoryError> {
+        self.count_write(id)?;
+        self.inner.write(id, buf)
+    }
+
+    fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), MemoryError> {
+        self.count_write(&"0".parse().unwrap())?;
+        self.inner.write_header(buf)
+    }
+
+    fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, MemoryError> {
+        self.inner.write_extended_header(idx, buf)
+    }
+
+    fn delete(self) {
+        self.inner.delete()
+    }
+}
@@ -0,0 +1,266 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::Backend;
+use nuts_container::{
+    Cipher, Compression, Container, ConvertOptionsBuilder, CreateOptionsBuilder, Digest, Kdf,
+    OpenOptionsBuilder,
+};
+use nuts_memory::{Id, MemoryBackend};
+use std::convert::TryInto;
+
+const COMPRESSIONS: [Compression; 2] = [Compression::Lz4, Compression::Zstd];
+
+fn text() -> Vec<u8> {
+    b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(8)
+}
+
+fn noise() -> Vec<u8> {
+    let mut x = 0x87654321u32;
+
+    (0..400)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as u8
+        })
+        .collect()
+}
+
+fn create_container(cipher: Cipher, compression: Compression) -> Container<MemoryBackend> {
+    create_container_on(MemoryBackend::new(), cipher, compression)
+}
+
+fn create_container_on(
+    backend: MemoryBackend,
+    cipher: Cipher,
+    compression: Compression,
+) -> Container<MemoryBackend> {
+    let options = CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .with_compression(compression)
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::create(backend, options).unwrap()
+}
+
+fn variable_backend() -> MemoryBackend {
+    MemoryBackend::new().with_variable_block_size()
+}
+
+fn open_container(backend: MemoryBackend) -> Container<MemoryBackend> {
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::open(backend, options).unwrap()
+}
+
+fn write_block(container: &mut Container<MemoryBackend>, data: &[u8]) -> Id {
+    let id = container.aquire().unwrap();
+
+    assert_eq!(container.write(&id, data).unwrap(), data.len());
+
+    id
+}
+
+fn assert_block(container: &mut Container<MemoryBackend>, id: &Id, data: &[u8]) {
+    let bsize = container.block_size() as usize;
+    let mut buf = vec![0xFF; bsize];
+
+    assert_eq!(container.read(id, &mut buf).unwrap(), bsize);
+    assert_eq!(buf[..data.len()], *data);
+    assert!(buf[data.len()..].iter().all(|n| *n == 0));
+}
+
+fn raw_block(backend: &mut MemoryBackend, id: &Id) -> Vec<u8> {
+    let mut buf = vec![0; Backend::block_size(backend) as usize];
+
+    backend.read(id, &mut buf).unwrap();
+
+    buf
+}
+
+#[test]
+fn block_size() {
+    for compression in COMPRESSIONS {
+        assert_eq!(
+            create_container(Cipher::None, compression).block_size(),
+            507
+        );
+        assert_eq!(
+            create_container(Cipher::Aes128Ctr, compression).block_size(),
+            491
+        );
+        assert_eq!(
+            create_container(Cipher::Aes128Gcm, compression).block_size(),
+            479
+        );
+    }
+}
+
+#[test]
+fn write_read() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        for compression in COMPRESSIONS {
+            let mut container = create_container(cipher, compression);
+            let empty = container.aquire().unwrap();
+            let text_id = write_block(&mut container, &text());
+            let noise_id = write_block(&mut container, &noise());
+
+            let mut container = open_container(container.into_backend());
+
+            assert_eq!(container.info().unwrap().compression, compression);
+            assert_block(&mut container, &empty, &[]);
+            assert_block(&mut container, &text_id, &text());
+            assert_block(&mut container, &noise_id, &noise());
+        }
+    }
+}
+
+#[test]
+fn compressed() {
+    for (compression, method) in [(Compression::Lz4, 1), (Compression::Zstd, 2)] {
+        let mut container = create_container(Cipher::None, compression);
+        let id = write_block(&mut container, &text());
+        let raw = raw_block(&mut container.into_backend(), &id);
+
+        let len = u32::from_be_bytes(raw[1..5].try_into().unwrap()) as usize;
+
+        assert_eq!(raw[0], method);
+        assert!(len < text().len());
+        assert!(raw[5 + len..].iter().all(|n| *n == 0));
+    }
+}
+
+#[test]
+fn stored() {
+    for compression in COMPRESSIONS {
+        let mut container = create_container(Cipher::None, compression);
+        let id = write_block(&mut container, &noise());
+        let raw = raw_block(&mut container.into_backend(), &id);
+
+        assert_eq!(raw[..5], [0, 0, 0, 0x01, 0x90]);
+        assert_eq!(raw[5..405], noise());
+    }
+}
+
+#[test]
+fn write_too_large() {
+    let mut container = create_container(Cipher::None, Compression::Lz4);
+    let id = container.aquire().unwrap();
+
+    assert_eq!(container.write(&id, &[1; 512]).unwrap(), 507);
+    assert_block(&mut container, &id, &[1; 507]);
+}
+
+#[test]
+fn rekey() {
+    for compression in COMPRESSIONS {
+        let mut container = create_container(Cipher::Aes128Gcm, compression);
+        let id = write_block(&mut container, &text());
+
+        container.rekey().unwrap();
+
+        let mut container = open_container(container.into_backend());
+
+        assert_block(&mut container, &id, &text());
+    }
+}
+
+#[test]
+fn convert() {
+    for compression in COMPRESSIONS {
+        let mut container = create_container(Cipher::Aes128Ctr, compression);
+        let id = write_block(&mut container, &text());
+
+        container
+            .convert(ConvertOptionsBuilder::new(Cipher::Aes256Gcm).build())
+            .unwrap();
+
+        let mut container = open_container(container.into_backend());
+        let info = container.info().unwrap();
+
+        assert_eq!(info.cipher, Cipher::Aes256Gcm);
+        assert_eq!(info.compression, compression);
+        assert_eq!(info.bsize_net, 479);
+        assert_block(&mut container, &id, &text());
+    }
+}
+
+#[test]
+fn variable_block_size() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        for compression in COMPRESSIONS {
+            let mut container = create_container_on(variable_backend(), cipher, compression);
+            let empty = container.aquire().unwrap();
+            let text_id = write_block(&mut container, &text());
+            let noise_id = write_block(&mut container, &noise());
+            let backend = container.into_backend();
+
+            // Only the frame is stored, the padding is omitted.
+            let overhead = 512 - 5 - create_container(cipher, compression).block_size() as usize;
+
+            assert_eq!(backend.get(&empty).unwrap().len(), 5 + overhead);
+            assert!(backend.get(&text_id).unwrap().len() < 5 + text().len() + overhead);
+            assert_eq!(
+                backend.get(&noise_id).unwrap().len(),
+                5 + noise().len() + overhead
+            );
+
+            let mut container = open_container(backend);
+
+            assert_block(&mut container, &empty, &[]);
+            assert_block(&mut container, &text_id, &text());
+            assert_block(&mut container, &noise_id, &noise());
+        }
+    }
+}
+
+#[test]
+fn variable_block_size_uncompressed() {
+    let mut container = create_container_on(variable_backend(), Cipher::None, Compression::None);
+    let id = write_block(&mut container, &text());
+
+    assert_eq!(container.into_backend().get(&id).unwrap().len(), 512);
+}
+
+#[test]
+fn variable_block_size_rekey() {
+    for compression in COMPRESSIONS {
+        let mut container = create_container_on(variable_backend(), Cipher::Aes128Gcm, compression);
+        let id = write_block(&mut container, &text());
+
+        container.rekey().unwrap();
+
+        let backend = container.into_backend();
+        assert!(backend.get(&id).unwrap().len() < 512);
+
+        let mut container = open_container(backend);
+
+        assert_block(&mut container, &id, &text());
+    }
+}
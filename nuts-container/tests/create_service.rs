@@ -45,7 +45,7 @@ fn inval_revision() {
 
     assert!(matches!(err.0, Error::Header(cause)
         if matches!(cause,HeaderError::InvalidRevision(expected, got)
-            if expected == 2 && got == 0)));
+            if expected == 3 && got == 0)));
 }
 
 #[test]
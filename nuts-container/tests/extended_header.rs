@@ -0,0 +1,355 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_backend::{Backend, Create, Open, ReceiveHeader, HEADER_MAX_SIZE};
+use nuts_container::{
+    Cipher, Container, ContainerResult, CreateOptionsBuilder, Digest, Error, HeaderError, Identity,
+    Kdf, ModifyOptionsBuilder, OpenOptionsBuilder,
+};
+use nuts_memory::{Error as MemoryError, Id, MemoryBackend, Settings};
+
+use crate::common::FailingBackend;
+
+const NUM_RECIPIENTS: usize = 10;
+
+/// A memory backend, which does not support extended header blocks.
+#[derive(Debug)]
+struct RootOnlyBackend(MemoryBackend);
+
+impl ReceiveHeader<Self> for RootOnlyBackend {
+    fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), MemoryError> {
+        self.0.get_header_bytes(bytes)
+    }
+}
+
+impl Create<Self> for RootOnlyBackend {
+    fn settings(&self) -> Settings {
+        Settings
+    }
+
+    fn build(
+        mut self,
+        header: [u8; HEADER_MAX_SIZE],
+        _overwrite: bool,
+    ) -> Result<RootOnlyBackend, MemoryError> {
+        self.write_header(&header)?;
+        Ok(self)
+    }
+}
+
+impl Open<Self> for RootOnlyBackend {
+    fn build(self, _settings: Settings) -> Result<RootOnlyBackend, MemoryError> {
+        Ok(self)
+    }
+}
+
+impl Backend for RootOnlyBackend {
+    type Settings = Settings;
+    type Err = MemoryError;
+    type Id = Id;
+    type Info = ();
+
+    fn info(&self) -> Result<(), MemoryError> {
+        self.0.info()
+    }
+
+    fn block_size(&self) -> u32 {
+        Backend::block_size(&self.0)
+    }
+
+    fn aquire(&mut self, buf: &[u8]) -> Result<Id, MemoryError> {
+        self.0.aquire(buf)
+    }
+
+    fn release(&mut self, id: Id) -> Result<(), MemoryError> {
+        self.0.release(id)
+    }
+
+    fn ids(&mut self) -> Result<Option<Vec<Id>>, MemoryError> {
+        self.0.ids()
+    }
+
+    fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize, MemoryError> {
+        self.0.read(id, buf)
+    }
+
+    fn write(&mut self, id: &Id, buf: &[u8]) -> Result<usize, MemoryError> {
+        self.0.write(id, buf)
+    }
+
+    fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), MemoryError> {
+        self.0.write_header(buf)
+    }
+
+    fn delete(self) {
+        self.0.delete()
+    }
+}
+
+fn create_container(identities: &[Identity]) -> Container<MemoryBackend> {
+    let mut builder = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"));
+
+    for identity in identities {
+        builder = builder.with_recipient(identity.recipient().unwrap());
+    }
+
+    let options = builder.build::<MemoryBackend>().unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let id = container.aquire().unwrap();
+    container.write(&id, b"xyz").unwrap();
+
+    container
+}
+
+fn open_container(
+    backend: MemoryBackend,
+    identity: Option<&Identity>,
+) -> ContainerResult<Container<MemoryBackend>, MemoryBackend> {
+    let mut builder = OpenOptionsBuilder::new();
+
+    match identity {
+        Some(identity) => builder = builder.with_identity(identity.clone()),
+        None => builder = builder.with_password_callback(|| Ok(b"abc".to_vec())),
+    }
+
+    let options = builder.build::<MemoryBackend>().unwrap();
+
+    Container::open(backend, options)
+}
+
+fn identities() -> Vec<Identity> {
+    (0..NUM_RECIPIENTS)
+        .map(|_| Identity::generate().unwrap())
+        .collect()
+}
+
+fn extended_header(backend: &mut MemoryBackend, idx: u32) -> [u8; HEADER_MAX_SIZE] {
+    let mut buf = [0; HEADER_MAX_SIZE];
+
+    backend.get_extended_header_bytes(idx, &mut buf).unwrap();
+
+    buf
+}
+
+fn assert_block(container: &mut Container<MemoryBackend>) {
+    let id = "1".parse().unwrap();
+    let mut buf = [0; 3];
+
+    assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"xyz");
+}
+
+#[test]
+fn create() {
+    let identities = identities();
+    let mut backend = create_container(&identities).into_backend();
+
+    assert_ne!(extended_header(&mut backend, 1), [0; HEADER_MAX_SIZE]);
+
+    for identity in identities.iter() {
+        let mut container = open_container(backend, Some(identity)).unwrap();
+
+        assert_eq!(container.info().unwrap().slots.len(), NUM_RECIPIENTS + 1);
+        assert_block(&mut container);
+
+        backend = container.into_backend();
+    }
+
+    let mut container = open_container(backend, None).unwrap();
+    assert_block(&mut container);
+}
+
+#[test]
+fn grow() {
+    let identities = identities();
+    let mut container = create_container(&[]);
+    let mut builder = ModifyOptionsBuilder::default();
+
+    for identity in identities.iter() {
+        builder = builder.add_recipient(identity.recipient().unwrap());
+    }
+
+    container.modify(builder.build()).unwrap();
+
+    let mut container = open_container(container.into_backend(), identities.last()).unwrap();
+
+    assert_eq!(container.info().unwrap().slots.len(), NUM_RECIPIENTS + 1);
+    assert_block(&mut container);
+}
+
+#[test]
+fn shrink() {
+    let identities = identities();
+    let mut container = create_container(&identities);
+    let mut builder = ModifyOptionsBuilder::default();
+
+    for idx in 1..=NUM_RECIPIENTS {
+        builder = builder.remove_slot(idx);
+    }
+
+    container.modify(builder.build()).unwrap();
+
+    // the outdated key slots are wiped
+    let mut backend = container.into_backend();
+    assert_eq!(extended_header(&mut backend, 1), [0; HEADER_MAX_SIZE]);
+
+    let err = open_container(backend, identities.first()).unwrap_err();
+    assert!(matches!(err, Error::Header(HeaderError::UnknownIdentity)));
+}
+
+#[test]
+fn interrupted() {
+    let identities = identities();
+    let backend = create_container(&identities).into_backend();
+
+    // interrupt the update before the root header is written
+    let options = OpenOptionsBuilder::new()
+        .with_identity(identities[0].clone())
+        .build::<FailingBackend>()
+        .unwrap();
+    let mut container = Container::open(FailingBackend::new(backend, Some(0)), options).unwrap();
+    let mut builder = ModifyOptionsBuilder::default();
+
+    for idx in 2..=NUM_RECIPIENTS {
+        builder = builder.remove_slot(idx);
+    }
+
+    container.modify(builder.build()).unwrap_err();
+
+    // the extended header blocks of the old header are still intact
+    let mut backend = container.into_backend().inner;
+    assert_ne!(extended_header(&mut backend, 1), [0; HEADER_MAX_SIZE]);
+
+    let mut container = open_container(backend, identities.last()).unwrap();
+
+    assert_eq!(container.info().unwrap().slots.len(), NUM_RECIPIENTS + 1);
+    assert_block(&mut container);
+}
+
+#[test]
+fn alternate() {
+    let identities = identities();
+    let mut container = create_container(&identities);
+
+    // every update writes the extended header blocks into the other slots
+    for n in 0..4 {
+        let mut builder = ModifyOptionsBuilder::default();
+
+        builder = builder.add_recipient(Identity::generate().unwrap().recipient().unwrap());
+        container.modify(builder.build()).unwrap();
+
+        let mut backend = container.into_backend();
+        let (live, stale) = if n % 2 == 0 { (2, 1) } else { (1, 2) };
+
+        assert_ne!(extended_header(&mut backend, live), [0; HEADER_MAX_SIZE]);
+        assert_eq!(extended_header(&mut backend, stale), [0; HEADER_MAX_SIZE]);
+
+        container = open_container(backend, identities.last()).unwrap();
+        assert_block(&mut container);
+    }
+}
+
+#[test]
+fn backup() {
+    let identities = identities();
+    let mut container = create_container(&identities);
+    let header = container.export_header().unwrap();
+
+    assert!(header.len() > HEADER_MAX_SIZE);
+    assert_eq!(header.len() % HEADER_MAX_SIZE, 0);
+
+    let mut backend = container.into_backend();
+    backend.write_header(&[0; HEADER_MAX_SIZE]).unwrap();
+    backend
+        .write_extended_header(1, &[0; HEADER_MAX_SIZE])
+        .unwrap();
+
+    let options = OpenOptionsBuilder::new()
+        .with_identity(identities[0].clone())
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::restore_header(backend, options, &header).unwrap();
+
+    let mut container = open_container(container.into_backend(), identities.last()).unwrap();
+    assert_block(&mut container);
+}
+
+#[test]
+fn backup_truncated() {
+    let identities = identities();
+    let mut container = create_container(&identities);
+    let header = container.export_header().unwrap();
+
+    let options = OpenOptionsBuilder::new()
+        .with_identity(identities[0].clone())
+        .build::<MemoryBackend>()
+        .unwrap();
+    let err = Container::restore_header(
+        container.into_backend(),
+        options,
+        &header[..HEADER_MAX_SIZE],
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::InvalidHeader)));
+}
+
+#[test]
+fn create_unsupported() {
+    let mut builder = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"));
+
+    for identity in identities() {
+        builder = builder.with_recipient(identity.recipient().unwrap());
+    }
+
+    let options = builder.build::<RootOnlyBackend>().unwrap();
+    let err = Container::create(RootOnlyBackend(MemoryBackend::new()), options).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Header(HeaderError::ExtendedHeaderUnsupported)
+    ));
+}
+
+#[test]
+fn open_unsupported() {
+    let identities = identities();
+    let backend = create_container(&identities).into_backend();
+
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .build::<RootOnlyBackend>()
+        .unwrap();
+    let err = Container::open(RootOnlyBackend(backend), options).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Header(HeaderError::ExtendedHeaderUnsupported)
+    ));
+}
@@ -0,0 +1,154 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_backend::{Backend, HEADER_MAX_SIZE};
+use nuts_container::{
+    Cipher, Container, Error, HeaderError, ModifyOptionsBuilder, OpenOptionsBuilder,
+};
+use nuts_memory::MemoryBackend;
+
+use crate::common::{assert_block, create_container, open_with_password as open, password_options};
+
+fn corrupt_header(container: Container<MemoryBackend>) -> MemoryBackend {
+    let mut backend = container.into_backend();
+
+    backend.write_header(&[0; HEADER_MAX_SIZE]).unwrap();
+
+    backend
+}
+
+fn restore(
+    backend: MemoryBackend,
+    header: &[u8],
+    password: &'static [u8],
+) -> Result<Container<MemoryBackend>, Error<MemoryBackend>> {
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(move || Ok(password.to_vec()))
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::restore_header(backend, options, header)
+}
+
+#[test]
+fn restore_corrupted() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        let mut container = create_container(password_options(cipher));
+        let header = container.export_header().unwrap();
+        let backend = corrupt_header(container);
+
+        let mut container = restore(backend, &header, b"abc").unwrap();
+        assert_block(&mut container);
+
+        // the header was written back
+        let mut container = open(container.into_backend(), b"abc").unwrap();
+        assert_block(&mut container);
+    }
+}
+
+#[test]
+fn restore_intact() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let header = container.export_header().unwrap();
+
+    let mut container = restore(container.into_backend(), &header, b"abc").unwrap();
+    assert_block(&mut container);
+}
+
+#[test]
+fn restore_new_password() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let header = container.export_header().unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .change_password(|| Ok(b"def".to_vec()))
+        .build();
+    container.modify(options).unwrap();
+
+    // the backup is protected by the password, which was active on export
+    let err = restore(container.into_backend(), &header, b"def").unwrap_err();
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn restore_old_password() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let header = container.export_header().unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .change_password(|| Ok(b"def".to_vec()))
+        .build();
+    container.modify(options).unwrap();
+
+    let container = restore(container.into_backend(), &header, b"abc").unwrap();
+
+    // the old password is restored
+    let mut container = open(container.into_backend(), b"abc").unwrap();
+    assert_block(&mut container);
+}
+
+#[test]
+fn restore_corrupted_backup() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let mut header = container.export_header().unwrap();
+
+    header[0] ^= 0xFF;
+
+    let err = restore(container.into_backend(), &header, b"abc").unwrap_err();
+    assert!(matches!(err, Error::Header(HeaderError::InvalidHeader)));
+}
+
+#[test]
+fn restore_other_container() {
+    let mut container1 = create_container(password_options(Cipher::Aes128Gcm));
+    let container2 = create_container(password_options(Cipher::Aes128Gcm));
+    let header = container1.export_header().unwrap();
+
+    let err = restore(container2.into_backend(), &header, b"abc").unwrap_err();
+    assert!(matches!(err, Error::HeaderMismatch));
+}
+
+#[test]
+fn restore_after_rekey() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let header = container.export_header().unwrap();
+
+    container.rekey().unwrap();
+
+    let err = restore(container.into_backend(), &header, b"abc").unwrap_err();
+    assert!(matches!(err, Error::HeaderMismatch));
+}
+
+#[test]
+fn restore_empty() {
+    let options = password_options(Cipher::Aes128Gcm)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+    let header = container.export_header().unwrap();
+    let backend = corrupt_header(container);
+
+    // nothing to verify
+    restore(backend, &header, b"abc").unwrap();
+}
@@ -0,0 +1,165 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_container::{
+    Cipher, Container, ContainerResult, CreateOptionsBuilder, Error, HeaderError,
+    ModifyOptionsBuilder, OpenOptionsBuilder, PasswordError,
+};
+use nuts_memory::MemoryBackend;
+
+use crate::common::{assert_block, clone_backend, fixture_path, kdf};
+
+fn create_container(keyfile: &str, password: Option<&'static [u8]>) -> MemoryBackend {
+    let mut builder = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_kdf(kdf())
+        .with_keyfile(fixture_path("keyfile", keyfile));
+
+    if let Some(password) = password {
+        builder = builder.with_password_callback(move || Ok(password.to_vec()));
+    }
+
+    common::create_container(builder).into_backend()
+}
+
+fn open_container(
+    backend: MemoryBackend,
+    keyfile: Option<&str>,
+    password: Option<&'static [u8]>,
+) -> ContainerResult<Container<MemoryBackend>, MemoryBackend> {
+    let mut builder = OpenOptionsBuilder::new();
+
+    if let Some(keyfile) = keyfile {
+        builder = builder.with_keyfile(fixture_path("keyfile", keyfile));
+    }
+
+    if let Some(password) = password {
+        builder = builder.with_password_callback(move || Ok(password.to_vec()));
+    }
+
+    common::open_container(backend, builder)
+}
+
+#[test]
+fn keyfile() {
+    let backend = create_container("sample", None);
+    let mut container = open_container(backend, Some("sample"), None).unwrap();
+
+    assert_block(&mut container);
+}
+
+#[test]
+fn keyfile_wrong_keyfile() {
+    let backend = create_container("sample", None);
+    let err = open_container(backend, Some("other"), None).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn keyfile_no_keyfile() {
+    let backend = create_container("sample", None);
+    let err = open_container(backend, None, Some(b"abc")).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn keyfile_no_such_keyfile() {
+    let backend = create_container("sample", None);
+    let err = open_container(backend, Some("no-such-file"), None).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Header(HeaderError::Password(PasswordError::Keyfile(_)))
+    ));
+}
+
+#[test]
+fn keyfile_and_password() {
+    let backend = create_container("sample", Some(b"abc"));
+    let mut container = open_container(backend, Some("sample"), Some(b"abc")).unwrap();
+
+    assert_block(&mut container);
+}
+
+#[test]
+fn keyfile_and_password_wrong_password() {
+    let backend = create_container("sample", Some(b"abc"));
+    let err = open_container(backend, Some("sample"), Some(b"def")).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn keyfile_and_password_no_password() {
+    let backend = create_container("sample", Some(b"abc"));
+    let err = open_container(backend, Some("sample"), None).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn keyfile_and_password_no_keyfile() {
+    let backend = create_container("sample", Some(b"abc"));
+    let err = open_container(backend, None, Some(b"abc")).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn keyfile_and_password_change_password() {
+    let backend = create_container("sample", Some(b"abc"));
+    let mut container = open_container(backend, Some("sample"), Some(b"abc")).unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .change_password(|| Ok(b"def".to_vec()))
+        .build();
+    container.modify(options).unwrap();
+
+    // the keyfile is still needed
+    let backend = container.into_backend();
+    let err = open_container(clone_backend(&backend), None, Some(b"def")).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+
+    let err = open_container(clone_backend(&backend), Some("sample"), Some(b"abc")).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+
+    let mut container = open_container(backend, Some("sample"), Some(b"def")).unwrap();
+
+    assert_block(&mut container);
+}
+
+#[test]
+fn none() {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .with_keyfile(fixture_path("keyfile", "no-such-file"))
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let backend = container.into_backend();
+    open_container(backend, Some("no-such-file"), None).unwrap();
+}
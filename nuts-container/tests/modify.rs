@@ -159,6 +159,28 @@ macro_rules! kdf_test {
     };
 }
 
+macro_rules! change_kdf_test {
+    ($name:ident, $json:literal, $kdf:expr) => {
+        #[test]
+        fn $name() {
+            let backend = {
+                let backend = open_backend_from_fixture("compat", $json);
+                let mut container = open_container(backend, OLD_PW).unwrap();
+
+                let options = ModifyOptionsBuilder::default().change_kdf($kdf).build();
+                container.modify(options).unwrap();
+
+                container.into_backend()
+            };
+
+            let container = open_container(backend, OLD_PW).unwrap();
+            let info = container.info().unwrap();
+
+            assert_eq!(info.kdf, $kdf);
+        }
+    };
+}
+
 mod v_0_6_8 {
     use nuts_container::{
         Cipher, CipherError, Digest, Error, HeaderError, Kdf, ModifyOptionsBuilder,
@@ -309,4 +331,36 @@ mod v_0_7_3 {
     password_test!(password_aes256gcm, "0.7.3-aes256gcm.json", Aes256Gcm);
     old_password_test!(old_password_aes256gcm(trust), "0.7.3-aes256gcm.json");
     kdf_test!(kdf_aes256gcm, "0.7.3-aes256gcm.json", Aes256Gcm);
+
+    change_kdf_test!(
+        argon2id_aes128ctr,
+        "0.7.3-aes128ctr.json",
+        Kdf::argon2id(8, 1, 1, b"12345678")
+    );
+    change_kdf_test!(
+        argon2id_aes128gcm,
+        "0.7.3-aes128gcm.json",
+        Kdf::argon2id(8, 1, 1, b"12345678")
+    );
+    change_kdf_test!(
+        argon2id_aes256gcm,
+        "0.7.3-aes256gcm.json",
+        Kdf::argon2id(8, 1, 1, b"12345678")
+    );
+
+    change_kdf_test!(
+        scrypt_aes128ctr,
+        "0.7.3-aes128ctr.json",
+        Kdf::scrypt(4, 1, 1, b"123")
+    );
+    change_kdf_test!(
+        scrypt_aes128gcm,
+        "0.7.3-aes128gcm.json",
+        Kdf::scrypt(4, 1, 1, b"123")
+    );
+    change_kdf_test!(
+        scrypt_aes256gcm,
+        "0.7.3-aes256gcm.json",
+        Kdf::scrypt(4, 1, 1, b"123")
+    );
 }
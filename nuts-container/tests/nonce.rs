@@ -0,0 +1,89 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_container::{Cipher, Container, CreateOptionsBuilder};
+use nuts_memory::MemoryBackend;
+
+fn setup_container(cipher: Cipher) -> Container<MemoryBackend> {
+    let options = CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::create(MemoryBackend::new(), options).unwrap()
+}
+
+macro_rules! nonce_tests {
+    ($name:ident, $cipher:ident, $iv_len:literal) => {
+        mod $name {
+            use nuts_container::Cipher;
+
+            use super::setup_container;
+
+            #[test]
+            fn different_blocks() {
+                let mut container = setup_container(Cipher::$cipher);
+                let id1 = container.aquire().unwrap();
+                let id2 = container.aquire().unwrap();
+
+                container.write(&id1, b"abc").unwrap();
+                container.write(&id2, b"abc").unwrap();
+
+                let block1 = container.backend().get(&id1).unwrap().to_vec();
+                let block2 = container.backend().get(&id2).unwrap().to_vec();
+
+                assert_ne!(block1[..$iv_len], block2[..$iv_len]);
+                assert_ne!(block1[$iv_len..], block2[$iv_len..]);
+            }
+
+            #[test]
+            fn same_block() {
+                let mut container = setup_container(Cipher::$cipher);
+                let id = container.aquire().unwrap();
+
+                container.write(&id, b"abc").unwrap();
+                let block1 = container.backend().get(&id).unwrap().to_vec();
+
+                container.write(&id, b"abc").unwrap();
+                let block2 = container.backend().get(&id).unwrap().to_vec();
+
+                assert_ne!(block1[..$iv_len], block2[..$iv_len]);
+                assert_ne!(block1[$iv_len..], block2[$iv_len..]);
+
+                let mut buf = [0; 3];
+                assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+                assert_eq!(buf, *b"abc");
+            }
+        }
+    };
+}
+
+nonce_tests!(aes128_ctr, Aes128Ctr, 16);
+nonce_tests!(aes128_gcm, Aes128Gcm, 12);
+nonce_tests!(aes128_gcm_siv, Aes128GcmSiv, 12);
+nonce_tests!(aes192_ctr, Aes192Ctr, 16);
+nonce_tests!(aes192_gcm, Aes192Gcm, 12);
+nonce_tests!(aes256_ctr, Aes256Ctr, 16);
+nonce_tests!(aes256_gcm, Aes256Gcm, 12);
+nonce_tests!(aes256_gcm_siv, Aes256GcmSiv, 12);
+nonce_tests!(chacha20_poly1305, ChaCha20Poly1305, 12);
+nonce_tests!(xchacha20_poly1305, XChaCha20Poly1305, 24);
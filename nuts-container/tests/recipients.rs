@@ -0,0 +1,177 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_container::{
+    Cipher, Container, ContainerResult, CreateOptionsBuilder, Error, HeaderError, Identity, Kdf,
+    ModifyOptionsBuilder, OpenOptionsBuilder, SlotInfo,
+};
+use nuts_memory::MemoryBackend;
+
+use crate::common::{assert_block, clone_backend, kdf};
+
+fn create_container(identity: &Identity, password: bool) -> MemoryBackend {
+    let mut builder = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_kdf(kdf())
+        .with_recipient(identity.recipient().unwrap());
+
+    if password {
+        builder = builder.with_password_callback(|| Ok(b"abc".to_vec()));
+    }
+
+    common::create_container(builder).into_backend()
+}
+
+fn open_container(
+    backend: MemoryBackend,
+    identity: Option<&Identity>,
+    password: Option<&'static [u8]>,
+) -> ContainerResult<Container<MemoryBackend>, MemoryBackend> {
+    let mut builder = OpenOptionsBuilder::new();
+
+    if let Some(identity) = identity {
+        builder = builder.with_identity(identity.clone());
+    }
+
+    if let Some(password) = password {
+        builder = builder.with_password_callback(move || Ok(password.to_vec()));
+    }
+
+    common::open_container(backend, builder)
+}
+
+#[test]
+fn recipient_only() {
+    let identity = Identity::generate().unwrap();
+    let backend = create_container(&identity, false);
+
+    let mut container = open_container(clone_backend(&backend), Some(&identity), None).unwrap();
+    let info = container.info().unwrap();
+
+    assert_eq!(info.kdf, Kdf::None);
+    assert_eq!(
+        info.slots,
+        [SlotInfo::Recipient(identity.recipient().unwrap())]
+    );
+    assert_eq!(info.active_slot, Some(0));
+    assert_block(&mut container);
+
+    let err = open_container(backend, None, Some(b"abc")).unwrap_err();
+    assert!(matches!(err, Error::Header(HeaderError::NotTrustworthy)));
+}
+
+#[test]
+fn recipient_and_password() {
+    let identity = Identity::generate().unwrap();
+    let backend = create_container(&identity, true);
+
+    let mut container = open_container(clone_backend(&backend), Some(&identity), None).unwrap();
+    let info = container.info().unwrap();
+
+    assert_eq!(
+        info.slots,
+        [
+            SlotInfo::Password(kdf()),
+            SlotInfo::Recipient(identity.recipient().unwrap())
+        ]
+    );
+    assert_eq!(info.active_slot, Some(1));
+    assert_block(&mut container);
+
+    let mut container = open_container(backend, None, Some(b"abc")).unwrap();
+
+    assert_eq!(container.info().unwrap().active_slot, Some(0));
+    assert_block(&mut container);
+}
+
+#[test]
+fn unknown_identity() {
+    let identity = Identity::generate().unwrap();
+    let backend = create_container(&identity, true);
+
+    let other = Identity::generate().unwrap();
+    let err = open_container(backend, Some(&other), None).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::UnknownIdentity)));
+}
+
+#[test]
+fn add_recipient() {
+    let identity = Identity::generate().unwrap();
+    let backend = create_container(&identity, true);
+    let mut container = open_container(backend, None, Some(b"abc")).unwrap();
+
+    let other = Identity::generate().unwrap();
+    let options = ModifyOptionsBuilder::default()
+        .add_recipient(other.recipient().unwrap())
+        .build();
+    container.modify(options).unwrap();
+
+    let backend = container.into_backend();
+    let mut container = open_container(backend, Some(&other), None).unwrap();
+
+    assert_eq!(container.info().unwrap().active_slot, Some(2));
+    assert_block(&mut container);
+}
+
+#[test]
+fn remove_recipient() {
+    let identity = Identity::generate().unwrap();
+    let backend = create_container(&identity, true);
+    let mut container = open_container(backend, None, Some(b"abc")).unwrap();
+
+    let options = ModifyOptionsBuilder::default().remove_slot(1).build();
+    container.modify(options).unwrap();
+
+    let backend = container.into_backend();
+    let err = open_container(backend, Some(&identity), None).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::UnknownIdentity)));
+}
+
+#[test]
+fn change_password_with_identity() {
+    let identity = Identity::generate().unwrap();
+    let backend = create_container(&identity, true);
+    let mut container = open_container(backend, Some(&identity), None).unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .change_password(|| Ok(b"def".to_vec()))
+        .build();
+    let err = container.modify(options).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NoPasswordSlot)));
+}
+
+#[test]
+fn none() {
+    let identity = Identity::generate().unwrap();
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .with_recipient(identity.recipient().unwrap())
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let container = open_container(container.into_backend(), Some(&identity), None).unwrap();
+    assert!(container.info().unwrap().slots.is_empty());
+}
@@ -0,0 +1,176 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_container::{
+    Cipher, Container, ConvertOptionsBuilder, Error, HeaderError, ModifyOptionsBuilder,
+    OpenOptionsBuilder, RecoveryKey,
+};
+use nuts_memory::MemoryBackend;
+use std::fs::File;
+
+use crate::common::{
+    assert_block, create_container, fixture_password, fixture_path, kdf, open_container,
+    open_with_password, password_options,
+};
+
+fn open_with_recovery_key(
+    backend: MemoryBackend,
+    recovery_key: &RecoveryKey,
+) -> Result<Container<MemoryBackend>, Error<MemoryBackend>> {
+    let builder = OpenOptionsBuilder::new().with_recovery_key(recovery_key.clone());
+
+    open_container(backend, builder)
+}
+
+#[test]
+fn open() {
+    for cipher in [Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        let container = create_container(password_options(cipher));
+        let recovery_key = container.recovery_key().unwrap();
+
+        // written down on paper and transcribed again
+        let recovery_key = recovery_key
+            .to_string()
+            .to_lowercase()
+            .parse::<RecoveryKey>()
+            .unwrap();
+
+        let mut container =
+            open_with_recovery_key(container.into_backend(), &recovery_key).unwrap();
+        let info = container.info().unwrap();
+
+        assert_eq!(info.cipher, cipher);
+        assert_eq!(info.active_slot, None);
+        assert_block(&mut container);
+    }
+}
+
+#[test]
+fn reset_password() {
+    let container = create_container(password_options(Cipher::Aes128Gcm));
+    let recovery_key = container.recovery_key().unwrap();
+    let mut container = open_with_recovery_key(container.into_backend(), &recovery_key).unwrap();
+
+    // the password cannot be changed, there is no active password slot
+    let options = ModifyOptionsBuilder::default()
+        .change_password(|| Ok(b"def".to_vec()))
+        .build();
+    let err = container.modify(options).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NoPasswordSlot)));
+
+    let options = ModifyOptionsBuilder::default()
+        .remove_slot(0)
+        .add_slot_with_kdf(kdf(), || Ok(b"def".to_vec()))
+        .build();
+
+    container.modify(options).unwrap();
+
+    let mut container = open_with_password(container.into_backend(), b"def").unwrap();
+
+    assert_block(&mut container);
+
+    // the recovery key is still valid
+    let mut container = open_with_recovery_key(container.into_backend(), &recovery_key).unwrap();
+
+    assert_block(&mut container);
+}
+
+#[test]
+fn rekey() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let recovery_key = container.recovery_key().unwrap();
+
+    container.rekey().unwrap();
+
+    let new_recovery_key = container.recovery_key().unwrap();
+    assert_ne!(new_recovery_key.to_string(), recovery_key.to_string());
+
+    let mut container =
+        open_with_recovery_key(container.into_backend(), &new_recovery_key).unwrap();
+
+    assert_block(&mut container);
+
+    // the old recovery key is invalidated
+    let err = open_with_recovery_key(container.into_backend(), &recovery_key).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::WrongRecoveryKey)));
+}
+
+#[test]
+fn convert() {
+    let mut container = create_container(password_options(Cipher::Aes128Gcm));
+    let recovery_key = container.recovery_key().unwrap();
+
+    container
+        .convert(ConvertOptionsBuilder::new(Cipher::Aes256Gcm).build())
+        .unwrap();
+
+    let new_recovery_key = container.recovery_key().unwrap();
+    let mut container =
+        open_with_recovery_key(container.into_backend(), &new_recovery_key).unwrap();
+
+    assert_block(&mut container);
+
+    let err = open_with_recovery_key(container.into_backend(), &recovery_key).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::WrongRecoveryKey)));
+}
+
+#[test]
+fn wrong_key() {
+    let container = create_container(password_options(Cipher::Aes128Gcm));
+    let other = create_container(password_options(Cipher::Aes128Gcm));
+    let recovery_key = other.recovery_key().unwrap();
+
+    let err = open_with_recovery_key(container.into_backend(), &recovery_key).unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::WrongRecoveryKey)));
+}
+
+#[test]
+fn none() {
+    let container = create_container(password_options(Cipher::None));
+    let err = container.recovery_key().unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::NotEncrypted)));
+}
+
+#[test]
+fn rev2() {
+    let file = File::open(fixture_path("compat", "0.7.3-aes128gcm.json")).unwrap();
+    let backend: MemoryBackend = serde_json::from_reader(file).unwrap();
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(fixture_password)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let container = Container::open(backend, options).unwrap();
+
+    let err = container.recovery_key().unwrap_err();
+
+    assert!(
+        matches!(err, Error::Header(HeaderError::InvalidRevision(expected, got))
+        if expected == 3 && got == 2)
+    );
+}
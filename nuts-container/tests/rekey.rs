@@ -0,0 +1,588 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+use nuts_backend::{Backend, Open};
+use nuts_container::{
+    Cipher, Container, ConvertOptionsBuilder, CreateOptionsBuilder, Digest, Error, HeaderError,
+    Identity, Kdf, ModifyOptionsBuilder, OpenOptionsBuilder,
+};
+use nuts_memory::{Id, MemoryBackend};
+use std::fmt;
+use std::fs::File;
+
+use crate::common::{
+    assert_blocks, create_blocks, fixture_password, fixture_path, open_blocks, raw_blocks,
+    read_block, FailingBackend, SampleService, BLOCKS,
+};
+
+#[test]
+fn rekey() {
+    for cipher in [
+        Cipher::Aes128Ctr,
+        Cipher::Aes256Ctr,
+        Cipher::Aes128Gcm,
+        Cipher::Aes256Gcm,
+        Cipher::ChaCha20Poly1305,
+        Cipher::XChaCha20Poly1305,
+        Cipher::Aes128GcmSiv,
+        Cipher::Aes256GcmSiv,
+    ] {
+        let (container, ids) = create_blocks(cipher);
+        let backend = container.into_backend();
+        let before = raw_blocks(&backend, &ids);
+
+        let mut container = open_blocks(backend);
+
+        container.rekey().unwrap();
+        assert_blocks(&mut container, &ids, &BLOCKS);
+
+        let backend = container.into_backend();
+        let after = raw_blocks(&backend, &ids);
+
+        for (lhs, rhs) in before.iter().zip(after.iter()) {
+            assert_ne!(lhs, rhs);
+        }
+
+        let mut container = open_blocks(backend);
+
+        assert_blocks(&mut container, &ids, &BLOCKS);
+    }
+}
+
+#[test]
+fn rekey_twice() {
+    let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+    let backend = container.into_backend();
+    let mut container = open_blocks(backend);
+
+    container.rekey().unwrap();
+    container.rekey().unwrap();
+    assert_blocks(&mut container, &ids, &BLOCKS);
+
+    let mut container = open_blocks(container.into_backend());
+
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn rekey_none() {
+    let (container, ids) = create_blocks(Cipher::None);
+    let backend = container.into_backend();
+    let before = raw_blocks(&backend, &ids);
+
+    let mut container = open_blocks(backend);
+
+    container.rekey().unwrap();
+    assert_blocks(&mut container, &ids, &BLOCKS);
+
+    assert_eq!(raw_blocks(container.backend(), &ids), before);
+}
+
+#[test]
+fn rekey_identity() {
+    let identity = Identity::generate().unwrap();
+    let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_recipient(identity.recipient().unwrap())
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+    let id = container.aquire().unwrap();
+
+    container.write(&id, b"abc").unwrap();
+
+    let options = OpenOptionsBuilder::new()
+        .with_identity(identity.clone())
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(container.into_backend(), options).unwrap();
+
+    container.rekey().unwrap();
+
+    let options = OpenOptionsBuilder::new()
+        .with_identity(identity)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(container.into_backend(), options).unwrap();
+    let mut buf = [0; 3];
+
+    assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"abc");
+}
+
+#[test]
+fn rekey_interrupted() {
+    for cipher in [Cipher::Aes128Ctr, Cipher::Aes128Gcm] {
+        for writes in 0.. {
+            let (container, ids) = create_blocks(cipher);
+            let backend = container.into_backend();
+            let mut container = open_blocks(FailingBackend::new(backend, Some(writes)));
+
+            if container.rekey().is_ok() {
+                // no interruption anymore, all interruption points are tested
+                assert!(writes > 0);
+                break;
+            }
+
+            // the container is usable while the rotation is pending
+            let backend = container.into_backend().inner;
+            let mut container = open_blocks(FailingBackend::new(backend, None));
+
+            assert_blocks(&mut container, &ids, &BLOCKS);
+
+            container.rekey().unwrap();
+            assert_blocks(&mut container, &ids, &BLOCKS);
+
+            let backend = container.into_backend().inner;
+            let mut container = open_blocks(backend);
+
+            assert_blocks(&mut container, &ids, &BLOCKS);
+        }
+    }
+}
+
+#[test]
+fn rekey_interrupted_write() {
+    let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+    let backend = container.into_backend();
+
+    // interrupted after the first block
+    let mut container = open_blocks(FailingBackend::new(backend, Some(3)));
+    container.rekey().unwrap_err();
+
+    let backend = container.into_backend().inner;
+    let mut container = open_blocks(FailingBackend::new(backend, None));
+
+    // update blocks before and after the interruption point
+    for id in ids.iter() {
+        container.write(id, b"xyz").unwrap();
+    }
+
+    let new_id = container.aquire().unwrap();
+    container.write(&new_id, b"xyz").unwrap();
+
+    container.rekey().unwrap();
+
+    let backend = container.into_backend().inner;
+    let mut container = open_blocks(backend);
+
+    for id in ids.iter().chain([new_id].iter()) {
+        let mut buf = [0; 3];
+
+        assert_eq!(container.read(id, &mut buf).unwrap(), 3);
+        assert_eq!(buf, *b"xyz");
+    }
+}
+
+#[test]
+fn rekey_ids_unsupported() {
+    let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+    let backend = FailingBackend::new(container.into_backend(), None).without_ids();
+    let mut container = open_blocks(backend);
+
+    let err = container.rekey().unwrap_err();
+    assert!(matches!(err, Error::IdsUnsupported));
+
+    let err = container.verify().unwrap_err();
+    assert!(matches!(err, Error::IdsUnsupported));
+
+    // the rotation was not started
+    let backend = container.into_backend().inner;
+    let mut container = open_blocks(FailingBackend::new(backend, None));
+
+    container.rekey().unwrap();
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+/// The containers of revision 0 to 2, each with a block `1` containing the
+/// version, which created the container.
+const FIXTURES: [(&str, &[u8]); 6] = [
+    ("0.6.8-none.json", b"0.6.8"),
+    ("0.6.8-aes128gcm.json", b"0.6.8"),
+    ("0.7.0-aes128ctr.json", b"0.7.0"),
+    ("0.7.1-aes256gcm.json", b"0.7.1"),
+    ("0.7.3-aes128ctr.json", b"0.7.3"),
+    ("0.7.3-aes128gcm.json", b"0.7.3"),
+];
+
+fn fixture_backend(name: &str) -> MemoryBackend {
+    let file = File::open(fixture_path("compat", name)).unwrap();
+
+    serde_json::from_reader(file).unwrap()
+}
+
+fn open_fixture<B: Backend + fmt::Debug, O: Open<B>>(backend: O) -> Container<B> {
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(fixture_password)
+        .build::<B>()
+        .unwrap();
+
+    Container::open(backend, options).unwrap()
+}
+
+fn assert_fixture<B: Backend<Id = Id> + fmt::Debug>(container: &mut Container<B>, version: &[u8]) {
+    let id = "1".parse().unwrap();
+    let mut buf = [0; 8];
+
+    assert_eq!(container.read(&id, &mut buf).unwrap(), 8);
+    assert_eq!(&buf[..version.len()], version);
+    assert!(buf[version.len()..].iter().all(|n| *n == 0));
+}
+
+#[test]
+fn upgrade() {
+    for (name, version) in FIXTURES {
+        let mut container = open_fixture(fixture_backend(name));
+        let cipher = container.info().unwrap().cipher;
+
+        assert!(container.info().unwrap().revision < 3);
+
+        container.rekey().unwrap();
+
+        let info = container.info().unwrap();
+        assert_eq!(info.revision, 3);
+        assert_eq!(info.cipher, cipher);
+        assert_eq!(info.bsize_net, net_block_size(cipher));
+        assert_fixture(&mut container, version);
+
+        let mut container = open_fixture(container.into_backend());
+
+        assert_eq!(container.info().unwrap().revision, 3);
+        assert_fixture(&mut container, version);
+
+        // a revision 3 container
+        let id = container.aquire().unwrap();
+        container.write(&id, b"xyz").unwrap();
+        assert_eq!(read_block(&mut container, &id), b"xyz");
+        container.rekey().unwrap();
+        assert_eq!(read_block(&mut container, &id), b"xyz");
+    }
+}
+
+#[test]
+fn upgrade_convert() {
+    let mut container = open_fixture(fixture_backend("0.7.3-aes128ctr.json"));
+
+    container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap();
+
+    let mut container = open_fixture(container.into_backend());
+    let info = container.info().unwrap();
+
+    assert_eq!(info.revision, 3);
+    assert_eq!(info.cipher, Cipher::Aes256Gcm);
+    assert_fixture(&mut container, b"0.7.3");
+}
+
+#[test]
+fn upgrade_none() {
+    let mut container = open_fixture(fixture_backend("0.7.3-none.json"));
+
+    container.rekey().unwrap();
+
+    let info = container.info().unwrap();
+    assert_eq!(info.revision, 3);
+    assert_eq!(info.cipher, Cipher::None);
+    assert_fixture(&mut container, b"0.7.3");
+
+    container
+        .convert(convert_options(Cipher::Aes128Gcm))
+        .unwrap();
+
+    let mut container = open_fixture(container.into_backend());
+    let info = container.info().unwrap();
+
+    assert_eq!(info.revision, 3);
+    assert_eq!(info.cipher, Cipher::Aes128Gcm);
+    assert_fixture(&mut container, b"0.7.3");
+}
+
+#[test]
+fn upgrade_interrupted() {
+    for writes in 0.. {
+        let backend = FailingBackend::new(fixture_backend("0.7.3-aes128gcm.json"), Some(writes));
+        let mut container = open_fixture(backend);
+
+        if container.rekey().is_ok() {
+            // no interruption anymore, all interruption points are tested
+            assert!(writes > 0);
+            break;
+        }
+
+        // the container is usable while the upgrade is pending
+        let backend = container.into_backend().inner;
+        let mut container = open_fixture(FailingBackend::new(backend, None));
+
+        assert_fixture(&mut container, b"0.7.3");
+
+        let id = container.aquire().unwrap();
+        container.write(&id, b"xyz").unwrap();
+
+        container.rekey().unwrap();
+
+        let mut container = open_fixture(container.into_backend().inner);
+
+        assert_eq!(container.info().unwrap().revision, 3);
+        assert_fixture(&mut container, b"0.7.3");
+        assert_eq!(read_block(&mut container, &id), b"xyz");
+    }
+}
+
+#[test]
+fn upgrade_service() {
+    let container = open_fixture(fixture_backend("0.7.1-aes128gcm.json"));
+    let service = Container::open_service::<SampleService>(container, true).unwrap();
+    let mut container = service.into_container();
+
+    // the nonce does not fit into the block size of the service
+    let err = container.rekey().unwrap_err();
+
+    assert!(matches!(err, Error::ServiceBlockSize(old, new)
+        if old == 496 && new == 484));
+    assert_eq!(container.info().unwrap().revision, 2);
+}
+
+fn convert_options(cipher: Cipher) -> nuts_container::ConvertOptions {
+    ConvertOptionsBuilder::new(cipher)
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build()
+}
+
+fn net_block_size(cipher: Cipher) -> u32 {
+    512 - cipher.iv_len() as u32 - cipher.tag_size()
+}
+
+#[test]
+fn convert() {
+    let ciphers = [
+        Cipher::None,
+        Cipher::Aes128Ctr,
+        Cipher::Aes256Gcm,
+        Cipher::XChaCha20Poly1305,
+    ];
+
+    for from in ciphers {
+        for into in ciphers.iter().copied().filter(|c| *c != from) {
+            let (container, ids) = create_blocks(from);
+            let backend = container.into_backend();
+            let mut container = open_blocks(backend);
+
+            container.convert(convert_options(into)).unwrap();
+
+            assert_eq!(container.info().unwrap().cipher, into);
+            assert_eq!(container.block_size(), net_block_size(into));
+            assert_blocks(&mut container, &ids, &BLOCKS);
+
+            let mut container = open_blocks(container.into_backend());
+
+            assert_eq!(container.info().unwrap().cipher, into);
+            assert_blocks(&mut container, &ids, &BLOCKS);
+        }
+    }
+}
+
+#[test]
+fn convert_from_none_password() {
+    let (container, ids) = create_blocks(Cipher::None);
+    let backend = container.into_backend();
+    let mut container = open_blocks(backend);
+    let options = ConvertOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"xyz".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build();
+
+    container.convert(options).unwrap();
+
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"xyz".to_vec()))
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(container.into_backend(), options).unwrap();
+    let info = container.info().unwrap();
+
+    assert_eq!(info.cipher, Cipher::Aes128Gcm);
+    assert_eq!(info.kdf, Kdf::pbkdf2(Digest::Sha1, 1, b"123"));
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn convert_identity() {
+    let identity = Identity::generate().unwrap();
+    let options = CreateOptionsBuilder::new(Cipher::Aes128Ctr)
+        .with_recipient(identity.recipient().unwrap())
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+    let id = container.aquire().unwrap();
+
+    container.write(&id, b"abc").unwrap();
+    container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap();
+
+    let options = OpenOptionsBuilder::new()
+        .with_identity(identity)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(container.into_backend(), options).unwrap();
+    let mut buf = [0; 3];
+
+    assert_eq!(container.info().unwrap().cipher, Cipher::Aes256Gcm);
+    assert_eq!(container.read(&id, &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"abc");
+}
+
+#[test]
+fn convert_interrupted() {
+    for (from, into) in [
+        (Cipher::None, Cipher::Aes256Gcm),
+        (Cipher::Aes128Ctr, Cipher::Aes256Gcm),
+        (Cipher::Aes256Gcm, Cipher::None),
+    ] {
+        for writes in 0.. {
+            let (container, ids) = create_blocks(from);
+            let backend = container.into_backend();
+            let mut container = open_blocks(FailingBackend::new(backend, Some(writes)));
+
+            if container.convert(convert_options(into)).is_ok() {
+                // no interruption anymore, all interruption points are tested
+                assert!(writes > 0);
+                break;
+            }
+
+            // the container is usable while the conversion is pending
+            let backend = container.into_backend().inner;
+            let mut container = open_blocks(FailingBackend::new(backend, None));
+
+            assert!(container.block_size() <= net_block_size(from));
+            assert_blocks(&mut container, &ids, &BLOCKS);
+
+            container.convert(convert_options(into)).unwrap();
+            assert_eq!(container.info().unwrap().cipher, into);
+            assert_blocks(&mut container, &ids, &BLOCKS);
+
+            let backend = container.into_backend().inner;
+            let mut container = open_blocks(backend);
+
+            assert_eq!(container.block_size(), net_block_size(into));
+            assert_blocks(&mut container, &ids, &BLOCKS);
+        }
+    }
+}
+
+#[test]
+fn convert_pending() {
+    let (container, _) = create_blocks(Cipher::Aes128Ctr);
+    let backend = container.into_backend();
+    let mut container = open_blocks(FailingBackend::new(backend, Some(3)));
+
+    container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap_err();
+
+    let backend = container.into_backend().inner;
+    let mut container = open_blocks(backend);
+
+    let err = container
+        .convert(convert_options(Cipher::Aes128Gcm))
+        .unwrap_err();
+
+    assert!(
+        matches!(err, Error::Header(HeaderError::PendingConversion(cipher))
+        if cipher == Cipher::Aes256Gcm)
+    );
+
+    // resumed by a rotation
+    container.rekey().unwrap();
+    assert_eq!(container.info().unwrap().cipher, Cipher::Aes256Gcm);
+}
+
+#[test]
+fn convert_block_too_large() {
+    let (container, ids) = create_blocks(Cipher::Aes128Ctr);
+    let backend = container.into_backend();
+    let mut container = open_blocks(backend);
+    let buf = vec![1; container.block_size() as usize];
+
+    container.write(&ids[2], &buf).unwrap();
+
+    let err = container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap_err();
+
+    assert!(matches!(err, Error::BlockTooLarge(id, size)
+        if id == ids[2].to_string() && size == net_block_size(Cipher::Aes256Gcm)));
+    assert_eq!(container.info().unwrap().cipher, Cipher::Aes128Ctr);
+
+    // a larger block size is fine
+    container.convert(convert_options(Cipher::None)).unwrap();
+    assert_eq!(container.info().unwrap().cipher, Cipher::None);
+}
+
+#[test]
+fn convert_service() {
+    let (container, _) = create_blocks(Cipher::Aes128Ctr);
+    let backend = container.into_backend();
+    let container = open_blocks(backend);
+    let service = Container::create_service::<SampleService>(container).unwrap();
+    let mut container = service.into_container();
+
+    let err = container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap_err();
+
+    assert!(matches!(err, Error::ServiceBlockSize(old, new)
+        if old == 496 && new == 484));
+
+    // the block size does not change
+    container
+        .convert(convert_options(Cipher::Aes256Ctr))
+        .unwrap();
+    assert_eq!(container.info().unwrap().cipher, Cipher::Aes256Ctr);
+}
+
+#[test]
+fn convert_inactive_slot() {
+    let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+    let backend = container.into_backend();
+    let mut container = open_blocks(backend);
+    let options = ModifyOptionsBuilder::default()
+        .add_slot_with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"), || Ok(b"xyz".to_vec()))
+        .build();
+
+    container.modify(options).unwrap();
+
+    let err = container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Header(HeaderError::InactiveKeySlot(idx)) if idx == 1));
+
+    let options = ModifyOptionsBuilder::default().remove_slot(1).build();
+
+    container.modify(options).unwrap();
+    container
+        .convert(convert_options(Cipher::Aes256Gcm))
+        .unwrap();
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
@@ -0,0 +1,136 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_container::{
+    Cipher, Container, CreateOptionsBuilder, Digest, Kdf, OpenOptionsBuilder, SharedContainer,
+};
+use nuts_memory::{Id, MemoryBackend};
+use std::thread;
+
+fn create_container(cipher: Cipher) -> (Container<MemoryBackend>, Vec<Id>) {
+    let options = CreateOptionsBuilder::new(cipher)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let ids = (0..16u8)
+        .map(|n| {
+            let id = container.aquire().unwrap();
+            container.write(&id, &[n; 3]).unwrap();
+            id
+        })
+        .collect();
+
+    (container, ids)
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn send_sync() {
+    assert_send_sync::<Container<MemoryBackend>>();
+    assert_send_sync::<SharedContainer<MemoryBackend>>();
+}
+
+#[test]
+fn send_container() {
+    let (container, ids) = create_container(Cipher::Aes128Gcm);
+
+    let mut container = thread::spawn(move || {
+        let options = OpenOptionsBuilder::new()
+            .with_password_callback(|| Ok(b"abc".to_vec()))
+            .build::<MemoryBackend>()
+            .unwrap();
+
+        Container::open(container.into_backend(), options).unwrap()
+    })
+    .join()
+    .unwrap();
+
+    let mut buf = [0; 3];
+
+    assert_eq!(container.read(&ids[1], &mut buf).unwrap(), 3);
+    assert_eq!(buf, [1; 3]);
+}
+
+#[test]
+fn read() {
+    for cipher in [Cipher::None, Cipher::Aes128Ctr, Cipher::Aes256Gcm] {
+        let (container, ids) = create_container(cipher);
+        let block_size = container.block_size();
+        let shared = container.into_shared().unwrap();
+
+        assert_eq!(shared.block_size(), block_size);
+        assert_eq!(shared.top_id(), None);
+
+        let handles = ids
+            .into_iter()
+            .enumerate()
+            .map(|(n, id)| {
+                let shared = shared.clone();
+
+                thread::spawn(move || {
+                    let mut buf = [0xFF; 4];
+
+                    for _ in 0..8 {
+                        assert_eq!(shared.read(&id, &mut buf).unwrap(), 4);
+                        assert_eq!(buf, [n as u8, n as u8, n as u8, 0]);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
+
+#[test]
+fn read_no_such_block() {
+    let (container, _) = create_container(Cipher::Aes128Gcm);
+    let shared = container.into_shared().unwrap();
+    let mut buf = [0; 3];
+
+    shared.read(&"4711".parse().unwrap(), &mut buf).unwrap_err();
+}
+
+#[test]
+fn try_into_container() {
+    let (container, ids) = create_container(Cipher::Aes128Gcm);
+    let shared = container.into_shared().unwrap();
+    let clone = shared.clone();
+
+    // another handle is still alive
+    let shared = shared.try_into_container().unwrap_err();
+
+    drop(clone);
+
+    let mut container = shared.try_into_container().unwrap();
+    let mut buf = [0; 3];
+
+    container.write(&ids[0], b"xyz").unwrap();
+    assert_eq!(container.read(&ids[0], &mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"xyz");
+}
@@ -0,0 +1,230 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_container::{
+    Cipher, Container, ContainerResult, CreateOptionsBuilder, Error, HeaderError,
+    ModifyOptionsBuilder, OpenOptionsBuilder, SlotInfo,
+};
+use nuts_memory::MemoryBackend;
+use std::fs::File;
+
+use crate::common::{
+    assert_block, fixture_password, fixture_path, kdf_with_iterations as kdf, open_with_password,
+    password_options,
+};
+
+fn slot(iterations: u32) -> SlotInfo {
+    SlotInfo::Password(kdf(iterations))
+}
+
+fn create_container(cipher: Cipher) -> Container<MemoryBackend> {
+    let mut container = common::create_container(password_options(cipher));
+
+    let options = ModifyOptionsBuilder::default()
+        .add_slot_with_kdf(kdf(2), || Ok(b"def".to_vec()))
+        .add_slot_with_kdf(kdf(3), || Ok(b"ghi".to_vec()))
+        .build();
+    container.modify(options).unwrap();
+
+    container
+}
+
+fn open_container(
+    backend: MemoryBackend,
+    password: &'static [u8],
+) -> ContainerResult<Container<MemoryBackend>, MemoryBackend> {
+    open_with_password(backend, password)
+}
+
+macro_rules! slot_tests {
+    ($name:ident, $cipher:ident, $wrong:pat) => {
+        mod $name {
+            use nuts_container::{Cipher, Error, HeaderError, ModifyOptionsBuilder};
+
+            use crate::common::{assert_block, clone_backend, kdf_with_iterations as kdf};
+
+            use super::{create_container, open_container, slot};
+
+            #[test]
+            fn open() {
+                let mut backend = create_container(Cipher::$cipher).into_backend();
+
+                for (idx, password) in [b"abc", b"def", b"ghi"].iter().enumerate() {
+                    let mut container = open_container(backend, *password).unwrap();
+                    let info = container.info().unwrap();
+
+                    assert_eq!(info.kdf, kdf(idx as u32 + 1));
+                    assert_eq!(info.slots, [slot(1), slot(2), slot(3)]);
+                    assert_eq!(info.active_slot, Some(idx));
+                    assert_block(&mut container);
+
+                    backend = container.into_backend();
+                }
+
+                let err = open_container(backend, b"xxx").unwrap_err();
+                assert!(matches!(err, Error::Header($wrong)));
+            }
+
+            #[test]
+            fn remove() {
+                let backend = create_container(Cipher::$cipher).into_backend();
+                let mut container = open_container(backend, b"def").unwrap();
+
+                let options = ModifyOptionsBuilder::default().remove_slot(0).build();
+                container.modify(options).unwrap();
+
+                let info = container.info().unwrap();
+                assert_eq!(info.slots, [slot(2), slot(3)]);
+                assert_eq!(info.active_slot, Some(0));
+
+                let backend = container.into_backend();
+                let err = open_container(clone_backend(&backend), b"abc").unwrap_err();
+                assert!(matches!(err, Error::Header($wrong)));
+
+                let mut container = open_container(backend, b"ghi").unwrap();
+                assert_eq!(container.info().unwrap().active_slot, Some(1));
+                assert_block(&mut container);
+            }
+
+            #[test]
+            fn remove_active() {
+                let backend = create_container(Cipher::$cipher).into_backend();
+                let mut container = open_container(backend, b"def").unwrap();
+
+                let options = ModifyOptionsBuilder::default().remove_slot(1).build();
+                let err = container.modify(options).unwrap_err();
+
+                assert!(matches!(err, Error::Header(HeaderError::ActiveKeySlot(1))));
+            }
+
+            #[test]
+            fn remove_unknown() {
+                let backend = create_container(Cipher::$cipher).into_backend();
+                let mut container = open_container(backend, b"def").unwrap();
+
+                let options = ModifyOptionsBuilder::default().remove_slot(3).build();
+                let err = container.modify(options).unwrap_err();
+
+                assert!(matches!(err, Error::Header(HeaderError::NoSuchKeySlot(3))));
+            }
+
+            #[test]
+            fn change_password() {
+                let backend = create_container(Cipher::$cipher).into_backend();
+                let mut container = open_container(backend, b"def").unwrap();
+
+                let options = ModifyOptionsBuilder::default()
+                    .change_password(|| Ok(b"123".to_vec()))
+                    .change_kdf(kdf(4))
+                    .build();
+                container.modify(options).unwrap();
+
+                let backend = container.into_backend();
+                let err = open_container(clone_backend(&backend), b"def").unwrap_err();
+                assert!(matches!(err, Error::Header($wrong)));
+
+                let mut container = open_container(backend, b"123").unwrap();
+                let info = container.info().unwrap();
+
+                assert_eq!(info.slots, [slot(1), slot(4), slot(3)]);
+                assert_eq!(info.active_slot, Some(1));
+                assert_block(&mut container);
+
+                let mut container = open_container(container.into_backend(), b"abc").unwrap();
+                assert_eq!(container.info().unwrap().active_slot, Some(0));
+                assert_block(&mut container);
+            }
+        }
+    };
+}
+
+slot_tests!(aes128_ctr, Aes128Ctr, HeaderError::WrongPassword);
+slot_tests!(aes128_gcm, Aes128Gcm, HeaderError::NotTrustworthy);
+slot_tests!(aes256_gcm, Aes256Gcm, HeaderError::NotTrustworthy);
+slot_tests!(
+    chacha20_poly1305,
+    ChaCha20Poly1305,
+    HeaderError::NotTrustworthy
+);
+
+#[test]
+fn none() {
+    let options = CreateOptionsBuilder::new(Cipher::None)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::create(MemoryBackend::new(), options).unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .add_slot(|| panic!("callback must not be invoked"))
+        .build();
+    container.modify(options).unwrap();
+
+    let info = container.info().unwrap();
+
+    assert!(info.slots.is_empty());
+    assert_eq!(info.active_slot, None);
+}
+
+#[test]
+fn default_kdf() {
+    let backend = create_container(Cipher::Aes128Gcm).into_backend();
+    let mut container = open_container(backend, b"abc").unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .add_slot(|| Ok(b"jkl".to_vec()))
+        .build();
+    container.modify(options).unwrap();
+
+    let mut container = open_container(container.into_backend(), b"jkl").unwrap();
+    let info = container.info().unwrap();
+
+    assert_eq!(info.active_slot, Some(3));
+    assert!(info.kdf.is_argon2id());
+    assert_block(&mut container);
+}
+
+#[test]
+fn rev2() {
+    let file = File::open(fixture_path("compat", "0.7.3-aes128gcm.json")).unwrap();
+    let backend: MemoryBackend = serde_json::from_reader(file).unwrap();
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(fixture_password)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(backend, options).unwrap();
+
+    let info = container.info().unwrap();
+    assert!(info.slots.is_empty());
+    assert_eq!(info.active_slot, None);
+
+    let options = ModifyOptionsBuilder::default()
+        .add_slot_with_kdf(kdf(1), || Ok(b"abc".to_vec()))
+        .build();
+    let err = container.modify(options).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Header(HeaderError::InvalidRevision(3, 2))
+    ));
+}
@@ -0,0 +1,424 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_backend::Backend;
+use nuts_container::{
+    Cipher, Container, Error, HeaderError, JournalError, OpenOptionsBuilder, SnapshotError,
+};
+use nuts_memory::{Id, MemoryBackend};
+use std::fmt;
+use std::fs::File;
+
+use crate::common::{
+    assert_blocks, create_blocks, fixture_password, fixture_path, read_block, FailingBackend,
+    BLOCKS,
+};
+
+const MODIFIED: [&[u8]; 3] = [b"xyz", b"def", b"uvw"];
+
+fn open_container<B: Backend + fmt::Debug, O: nuts_backend::Open<B>>(
+    backend: O,
+    snapshot: Option<&str>,
+) -> Container<B> {
+    let mut builder = OpenOptionsBuilder::new().with_password_callback(|| Ok(b"abc".to_vec()));
+
+    if let Some(name) = snapshot {
+        builder = builder.with_snapshot(name);
+    }
+
+    common::open_container(backend, builder).unwrap()
+}
+
+fn reopen(container: Container<MemoryBackend>, snapshot: Option<&str>) -> Container<MemoryBackend> {
+    open_container(container.into_backend(), snapshot)
+}
+
+fn backend_ids(container: Container<MemoryBackend>) -> (Container<MemoryBackend>, Vec<Id>) {
+    let mut backend = container.into_backend();
+    let ids = common::backend_ids(&mut backend);
+
+    (open_container(backend, None), ids)
+}
+
+/// Writes the blocks 0 and 2, block 3 is released.
+fn modify<B: Backend<Id = Id> + fmt::Debug>(
+    container: &mut Container<B>,
+    ids: &[Id],
+) -> Result<(), Error<B>> {
+    container.write(&ids[0], b"xyz")?;
+    container.write(&ids[2], b"uvw")?;
+    container.release(ids[3])
+}
+
+#[test]
+fn create() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    modify(&mut container, &ids).unwrap();
+
+    assert_blocks(&mut container, &ids, &MODIFIED);
+
+    let mut container = reopen(container, None);
+    assert_blocks(&mut container, &ids, &MODIFIED);
+
+    let mut container = reopen(container, Some("s1"));
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn create_many() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    container.write(&ids[0], b"xyz").unwrap();
+    container.create_snapshot("s2").unwrap();
+    container.write(&ids[0], b"uvw").unwrap();
+    container.release(ids[1]).unwrap();
+
+    let snapshots = container
+        .snapshots()
+        .into_iter()
+        .map(|info| (info.name, info.blocks))
+        .collect::<Vec<_>>();
+
+    assert_eq!(snapshots, [("s1".to_string(), 4), ("s2".to_string(), 4)]);
+
+    let mut container = reopen(container, None);
+    assert_eq!(read_block(&mut container, &ids[0]), b"uvw");
+
+    let mut container = reopen(container, Some("s1"));
+    assert_blocks(&mut container, &ids, &BLOCKS);
+
+    let mut container = reopen(container, Some("s2"));
+    assert_blocks(&mut container, &ids, &[b"xyz", b"def", b"ghi", b"jkl"]);
+}
+
+#[test]
+fn create_exists() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+
+    let err = container.create_snapshot("s1").unwrap_err();
+
+    assert!(matches!(err, Error::Snapshot(SnapshotError::Exists(name)) if name == "s1"));
+    assert_eq!(container.snapshots().len(), 1);
+}
+
+#[test]
+fn create_write_back() {
+    let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_cache(8)
+        .with_write_back(true)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(container.into_backend(), options).unwrap();
+
+    container.write(&ids[0], b"xyz").unwrap();
+    container.create_snapshot("s1").unwrap();
+    container.write(&ids[0], b"uvw").unwrap();
+    container.flush().unwrap();
+
+    let mut container = reopen(container, None);
+    assert_eq!(read_block(&mut container, &ids[0]), b"uvw");
+
+    let mut container = reopen(container, Some("s1"));
+    assert_eq!(read_block(&mut container, &ids[0]), b"xyz");
+}
+
+#[test]
+fn create_pending() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+
+    let err = container.create_snapshot("s1").unwrap_err();
+
+    assert!(matches!(err, Error::Journal(JournalError::Pending)));
+}
+
+#[test]
+fn create_rev2() {
+    let file = File::open(fixture_path("compat", "0.7.3-aes128gcm.json")).unwrap();
+    let backend: MemoryBackend = serde_json::from_reader(file).unwrap();
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(fixture_password)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(backend, options).unwrap();
+
+    let err = container.create_snapshot("s1").unwrap_err();
+
+    assert!(
+        matches!(err, Error::Header(HeaderError::InvalidRevision(expected, got))
+        if expected == 3 && got == 2)
+    );
+}
+
+#[test]
+fn open_not_found() {
+    let (container, _) = create_blocks(Cipher::Aes128Gcm);
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_snapshot("s1")
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    let err = Container::open(container.into_backend(), options).unwrap_err();
+
+    assert!(matches!(err, Error::Snapshot(SnapshotError::NotFound(name)) if name == "s1"));
+}
+
+#[test]
+fn open_read_only() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    let id = container.aquire().unwrap();
+
+    let mut container = reopen(container, Some("s1"));
+
+    let err = container.write(&ids[0], b"xyz").unwrap_err();
+    assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+    let err = container.release(ids[0]).unwrap_err();
+    assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+    let err = container.aquire().unwrap_err();
+    assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+    let err = container.begin().unwrap_err();
+    assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+    let err = container.delete_snapshot("s1").unwrap_err();
+    assert!(matches!(err, Error::Snapshot(SnapshotError::ReadOnly)));
+
+    // the block was aquired after the snapshot was created
+    let err = container.read(&id, &mut [0; 3]).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Snapshot(SnapshotError::NoSuchBlock(_))
+    ));
+
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn read_many() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    modify(&mut container, &ids).unwrap();
+
+    let mut container = reopen(container, Some("s1"));
+    let mut bufs = vec![[0; 3]; ids.len()];
+    let mut blocks = ids
+        .iter()
+        .zip(bufs.iter_mut())
+        .map(|(id, buf)| (id, &mut buf[..]))
+        .collect::<Vec<_>>();
+
+    assert_eq!(container.read_many(&mut blocks).unwrap(), [3; 4]);
+    assert_eq!(bufs, [*b"abc", *b"def", *b"ghi", *b"jkl"]);
+}
+
+#[test]
+fn shared() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    modify(&mut container, &ids).unwrap();
+
+    let live = container.into_shared().unwrap();
+    let mut buf = [0; 3];
+
+    live.read(&ids[0], &mut buf).unwrap();
+    assert_eq!(buf, *b"xyz");
+
+    let container = live.try_into_container().unwrap();
+    let snapshot = reopen(container, Some("s1")).into_shared().unwrap();
+
+    snapshot.read(&ids[0], &mut buf).unwrap();
+    assert_eq!(buf, *b"abc");
+}
+
+#[test]
+fn transaction() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    container.begin().unwrap();
+    modify(&mut container, &ids).unwrap();
+    container.commit().unwrap();
+
+    let mut container = reopen(container, None);
+    assert_blocks(&mut container, &ids, &MODIFIED);
+
+    let mut container = reopen(container, Some("s1"));
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn rekey() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    modify(&mut container, &ids).unwrap();
+    container.rekey().unwrap();
+
+    let mut container = reopen(container, None);
+    assert_blocks(&mut container, &ids, &MODIFIED);
+
+    let mut container = reopen(container, Some("s1"));
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn delete() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    modify(&mut container, &ids).unwrap();
+    container.delete_snapshot("s1").unwrap();
+
+    assert!(container.snapshots().is_empty());
+
+    // the copies are moved back, the released block is released now
+    let (mut container, backend_ids) = backend_ids(reopen(container, None));
+
+    assert_eq!(backend_ids, ids[..3]);
+    assert!(container.snapshots().is_empty());
+    assert_blocks(&mut container, &ids, &MODIFIED);
+}
+
+#[test]
+fn delete_shared() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.create_snapshot("s1").unwrap();
+    container.write(&ids[0], b"xyz").unwrap();
+    container.create_snapshot("s2").unwrap();
+    modify(&mut container, &ids).unwrap();
+    container.write(&ids[0], b"123").unwrap();
+
+    container.delete_snapshot("s1").unwrap();
+
+    let mut container = reopen(container, Some("s2"));
+    assert_blocks(&mut container, &ids, &[b"xyz", b"def", b"ghi", b"jkl"]);
+
+    let mut container = reopen(container, None);
+    assert_blocks(&mut container, &ids, &[b"123", b"def", b"uvw"]);
+
+    container.delete_snapshot("s2").unwrap();
+
+    let (mut container, backend_ids) = backend_ids(container);
+
+    assert_eq!(backend_ids, ids[..3]);
+    assert_blocks(&mut container, &ids, &[b"123", b"def", b"uvw"]);
+}
+
+#[test]
+fn delete_not_found() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    let err = container.delete_snapshot("s1").unwrap_err();
+
+    assert!(matches!(err, Error::Snapshot(SnapshotError::NotFound(name)) if name == "s1"));
+}
+
+#[test]
+fn write_interrupted() {
+    for writes in 0.. {
+        let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+        container.create_snapshot("s1").unwrap();
+
+        let mut container = open_container(
+            FailingBackend::new(container.into_backend(), Some(writes)),
+            None,
+        );
+
+        if modify(&mut container, &ids).is_ok() {
+            // no interruption anymore, all interruption points are tested
+            assert!(writes > 0);
+            break;
+        }
+
+        // the snapshot is never modified
+        let backend = container.into_backend().inner;
+        let mut container = open_container(backend, Some("s1"));
+
+        assert_blocks(&mut container, &ids, &BLOCKS);
+
+        // a block is either written or unchanged
+        let mut container = reopen(container, None);
+
+        for (idx, buf) in MODIFIED.iter().enumerate() {
+            let data = read_block(&mut container, &ids[idx]);
+            assert!(data == *buf || data == BLOCKS[idx]);
+        }
+    }
+}
+
+#[test]
+fn delete_interrupted() {
+    for writes in 0.. {
+        let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+        container.create_snapshot("s1").unwrap();
+        modify(&mut container, &ids).unwrap();
+
+        let mut container = open_container(
+            FailingBackend::new(container.into_backend(), Some(writes)),
+            None,
+        );
+
+        if container.delete_snapshot("s1").is_ok() {
+            // no interruption anymore, all interruption points are tested
+            assert!(writes > 0);
+            break;
+        }
+
+        // the live container is never modified
+        let backend = container.into_backend().inner;
+        let mut container = open_container(backend, None);
+
+        assert_blocks(&mut container, &ids, &MODIFIED);
+
+        // the snapshot is either deleted or unchanged
+        if container.snapshots().is_empty() {
+            continue;
+        }
+
+        let mut container = reopen(container, Some("s1"));
+        assert_blocks(&mut container, &ids, &BLOCKS);
+
+        let mut container = reopen(container, None);
+        container.delete_snapshot("s1").unwrap();
+        assert_blocks(&mut container, &ids, &MODIFIED);
+    }
+}
@@ -0,0 +1,240 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+
+use nuts_container::{
+    Cipher, Container, CreateOptionsBuilder, Digest, Error, HeaderError, Kdf, OpenOptionsBuilder,
+    Stream, StreamError,
+};
+use nuts_memory::MemoryBackend;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::common::SampleService;
+
+fn create_container() -> Container<MemoryBackend> {
+    let options = CreateOptionsBuilder::new(Cipher::Aes128Gcm)
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_kdf(Kdf::pbkdf2(Digest::Sha1, 1, b"123"))
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::create(MemoryBackend::new(), options).unwrap()
+}
+
+fn open_container(backend: MemoryBackend) -> Container<MemoryBackend> {
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .build::<MemoryBackend>()
+        .unwrap();
+
+    Container::open(backend, options).unwrap()
+}
+
+fn create_stream() -> Stream<MemoryBackend> {
+    Container::create_service::<Stream<MemoryBackend>>(create_container()).unwrap()
+}
+
+fn reopen_stream(stream: Stream<MemoryBackend>) -> Stream<MemoryBackend> {
+    let backend = stream.into_container().into_backend();
+
+    Container::open_service::<Stream<MemoryBackend>>(open_container(backend), false).unwrap()
+}
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|n| (n % 251) as u8).collect()
+}
+
+fn read_all(stream: &mut Stream<MemoryBackend>) -> Vec<u8> {
+    let mut buf = vec![];
+
+    stream.seek(SeekFrom::Start(0)).unwrap();
+    stream.read_to_end(&mut buf).unwrap();
+
+    buf
+}
+
+#[test]
+fn empty() {
+    let mut stream = create_stream();
+
+    assert_eq!(stream.len(), 0);
+    assert!(stream.is_empty());
+    assert_eq!(stream.read(&mut [0; 8]).unwrap(), 0);
+
+    let mut stream = reopen_stream(stream);
+
+    assert!(stream.is_empty());
+    assert_eq!(stream.read(&mut [0; 8]).unwrap(), 0);
+}
+
+#[test]
+fn write_read() {
+    for len in [1, 483, 484, 485, 10_000, 500_000] {
+        let data = data(len);
+        let mut stream = create_stream();
+
+        stream.write_all(&data).unwrap();
+
+        assert_eq!(stream.len(), len as u64);
+        assert_eq!(stream.stream_position().unwrap(), len as u64);
+        assert_eq!(read_all(&mut stream), data);
+
+        let mut stream = reopen_stream(stream);
+
+        assert_eq!(stream.len(), len as u64);
+        assert_eq!(read_all(&mut stream), data);
+    }
+}
+
+#[test]
+fn overwrite() {
+    let mut expected = data(5000);
+    let mut stream = create_stream();
+
+    stream.write_all(&expected).unwrap();
+
+    stream.seek(SeekFrom::Start(480)).unwrap();
+    stream.write_all(&[0xFF; 1000]).unwrap();
+    expected[480..1480].copy_from_slice(&[0xFF; 1000]);
+
+    stream.seek(SeekFrom::End(-10)).unwrap();
+    stream.write_all(&[0xEE; 20]).unwrap();
+    expected.truncate(4990);
+    expected.extend_from_slice(&[0xEE; 20]);
+
+    assert_eq!(stream.len(), 5010);
+    assert_eq!(read_all(&mut stream), expected);
+
+    let mut stream = reopen_stream(stream);
+    assert_eq!(read_all(&mut stream), expected);
+}
+
+#[test]
+fn read_at() {
+    let data = data(5000);
+    let mut stream = create_stream();
+
+    stream.write_all(&data).unwrap();
+
+    let mut buf = [0; 100];
+
+    stream.seek(SeekFrom::Start(950)).unwrap();
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, data[950..1050]);
+
+    stream.seek(SeekFrom::End(-50)).unwrap();
+    assert_eq!(stream.read(&mut buf).unwrap(), 50);
+    assert_eq!(buf[..50], data[4950..]);
+
+    stream.seek(SeekFrom::End(10)).unwrap();
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn write_past_end() {
+    let mut stream = create_stream();
+
+    stream.write_all(b"abc").unwrap();
+    stream.seek(SeekFrom::Start(2000)).unwrap();
+    stream.write_all(b"xyz").unwrap();
+
+    let mut expected = vec![0; 2003];
+    expected[..3].copy_from_slice(b"abc");
+    expected[2000..].copy_from_slice(b"xyz");
+
+    assert_eq!(stream.len(), 2003);
+    assert_eq!(read_all(&mut stream), expected);
+}
+
+#[test]
+fn set_len() {
+    let data = data(5000);
+    let mut stream = create_stream();
+
+    stream.write_all(&data).unwrap();
+
+    stream.set_len(1000).unwrap();
+    assert_eq!(stream.len(), 1000);
+    assert_eq!(stream.stream_position().unwrap(), 5000);
+    assert_eq!(read_all(&mut stream), data[..1000]);
+
+    // the truncated bytes are not visible again
+    stream.set_len(3000).unwrap();
+
+    let mut expected = data[..1000].to_vec();
+    expected.resize(3000, 0);
+
+    assert_eq!(read_all(&mut stream), expected);
+
+    let mut stream = reopen_stream(stream);
+
+    assert_eq!(stream.len(), 3000);
+    assert_eq!(read_all(&mut stream), expected);
+
+    stream.set_len(0).unwrap();
+    assert!(stream.is_empty());
+    assert!(read_all(&mut stream).is_empty());
+}
+
+#[test]
+fn set_len_releases_blocks() {
+    let mut stream = create_stream();
+
+    stream.write_all(&data(100_000)).unwrap();
+    stream.set_len(0).unwrap();
+
+    let mut backend = stream.into_container().into_backend();
+    let ids = nuts_backend::Backend::ids(&mut backend).unwrap().unwrap();
+
+    // only the super-block is left
+    assert_eq!(ids.len(), 1);
+}
+
+#[test]
+fn copy() {
+    let data = data(20_000);
+    let mut stream = create_stream();
+
+    assert_eq!(io::copy(&mut data.as_slice(), &mut stream).unwrap(), 20_000);
+    stream.flush().unwrap();
+
+    let mut stream = reopen_stream(stream);
+    let mut buf = vec![];
+
+    assert_eq!(io::copy(&mut stream, &mut buf).unwrap(), 20_000);
+    assert_eq!(buf, data);
+}
+
+#[test]
+fn open_other_service() {
+    let container = create_container();
+    let service = Container::create_service::<SampleService>(container).unwrap();
+    let container = open_container(service.into_container().into_backend());
+
+    let err = Container::open_service::<Stream<MemoryBackend>>(container, false).unwrap_err();
+
+    assert!(matches!(
+        err,
+        StreamError::Container(Error::Header(HeaderError::UnexpectedSid { .. }))
+    ));
+}
@@ -0,0 +1,336 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+mod common;
+use nuts_container::{
+    Cipher, Container, Error, HeaderError, JournalError, ModifyOptionsBuilder, OpenOptionsBuilder,
+};
+use nuts_memory::MemoryBackend;
+use std::fs::File;
+
+use crate::common::{
+    assert_blocks, backend_ids, create_blocks, fixture_password, fixture_path, open_blocks,
+    raw_blocks, read_block, FailingBackend, BLOCKS,
+};
+
+#[test]
+fn commit() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    let raw = raw_blocks(container.backend(), &ids);
+
+    container.begin().unwrap();
+    container.write(&ids[0], b"xyz").unwrap();
+    container.write(&ids[2], b"uvw").unwrap();
+
+    // the transaction reads its own writes, the backend is not modified yet
+    assert_blocks(&mut container, &ids, &[b"xyz", b"def", b"uvw", b"jkl"]);
+    assert_eq!(raw_blocks(container.backend(), &ids), raw);
+
+    container.commit().unwrap();
+    assert_blocks(&mut container, &ids, &[b"xyz", b"def", b"uvw", b"jkl"]);
+
+    // the blocks of the journal are released
+    let mut backend = container.into_backend();
+    assert_eq!(backend_ids(&mut backend), ids);
+
+    let mut container = open_blocks(backend);
+    assert_blocks(&mut container, &ids, &[b"xyz", b"def", b"uvw", b"jkl"]);
+}
+
+#[test]
+fn commit_empty() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+    container.commit().unwrap();
+
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn commit_many() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+
+    // the journal index does not fit into a single block
+    let new_ids = container.aquire_many(200).unwrap();
+
+    for id in new_ids.iter() {
+        container.write(id, id.to_string().as_bytes()).unwrap();
+    }
+
+    container.commit().unwrap();
+
+    let mut backend = container.into_backend();
+    assert_eq!(backend_ids(&mut backend).len(), ids.len() + new_ids.len());
+
+    let mut container = open_blocks(backend);
+
+    for id in new_ids.iter() {
+        let expected = id.to_string();
+        let mut buf = vec![0; expected.len()];
+
+        container.read(id, &mut buf).unwrap();
+        assert_eq!(buf, expected.as_bytes());
+    }
+}
+
+#[test]
+fn write_many_read_many() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+    container
+        .write_many(&[(&ids[1], b"xyz"), (&ids[3], b"uvw")])
+        .unwrap();
+
+    let mut bufs = [[0; 3]; 4];
+    let mut blocks = ids
+        .iter()
+        .zip(bufs.iter_mut())
+        .map(|(id, buf)| (id, &mut buf[..]))
+        .collect::<Vec<_>>();
+
+    assert_eq!(container.read_many(&mut blocks).unwrap(), [3; 4]);
+    assert_eq!(bufs, [*b"abc", *b"xyz", *b"ghi", *b"uvw"]);
+
+    container.commit().unwrap();
+
+    let mut container = open_blocks(container.into_backend());
+    assert_blocks(&mut container, &ids, &[b"abc", b"xyz", b"ghi", b"uvw"]);
+}
+
+#[test]
+fn write_back() {
+    let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(|| Ok(b"abc".to_vec()))
+        .with_cache(2)
+        .with_write_back(true)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(container.into_backend(), options).unwrap();
+
+    container.write(&ids[0], b"xyz").unwrap();
+
+    container.begin().unwrap();
+    container.write(&ids[0], b"uvw").unwrap();
+    container.write(&ids[1], b"rst").unwrap();
+    container.commit().unwrap();
+
+    assert_blocks(&mut container, &ids, &[b"uvw", b"rst", b"ghi", b"jkl"]);
+
+    // committed blocks are written through the cache
+    let mut container = open_blocks(container.into_backend());
+    assert_blocks(&mut container, &ids, &[b"uvw", b"rst", b"ghi", b"jkl"]);
+}
+
+#[test]
+fn release() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+    container.write(&ids[1], b"xyz").unwrap();
+    container.release(ids[1]).unwrap();
+    container.release(ids[2]).unwrap();
+
+    // released on commit
+    assert_eq!(read_block(&mut container, &ids[2]), b"ghi");
+
+    container.commit().unwrap();
+
+    let mut backend = container.into_backend();
+    assert_eq!(backend_ids(&mut backend), [ids[0], ids[3]]);
+}
+
+#[test]
+fn rollback() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+
+    let new_id = container.aquire().unwrap();
+
+    container.write(&new_id, b"xyz").unwrap();
+    container.write(&ids[0], b"xyz").unwrap();
+    container.release(ids[1]).unwrap();
+    container.rollback().unwrap();
+
+    assert_blocks(&mut container, &ids, &BLOCKS);
+
+    // the aquired block is released again
+    let mut backend = container.into_backend();
+    assert_eq!(backend_ids(&mut backend), ids);
+
+    let mut container = open_blocks(backend);
+    assert_blocks(&mut container, &ids, &BLOCKS);
+}
+
+#[test]
+fn begin_pending() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+
+    let err = container.begin().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::Pending)));
+
+    container.commit().unwrap();
+    container.begin().unwrap();
+}
+
+#[test]
+fn into_shared_pending() {
+    let (mut container, ids) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+    container.write(&ids[0], b"xyz").unwrap();
+
+    let (err, mut container) = *container.into_shared().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::Pending)));
+
+    // the transaction is still pending
+    container.commit().unwrap();
+    assert_eq!(read_block(&mut container, &ids[0]), b"xyz");
+}
+
+#[test]
+fn commit_not_pending() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    let err = container.commit().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::NotPending)));
+
+    container.begin().unwrap();
+    container.commit().unwrap();
+
+    let err = container.commit().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::NotPending)));
+}
+
+#[test]
+fn rollback_not_pending() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    let err = container.rollback().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::NotPending)));
+
+    container.begin().unwrap();
+    container.rollback().unwrap();
+
+    let err = container.rollback().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::NotPending)));
+}
+
+#[test]
+fn modify_pending() {
+    let (mut container, _) = create_blocks(Cipher::Aes128Gcm);
+
+    container.begin().unwrap();
+
+    let options = ModifyOptionsBuilder::default()
+        .change_password(|| Ok(b"xyz".to_vec()))
+        .build();
+    let err = container.modify(options).unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::Pending)));
+
+    let err = container.rekey().unwrap_err();
+    assert!(matches!(err, Error::Journal(JournalError::Pending)));
+}
+
+#[test]
+fn rev2() {
+    let file = File::open(fixture_path("compat", "0.7.3-aes128gcm.json")).unwrap();
+    let backend: MemoryBackend = serde_json::from_reader(file).unwrap();
+    let options = OpenOptionsBuilder::new()
+        .with_password_callback(fixture_password)
+        .build::<MemoryBackend>()
+        .unwrap();
+    let mut container = Container::open(backend, options).unwrap();
+
+    let err = container.begin().unwrap_err();
+
+    assert!(
+        matches!(err, Error::Header(HeaderError::InvalidRevision(expected, got))
+        if expected == 3 && got == 2)
+    );
+}
+
+#[test]
+fn commit_interrupted() {
+    commit_interrupted_with(true);
+}
+
+#[test]
+fn commit_interrupted_without_ids() {
+    // the journal is replayed without listing the blocks
+    commit_interrupted_with(false);
+}
+
+fn failing_backend(backend: MemoryBackend, writes: Option<usize>, ids: bool) -> FailingBackend {
+    let backend = FailingBackend::new(backend, writes);
+
+    if ids {
+        backend
+    } else {
+        backend.without_ids()
+    }
+}
+
+fn commit_interrupted_with(ids_supported: bool) {
+    for writes in 0.. {
+        let (container, ids) = create_blocks(Cipher::Aes128Gcm);
+        let mut container = open_blocks(failing_backend(
+            container.into_backend(),
+            Some(writes),
+            ids_supported,
+        ));
+
+        container.begin().unwrap();
+        container.write(&ids[0], b"xyz").unwrap();
+        container.write(&ids[2], b"uvw").unwrap();
+        container.release(ids[3]).unwrap();
+
+        if container.commit().is_ok() {
+            // no interruption anymore, all interruption points are tested
+            assert!(writes > 0);
+            break;
+        }
+
+        // either all or none of the updates are stored
+        let backend = container.into_backend().inner;
+        let mut container = open_blocks(failing_backend(backend, None, ids_supported));
+
+        if read_block(&mut container, &ids[0]) == b"xyz" {
+            assert_blocks(&mut container, &ids, &[b"xyz", b"def", b"uvw"]);
+            assert!(container.backend().inner.get(&ids[3]).is_none());
+        } else {
+            assert_blocks(&mut container, &ids, &BLOCKS);
+        }
+
+        // the journal is removed
+        container.begin().unwrap();
+        container.commit().unwrap();
+    }
+}
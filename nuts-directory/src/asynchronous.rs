@@ -0,0 +1,211 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+#[cfg(test)]
+mod tests;
+
+use async_trait::async_trait;
+use nuts_backend::{
+    AsyncBackend, AsyncCreate, AsyncOpen, AsyncReceiveHeader, Create, Open, HEADER_MAX_SIZE,
+};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::{
+    aquire_block, delete_backend, list_ids, read_block, read_extended_header, read_header,
+    release_block, write_block, write_extended_header, write_header, CreateOptions,
+    DirectoryBackend, Id, Info, OpenOptions, Settings,
+};
+
+/// Runs `f` with the given `path` on the thread pool.
+async fn unblock<T, F>(path: &Path, f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce(&Path) -> T + Send + 'static,
+{
+    let path = path.to_path_buf();
+
+    blocking::unblock(move || f(&path)).await
+}
+
+/// Receives the header block `idx` (`0` is the root header) from `path`.
+async fn receive_header(path: &Path, idx: u32, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<()> {
+    let (result, buf) = unblock(path, move |path| {
+        let mut buf = [0; HEADER_MAX_SIZE];
+
+        let result = match idx {
+            0 => read_header(path, &mut buf),
+            _ => read_extended_header(path, idx, &mut buf),
+        };
+
+        (result, buf)
+    })
+    .await;
+
+    *bytes = buf;
+
+    result
+}
+
+#[async_trait]
+impl<P: AsRef<Path> + Send + Sync> AsyncReceiveHeader<Self> for DirectoryBackend<P> {
+    async fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<()> {
+        receive_header(self.path.as_ref(), 0, bytes).await
+    }
+
+    async fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool> {
+        receive_header(self.path.as_ref(), idx, bytes)
+            .await
+            .map(|()| true)
+    }
+}
+
+#[async_trait]
+impl<P: AsRef<Path> + Send + Sync> AsyncBackend for DirectoryBackend<P> {
+    async fn info(&self) -> Result<Info> {
+        Ok(Info { bsize: self.bsize })
+    }
+
+    fn block_size(&self) -> u32 {
+        self.bsize
+    }
+
+    fn variable_block_size(&self) -> bool {
+        true
+    }
+
+    async fn aquire(&mut self, buf: &[u8]) -> Result<Id> {
+        let bsize = self.bsize;
+        let buf = buf.to_vec();
+
+        unblock(self.path.as_ref(), move |path| {
+            aquire_block(path, bsize, &buf)
+        })
+        .await
+    }
+
+    async fn release(&mut self, id: Id) -> Result<()> {
+        unblock(self.path.as_ref(), move |path| release_block(path, &id)).await
+    }
+
+    async fn ids(&mut self) -> Result<Option<Vec<Id>>> {
+        unblock(self.path.as_ref(), list_ids).await.map(Some)
+    }
+
+    async fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize> {
+        let bsize = self.bsize;
+        let id = id.clone();
+        let mut vec = vec![0; buf.len()];
+
+        let (result, vec) = unblock(self.path.as_ref(), move |path| {
+            (read_block(path, &id, bsize, &mut vec), vec)
+        })
+        .await;
+
+        if let Ok(n) = result {
+            buf[..n].copy_from_slice(&vec[..n]);
+        }
+
+        result
+    }
+
+    async fn write(&mut self, id: &Id, buf: &[u8]) -> Result<usize> {
+        let bsize = self.bsize;
+        let id = id.clone();
+        let buf = buf.to_vec();
+
+        unblock(self.path.as_ref(), move |path| {
+            write_block(path, &id, false, false, bsize, &buf)
+        })
+        .await
+    }
+
+    async fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<()> {
+        let bsize = self.bsize;
+        let buf = *buf;
+
+        unblock(self.path.as_ref(), move |path| {
+            write_header(path, bsize, &buf)
+        })
+        .await
+    }
+
+    async fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool> {
+        let bsize = self.bsize;
+        let buf = *buf;
+
+        unblock(self.path.as_ref(), move |path| {
+            write_extended_header(path, idx, bsize, &buf)
+        })
+        .await
+        .map(|()| true)
+    }
+
+    async fn delete(self) {
+        unblock(self.path.as_ref(), delete_backend).await
+    }
+}
+
+#[async_trait]
+impl<P: AsRef<Path> + Send + Sync + 'static> AsyncCreate<DirectoryBackend<P>> for CreateOptions<P> {
+    fn settings(&self) -> Settings {
+        Create::settings(self)
+    }
+
+    async fn build(
+        self,
+        header: [u8; HEADER_MAX_SIZE],
+        overwrite: bool,
+    ) -> Result<DirectoryBackend<P>> {
+        blocking::unblock(move || Create::build(self, header, overwrite)).await
+    }
+}
+
+#[async_trait]
+impl<P: AsRef<Path> + Send + Sync> AsyncReceiveHeader<DirectoryBackend<P>> for OpenOptions<P> {
+    async fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<()> {
+        receive_header(self.path(), 0, bytes).await
+    }
+
+    async fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool> {
+        receive_header(self.path(), idx, bytes).await.map(|()| true)
+    }
+}
+
+#[async_trait]
+impl<P: AsRef<Path> + Send + Sync> AsyncOpen<DirectoryBackend<P>> for OpenOptions<P> {
+    async fn build(self, settings: Settings) -> Result<DirectoryBackend<P>> {
+        Open::build(self, settings)
+    }
+}
@@ -0,0 +1,128 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use nuts_backend::{AsyncBackend, AsyncCreate, AsyncOpen, AsyncReceiveHeader, HEADER_MAX_SIZE};
+use pollster::block_on;
+use std::path::PathBuf;
+use tempfile::{Builder, TempDir};
+
+use crate::{CreateOptions, DirectoryBackend, Error, OpenOptions};
+
+fn tmp_dir() -> TempDir {
+    Builder::new().prefix("nuts-directory").tempdir().unwrap()
+}
+
+fn create(dir: &TempDir) -> DirectoryBackend<PathBuf> {
+    let options = CreateOptions::for_path(dir.path().to_path_buf());
+
+    block_on(AsyncCreate::build(options, [1; HEADER_MAX_SIZE], false)).unwrap()
+}
+
+#[test]
+fn create_exists() {
+    let dir = tmp_dir();
+
+    create(&dir);
+
+    let options = CreateOptions::for_path(dir.path().to_path_buf());
+    let err = block_on(AsyncCreate::build(options, [2; HEADER_MAX_SIZE], false)).unwrap_err();
+
+    assert!(matches!(err, Error::Exists));
+}
+
+#[test]
+fn open() {
+    let dir = tmp_dir();
+    let settings = AsyncCreate::settings(&CreateOptions::for_path(dir.path().to_path_buf()));
+
+    create(&dir);
+
+    block_on(async {
+        let mut options = OpenOptions::for_path(dir.path());
+        let mut buf = [0; HEADER_MAX_SIZE];
+
+        options.get_header_bytes(&mut buf).await.unwrap();
+        assert_eq!(buf, [1; HEADER_MAX_SIZE]);
+
+        let backend = AsyncOpen::build(options, settings).await.unwrap();
+        assert_eq!(AsyncBackend::block_size(&backend), 512);
+    });
+}
+
+#[test]
+fn blocks() {
+    let dir = tmp_dir();
+    let mut backend = create(&dir);
+
+    block_on(async {
+        let id = backend.aquire(b"xyz").await.unwrap();
+        let mut buf = [0; 512];
+
+        assert!(backend.variable_block_size());
+        assert_eq!(backend.ids().await.unwrap(), Some(vec![id.clone()]));
+        assert_eq!(backend.read(&id, &mut buf).await.unwrap(), 3);
+        assert_eq!(buf[..3], *b"xyz");
+
+        assert_eq!(backend.write(&id, b"abc").await.unwrap(), 3);
+        assert_eq!(backend.read(&id, &mut buf[..3]).await.unwrap(), 3);
+        assert_eq!(buf[..3], *b"abc");
+
+        backend.release(id.clone()).await.unwrap();
+        assert!(backend.ids().await.unwrap().unwrap().is_empty());
+        assert!(backend.read(&id, &mut buf).await.is_err());
+    });
+}
+
+#[test]
+fn header() {
+    let dir = tmp_dir();
+    let mut backend = create(&dir);
+
+    block_on(async {
+        let mut buf = [0; HEADER_MAX_SIZE];
+
+        backend.write_header(&[2; HEADER_MAX_SIZE]).await.unwrap();
+        backend
+            .write_extended_header(1, &[3; HEADER_MAX_SIZE])
+            .await
+            .unwrap();
+
+        backend.get_header_bytes(&mut buf).await.unwrap();
+        assert_eq!(buf, [2; HEADER_MAX_SIZE]);
+
+        backend
+            .get_extended_header_bytes(1, &mut buf)
+            .await
+            .unwrap();
+        assert_eq!(buf, [3; HEADER_MAX_SIZE]);
+    });
+}
+
+#[test]
+fn delete() {
+    let dir = tmp_dir();
+    let backend = create(&dir);
+
+    block_on(backend.delete());
+
+    assert!(!dir.path().exists());
+}
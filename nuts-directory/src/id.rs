@@ -94,6 +94,25 @@ impl Id {
         Id([u8::MIN; SIZE])
     }
 
+    /// Returns the id of the extended header block `idx`.
+    ///
+    /// The id of the header ([`Id::min()`]) is followed by the ids of the
+    /// extended header blocks: The last four bytes contain `idx`, all other
+    /// bytes are zero.
+    pub(crate) fn extended_header(idx: u32) -> Id {
+        let mut buf = [u8::MIN; SIZE];
+
+        buf[SIZE - 4..].copy_from_slice(&idx.to_be_bytes());
+
+        Id(buf)
+    }
+
+    /// Tests whether this id is reserved for the header or an extended
+    /// header block.
+    pub(crate) fn is_header(&self) -> bool {
+        self.0[..SIZE - 4].iter().all(|n| *n == u8::MIN)
+    }
+
     fn as_hex(&self) -> String {
         let mut target = String::with_capacity(2 * SIZE);
 
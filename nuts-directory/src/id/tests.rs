@@ -42,6 +42,20 @@ fn min() {
     assert_eq!(id.0, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
 }
 
+#[test]
+fn extended_header() {
+    let id = Id::extended_header(0x01020304);
+    assert_eq!(id.0, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn is_header() {
+    assert!(Id::min().is_header());
+    assert!(Id::extended_header(1).is_header());
+    assert!(Id::extended_header(u32::MAX).is_header());
+    assert!(!Id::generate().unwrap().is_header());
+}
+
 #[test]
 fn as_hex() {
     let id = Id::generate().unwrap();
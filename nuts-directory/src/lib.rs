@@ -36,7 +36,15 @@
 //!    `<first two chars>/<next two chars>/<remaining chars>`
 //!
 //! The header of the container is stored in the file
-//! `00/00/0000000000000000000000000000`.
+//! `00/00/0000000000000000000000000000`. Extended header blocks are stored
+//! next to it, the last eight chars contain the number of the block (e.g.
+//! `00/00/0000000000000000000000000001`).
+//!
+//! The backend has a [variable block size](Backend::variable_block_size): a
+//! file contains only the data passed to the backend, which can be less than
+//! the block size (e.g. a compressed block). The header files are always
+//! padded to the block size. Note that the size of a file reveals the
+//! compressed size of the data of its block.
 //!
 //! # Create a new backend instance
 //!
@@ -51,10 +59,19 @@
 //! passed to the [`Container::open`] method. You need the directory where the
 //! backend put its blocks.
 //!
+//! # Asynchronous backend
+//!
+//! With the `async` feature enabled the [`DirectoryBackend`] (and its
+//! [`CreateOptions`] and [`OpenOptions`]) implement the asynchronous backend
+//! traits of the [`nuts_backend`] crate as well. The file operations are
+//! executed on a thread pool, thus they do not block the executor.
+//!
 //! [nuts]: https://crates.io/crates/nuts-container
 //! [`Container::create`]: https://docs.rs/nuts-container/latest/nuts_container/container/struct.Container.html#method.create
 //! [`Container::open`]: https://docs.rs/nuts-container/latest/nuts_container/container/struct.Container.html#method.open
 
+#[cfg(feature = "async")]
+mod asynchronous;
 mod error;
 mod id;
 mod info;
@@ -78,11 +95,20 @@ fn read_block(path: &Path, id: &Id, bsize: u32, buf: &mut [u8]) -> Result<usize>
     let mut fh = fs::OpenOptions::new().read(true).open(path)?;
 
     let len = cmp::min(buf.len(), bsize as usize);
-    let target = &mut buf[..len];
-
-    fh.read_exact(target)?;
+    let mut nread = 0;
+
+    // A block is stored with its actual length, which can be less than the
+    // block size.
+    while nread < len {
+        match fh.read(&mut buf[nread..len]) {
+            Ok(0) => break,
+            Ok(n) => nread += n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
 
-    Ok(len)
+    Ok(nread)
 }
 
 fn write_block(
@@ -128,8 +154,9 @@ fn write_block(
         .create_new(true)
         .open(&tmp_path)?;
 
+    // Only the header is padded, the backend has a variable block size.
     let len = cmp::min(buf.len(), bsize as usize);
-    let pad_len = bsize as usize - len;
+    let pad_len = if header { bsize as usize - len } else { 0 };
 
     fh.write_all(&buf[..len])?;
     fh.write_all(&vec![0; pad_len])?;
@@ -140,14 +167,110 @@ fn write_block(
     Ok(len)
 }
 
+fn list_ids(path: &Path) -> Result<Vec<Id>> {
+    let mut ids = vec![];
+
+    for dir1 in fs::read_dir(path)? {
+        let dir1 = dir1?;
+
+        if !dir1.file_type()?.is_dir() {
+            continue;
+        }
+
+        for dir2 in fs::read_dir(dir1.path())? {
+            let dir2 = dir2?;
+
+            if !dir2.file_type()?.is_dir() {
+                continue;
+            }
+
+            for file in fs::read_dir(dir2.path())? {
+                let file = file?;
+
+                if !file.file_type()?.is_file() {
+                    continue;
+                }
+
+                // Rebuild the hex string of the id from the path, anything
+                // which is not an id (like temporary files) is skipped.
+                let hex = [dir1.file_name(), dir2.file_name(), file.file_name()]
+                    .iter()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .collect::<String>();
+
+                if let Ok(id) = hex.parse::<Id>() {
+                    if !id.is_header() {
+                        ids.push(id);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(ids)
+}
+
+fn read_header_block(path: &Path, id: &Id, buf: &mut [u8]) -> Result<()> {
+    let len = cmp::min(buf.len(), HEADER_MAX_SIZE);
+
+    // Unlike the data blocks the header is always padded.
+    if read_block(path, id, HEADER_MAX_SIZE as u32, buf)? < len {
+        return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(())
+}
+
 fn read_header(path: &Path, buf: &mut [u8]) -> Result<()> {
-    read_block(path, &Id::min(), HEADER_MAX_SIZE as u32, buf).map(|_| ())
+    read_header_block(path, &Id::min(), buf)
 }
 
 fn write_header(path: &Path, bsize: u32, buf: &[u8]) -> Result<()> {
     write_block(path, &Id::min(), false, true, bsize, buf).map(|_| ())
 }
 
+fn read_extended_header(path: &Path, idx: u32, buf: &mut [u8]) -> Result<()> {
+    read_header_block(path, &Id::extended_header(idx), buf)
+}
+
+fn write_extended_header(path: &Path, idx: u32, bsize: u32, buf: &[u8]) -> Result<()> {
+    let id = Id::extended_header(idx);
+
+    write_block(path, &id, false, true, bsize, buf).map(|_| ())
+}
+
+fn aquire_block(path: &Path, bsize: u32, buf: &[u8]) -> Result<Id> {
+    const MAX: u8 = 3;
+
+    for n in 0..MAX {
+        let id = Id::generate()?;
+
+        match write_block(path, &id, true, false, bsize, buf) {
+            Ok(_) => return Ok(id),
+            Err(Error::Io(err)) => {
+                if err.kind() == ErrorKind::AlreadyExists {
+                    warn!("Id {} already exists try again ({}/{})", id, n + 1, MAX);
+                } else {
+                    return Err(err.into());
+                }
+            }
+            Err(err) => return Err(err),
+        };
+    }
+
+    Err(Error::UniqueId)
+}
+
+fn release_block(path: &Path, id: &Id) -> Result<()> {
+    Ok(fs::remove_file(id.to_pathbuf(path))?)
+}
+
+fn delete_backend(path: &Path) {
+    if let Err(err) = fs::remove_dir_all(path) {
+        error!("failed to delete backend instance: {}", err);
+    }
+}
+
 #[derive(Debug)]
 pub struct DirectoryBackend<P: AsRef<Path>> {
     bsize: u32,
@@ -158,6 +281,14 @@ impl<P: AsRef<Path>> ReceiveHeader<Self> for DirectoryBackend<P> {
     fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<()> {
         read_header(self.path.as_ref(), bytes)
     }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool> {
+        read_extended_header(self.path.as_ref(), idx, bytes).map(|()| true)
+    }
 }
 
 impl<P: AsRef<Path>> Backend for DirectoryBackend<P> {
@@ -174,32 +305,20 @@ impl<P: AsRef<Path>> Backend for DirectoryBackend<P> {
         self.bsize
     }
 
-    fn aquire(&mut self, buf: &[u8]) -> Result<Self::Id> {
-        const MAX: u8 = 3;
-
-        for n in 0..MAX {
-            let id = Id::generate()?;
-
-            match write_block(self.path.as_ref(), &id, true, false, self.bsize, buf) {
-                Ok(_) => return Ok(id),
-                Err(Error::Io(err)) => {
-                    if err.kind() == ErrorKind::AlreadyExists {
-                        warn!("Id {} already exists try again ({}/{})", id, n + 1, MAX);
-                    } else {
-                        return Err(err.into());
-                    }
-                }
-                Err(err) => return Err(err),
-            };
-        }
+    fn variable_block_size(&self) -> bool {
+        true
+    }
 
-        Err(Error::UniqueId)
+    fn aquire(&mut self, buf: &[u8]) -> Result<Self::Id> {
+        aquire_block(self.path.as_ref(), self.bsize, buf)
     }
 
     fn release(&mut self, id: Self::Id) -> Result<()> {
-        let path = id.to_pathbuf(self.path.as_ref());
+        release_block(self.path.as_ref(), &id)
+    }
 
-        Ok(fs::remove_file(path)?)
+    fn ids(&mut self) -> Result<Option<Vec<Id>>> {
+        list_ids(self.path.as_ref()).map(Some)
     }
 
     fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize> {
@@ -214,9 +333,11 @@ impl<P: AsRef<Path>> Backend for DirectoryBackend<P> {
         write_header(self.path.as_ref(), self.bsize, buf)
     }
 
+    fn write_extended_header(&mut self, idx: u32, buf: &[u8; HEADER_MAX_SIZE]) -> Result<bool> {
+        write_extended_header(self.path.as_ref(), idx, self.bsize, buf).map(|()| true)
+    }
+
     fn delete(self) {
-        if let Err(err) = fs::remove_dir_all(self.path) {
-            error!("failed to delete backend instance: {}", err);
-        }
+        delete_backend(self.path.as_ref())
     }
 }
@@ -29,7 +29,7 @@ use std::path::Path;
 
 use crate::error::{Error, Result};
 use crate::id::Id;
-use crate::{read_header, write_header, DirectoryBackend};
+use crate::{read_extended_header, read_header, write_header, DirectoryBackend};
 
 const BLOCK_MIN_SIZE: u32 = 512;
 
@@ -122,12 +122,25 @@ impl<P: AsRef<Path>> OpenOptions<P> {
     pub fn for_path(path: P) -> OpenOptions<P> {
         OpenOptions { path }
     }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn path(&self) -> &Path {
+        self.path.as_ref()
+    }
 }
 
 impl<P: AsRef<Path>> ReceiveHeader<DirectoryBackend<P>> for OpenOptions<P> {
     fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<()> {
         read_header(self.path.as_ref(), bytes)
     }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool> {
+        read_extended_header(self.path.as_ref(), idx, bytes).map(|()| true)
+    }
 }
 
 impl<P: AsRef<Path>> Open<DirectoryBackend<P>> for OpenOptions<P> {
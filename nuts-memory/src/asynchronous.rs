@@ -0,0 +1,117 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use async_trait::async_trait;
+use nuts_backend::{
+    AsyncBackend, AsyncCreate, AsyncOpen, AsyncReceiveHeader, Backend, Create, Open, ReceiveHeader,
+    HEADER_MAX_SIZE,
+};
+
+use crate::{Error, Id, MemoryBackend, Settings};
+
+#[async_trait]
+impl AsyncReceiveHeader<Self> for MemoryBackend {
+    async fn get_header_bytes(&mut self, bytes: &mut [u8; HEADER_MAX_SIZE]) -> Result<(), Error> {
+        ReceiveHeader::get_header_bytes(self, bytes)
+    }
+
+    async fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, Error> {
+        ReceiveHeader::get_extended_header_bytes(self, idx, bytes)
+    }
+}
+
+#[async_trait]
+impl AsyncCreate<Self> for MemoryBackend {
+    fn settings(&self) -> Settings {
+        Settings
+    }
+
+    async fn build(
+        self,
+        header: [u8; HEADER_MAX_SIZE],
+        overwrite: bool,
+    ) -> Result<MemoryBackend, Error> {
+        Create::build(self, header, overwrite)
+    }
+}
+
+#[async_trait]
+impl AsyncOpen<Self> for MemoryBackend {
+    async fn build(self, settings: Settings) -> Result<MemoryBackend, Error> {
+        Open::build(self, settings)
+    }
+}
+
+#[async_trait]
+impl AsyncBackend for MemoryBackend {
+    async fn info(&self) -> Result<(), Error> {
+        Backend::info(self)
+    }
+
+    fn block_size(&self) -> u32 {
+        self.bsize
+    }
+
+    fn variable_block_size(&self) -> bool {
+        self.variable
+    }
+
+    async fn aquire(&mut self, buf: &[u8]) -> Result<Id, Error> {
+        Backend::aquire(self, buf)
+    }
+
+    async fn release(&mut self, id: Id) -> Result<(), Error> {
+        Backend::release(self, id)
+    }
+
+    async fn ids(&mut self) -> Result<Option<Vec<Id>>, Error> {
+        Backend::ids(self)
+    }
+
+    async fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize, Error> {
+        Backend::read(self, id, buf)
+    }
+
+    async fn write(&mut self, id: &Id, buf: &[u8]) -> Result<usize, Error> {
+        Backend::write(self, id, buf)
+    }
+
+    async fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), Error> {
+        Backend::write_header(self, buf)
+    }
+
+    async fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, Error> {
+        Backend::write_extended_header(self, idx, buf)
+    }
+
+    async fn delete(self) {
+        Backend::delete(self)
+    }
+}
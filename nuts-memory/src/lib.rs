@@ -29,11 +29,17 @@
 //! It stores the content of the data blocks in a [hash](HashMap) indexed by
 //! the [`Id`](nuts_backend::Backend::Id) of this backend, where the
 //! [id](nuts_backend::Backend::Id) is a simple `u32` value.
+//!
+//! With the `async` feature enabled the [`MemoryBackend`] implements the
+//! asynchronous backend traits of the [`nuts_backend`] crate as well. Because
+//! all data are kept in memory, the asynchronous calls never block.
+
+#[cfg(feature = "async")]
+mod asynchronous;
 
 use nuts_backend::{Backend, Binary, Create, IdSize, Open, ReceiveHeader, HEADER_MAX_SIZE};
 use nuts_bytes::{FromBytes, ToBytes};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::num::ParseIntError;
@@ -77,6 +83,10 @@ pub enum Error {
     #[error("no header data available")]
     NoHeader,
 
+    /// Tried to read an extended header block, which does not exist
+    #[error("no extended header block {0} available")]
+    NoExtendedHeader(u32),
+
     /// Failed to serialize binary data.
     #[error(transparent)]
     Bytes(#[from] nuts_bytes::Error),
@@ -148,6 +158,10 @@ pub struct MemoryBackend {
         serialize_with = "serialize_header"
     )]
     header: Option<[u8; HEADER_MAX_SIZE]>,
+    #[serde(default)]
+    extended_header: HashMap<u32, Vec<u8>>,
+    #[serde(default)]
+    variable: bool,
 }
 
 impl MemoryBackend {
@@ -165,9 +179,20 @@ impl MemoryBackend {
             bsize,
             blocks: HashMap::new(),
             header: None,
+            extended_header: HashMap::new(),
+            variable: false,
         }
     }
 
+    /// Switches to a [variable block size](Backend::variable_block_size).
+    ///
+    /// A block stores only the data passed to the backend, shorter data are
+    /// not padded with zero bytes.
+    pub fn with_variable_block_size(mut self) -> MemoryBackend {
+        self.variable = true;
+        self
+    }
+
     /// Returns the block size specified for this backend instance.
     pub fn block_size(&self) -> u32 {
         self.bsize
@@ -193,15 +218,13 @@ impl MemoryBackend {
     ///
     /// Assigns the first [`block-size`](Self::block_size) bytes from `data` to
     /// the new block. If `data` does not have [`block-size`](Self::block_size)
-    /// bytes, the new block is padded with zero bytes.
+    /// bytes, the new block is padded with zero bytes (unless the backend has
+    /// a [variable block size](Self::with_variable_block_size)).
     ///
     /// Returns the id of the new block.
     pub fn insert_data(&mut self, data: &[u8]) -> Result<Id, Error> {
         let id = Id(self.max_id() + 1);
-        let mut block = vec![0; self.bsize as usize];
-
-        let n = cmp::min(block.len(), data.len());
-        block[..n].copy_from_slice(&data[..n]);
+        let block = self.make_block(data);
 
         match self.blocks.insert(id.0, block) {
             Some(_) => Err(Error::AlreadAquired(id)),
@@ -212,6 +235,17 @@ impl MemoryBackend {
     fn max_id(&self) -> u32 {
         *self.blocks.keys().max().unwrap_or(&0)
     }
+
+    fn make_block(&self, data: &[u8]) -> Vec<u8> {
+        let n = cmp::min(self.bsize as usize, data.len());
+        let mut block = data[..n].to_vec();
+
+        if !self.variable {
+            block.resize(self.bsize as usize, 0);
+        }
+
+        block
+    }
 }
 
 impl Default for MemoryBackend {
@@ -230,6 +264,20 @@ impl ReceiveHeader<Self> for MemoryBackend {
             None => Err(Error::NoHeader),
         }
     }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, Error> {
+        match self.extended_header.get(&idx) {
+            Some(source) => {
+                bytes.copy_from_slice(source);
+                Ok(true)
+            }
+            None => Err(Error::NoExtendedHeader(idx)),
+        }
+    }
 }
 
 impl Create<Self> for MemoryBackend {
@@ -267,6 +315,10 @@ impl Backend for MemoryBackend {
         self.bsize
     }
 
+    fn variable_block_size(&self) -> bool {
+        self.variable
+    }
+
     fn aquire(&mut self, buf: &[u8]) -> Result<Id, Error> {
         self.insert_data(buf)
     }
@@ -276,6 +328,10 @@ impl Backend for MemoryBackend {
         Ok(())
     }
 
+    fn ids(&mut self) -> Result<Option<Vec<Id>>, Error> {
+        Ok(Some(self.blocks.keys().map(|n| Id(*n)).collect()))
+    }
+
     fn read(&mut self, id: &Id, buf: &mut [u8]) -> Result<usize, Error> {
         match self.blocks.get(&id.0) {
             Some(src) => {
@@ -293,19 +349,12 @@ impl Backend for MemoryBackend {
     }
 
     fn write(&mut self, id: &Id, buf: &[u8]) -> Result<usize, Error> {
+        let block = self.make_block(buf);
+
         match self.blocks.get_mut(&id.0) {
             Some(target) => {
-                let mut source = Cow::from(buf);
-                let mut len = source.len();
-
-                if len != self.bsize as usize {
-                    len = cmp::min(source.len(), self.bsize as usize);
-                    source.to_mut().resize(self.bsize as usize, 0);
-                }
-
-                target.copy_from_slice(&source);
-
-                Ok(len)
+                *target = block;
+                Ok(cmp::min(buf.len(), self.bsize as usize))
             }
             None => Err(Error::NoSuchId(*id)),
         }
@@ -316,6 +365,15 @@ impl Backend for MemoryBackend {
         Ok(())
     }
 
+    fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, Error> {
+        self.extended_header.insert(idx, buf.to_vec());
+        Ok(true)
+    }
+
     fn delete(self) {
         // noop
     }
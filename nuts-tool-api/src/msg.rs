@@ -151,23 +151,49 @@ pub enum Request {
     /// * The response must be a [`OkResponse::Bytes`] variant.
     Aquire(Vec<u8>),
 
+    /// Request to aquire several new blocks in the backend.
+    ///
+    /// * The argument contains the initial data of each block.
+    /// * The response must be a [`OkResponse::BytesList`] variant.
+    AquireMany(Vec<Vec<u8>>),
+
     /// Request to release a block in the backend.
     ///
     /// * The argument contains the binary data of the id to release.
     /// * The response must be a [`OkResponse::Void`] variant.
     Release(Vec<u8>),
 
+    /// Ask for the ids of all aquired blocks.
+    ///
+    /// * The response must be a [`OkResponse::BytesList`] variant.
+    /// * A backend, which cannot list its blocks, responds with
+    ///   [`ErrorResponse::Unsupported`].
+    Ids,
+
     /// Request to read the header data of the backend.
     ///
     /// * The response must be a [`OkResponse::Bytes`] variant.
     ReadHeader,
 
+    /// Request to read an extended header block of the backend.
+    ///
+    /// * The argument contains the number of the block.
+    /// * The response must be a [`OkResponse::Bytes`] variant.
+    ReadExtendedHeader(u32),
+
     /// Requerst to write the header of the backend.
     ///
     /// * The argument contains the header data to be written.
     /// * The response must be a [`OkResponse::Void`] variant.
     WriteHeader(Vec<u8>),
 
+    /// Request to write an extended header block of the backend.
+    ///
+    /// * The first argument contains the number of the block.
+    /// * The second argument contains the data to be written.
+    /// * The response must be a [`OkResponse::Void`] variant.
+    WriteExtendedHeader(u32, Vec<u8>),
+
     /// Request to read a block in the backend.
     ///
     /// * The argument contains the binary data of the id to read.
@@ -181,6 +207,20 @@ pub enum Request {
     /// * The response must be a [`OkResponse::Usize`] variant.
     Write(Vec<u8>, Vec<u8>),
 
+    /// Request to read several blocks in the backend.
+    ///
+    /// * The argument contains the binary data of the ids to read.
+    /// * The response must be a [`OkResponse::BytesList`] variant.
+    ReadMany(Vec<Vec<u8>>),
+
+    /// Request to write several blocks in the backend.
+    ///
+    /// * The first argument contains the binary data of the ids to write.
+    /// * The second argument contains the data to be written into each
+    ///   block.
+    /// * The response must be a [`OkResponse::UsizeList`] variant.
+    WriteMany(Vec<Vec<u8>>, Vec<Vec<u8>>),
+
     /// Asks to delete the backend.
     ///
     /// * The response must be a [`OkResponse::Void`] variant.
@@ -203,11 +243,17 @@ impl Request {
     as_into_impls!(as_create + into_create => Create (arg1: Vec<u8>, args: bool));
     as_into_impls!(as_info + into_info => Info);
     as_into_impls!(as_aquire + into_aquire => Aquire (arg1: Vec<u8>));
+    as_into_impls!(as_aquire_many + into_aquire_many => AquireMany (arg1: Vec<Vec<u8>>));
     as_into_impls!(as_release + into_release => Release (arg1: Vec<u8>));
+    as_into_impls!(as_ids + into_ids => Ids);
     as_into_impls!(as_read_header + into_read_header => ReadHeader);
+    as_into_impls!(as_read_extended_header + into_read_extended_header => ReadExtendedHeader (arg1: u32));
     as_into_impls!(as_write_header + into_write_header => WriteHeader (arg1: Vec<u8>));
+    as_into_impls!(as_write_extended_header + into_write_extended_header => WriteExtendedHeader (arg1: u32, arg2: Vec<u8>));
     as_into_impls!(as_read + into_read => Read (arg1: Vec<u8>));
     as_into_impls!(as_write + into_write => Write (arg1: Vec<u8>, arg2: Vec<u8>));
+    as_into_impls!(as_read_many + into_read_many => ReadMany (arg1: Vec<Vec<u8>>));
+    as_into_impls!(as_write_many + into_write_many => WriteMany (arg1: Vec<Vec<u8>>, arg2: Vec<Vec<u8>>));
     as_into_impls!(as_delete + into_delete => Delete);
     as_into_impls!(as_quit + into_quit => Quit);
 }
@@ -229,18 +275,40 @@ impl fmt::Debug for Request {
                 .finish(),
             Self::Info => write!(fmt, "Info"),
             Self::Aquire(arg) => fmt.debug_tuple("Aquire").field(&VecDebug(arg)).finish(),
+            Self::AquireMany(arg) => fmt
+                .debug_tuple("AquireMany")
+                .field(&arg.iter().map(VecDebug).collect::<Vec<_>>())
+                .finish(),
             Self::Release(arg) => fmt.debug_tuple("Release").field(&VecDebug(arg)).finish(),
+            Self::Ids => write!(fmt, "Ids"),
             Self::ReadHeader => write!(fmt, "ReadHeader"),
+            Self::ReadExtendedHeader(arg) => {
+                fmt.debug_tuple("ReadExtendedHeader").field(arg).finish()
+            }
             Self::WriteHeader(arg) => fmt
                 .debug_tuple("WriteHeader")
                 .field(&VecDebug(arg))
                 .finish(),
+            Self::WriteExtendedHeader(arg1, arg2) => fmt
+                .debug_tuple("WriteExtendedHeader")
+                .field(arg1)
+                .field(&VecDebug(arg2))
+                .finish(),
             Self::Read(arg) => fmt.debug_tuple("Read").field(&VecDebug(arg)).finish(),
             Self::Write(arg1, arg2) => fmt
                 .debug_tuple("Write")
                 .field(&VecDebug(arg1))
                 .field(&VecDebug(arg2))
                 .finish(),
+            Self::ReadMany(arg) => fmt
+                .debug_tuple("ReadMany")
+                .field(&arg.iter().map(VecDebug).collect::<Vec<_>>())
+                .finish(),
+            Self::WriteMany(arg1, arg2) => fmt
+                .debug_tuple("WriteMany")
+                .field(&arg1.iter().map(VecDebug).collect::<Vec<_>>())
+                .field(&arg2.iter().map(VecDebug).collect::<Vec<_>>())
+                .finish(),
             Self::Delete => write!(fmt, "Delete"),
             Self::Quit => write!(fmt, "Quit"),
         }
@@ -274,11 +342,23 @@ impl Response {
         Self::Ok(OkResponse::Usize(value))
     }
 
+    /// Creates a successful response with an attached
+    /// [`OkResponse::UsizeList`].
+    pub fn ok_usize_list(value: Vec<usize>) -> Response {
+        Self::Ok(OkResponse::UsizeList(value))
+    }
+
     /// Creates a successful response with an attached [`OkResponse::Bytes`].
     pub fn ok_bytes(value: Vec<u8>) -> Response {
         Self::Ok(OkResponse::Bytes(value))
     }
 
+    /// Creates a successful response with an attached
+    /// [`OkResponse::BytesList`].
+    pub fn ok_bytes_list(value: Vec<Vec<u8>>) -> Response {
+        Self::Ok(OkResponse::BytesList(value))
+    }
+
     /// Creates a successful response with an attached [`OkResponse::String`].
     pub fn ok_string(value: String) -> Response {
         Self::Ok(OkResponse::String(value))
@@ -319,9 +399,15 @@ pub enum OkResponse {
     /// A successful response with an attached [`usize`].
     Usize(usize),
 
+    /// A successful response with an attached list of [`usize`].
+    UsizeList(Vec<usize>),
+
     /// A successful response with an attached [`Vec<u8>`].
     Bytes(Vec<u8>),
 
+    /// A successful response with an attached list of [`Vec<u8>`].
+    BytesList(Vec<Vec<u8>>),
+
     /// A successful response with an attached [`String`].
     String(String),
 
@@ -335,7 +421,12 @@ impl fmt::Debug for OkResponse {
             Self::Void => write!(fmt, "Void"),
             Self::U32(arg) => fmt.debug_tuple("U32").field(arg).finish(),
             Self::Usize(arg) => fmt.debug_tuple("Usize").field(arg).finish(),
+            Self::UsizeList(arg) => fmt.debug_tuple("UsizeList").field(arg).finish(),
             Self::Bytes(arg) => fmt.debug_tuple("Bytes").field(&VecDebug(arg)).finish(),
+            Self::BytesList(arg) => fmt
+                .debug_tuple("BytesList")
+                .field(&arg.iter().map(VecDebug).collect::<Vec<_>>())
+                .finish(),
             Self::String(arg) => fmt.debug_tuple("String").field(arg).finish(),
             Self::Map(arg) => fmt.debug_tuple("Map").field(arg).finish(),
         }
@@ -352,6 +443,12 @@ pub enum ErrorResponse {
     /// backend was not opened yet.
     NotApplicable,
 
+    /// The call is not supported by the attached backend.
+    ///
+    /// I.e. the backend does not implement extended header blocks or cannot
+    /// list its blocks.
+    Unsupported,
+
     /// Could not convert an id into its binary representation.
     InvalidId,
 
@@ -388,6 +485,7 @@ impl fmt::Display for ErrorResponse {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::NotApplicable => write!(fmt, "the call is not applicable"),
+            Self::Unsupported => write!(fmt, "the call is not supported by the backend"),
             Self::InvalidId => write!(fmt, "could not parse id"),
             Self::InvalidIdData => write!(fmt, "could not create id"),
             Self::InvalidSettingsData => write!(fmt, "could not create settings"),
@@ -175,6 +175,20 @@ pub trait PluginHandler<B: Backend> {
         }
     }
 
+    /// Handles the [`Request::AquireMany`] command.
+    fn handle_aquire_many(
+        &self,
+        backend: &mut B,
+        bytes: &[Vec<u8>],
+    ) -> Result<Vec<Vec<u8>>, ErrorResponse> {
+        let bufs = bytes.iter().map(|buf| buf.as_slice()).collect::<Vec<_>>();
+
+        match B::aquire_many(backend, &bufs) {
+            Ok(ids) => Ok(ids.iter().map(<B::Id as Binary>::as_bytes).collect()),
+            Err(err) => Err(ErrorResponse::backend::<B>(err)),
+        }
+    }
+
     /// Handles the [`Request::Release`] command.
     fn handle_release(&self, backend: &mut B, id: &[u8]) -> Result<(), ErrorResponse> {
         let id = <B::Id as Binary>::from_bytes(id).ok_or(ErrorResponse::InvalidIdData)?;
@@ -182,6 +196,15 @@ pub trait PluginHandler<B: Backend> {
         B::release(backend, id).map_err(|err| ErrorResponse::backend::<B>(err))
     }
 
+    /// Handles the [`Request::Ids`] command.
+    fn handle_ids(&self, backend: &mut B) -> Result<Vec<Vec<u8>>, ErrorResponse> {
+        match B::ids(backend) {
+            Ok(Some(ids)) => Ok(ids.iter().map(<B::Id as Binary>::as_bytes).collect()),
+            Ok(None) => Err(ErrorResponse::Unsupported),
+            Err(err) => Err(ErrorResponse::backend::<B>(err)),
+        }
+    }
+
     /// Handles the [`Request::ReadHeader`] command.
     fn handle_read_header<T: ReceiveHeader<B>>(
         &self,
@@ -195,6 +218,21 @@ pub trait PluginHandler<B: Backend> {
         }
     }
 
+    /// Handles the [`Request::ReadExtendedHeader`] command.
+    fn handle_read_extended_header<T: ReceiveHeader<B>>(
+        &self,
+        header: &mut T,
+        idx: u32,
+    ) -> Result<Vec<u8>, ErrorResponse> {
+        let mut bytes = [0; HEADER_MAX_SIZE];
+
+        match header.get_extended_header_bytes(idx, &mut bytes) {
+            Ok(true) => Ok(bytes.to_vec()),
+            Ok(false) => Err(ErrorResponse::Unsupported),
+            Err(err) => Err(ErrorResponse::backend::<B>(err)),
+        }
+    }
+
     /// Handles the [`Request::WriteHeader`] command.
     fn handle_write_header(&self, backend: &mut B, header: &[u8]) -> Result<(), ErrorResponse> {
         let header = into_header_bytes(header)?;
@@ -202,6 +240,22 @@ pub trait PluginHandler<B: Backend> {
         B::write_header(backend, &header).map_err(|err| ErrorResponse::backend::<B>(err))
     }
 
+    /// Handles the [`Request::WriteExtendedHeader`] command.
+    fn handle_write_extended_header(
+        &self,
+        backend: &mut B,
+        idx: u32,
+        header: &[u8],
+    ) -> Result<(), ErrorResponse> {
+        let header = into_header_bytes(header)?;
+
+        match B::write_extended_header(backend, idx, &header) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(ErrorResponse::Unsupported),
+            Err(err) => Err(ErrorResponse::backend::<B>(err)),
+        }
+    }
+
     /// Handles the [`Request::Read`] command.
     fn handle_read(&self, backend: &mut B, id: &[u8]) -> Result<Vec<u8>, ErrorResponse> {
         let id = <B::Id as Binary>::from_bytes(id).ok_or(ErrorResponse::InvalidIdData)?;
@@ -230,6 +284,66 @@ pub trait PluginHandler<B: Backend> {
         B::write(backend, &id, bytes).map_err(|err| ErrorResponse::backend::<B>(err))
     }
 
+    /// Handles the [`Request::ReadMany`] command.
+    fn handle_read_many(
+        &self,
+        backend: &mut B,
+        ids: &[Vec<u8>],
+    ) -> Result<Vec<Vec<u8>>, ErrorResponse> {
+        let ids = ids
+            .iter()
+            .map(|id| <B::Id as Binary>::from_bytes(id).ok_or(ErrorResponse::InvalidIdData))
+            .collect::<Result<Vec<_>, _>>()?;
+        let bsize = B::block_size(backend) as usize;
+        let mut bufs = vec![vec![0; bsize]; ids.len()];
+
+        let mut blocks = ids
+            .iter()
+            .zip(bufs.iter_mut())
+            .map(|(id, buf)| (id, buf.as_mut_slice()))
+            .collect::<Vec<_>>();
+
+        let nread =
+            B::read_many(backend, &mut blocks).map_err(|err| ErrorResponse::backend::<B>(err))?;
+
+        Ok(bufs
+            .into_iter()
+            .zip(nread)
+            .map(|(mut buf, n)| {
+                buf.truncate(n);
+                buf
+            })
+            .collect())
+    }
+
+    /// Handles the [`Request::WriteMany`] command.
+    fn handle_write_many(
+        &self,
+        backend: &mut B,
+        ids: &[Vec<u8>],
+        bytes: &[Vec<u8>],
+    ) -> Result<Vec<usize>, ErrorResponse> {
+        if ids.len() != bytes.len() {
+            return Err(ErrorResponse::message(
+                "number of ids and blocks does not match",
+            ));
+        }
+
+        let ids = ids
+            .iter()
+            .map(|id| <B::Id as Binary>::from_bytes(id).ok_or(ErrorResponse::InvalidIdData))
+            .collect::<Result<Vec<_>, _>>()?;
+        let bsize = B::block_size(backend) as usize;
+
+        let blocks = ids
+            .iter()
+            .zip(bytes.iter())
+            .map(|(id, buf)| (id, &buf[..cmp::min(buf.len(), bsize)]))
+            .collect::<Vec<_>>();
+
+        B::write_many(backend, &blocks).map_err(|err| ErrorResponse::backend::<B>(err))
+    }
+
     fn handle_delete(&self, backend: B) -> Result<(), ErrorResponse> {
         B::delete(backend);
         Ok(())
@@ -322,11 +436,19 @@ impl<'a, B: Backend, T: PluginHandler<B>> OpenCreateHandler<'a, B, T> {
                         Request::IdToString(ref bytes) => self.on_id_to_string(bytes),
                         Request::Info => self.on_info(),
                         Request::Aquire(ref bytes) => self.on_aquire(bytes),
+                        Request::AquireMany(ref bytes) => self.on_aquire_many(bytes),
                         Request::Release(ref id) => self.on_release(id),
+                        Request::Ids => self.on_ids(),
                         Request::ReadHeader => self.on_read_header(),
+                        Request::ReadExtendedHeader(idx) => self.on_read_extended_header(idx),
                         Request::WriteHeader(ref header) => self.on_write_header(header),
+                        Request::WriteExtendedHeader(idx, ref header) => {
+                            self.on_write_extended_header(idx, header)
+                        }
                         Request::Read(ref id) => self.on_read(id),
                         Request::Write(ref id, ref bytes) => self.on_write(id, bytes),
+                        Request::ReadMany(ref ids) => self.on_read_many(ids),
+                        Request::WriteMany(ref ids, ref bytes) => self.on_write_many(ids, bytes),
                         Request::Delete => self.on_delete(),
                         Request::Quit => self.on_quit(),
                     };
@@ -446,6 +568,17 @@ impl<'a, B: Backend, T: PluginHandler<B>> OpenCreateHandler<'a, B, T> {
         }
     }
 
+    fn on_aquire_many(&mut self, bytes: &[Vec<u8>]) -> Response {
+        if let Some(backend) = self.backend.as_mut() {
+            match self.handler.handle_aquire_many(backend, bytes) {
+                Ok(ids) => Response::ok_bytes_list(ids),
+                Err(err) => Response::Err(err),
+            }
+        } else {
+            Response::err_not_applicable()
+        }
+    }
+
     fn on_release(&mut self, id: &[u8]) -> Response {
         if let Some(backend) = self.backend.as_mut() {
             match self.handler.handle_release(backend, id) {
@@ -457,6 +590,17 @@ impl<'a, B: Backend, T: PluginHandler<B>> OpenCreateHandler<'a, B, T> {
         }
     }
 
+    fn on_ids(&mut self) -> Response {
+        if let Some(backend) = self.backend.as_mut() {
+            match self.handler.handle_ids(backend) {
+                Ok(ids) => Response::ok_bytes_list(ids),
+                Err(err) => Response::Err(err),
+            }
+        } else {
+            Response::err_not_applicable()
+        }
+    }
+
     fn on_read_header(&mut self) -> Response {
         if let Some(args) = self.command.as_open() {
             if let Some(mut builder) = self.handler.open_builder(args) {
@@ -477,6 +621,26 @@ impl<'a, B: Backend, T: PluginHandler<B>> OpenCreateHandler<'a, B, T> {
         }
     }
 
+    fn on_read_extended_header(&mut self, idx: u32) -> Response {
+        if let Some(args) = self.command.as_open() {
+            if let Some(mut builder) = self.handler.open_builder(args) {
+                match self.handler.handle_read_extended_header(&mut builder, idx) {
+                    Ok(header) => Response::ok_bytes(header),
+                    Err(err) => Response::Err(err),
+                }
+            } else {
+                Response::err_message("unable to build an open-builder")
+            }
+        } else if let Some(backend) = self.backend.as_mut() {
+            match self.handler.handle_read_extended_header(backend, idx) {
+                Ok(header) => Response::ok_bytes(header),
+                Err(err) => Response::Err(err),
+            }
+        } else {
+            Response::err_not_applicable()
+        }
+    }
+
     fn on_write_header(&mut self, header: &[u8]) -> Response {
         if let Some(backend) = self.backend.as_mut() {
             match self.handler.handle_write_header(backend, header) {
@@ -488,6 +652,20 @@ impl<'a, B: Backend, T: PluginHandler<B>> OpenCreateHandler<'a, B, T> {
         }
     }
 
+    fn on_write_extended_header(&mut self, idx: u32, header: &[u8]) -> Response {
+        if let Some(backend) = self.backend.as_mut() {
+            match self
+                .handler
+                .handle_write_extended_header(backend, idx, header)
+            {
+                Ok(()) => Response::ok_void(),
+                Err(err) => Response::Err(err),
+            }
+        } else {
+            Response::err_not_applicable()
+        }
+    }
+
     fn on_read(&mut self, id: &[u8]) -> Response {
         if let Some(backend) = self.backend.as_mut() {
             match self.handler.handle_read(backend, id) {
@@ -510,6 +688,28 @@ impl<'a, B: Backend, T: PluginHandler<B>> OpenCreateHandler<'a, B, T> {
         }
     }
 
+    fn on_read_many(&mut self, ids: &[Vec<u8>]) -> Response {
+        if let Some(backend) = self.backend.as_mut() {
+            match self.handler.handle_read_many(backend, ids) {
+                Ok(data) => Response::ok_bytes_list(data),
+                Err(err) => Response::Err(err),
+            }
+        } else {
+            Response::err_not_applicable()
+        }
+    }
+
+    fn on_write_many(&mut self, ids: &[Vec<u8>], bytes: &[Vec<u8>]) -> Response {
+        if let Some(backend) = self.backend.as_mut() {
+            match self.handler.handle_write_many(backend, ids, bytes) {
+                Ok(nums) => Response::ok_usize_list(nums),
+                Err(err) => Response::Err(err),
+            }
+        } else {
+            Response::err_not_applicable()
+        }
+    }
+
     fn on_delete(&mut self) -> Response {
         if let Some(backend) = self.backend.take() {
             match self.handler.handle_delete(backend) {
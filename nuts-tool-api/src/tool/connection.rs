@@ -204,11 +204,17 @@ impl PluginConnection {
     handshake_func!(create(header: Vec<u8>, overwrite: bool) -> (), Request::Create(header, overwrite), OkResponse::Void => Ok(()));
     handshake_func!(info() -> HashMap<String, String>, Request::Info, OkResponse::Map(map) => Ok(map));
     handshake_func!(aquire(bytes: Vec<u8>) -> Vec<u8>, Request::Aquire(bytes), OkResponse::Bytes(bytes) => Ok(bytes));
+    handshake_func!(aquire_many(bytes: Vec<Vec<u8>>) -> Vec<Vec<u8>>, Request::AquireMany(bytes), OkResponse::BytesList(ids) => Ok(ids));
     handshake_func!(release(id: Vec<u8>) -> (), Request::Release(id), OkResponse::Void => Ok(()));
+    handshake_func!(ids() -> Vec<Vec<u8>>, Request::Ids, OkResponse::BytesList(ids) => Ok(ids));
     handshake_func!(read_header() -> Vec<u8>, Request::ReadHeader, OkResponse::Bytes(bytes) => Ok(bytes));
+    handshake_func!(read_extended_header(idx: u32) -> Vec<u8>, Request::ReadExtendedHeader(idx), OkResponse::Bytes(bytes) => Ok(bytes));
     handshake_func!(write_header(bytes: Vec<u8>) -> (), Request::WriteHeader(bytes), OkResponse::Void => Ok(()));
+    handshake_func!(write_extended_header(idx: u32, bytes: Vec<u8>) -> (), Request::WriteExtendedHeader(idx, bytes), OkResponse::Void => Ok(()));
     handshake_func!(read(id: Vec<u8>) -> Vec<u8>, Request::Read(id), OkResponse::Bytes(bytes) => Ok(bytes));
     handshake_func!(write(id: Vec<u8>, bytes: Vec<u8>) -> usize, Request::Write(id, bytes), OkResponse::Usize(num) => Ok(num));
+    handshake_func!(read_many(ids: Vec<Vec<u8>>) -> Vec<Vec<u8>>, Request::ReadMany(ids), OkResponse::BytesList(bytes) => Ok(bytes));
+    handshake_func!(write_many(ids: Vec<Vec<u8>>, bytes: Vec<Vec<u8>>) -> Vec<usize>, Request::WriteMany(ids, bytes), OkResponse::UsizeList(nums) => Ok(nums));
     handshake_func!(delete() -> (), Request::Delete, OkResponse::Void => Ok(()));
 
     pub fn quit(&mut self) -> PluginResult<()> {
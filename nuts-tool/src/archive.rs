@@ -23,7 +23,7 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use log::{debug, error, trace, warn};
-use nuts_archive::{Archive, Group};
+use nuts_archive::{Archive, Entry, Group};
 use std::fs::{self, File, Metadata};
 use std::io::Read;
 use std::path::Path;
@@ -61,6 +61,20 @@ macro_rules! into_utc {
     };
 }
 
+macro_rules! copy_attributes {
+    ($entry:ident -> $builder:ident) => {
+        $builder.set_created(*$entry.created());
+        $builder.set_changed(*$entry.changed());
+        $builder.set_modified(*$entry.modified());
+
+        for group in [Group::User, Group::Group, Group::Other] {
+            $builder.set_readable(group, $entry.can_read(group));
+            $builder.set_writable(group, $entry.can_write(group));
+            $builder.set_executable(group, $entry.can_execute(group));
+        }
+    };
+}
+
 fn changed(metadata: &Metadata) -> DateTime<Utc> {
     if cfg!(unix) {
         DateTime::from_timestamp(metadata.ctime(), 0).unwrap_or_else(|| {
@@ -212,3 +226,59 @@ pub fn append_recursive(archive: &mut Archive<PluginBackend>, path: &Path) -> Re
 
     Ok(())
 }
+
+pub fn copy_archive(
+    source: &mut Archive<PluginBackend>,
+    target: &mut Archive<PluginBackend>,
+) -> Result<()> {
+    let block_size = source.as_ref().block_size() as usize;
+    let mut buf = vec![0; block_size];
+    let mut entry_opt = source.first();
+
+    while let Some(result) = entry_opt {
+        let mut entry = result?;
+
+        debug!("copy {}", entry.name());
+
+        match &mut entry {
+            Entry::File(file) => {
+                let mut builder = target.append_file(file.name());
+
+                copy_attributes!(file -> builder);
+
+                let mut target_entry = builder.build()?;
+
+                loop {
+                    let n = file.read(&mut buf)?;
+                    trace!("{} bytes read from {}", n, file.name());
+
+                    if n > 0 {
+                        target_entry.write_all(&buf[..n])?;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            Entry::Directory(dir) => {
+                let mut builder = target.append_directory(dir.name());
+
+                copy_attributes!(dir -> builder);
+
+                builder.build()?;
+            }
+            Entry::Symlink(symlink) => {
+                let mut builder = target.append_symlink(symlink.name(), symlink.target());
+
+                copy_attributes!(symlink -> builder);
+
+                builder.build()?;
+            }
+        }
+
+        say!("c {}", entry.name());
+
+        entry_opt = entry.next();
+    }
+
+    Ok(())
+}
@@ -23,6 +23,7 @@
 use log::error;
 use nuts_backend::{Backend, Binary, Create, IdSize, Open, ReceiveHeader, HEADER_MAX_SIZE};
 use nuts_tool_api::tool::{Plugin, PluginConnection, PluginError};
+use nuts_tool_api::ErrorResponse;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -42,6 +43,14 @@ fn setup_connection(mut connection: PluginConnection) -> Result<(), PluginError>
     Ok(())
 }
 
+fn supported<T>(result: Result<T, PluginError>) -> Result<Option<T>, PluginError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(PluginError::Response(ErrorResponse::Unsupported)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
 fn with_connection<T, F: FnOnce(&mut PluginConnection) -> Result<T, PluginError>>(
     f: F,
 ) -> Result<T, PluginError> {
@@ -124,6 +133,22 @@ impl ReceiveHeader<PluginBackend> for PluginBackendOpenBuilder {
 
         Ok(())
     }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, PluginError> {
+        let result = with_connection(|conn| conn.read_extended_header(idx));
+
+        match supported(result)? {
+            Some(header) => {
+                bytes.copy_from_slice(&header[..HEADER_MAX_SIZE]);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 impl Open<PluginBackend> for PluginBackendOpenBuilder {
@@ -190,6 +215,22 @@ impl ReceiveHeader<PluginBackend> for PluginBackend {
 
         Ok(())
     }
+
+    fn get_extended_header_bytes(
+        &mut self,
+        idx: u32,
+        bytes: &mut [u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, PluginError> {
+        let result = with_connection(|conn| conn.read_extended_header(idx));
+
+        match supported(result)? {
+            Some(header) => {
+                bytes.copy_from_slice(&header[..HEADER_MAX_SIZE]);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 impl Backend for PluginBackend {
@@ -212,10 +253,23 @@ impl Backend for PluginBackend {
         Ok(PluginId(id))
     }
 
+    fn aquire_many(&mut self, bufs: &[&[u8]]) -> Result<Vec<PluginId>, PluginError> {
+        let bufs = bufs.iter().map(|buf| buf.to_vec()).collect();
+        let ids = with_connection(|conn| conn.aquire_many(bufs))?;
+
+        Ok(ids.into_iter().map(PluginId).collect())
+    }
+
     fn release(&mut self, id: PluginId) -> Result<(), PluginError> {
         with_connection(|conn| conn.release(id.0))
     }
 
+    fn ids(&mut self) -> Result<Option<Vec<PluginId>>, PluginError> {
+        let result = with_connection(|conn| conn.ids());
+
+        supported(result).map(|opt| opt.map(|ids| ids.into_iter().map(PluginId).collect()))
+    }
+
     fn read(&mut self, id: &PluginId, buf: &mut [u8]) -> Result<usize, PluginError> {
         let bytes = with_connection(|conn| conn.read(id.0.clone()))?;
 
@@ -229,10 +283,47 @@ impl Backend for PluginBackend {
         with_connection(|conn| conn.write(id.0.clone(), buf.to_vec()))
     }
 
+    fn read_many(
+        &mut self,
+        blocks: &mut [(&PluginId, &mut [u8])],
+    ) -> Result<Vec<usize>, PluginError> {
+        let ids = blocks.iter().map(|(id, _)| id.0.clone()).collect();
+        let bytes = with_connection(|conn| conn.read_many(ids))?;
+
+        Ok(blocks
+            .iter_mut()
+            .zip(bytes)
+            .map(|((_, buf), bytes)| {
+                let n = cmp::min(bytes.len(), buf.len());
+                buf[..n].copy_from_slice(&bytes[..n]);
+                n
+            })
+            .collect())
+    }
+
+    fn write_many(&mut self, blocks: &[(&PluginId, &[u8])]) -> Result<Vec<usize>, PluginError> {
+        let (ids, bufs) = blocks
+            .iter()
+            .map(|(id, buf)| (id.0.clone(), buf.to_vec()))
+            .unzip();
+
+        with_connection(|conn| conn.write_many(ids, bufs))
+    }
+
     fn write_header(&mut self, buf: &[u8; HEADER_MAX_SIZE]) -> Result<(), PluginError> {
         with_connection(|conn| conn.write_header(buf.to_vec()))
     }
 
+    fn write_extended_header(
+        &mut self,
+        idx: u32,
+        buf: &[u8; HEADER_MAX_SIZE],
+    ) -> Result<bool, PluginError> {
+        let result = with_connection(|conn| conn.write_extended_header(idx, buf.to_vec()));
+
+        supported(result).map(|opt| opt.is_some())
+    }
+
     fn delete(self) {
         if let Err(err) = with_connection(|conn| conn.delete()) {
             error!("failed to delete backend instance: {}", err);
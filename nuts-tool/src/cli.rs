@@ -26,12 +26,13 @@ pub mod error;
 pub mod global;
 pub mod password;
 pub mod plugin;
+pub mod recipient;
 
 use anyhow::{anyhow, Result};
 use clap::{crate_version, Parser, Subcommand};
 use env_logger::Builder;
 use log::LevelFilter;
-use nuts_container::{Container, OpenOptionsBuilder};
+use nuts_container::{Container, OpenOptions, OpenOptionsBuilder};
 use nuts_tool_api::tool::Plugin;
 use rprompt::prompt_reply;
 
@@ -41,6 +42,7 @@ use crate::cli::container::ContainerArgs;
 use crate::cli::global::{GlobalArgs, GLOBALS};
 use crate::cli::password::password_from_source;
 use crate::cli::plugin::PluginArgs;
+use crate::cli::recipient::identity_from_file;
 use crate::config::{ContainerConfig, PluginConfig};
 
 #[derive(Debug, Parser)]
@@ -96,6 +98,29 @@ impl Commands {
 }
 
 fn open_container(name: &str) -> Result<Container<PluginBackend>> {
+    let plugin_builder = open_plugin_builder(name)?;
+    let options = open_options()?;
+
+    Container::open(plugin_builder, options).map_err(|err| err.into())
+}
+
+fn open_snapshot(name: &str, snapshot: &str) -> Result<Container<PluginBackend>> {
+    let plugin_builder = open_plugin_builder(name)?;
+    let options = open_options_builder()?
+        .with_snapshot(snapshot)
+        .build::<PluginBackend>()?;
+
+    Container::open(plugin_builder, options).map_err(|err| err.into())
+}
+
+fn restore_container(name: &str, header: &[u8]) -> Result<Container<PluginBackend>> {
+    let plugin_builder = open_plugin_builder(name)?;
+    let options = open_options()?;
+
+    Container::restore_header(plugin_builder, options, header).map_err(|err| err.into())
+}
+
+fn open_plugin_builder(name: &str) -> Result<PluginBackendOpenBuilder> {
     let container_config = ContainerConfig::load()?;
     let plugin_config = PluginConfig::load()?;
     let verbose = GLOBALS.with_borrow(|g| g.verbose);
@@ -106,12 +131,44 @@ fn open_container(name: &str) -> Result<Container<PluginBackend>> {
     let exe = plugin_config.path(plugin)?;
 
     let plugin = Plugin::new(&exe);
-    let plugin_builder = PluginBackendOpenBuilder::new(plugin, name, verbose)?;
 
-    let builder = OpenOptionsBuilder::new().with_password_callback(password_from_source);
-    let options = builder.build::<PluginBackend>()?;
+    Ok(PluginBackendOpenBuilder::new(plugin, name, verbose)?)
+}
 
-    Container::open(plugin_builder, options).map_err(|err| err.into())
+fn open_options() -> Result<OpenOptions> {
+    Ok(open_options_builder()?.build::<PluginBackend>()?)
+}
+
+fn open_options_builder() -> Result<OpenOptionsBuilder> {
+    let (keyfile, identity, recovery_key, console) = GLOBALS.with_borrow(|g| {
+        (
+            g.keyfile.clone(),
+            g.identity.clone(),
+            g.recovery_key.clone(),
+            g.password_source.is_console(),
+        )
+    });
+    let mut builder = OpenOptionsBuilder::new();
+
+    // With a keyfile or an identity the password is only used, if it is
+    // explicitly passed to the tool.
+    if (keyfile.is_none() && identity.is_none()) || !console {
+        builder = builder.with_password_callback(password_from_source);
+    }
+
+    if let Some(recovery_key) = recovery_key {
+        builder = builder.with_recovery_key(recovery_key);
+    }
+
+    if let Some(path) = keyfile {
+        builder = builder.with_keyfile(path);
+    }
+
+    if let Some(path) = identity {
+        builder = builder.with_identity(identity_from_file(&path)?);
+    }
+
+    Ok(builder)
 }
 
 pub fn prompt_yes_no(prompt: &str, force: bool) -> Result<bool> {
@@ -23,14 +23,20 @@
 use anyhow::{anyhow, Result};
 use clap::{ArgAction, Args};
 use log::debug;
-use nuts_container::LATEST_REVISION;
+use nuts_archive::ArchiveFactory;
+use nuts_container::{Container, LATEST_REVISION};
 use std::cmp::Ordering;
 
+use crate::archive::copy_archive;
 use crate::cli::archive::open_archive;
 use crate::cli::error::ExitOnly;
-use crate::cli::prompt_yes_no;
+use crate::cli::{open_container, prompt_yes_no};
 use crate::say;
 
+/// The first revision, where the header stores the service identifier. Up to
+/// this revision the migration can be performed in place.
+const SID_REVISION: u32 = 2;
+
 #[derive(Args, Debug)]
 pub struct ArchiveMigrateArgs {
     /// Instead of executing the migration, simply check whether one is
@@ -43,6 +49,15 @@ pub struct ArchiveMigrateArgs {
     #[clap(short, long, action = ArgAction::SetTrue)]
     yes: bool,
 
+    /// Copies the archive into the container with the name INTO. Since
+    /// revision 3 each block stores its own nonce, which changes the layout
+    /// of the blocks. So, a migration into revision 3 cannot be performed in
+    /// place. Create a new container with `nuts container create` and copy
+    /// the archive into the new container. Both containers must be protected
+    /// with the same password.
+    #[clap(long, value_name = "INTO")]
+    into: Option<String>,
+
     /// Specifies the name of the container
     #[clap(short, long, env = "NUTS_CONTAINER")]
     container: String,
@@ -73,11 +88,15 @@ impl ArchiveMigrateArgs {
 
                 if self.verify {
                     Err(ExitOnly::new(1).into())
-                } else if prompt_yes_no("Do you really want to start the migration?", self.yes)? {
-                    open_archive(&self.container, true).map(|_| ())
+                } else if let Some(into) = self.into.as_ref() {
+                    self.copy_into(into)
+                } else if info.revision < SID_REVISION {
+                    self.migrate_in_place()
                 } else {
-                    say!("aborted");
-                    Ok(())
+                    Err(anyhow!(
+                        "cannot migrate revision {} in place, copy the archive into a new container (--into)",
+                        info.revision
+                    ))
                 }
             }
             Ordering::Greater => Err(anyhow!(
@@ -87,4 +106,40 @@ impl ArchiveMigrateArgs {
             )),
         }
     }
+
+    fn migrate_in_place(&self) -> Result<()> {
+        if prompt_yes_no("Do you really want to start the migration?", self.yes)? {
+            open_archive(&self.container, true)?;
+
+            say!(
+                "container migrated to revision {}, use --into to migrate to revision {}",
+                SID_REVISION,
+                LATEST_REVISION
+            );
+        } else {
+            say!("aborted");
+        }
+
+        Ok(())
+    }
+
+    fn copy_into(&self, into: &str) -> Result<()> {
+        let prompt = format!("Do you really want to copy the archive into {}?", into);
+
+        if !prompt_yes_no(&prompt, self.yes)? {
+            say!("aborted");
+            return Ok(());
+        }
+
+        let mut source = open_archive(&self.container, false)?;
+
+        let container = open_container(into)?;
+        let mut target = Container::create_service::<ArchiveFactory>(container)?;
+
+        copy_archive(&mut source, &mut target)?;
+
+        say!("archive copied into {}", into);
+
+        Ok(())
+    }
 }
@@ -23,38 +23,56 @@
 pub mod aquire;
 pub mod attach;
 pub mod change;
+pub mod convert;
 pub mod create;
 pub mod delete;
+pub mod header;
 pub mod info;
 pub mod list;
 pub mod read;
+pub mod recovery;
+pub mod rekey;
 pub mod release;
+pub mod snapshot;
+pub mod verify;
 pub mod write;
 
 use anyhow::Result;
 use clap::builder::PossibleValue;
 use clap::{Args, Subcommand, ValueEnum};
-use nuts_container::Cipher;
+use nuts_container::{Cipher, Compression};
 use std::ops::Deref;
 
 use crate::cli::container::aquire::ContainerAquireArgs;
 use crate::cli::container::attach::ContainerAttachArgs;
 use crate::cli::container::change::ContainerChangeArgs;
+use crate::cli::container::convert::ContainerConvertArgs;
 use crate::cli::container::create::ContainerCreateArgs;
 use crate::cli::container::delete::ContainerDeleteArgs;
+use crate::cli::container::header::ContainerHeaderArgs;
 use crate::cli::container::info::ContainerInfoArgs;
 use crate::cli::container::list::ContainerListArgs;
 use crate::cli::container::read::ContainerReadArgs;
+use crate::cli::container::recovery::ContainerRecoveryArgs;
+use crate::cli::container::rekey::ContainerRekeyArgs;
 use crate::cli::container::release::ContainerReleaseArgs;
+use crate::cli::container::snapshot::ContainerSnapshotArgs;
+use crate::cli::container::verify::ContainerVerifyArgs;
 use crate::cli::container::write::ContainerWriteArgs;
 
 const AES128_GCM: &str = "aes128-gcm";
 const AES192_GCM: &str = "aes192-gcm";
 const AES256_GCM: &str = "aes256-gcm";
+const AES128_GCM_SIV: &str = "aes128-gcm-siv";
+const AES256_GCM_SIV: &str = "aes256-gcm-siv";
 const AES128_CTR: &str = "aes128-ctr";
 const AES192_CTR: &str = "aes192-ctr";
 const AES256_CTR: &str = "aes256-ctr";
+const CHACHA20_POLY1305: &str = "chacha20-poly1305";
+const XCHACHA20_POLY1305: &str = "xchacha20-poly1305";
 const NONE: &str = "none";
+const LZ4: &str = "lz4";
+const ZSTD: &str = "zstd";
 
 #[derive(Clone, Debug)]
 pub struct CliCipher(Cipher);
@@ -79,9 +97,13 @@ impl ValueEnum for CliCipher {
             CliCipher(Cipher::Aes128Gcm),
             CliCipher(Cipher::Aes192Gcm),
             CliCipher(Cipher::Aes256Gcm),
+            CliCipher(Cipher::Aes128GcmSiv),
+            CliCipher(Cipher::Aes256GcmSiv),
             CliCipher(Cipher::Aes192Ctr),
             CliCipher(Cipher::Aes256Ctr),
             CliCipher(Cipher::Aes128Ctr),
+            CliCipher(Cipher::ChaCha20Poly1305),
+            CliCipher(Cipher::XChaCha20Poly1305),
             CliCipher(Cipher::None),
         ]
     }
@@ -95,6 +117,41 @@ impl ValueEnum for CliCipher {
             Cipher::Aes128Gcm => AES128_GCM,
             Cipher::Aes192Gcm => AES192_GCM,
             Cipher::Aes256Gcm => AES256_GCM,
+            Cipher::ChaCha20Poly1305 => CHACHA20_POLY1305,
+            Cipher::XChaCha20Poly1305 => XCHACHA20_POLY1305,
+            Cipher::Aes128GcmSiv => AES128_GCM_SIV,
+            Cipher::Aes256GcmSiv => AES256_GCM_SIV,
+        };
+
+        Some(PossibleValue::new(value))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CliCompression(Compression);
+
+impl Deref for CliCompression {
+    type Target = Compression;
+
+    fn deref(&self) -> &Compression {
+        &self.0
+    }
+}
+
+impl ValueEnum for CliCompression {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            CliCompression(Compression::Lz4),
+            CliCompression(Compression::Zstd),
+            CliCompression(Compression::None),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let value = match self.0 {
+            Compression::None => NONE,
+            Compression::Lz4 => LZ4,
+            Compression::Zstd => ZSTD,
         };
 
         Some(PossibleValue::new(value))
@@ -127,12 +184,21 @@ pub enum ContainerCommand {
     /// Modifies the container
     Change(ContainerChangeArgs),
 
+    /// Converts the container into another cipher
+    ///
+    /// All blocks are re-encrypted with the new cipher. An interrupted run
+    /// can be resumed by calling the command again.
+    Convert(ContainerConvertArgs),
+
     /// Creates a nuts-container
     Create(ContainerCreateArgs),
 
     /// Removes a container again
     Delete(ContainerDeleteArgs),
 
+    /// Creates resp. restores a backup of the container header
+    Header(ContainerHeaderArgs),
+
     /// Prints general information about the container
     Info(ContainerInfoArgs),
 
@@ -142,9 +208,29 @@ pub enum ContainerCommand {
     /// Reads a block from the container
     Read(ContainerReadArgs),
 
+    /// Manages the recovery key of the container
+    Recovery(ContainerRecoveryArgs),
+
+    /// Re-encrypts all blocks of the container with a new master-key
+    ///
+    /// An interrupted run can be resumed by calling the command again. The key
+    /// slots are wrapped with a new header key, thus the old recovery key
+    /// becomes invalid. Other password slots than the one used to open the
+    /// container must be removed before.
+    Rekey(ContainerRekeyArgs),
+
     /// Releases a block again
     Release(ContainerReleaseArgs),
 
+    /// Manages snapshots of the container
+    Snapshot(ContainerSnapshotArgs),
+
+    /// Verifies the integrity of the container
+    ///
+    /// Reads the header and every block of the container and reports damaged
+    /// blocks. The exit code is 1 if the container is damaged, otherwise 0.
+    Verify(ContainerVerifyArgs),
+
     /// Writes a block into the container
     Write(ContainerWriteArgs),
 }
@@ -155,12 +241,18 @@ impl ContainerCommand {
             Self::Aquire(args) => args.run(),
             Self::Attach(args) => args.run(),
             Self::Change(args) => args.run(),
+            Self::Convert(args) => args.run(),
             Self::Create(args) => args.run(),
             Self::Delete(args) => args.run(),
+            Self::Header(args) => args.run(),
             Self::Info(args) => args.run(),
             Self::List(args) => args.run(),
             Self::Read(args) => args.run(),
+            Self::Recovery(args) => args.run(),
+            Self::Rekey(args) => args.run(),
             Self::Release(args) => args.run(),
+            Self::Snapshot(args) => args.run(),
+            Self::Verify(args) => args.run(),
             Self::Write(args) => args.run(),
         }
     }
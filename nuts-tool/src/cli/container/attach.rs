@@ -47,6 +47,15 @@ pub struct ContainerAttachArgs {
 
     #[clap(long, hide = true)]
     password_from_file: Option<PathBuf>,
+
+    #[clap(long, hide = true)]
+    keyfile: Option<PathBuf>,
+
+    #[clap(long, hide = true)]
+    identity: Option<PathBuf>,
+
+    #[clap(long, hide = true)]
+    recovery_key: Option<String>,
 }
 
 impl ContainerAttachArgs {
@@ -22,12 +22,14 @@
 
 pub mod kdf;
 pub mod password;
+pub mod slot;
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
 
 use crate::cli::container::change::kdf::ContainerChangeKdfArgs;
 use crate::cli::container::change::password::ContainerChangePasswordArgs;
+use crate::cli::container::change::slot::ContainerChangeSlotArgs;
 
 #[derive(Args, Debug)]
 pub struct ContainerChangeArgs {
@@ -48,6 +50,9 @@ pub enum ContainerChangeCommand {
 
     /// Changes the password of the container
     Password(ContainerChangePasswordArgs),
+
+    /// Manages the key slots of the container
+    Slot(ContainerChangeSlotArgs),
 }
 
 impl ContainerChangeCommand {
@@ -55,6 +60,7 @@ impl ContainerChangeCommand {
         match self {
             Self::Kdf(args) => args.run(),
             Self::Password(args) => args.run(),
+            Self::Slot(args) => args.run(),
         }
     }
 }
@@ -44,6 +44,20 @@ pub struct ContainerChangeKdfArgs {
     /// Selects PBKDF2 with the given digest (default: sha256),
     /// the given number of iterations (default: 65536) and salt
     /// length (default: 16).
+    ///
+    /// For Argon2id: argon2id[:[<MEMORY>]:[<ITERATIONS>]:[<PARALLELISM>]:[<SALT_LENGTH>]]
+    ///
+    /// Selects Argon2id with the given memory in KiB (default:
+    /// 19456), the given number of iterations (default: 2),
+    /// the given degree of parallelism (default: 1) and salt
+    /// length (default: 16).
+    ///
+    /// For scrypt: scrypt[:[<LOG_N>]:[<R>]:[<P>]:[<SALT_LENGTH>]]
+    ///
+    /// Selects scrypt with the given cost parameter as binary
+    /// logarithm (default: 17), the given block size (default: 8),
+    /// the given parallelization (default: 1) and salt length
+    /// (default: 16).
     kdf: Kdf,
 
     /// Specifies the name of the container
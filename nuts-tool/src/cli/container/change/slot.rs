@@ -0,0 +1,66 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use crate::cli::container::change::slot::add::ContainerChangeSlotAddArgs;
+use crate::cli::container::change::slot::list::ContainerChangeSlotListArgs;
+use crate::cli::container::change::slot::remove::ContainerChangeSlotRemoveArgs;
+
+#[derive(Args, Debug)]
+pub struct ContainerChangeSlotArgs {
+    #[clap(subcommand)]
+    command: ContainerChangeSlotCommand,
+}
+
+impl ContainerChangeSlotArgs {
+    pub fn run(&self) -> Result<()> {
+        self.command.run()
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContainerChangeSlotCommand {
+    /// Adds a key slot with a new password to the container
+    Add(ContainerChangeSlotAddArgs),
+
+    /// Removes a key slot from the container
+    Remove(ContainerChangeSlotRemoveArgs),
+
+    /// Lists the key slots of the container
+    List(ContainerChangeSlotListArgs),
+}
+
+impl ContainerChangeSlotCommand {
+    pub fn run(&self) -> Result<()> {
+        match self {
+            Self::Add(args) => args.run(),
+            Self::Remove(args) => args.run(),
+            Self::List(args) => args.run(),
+        }
+    }
+}
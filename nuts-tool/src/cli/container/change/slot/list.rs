@@ -0,0 +1,60 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use anyhow::Result;
+use clap::Args;
+use log::debug;
+use nuts_container::SlotInfo;
+
+use crate::cli::open_container;
+use crate::say;
+
+#[derive(Args, Debug)]
+pub struct ContainerChangeSlotListArgs {
+    /// Specifies the name of the container
+    #[clap(short, long, env = "NUTS_CONTAINER")]
+    container: String,
+}
+
+impl ContainerChangeSlotListArgs {
+    pub fn run(&self) -> Result<()> {
+        debug!("args: {:?}", self);
+
+        let container = open_container(&self.container)?;
+        let info = container.info()?;
+
+        for (idx, slot) in info.slots.iter().enumerate() {
+            let slot = match slot {
+                SlotInfo::Password(kdf) => kdf.to_string(),
+                SlotInfo::Recipient(recipient) => recipient.to_string(),
+            };
+
+            if info.active_slot == Some(idx) {
+                say!("{} {} (active)", idx, slot);
+            } else {
+                say!("{} {}", idx, slot);
+            }
+        }
+
+        Ok(())
+    }
+}
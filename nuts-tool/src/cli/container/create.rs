@@ -30,9 +30,10 @@ use std::os::fd::RawFd;
 use std::path::PathBuf;
 
 use crate::backend::{PluginBackend, PluginBackendCreateBuilder};
-use crate::cli::container::{CliCipher, AES256_GCM};
+use crate::cli::container::{CliCipher, CliCompression, AES256_GCM, NONE};
 use crate::cli::global::PasswordSource;
 use crate::cli::password::password_from_source_twice;
+use crate::cli::recipient::recipient_from_file;
 use crate::config::{ContainerConfig, PluginConfig};
 
 thread_local! {
@@ -56,6 +57,19 @@ pub struct ContainerCreateArgs {
     #[clap(short, long, value_parser = value_parser!(CliCipher), default_value = AES256_GCM)]
     cipher: CliCipher,
 
+    /// Compresses the blocks of the container with COMPRESSION.
+    ///
+    /// The blocks are compressed before they are encrypted.
+    /// Blocks, which do not compress, are stored as-is. Note
+    /// that the net block size of the container is reduced by
+    /// a small frame stored in front of each block.
+    ///
+    /// On a backend, which stores blocks with their actual
+    /// size (like the directory backend), the size of a block
+    /// reveals how well its content compressed.
+    #[clap(short = 'z', long, value_parser = value_parser!(CliCompression), default_value = NONE)]
+    compression: CliCompression,
+
     /// Specifies the key derivation function.
     ///
     /// There are two ways to specify the KDF. The short form
@@ -71,9 +85,33 @@ pub struct ContainerCreateArgs {
     /// Selects PBKDF2 with the given digest (default: sha256),
     /// the given number of iterations (default: 65536) and salt
     /// length (default: 16).
+    ///
+    /// For Argon2id: argon2id[:[<MEMORY>]:[<ITERATIONS>]:[<PARALLELISM>]:[<SALT_LENGTH>]]
+    ///
+    /// Selects Argon2id with the given memory in KiB (default:
+    /// 19456), the given number of iterations (default: 2),
+    /// the given degree of parallelism (default: 1) and salt
+    /// length (default: 16).
+    ///
+    /// For scrypt: scrypt[:[<LOG_N>]:[<R>]:[<P>]:[<SALT_LENGTH>]]
+    ///
+    /// Selects scrypt with the given cost parameter as binary
+    /// logarithm (default: 17), the given block size (default: 8),
+    /// the given parallelization (default: 1) and salt length
+    /// (default: 16).
     #[clap(short, long, value_parser)]
     kdf: Option<Kdf>,
 
+    /// Wraps the key of the container to the recipient read from <PATH>.
+    ///
+    /// The recipient is a PEM encoded X25519 public key. The
+    /// container can then be opened with the related identity
+    /// (see --identity). Can be specified multiple times. Unless
+    /// the password is read from a file descriptor, a file or a
+    /// keyfile, the container is protected by the recipients only.
+    #[clap(short, long, action = ArgAction::Append, value_name = "PATH")]
+    recipient: Vec<PathBuf>,
+
     /// If set, overwrites an existing container
     #[clap(short, long, action = ArgAction::SetTrue)]
     overwrite: bool,
@@ -90,6 +128,9 @@ pub struct ContainerCreateArgs {
 
     #[clap(from_global)]
     password_from_file: Option<PathBuf>,
+
+    #[clap(from_global)]
+    keyfile: Option<PathBuf>,
 }
 
 impl ContainerCreateArgs {
@@ -109,16 +150,32 @@ impl ContainerCreateArgs {
             self.name
         );
 
-        SOURCE.with_borrow_mut(|src| {
-            *src = PasswordSource::new(self.password_from_fd, self.password_from_file.clone())
-        });
+        let source = PasswordSource::new(self.password_from_fd, self.password_from_file.clone());
+        let console = source.is_console();
+
+        SOURCE.with_borrow_mut(|src| *src = source);
 
         let backend_options =
             PluginBackendCreateBuilder::new(plugin, &self.name, self.verbose, &self.plugin_args)?;
         let mut builder = CreateOptionsBuilder::new(*self.cipher)
-            .with_password_callback(password_callback)
+            .with_compression(*self.compression)
             .with_overwrite(self.overwrite);
 
+        // With a keyfile or recipients the password is only used, if it is
+        // explicitly passed to the tool.
+        if (self.keyfile.is_none() && self.recipient.is_empty()) || !console {
+            builder = builder.with_password_callback(password_callback);
+        }
+
+        if let Some(path) = self.keyfile.as_ref() {
+            debug!("keyfile: {}", path.display());
+            builder = builder.with_keyfile(path);
+        }
+
+        for path in self.recipient.iter() {
+            builder = builder.with_recipient(recipient_from_file(path)?);
+        }
+
         if self.cipher != Cipher::None {
             if let Some(kdf) = self.kdf.clone() {
                 debug!("kdf: {:?}", kdf);
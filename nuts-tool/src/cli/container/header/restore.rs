@@ -0,0 +1,67 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Args};
+use log::debug;
+use nuts_backend::HEADER_MAX_SIZE;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cli::{prompt_yes_no, restore_container};
+use crate::say;
+
+#[derive(Args, Debug)]
+pub struct ContainerHeaderRestoreArgs {
+    /// Reads the header from the file <PATH>
+    #[clap(value_name = "PATH")]
+    path: PathBuf,
+
+    /// Specifies the name of the container
+    #[clap(short, long, env = "NUTS_CONTAINER")]
+    container: String,
+
+    /// Say yes, don't prompt for restoring the header
+    #[clap(short, long, action = ArgAction::SetTrue)]
+    yes: bool,
+}
+
+impl ContainerHeaderRestoreArgs {
+    pub fn run(&self) -> Result<()> {
+        debug!("args: {:?}", self);
+
+        let header = fs::read(&self.path)?;
+
+        if header.is_empty() || header.len() % HEADER_MAX_SIZE != 0 {
+            return Err(anyhow!("invalid header backup: {}", self.path.display()));
+        }
+
+        if !prompt_yes_no("Do you really want to replace the header?", self.yes)? {
+            say!("aborted");
+            return Ok(());
+        }
+
+        restore_container(&self.container, &header)?;
+
+        Ok(())
+    }
+}
@@ -59,6 +59,7 @@ impl ContainerInfoArgs {
         say!("{:<key_width$} {}", "plugin:", plugin);
         say!("{:<key_width$} {}", "revision:", info.revision);
         say!("{:<key_width$} {}", "cipher:", info.cipher);
+        say!("{:<key_width$} {}", "compression:", info.compression);
         say!("{:<key_width$} {}", "kdf:", info.kdf.to_string());
         say!("{:<key_width$} {}", "block size (gross):", info.bsize_gross);
         say!("{:<key_width$} {}", "block size (net):", info.bsize_net);
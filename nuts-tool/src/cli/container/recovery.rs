@@ -0,0 +1,58 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+pub mod export;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use crate::cli::container::recovery::export::ContainerRecoveryExportArgs;
+
+#[derive(Args, Debug)]
+pub struct ContainerRecoveryArgs {
+    #[clap(subcommand)]
+    command: ContainerRecoveryCommand,
+}
+
+impl ContainerRecoveryArgs {
+    pub fn run(&self) -> Result<()> {
+        self.command.run()
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContainerRecoveryCommand {
+    /// Prints the recovery key of the container
+    ///
+    /// The recovery key opens the container without a password (see the
+    /// --recovery-key option). Write it down and keep it in a safe place,
+    /// anybody with the recovery key has full access to the container.
+    Export(ContainerRecoveryExportArgs),
+}
+
+impl ContainerRecoveryCommand {
+    pub fn run(&self) -> Result<()> {
+        match self {
+            Self::Export(args) => args.run(),
+        }
+    }
+}
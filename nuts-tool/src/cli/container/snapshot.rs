@@ -0,0 +1,77 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod read;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use crate::cli::container::snapshot::create::ContainerSnapshotCreateArgs;
+use crate::cli::container::snapshot::delete::ContainerSnapshotDeleteArgs;
+use crate::cli::container::snapshot::list::ContainerSnapshotListArgs;
+use crate::cli::container::snapshot::read::ContainerSnapshotReadArgs;
+
+#[derive(Args, Debug)]
+pub struct ContainerSnapshotArgs {
+    #[clap(subcommand)]
+    command: ContainerSnapshotCommand,
+}
+
+impl ContainerSnapshotArgs {
+    pub fn run(&self) -> Result<()> {
+        self.command.run()
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContainerSnapshotCommand {
+    /// Creates a snapshot of the container
+    ///
+    /// The snapshot preserves the current state of all blocks. Blocks
+    /// modified afterwards are copied, the snapshot itself is never changed.
+    Create(ContainerSnapshotCreateArgs),
+
+    /// Deletes a snapshot
+    ///
+    /// Blocks only used by the snapshot are released.
+    Delete(ContainerSnapshotDeleteArgs),
+
+    /// Lists all snapshots of the container
+    List(ContainerSnapshotListArgs),
+
+    /// Reads a block from a snapshot
+    Read(ContainerSnapshotReadArgs),
+}
+
+impl ContainerSnapshotCommand {
+    pub fn run(&self) -> Result<()> {
+        match self {
+            Self::Create(args) => args.run(),
+            Self::Delete(args) => args.run(),
+            Self::List(args) => args.run(),
+            Self::Read(args) => args.run(),
+        }
+    }
+}
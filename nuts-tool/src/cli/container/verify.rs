@@ -0,0 +1,170 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use log::debug;
+use nuts_container::{CipherError, Error, VerifyReport};
+use serde::Serialize;
+
+use crate::backend::PluginBackend;
+use crate::cli::error::ExitOnly;
+use crate::cli::open_container;
+use crate::say;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct Damage {
+    id: String,
+    kind: &'static str,
+    error: String,
+}
+
+impl Damage {
+    fn new(id: String, err: &Error<PluginBackend>) -> Damage {
+        let kind = match err {
+            Error::Backend(_) => "unreadable",
+            Error::Cipher(CipherError::NotTrustworthy) => "not-trustworthy",
+            _ => "invalid",
+        };
+
+        Damage {
+            id,
+            kind,
+            error: err.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    ok: bool,
+    error: Option<String>,
+    header: Option<String>,
+    blocks: usize,
+    damaged: Vec<Damage>,
+}
+
+impl Report {
+    /// The container cannot be opened because of its header.
+    fn header(err: Error<PluginBackend>) -> Report {
+        Report {
+            ok: false,
+            error: None,
+            header: Some(err.to_string()),
+            blocks: 0,
+            damaged: vec![],
+        }
+    }
+
+    /// The verification could not be performed at all.
+    fn error(err: anyhow::Error) -> Report {
+        Report {
+            ok: false,
+            error: Some(err.to_string()),
+            header: None,
+            blocks: 0,
+            damaged: vec![],
+        }
+    }
+}
+
+impl From<VerifyReport<PluginBackend>> for Report {
+    fn from(report: VerifyReport<PluginBackend>) -> Report {
+        Report {
+            ok: report.is_ok(),
+            error: None,
+            header: report.header.as_ref().map(|err| err.to_string()),
+            blocks: report.blocks,
+            damaged: report
+                .damaged
+                .iter()
+                .map(|(id, err)| Damage::new(id.to_string(), err))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ContainerVerifyArgs {
+    /// Specifies the format of the report
+    #[clap(short, long, value_parser, default_value = "text")]
+    format: ReportFormat,
+
+    /// Specifies the name of the container
+    #[clap(short, long, env = "NUTS_CONTAINER")]
+    container: String,
+}
+
+impl ContainerVerifyArgs {
+    pub fn run(&self) -> Result<()> {
+        debug!("args: {:?}", self);
+
+        let report = match self.format {
+            ReportFormat::Text => {
+                let report = self.verify()?;
+
+                Self::print_text(&report);
+                report
+            }
+            ReportFormat::Json => {
+                let report = self.verify().unwrap_or_else(Report::error);
+                let json = serde_json::to_string(&report)?;
+
+                say!("{}", json);
+                report
+            }
+        };
+
+        if report.ok {
+            Ok(())
+        } else {
+            Err(ExitOnly::new(1).into())
+        }
+    }
+
+    fn verify(&self) -> Result<Report> {
+        // A container, which cannot be opened (e.g. because of a damaged
+        // header), is reported as well.
+        let mut container = match open_container(&self.container) {
+            Ok(container) => container,
+            Err(err) => return err.downcast().map(Report::header),
+        };
+
+        Ok(Report::from(container.verify()?))
+    }
+
+    fn print_text(report: &Report) {
+        say!("header:  {}", report.header.as_deref().unwrap_or("ok"));
+        say!("blocks:  {}", report.blocks);
+        say!("damaged: {}", report.damaged.len());
+
+        for damage in report.damaged.iter() {
+            say!("{} {} {}", damage.id, damage.kind, damage.error);
+        }
+    }
+}
@@ -22,6 +22,7 @@
 
 use clap::{ArgAction, ArgGroup, Args};
 use log::debug;
+use nuts_container::RecoveryKey;
 use std::cell::RefCell;
 use std::os::fd::RawFd;
 use std::path::PathBuf;
@@ -37,6 +38,9 @@ pub struct GlobalValues {
     pub verbose: u8,
     pub say: Say,
     pub password_source: PasswordSource,
+    pub keyfile: Option<PathBuf>,
+    pub identity: Option<PathBuf>,
+    pub recovery_key: Option<RecoveryKey>,
 }
 
 impl GlobalValues {
@@ -52,8 +56,33 @@ impl GlobalValues {
             self.password_source = PasswordSource::Console;
         }
     }
+
+    fn init_keyfile(&mut self, args: &GlobalArgs) {
+        if let Some(path) = args.keyfile.as_ref() {
+            debug!("read keyfile from path {}", path.display());
+        }
+
+        self.keyfile = args.keyfile.clone();
+    }
+
+    fn init_identity(&mut self, args: &GlobalArgs) {
+        if let Some(path) = args.identity.as_ref() {
+            debug!("read identity from path {}", path.display());
+        }
+
+        self.identity = args.identity.clone();
+    }
+
+    fn init_recovery_key(&mut self, args: &GlobalArgs) {
+        if args.recovery_key.is_some() {
+            debug!("open with recovery key");
+        }
+
+        self.recovery_key = args.recovery_key.clone();
+    }
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum PasswordSource {
     Fd(RawFd),
     Path(PathBuf),
@@ -70,6 +99,11 @@ impl PasswordSource {
             Self::Console
         }
     }
+
+    /// Tests whether the password is read from the console.
+    pub fn is_console(&self) -> bool {
+        matches!(self, Self::Console)
+    }
 }
 
 impl Default for PasswordSource {
@@ -98,6 +132,26 @@ pub struct GlobalArgs {
     /// first line until a `\n` is read.
     #[clap(long, group = "password", global = true, value_name = "PATH")]
     pub password_from_file: Option<PathBuf>,
+
+    /// Feeds the contents of the specified file <PATH> into the key
+    /// derivation function. Unless the password is read from a file
+    /// descriptor or a file, the keyfile alone protects the container and
+    /// no password is asked for.
+    #[clap(long, global = true, value_name = "PATH")]
+    pub keyfile: Option<PathBuf>,
+
+    /// Reads the identity (a PEM encoded X25519 private key) from the
+    /// specified file <PATH>. The identity unlocks the container, if it was
+    /// added as a recipient. Unless the password is read from a file
+    /// descriptor or a file, no password is asked for.
+    #[clap(long, global = true, value_name = "PATH")]
+    pub identity: Option<PathBuf>,
+
+    /// Opens the container with the specified recovery key <KEY> (see
+    /// `container recovery export`). The key slots are skipped, no password
+    /// is asked for.
+    #[clap(long, global = true, value_name = "KEY")]
+    pub recovery_key: Option<RecoveryKey>,
 }
 
 impl GlobalArgs {
@@ -106,6 +160,9 @@ impl GlobalArgs {
             g.verbose = self.verbose;
             g.say.set_quiet(self.quiet);
             g.init_password_source(self);
+            g.init_keyfile(self);
+            g.init_identity(self);
+            g.init_recovery_key(self);
         });
     }
 }
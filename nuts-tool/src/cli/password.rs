@@ -20,26 +20,40 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
 // IN THE SOFTWARE.
 
-use lazy_static::lazy_static;
 use rpassword::prompt_password;
+use std::cell::RefCell;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 use std::os::fd::FromRawFd;
+use std::ptr;
+use std::sync::atomic::{self, Ordering};
 
 use crate::cli::global::{PasswordSource, GLOBALS};
 
-fn ask_for_password() -> Result<Vec<u8>, String> {
-    lazy_static! {
-        static ref RESULT: Result<String, String> =
-            prompt_password("Enter a password: ").map_err(|err| err.to_string());
-    }
+thread_local! {
+    // Passwords, which were successfully read, by their source.
+    static PASSWORDS: RefCell<Vec<(PasswordSource, CachedPassword)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A cached password, which is wiped, when it is dropped.
+struct CachedPassword(Vec<u8>);
 
-    match RESULT.as_ref() {
-        Ok(s) => Ok(s.as_bytes().to_vec()),
-        Err(s) => Err(s.clone()),
+impl Drop for CachedPassword {
+    fn drop(&mut self) {
+        for elem in self.0.iter_mut() {
+            unsafe { ptr::write_volatile(elem, 0) };
+        }
+
+        atomic::compiler_fence(Ordering::SeqCst);
     }
 }
 
+fn ask_for_password() -> Result<Vec<u8>, String> {
+    prompt_password("Enter a password: ")
+        .map(|s| s.as_bytes().to_vec())
+        .map_err(|err| err.to_string())
+}
+
 fn ask_for_password_twice(prompt: &str) -> Result<Vec<u8>, String> {
     let pass1 = prompt_password(format!("{}: ", prompt)).map_err(|err| err.to_string())?;
     let pass2 = prompt_password(format!("{} (repeat): ", prompt)).map_err(|err| err.to_string())?;
@@ -84,7 +98,29 @@ fn password_from_source_or<F: FnOnce() -> Result<Vec<u8>, String>>(
 }
 
 pub fn password_from_source() -> Result<Vec<u8>, String> {
-    GLOBALS.with_borrow(|g| password_from_source_or(&g.password_source, ask_for_password))
+    let source = GLOBALS.with_borrow(|g| g.password_source.clone());
+
+    // A password is read only once from its source. A file descriptor cannot
+    // be read twice, and the user should not be asked again, when a command
+    // opens more than one container. A failed attempt is not remembered, the
+    // next call tries again.
+    let cached = PASSWORDS.with_borrow(|passwords| {
+        passwords
+            .iter()
+            .find(|(src, _)| *src == source)
+            .map(|(_, password)| password.0.clone())
+    });
+
+    if let Some(password) = cached {
+        return Ok(password);
+    }
+
+    let password = password_from_source_or(&source, ask_for_password)?;
+
+    PASSWORDS
+        .with_borrow_mut(|passwords| passwords.push((source, CachedPassword(password.clone()))));
+
+    Ok(password)
 }
 
 pub fn password_from_source_twice(
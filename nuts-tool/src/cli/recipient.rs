@@ -0,0 +1,50 @@
+// MIT License
+//
+// Copyright (c) 2024 Robin Doer
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+use anyhow::{anyhow, Result};
+use nuts_container::{Identity, Recipient};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+fn read_error<E: fmt::Display>(kind: &str, path: &Path, err: E) -> anyhow::Error {
+    anyhow!(
+        "failed to read the {} from {}: {}",
+        kind,
+        path.display(),
+        err
+    )
+}
+
+/// Reads a PEM encoded X25519 public key from the file at `path`.
+pub fn recipient_from_file(path: &Path) -> Result<Recipient> {
+    let pem = fs::read(path).map_err(|err| read_error("recipient", path, err))?;
+
+    Recipient::from_pem(&pem).map_err(|err| read_error("recipient", path, err))
+}
+
+/// Reads a PEM encoded X25519 private key from the file at `path`.
+pub fn identity_from_file(path: &Path) -> Result<Identity> {
+    let pem = fs::read(path).map_err(|err| read_error("identity", path, err))?;
+
+    Identity::from_pem(&pem).map_err(|err| read_error("identity", path, err))
+}
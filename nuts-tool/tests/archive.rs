@@ -148,7 +148,7 @@ fn add() {
     archive_add(&tmp_dir, "sample", Some(b"xxx"))
         .assert()
         .code(1)
-        .stdout("the plaintext is not trustworthy\n")
+        .stdout("the header is not trustworthy, wrong password or modified header\n")
         .stderr("");
 
     archive_add(&tmp_dir, "sample", Some(b"123"))
@@ -242,7 +242,7 @@ fn create() {
     archive_create(&tmp_dir, "sample", Some(b"xxx"))
         .assert()
         .code(1)
-        .stdout("the plaintext is not trustworthy\n")
+        .stdout("the header is not trustworthy, wrong password or modified header\n")
         .stderr("");
 
     archive_create(&tmp_dir, "sample", Some(b"123"))